@@ -1,18 +1,27 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Sample, SampleFormat, SupportedStreamConfig};
 use serde::{Deserialize, Serialize};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // Pre-allocate buffer for ~30 seconds of 16kHz mono audio
 // This reduces dynamic allocations during recording
 const TARGET_SAMPLE_RATE: u32 = 16_000;
 const INITIAL_BUFFER_CAPACITY: usize = TARGET_SAMPLE_RATE as usize * 30;
-const MAX_RECORDING_SECONDS: usize = 5 * 60;
+pub const MAX_RECORDING_SECONDS: usize = 5 * 60;
 const MAX_RECORDING_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * MAX_RECORDING_SECONDS;
 
+// Long-form recordings are spooled to a 16-bit WAV on disk and only the most
+// recent few seconds stay in memory. They are capped at the same length that
+// file transcription accepts, which bounds the spool file to roughly 58 MB.
+const LONG_FORM_RING_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * 5;
+const MAX_LONG_FORM_SECONDS: usize = 30 * 60;
+const MAX_LONG_FORM_SAMPLES: usize = TARGET_SAMPLE_RATE as usize * MAX_LONG_FORM_SECONDS;
+
 pub enum RecorderCommand {
     Stop,
 }
@@ -20,11 +29,124 @@ pub enum RecorderCommand {
 pub struct AudioRecorder {
     samples: Arc<Mutex<Vec<f32>>>,
     is_recording: Arc<AtomicBool>,
+    is_paused: Arc<AtomicBool>,
+    // Meter level of the latest captured block, stored as f32 bits
+    input_level: Arc<AtomicU32>,
+    silence_auto_stop: Option<SilenceAutoStop>,
+    // Set when the last recording ended itself after silence
+    stopped_on_silence: Arc<AtomicBool>,
+    started_at: Option<Instant>,
+    paused_at: Option<Instant>,
+    paused_total: Duration,
     input_device_name: Option<String>,
+    // Saved microphone used when no device is set explicitly; skipped if it
+    // is no longer connected
+    preferred_input_device: Option<String>,
     output_device_name: Option<String>,
     capture_source: AudioCaptureSource,
+    long_form_dir: Option<PathBuf>,
+    preallocated_samples: usize,
+    // Samples of the last stopped take, kept for `waveform`. Long-form takes
+    // are not kept, as a second copy of an hour-long take is too much memory
+    last_recording: Vec<f32>,
+    spool: Option<(PathBuf, Arc<Mutex<AudioSpool>>)>,
     command_sender: Option<mpsc::Sender<RecorderCommand>>,
     thread_handle: Option<JoinHandle<()>>,
+    // Pre-roll length in samples; 0 keeps the microphone closed when idle
+    preroll_samples: usize,
+    preroll: Option<PrerollStream>,
+}
+
+/// Writes captured audio to a temporary WAV file during long-form recordings.
+struct AudioSpool {
+    writer: Option<hound::WavWriter<std::io::BufWriter<std::fs::File>>>,
+    samples_written: usize,
+    error: Option<String>,
+}
+
+impl AudioSpool {
+    fn create(path: &Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create recording directory: {}", e))?;
+        }
+
+        let writer = hound::WavWriter::create(path, wav_spec())
+            .map_err(|e| format!("Failed to create recording file: {}", e))?;
+
+        Ok(Self {
+            writer: Some(writer),
+            samples_written: 0,
+            error: None,
+        })
+    }
+
+    /// Appends samples, returning `false` once the spool is full or has failed.
+    fn write(&mut self, samples: &[f32]) -> bool {
+        let Some(writer) = self.writer.as_mut() else {
+            return false;
+        };
+
+        let remaining = MAX_LONG_FORM_SAMPLES.saturating_sub(self.samples_written);
+        let take = samples.len().min(remaining);
+
+        for &sample in &samples[..take] {
+            if let Err(e) = writer.write_sample(sample_to_i16(sample)) {
+                self.error = Some(format!("Failed to write recording file: {}", e));
+                return false;
+            }
+        }
+
+        self.samples_written += take;
+        self.samples_written < MAX_LONG_FORM_SAMPLES
+    }
+
+    fn finalize(&mut self) -> Result<(), String> {
+        if let Some(writer) = self.writer.take() {
+            writer
+                .finalize()
+                .map_err(|e| format!("Failed to finalize recording file: {}", e))?;
+        }
+
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Where a capture stream delivers its processed samples. Samples are
+/// dropped while `is_paused` is set, without stopping the stream.
+#[derive(Clone)]
+struct CaptureSink {
+    samples: Arc<Mutex<Vec<f32>>>,
+    /// When set, only this many of the most recent samples are kept
+    keep_latest: Option<usize>,
+    spool: Option<Arc<Mutex<AudioSpool>>>,
+    is_paused: Arc<AtomicBool>,
+    input_level: Arc<AtomicU32>,
+    auto_stop: Option<SilenceAutoStop>,
+    stopped_on_silence: Arc<AtomicBool>,
+    /// The pre-roll ring's fill flag, cleared by the first audio this sink
+    /// receives so the ring ends where the take begins instead of both
+    /// holding the seam
+    preroll_filling: Option<Arc<AtomicBool>>,
+}
+
+impl CaptureSink {
+    /// An in-memory sink sharing this sink's pause state
+    fn memory(&self, samples: Arc<Mutex<Vec<f32>>>) -> Self {
+        Self {
+            samples,
+            keep_latest: None,
+            spool: None,
+            is_paused: self.is_paused.clone(),
+            input_level: self.input_level.clone(),
+            auto_stop: self.auto_stop,
+            stopped_on_silence: self.stopped_on_silence.clone(),
+            preroll_filling: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
@@ -37,10 +159,42 @@ pub enum AudioCaptureSource {
 
 #[derive(Debug, Clone, Serialize)]
 pub struct AudioInputDevice {
+    /// Identifies the device when selecting it. Same as `name` unless another
+    /// device reports that name too, see `input_device_ids`.
+    pub id: String,
     pub name: String,
     pub is_default: bool,
 }
 
+/// Ids for input devices given their names in enumeration order. A name
+/// reported by one device is its own id; devices sharing a name get
+/// `name#1`, `name#2`, ... so each can still be picked.
+pub fn input_device_ids(names: &[String]) -> Vec<String> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    names
+        .iter()
+        .map(|name| {
+            let occurrence = seen.entry(name.as_str()).or_insert(0);
+            *occurrence += 1;
+            if names.iter().filter(|other| *other == name).count() > 1 {
+                format!("{}#{}", name, occurrence)
+            } else {
+                name.clone()
+            }
+        })
+        .collect()
+}
+
+/// Position of the device matching `requested`, which may be an id from
+/// `input_device_ids` or a plain name. A plain name shared by several devices
+/// (e.g. saved before a duplicate appeared) selects the first of them.
+pub fn resolve_input_device(names: &[String], requested: &str) -> Option<usize> {
+    input_device_ids(names)
+        .iter()
+        .position(|id| id == requested)
+        .or_else(|| names.iter().position(|name| name == requested))
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct AudioOutputDevice {
     pub name: String,
@@ -56,11 +210,25 @@ impl AudioRecorder {
         Ok(Self {
             samples: Arc::new(Mutex::new(Vec::with_capacity(INITIAL_BUFFER_CAPACITY))),
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
+            input_level: Arc::new(AtomicU32::new(0)),
+            silence_auto_stop: None,
+            stopped_on_silence: Arc::new(AtomicBool::new(false)),
+            started_at: None,
+            paused_at: None,
+            paused_total: Duration::ZERO,
             input_device_name: None,
+            preferred_input_device: None,
             output_device_name: None,
             capture_source: AudioCaptureSource::Mic,
+            long_form_dir: None,
+            preallocated_samples: INITIAL_BUFFER_CAPACITY,
+            last_recording: Vec::new(),
+            spool: None,
             command_sender: None,
             thread_handle: None,
+            preroll_samples: 0,
+            preroll: None,
         })
     }
 
@@ -70,15 +238,25 @@ impl AudioRecorder {
             .default_input_device()
             .and_then(|device| device.name().ok());
 
-        let devices = host
+        let names: Vec<String> = host
             .input_devices()
             .map_err(|e| format!("Failed to list input devices: {}", e))?
-            .filter_map(|device| {
-                let name = device.name().ok()?;
-                Some(AudioInputDevice {
-                    is_default: default_name.as_deref() == Some(name.as_str()),
-                    name,
-                })
+            .filter_map(|device| device.name().ok())
+            .collect();
+        // Only the first of several devices sharing the default's name is
+        // marked, since the host doesn't say which one it means
+        let default_index = default_name
+            .as_deref()
+            .and_then(|default_name| names.iter().position(|name| name == default_name));
+
+        let devices = input_device_ids(&names)
+            .into_iter()
+            .zip(names)
+            .enumerate()
+            .map(|(index, (id, name))| AudioInputDevice {
+                id,
+                name,
+                is_default: default_index == Some(index),
             })
             .collect();
 
@@ -106,24 +284,130 @@ impl AudioRecorder {
         Ok(devices)
     }
 
+    /// Whether `requested` names an input device, by id or plain name
+    fn input_device_exists(requested: &str) -> Result<bool, String> {
+        let names: Vec<String> = Self::list_input_devices()?
+            .into_iter()
+            .map(|device| device.name)
+            .collect();
+        Ok(resolve_input_device(&names, requested).is_some())
+    }
+
+    /// Select the input device by id from `list_input_devices` or by name;
+    /// `None` picks the default microphone
     pub fn set_input_device(&mut self, name: Option<String>) -> Result<(), String> {
         if self.is_recording.load(Ordering::SeqCst) {
             return Err("Cannot change input device while recording".to_string());
         }
 
         if let Some(ref device_name) = name {
-            let exists = Self::list_input_devices()?
-                .iter()
-                .any(|device| device.name == *device_name);
+            let exists = Self::input_device_exists(device_name)?;
             if !exists {
                 return Err(format!("Input device not found: {}", device_name));
             }
         }
 
         self.input_device_name = name;
+        self.restart_preroll()
+    }
+
+    /// Microphone to record from when none is set with `set_input_device`,
+    /// usually the one saved in settings. Unlike an explicit device, a
+    /// preferred one that has gone missing falls back to the default.
+    pub fn set_preferred_input_device(&mut self, name: Option<String>) {
+        if self.preferred_input_device == name {
+            return;
+        }
+        self.preferred_input_device = name;
+        if self.input_device_name.is_none() {
+            if let Err(e) = self.restart_preroll() {
+                println!("[AUDIO] Failed to reopen pre-roll stream: {}", e);
+            }
+        }
+    }
+
+    /// Keep the last `duration` of microphone audio while idle and put it in
+    /// front of the next recording, so speech that starts before the input
+    /// stream is up is not clipped. The microphone stays open between
+    /// recordings while this is on; zero turns it off.
+    pub fn set_preroll(&mut self, duration: Duration) -> Result<(), String> {
+        let samples = (duration.as_secs_f64() * TARGET_SAMPLE_RATE as f64) as usize;
+        if samples == self.preroll_samples && (samples == 0 || self.preroll.is_some()) {
+            return Ok(());
+        }
+        self.preroll_samples = samples;
+        self.restart_preroll()
+    }
+
+    /// Reopen the pre-roll stream for the current microphone. Nothing is
+    /// opened when pre-roll is off or only system audio is captured.
+    fn restart_preroll(&mut self) -> Result<(), String> {
+        self.preroll = None;
+        if self.preroll_samples == 0 || self.capture_source == AudioCaptureSource::System {
+            return Ok(());
+        }
+
+        let preroll =
+            PrerollStream::start(self.input_device_for_recording(), self.preroll_samples)?;
+        if self.is_recording() {
+            preroll.take();
+        }
+        self.preroll = Some(preroll);
         Ok(())
     }
 
+    /// Put the buffered pre-roll in front of a fresh in-memory microphone
+    /// take. The ring stopped filling at the take's first callback, so none
+    /// of it repeats the start of the take; it stays stopped until the
+    /// recording ends.
+    fn prepend_preroll(&mut self, append: bool) {
+        let Some(ref preroll) = self.preroll else {
+            return;
+        };
+        let lead = preroll.take();
+        if append || self.spool.is_some() || self.capture_source != AudioCaptureSource::Mic {
+            return;
+        }
+        self.samples.lock().unwrap().splice(0..0, lead);
+    }
+
+    fn resume_preroll(&self) {
+        if let Some(ref preroll) = self.preroll {
+            preroll.resume();
+        }
+    }
+
+    /// Stop recordings on their own after a stretch of silence, or never
+    /// with `None`. Applies from the next recording started.
+    pub fn set_silence_auto_stop(&mut self, auto_stop: Option<SilenceAutoStop>) {
+        self.silence_auto_stop = auto_stop;
+    }
+
+    /// Whether the last recording ended itself after silence rather than
+    /// being stopped. Its samples are still collected by `stop_recording`.
+    pub fn stopped_on_silence(&self) -> bool {
+        self.stopped_on_silence.load(Ordering::SeqCst)
+    }
+
+    /// The explicit input device, else the preferred one if it is connected
+    fn input_device_for_recording(&self) -> Option<String> {
+        if self.input_device_name.is_some() {
+            return self.input_device_name.clone();
+        }
+
+        let preferred = self.preferred_input_device.as_ref()?;
+        match Self::input_device_exists(preferred) {
+            Ok(true) => Some(preferred.clone()),
+            _ => {
+                println!(
+                    "[AUDIO] Saved input device {} is not available, using the default",
+                    preferred
+                );
+                None
+            }
+        }
+    }
+
     pub fn set_capture_config(
         &mut self,
         capture_source: AudioCaptureSource,
@@ -139,9 +423,7 @@ impl AudioRecorder {
             AudioCaptureSource::Mic | AudioCaptureSource::Both
         ) {
             if let Some(ref device_name) = input_device_name {
-                let exists = Self::list_input_devices()?
-                    .iter()
-                    .any(|device| device.name == *device_name);
+                let exists = Self::input_device_exists(device_name)?;
                 if !exists {
                     return Err(format!("Input device not found: {}", device_name));
                 }
@@ -165,30 +447,106 @@ impl AudioRecorder {
         self.capture_source = capture_source;
         self.input_device_name = input_device_name;
         self.output_device_name = output_device_name;
+        self.restart_preroll()
+    }
+
+    /// Enables long-form mode, spooling recordings to a WAV file in `dir`
+    /// instead of holding them in memory. `None` restores in-memory recording.
+    pub fn set_long_form_dir(&mut self, dir: Option<PathBuf>) -> Result<(), String> {
+        if self.is_recording.load(Ordering::SeqCst) {
+            return Err("Cannot change long-form mode while recording".to_string());
+        }
+
+        self.long_form_dir = dir;
+        Ok(())
+    }
+
+    /// Sets how many seconds of audio the sample buffer reserves when a
+    /// recording starts, capped at the max recording length.
+    pub fn set_preallocated_seconds(&mut self, seconds: usize) -> Result<(), String> {
+        if self.is_recording.load(Ordering::SeqCst) {
+            return Err("Cannot change buffer pre-allocation while recording".to_string());
+        }
+
+        self.preallocated_samples =
+            seconds.min(MAX_RECORDING_SECONDS) * TARGET_SAMPLE_RATE as usize;
         Ok(())
     }
 
     pub fn start_recording(&mut self) -> Result<(), String> {
+        self.begin_recording(false)
+    }
+
+    /// Start another take that appends to the samples already recorded
+    /// instead of replacing them. The combined takes share the max-duration cap.
+    pub fn continue_recording(&mut self) -> Result<(), String> {
+        if self.long_form_dir.is_some() {
+            return Err("Appending takes is not available in long-form mode".to_string());
+        }
+        if self.samples.lock().unwrap().len() >= MAX_RECORDING_SAMPLES {
+            return Err("Recording has reached the maximum length".to_string());
+        }
+
+        self.begin_recording(true)
+    }
+
+    fn begin_recording(&mut self, append: bool) -> Result<(), String> {
         if self.is_recording.load(Ordering::SeqCst) {
             return Err("Already recording".to_string());
         }
 
+        let spool = match self.long_form_dir {
+            Some(ref dir) => {
+                if self.capture_source == AudioCaptureSource::Both {
+                    return Err(
+                        "Long-form recording is not available when capturing both microphone and system audio"
+                            .to_string(),
+                    );
+                }
+
+                let path = dir.join(format!("long_form_{}.wav", uuid::Uuid::new_v4()));
+                let spool = Arc::new(Mutex::new(AudioSpool::create(&path)?));
+                self.spool = Some((path, spool.clone()));
+                Some(spool)
+            }
+            None => None,
+        };
+
         // Clear previous samples but keep capacity
         {
             let mut samples = self.samples.lock().unwrap();
-            samples.clear();
-            // Ensure we have enough capacity pre-allocated
-            let current_capacity = samples.capacity();
-            if current_capacity < INITIAL_BUFFER_CAPACITY {
-                samples.reserve(INITIAL_BUFFER_CAPACITY - current_capacity);
+            if !append {
+                samples.clear();
+            }
+            // Ensure we have enough capacity pre-allocated. Long-form mode
+            // only keeps a short ring in memory.
+            let target = if spool.is_some() {
+                LONG_FORM_RING_SAMPLES
+            } else {
+                self.preallocated_samples
+            };
+            if samples.capacity() < target {
+                samples.reserve_exact(target - samples.len());
             }
         }
 
         let (cmd_tx, cmd_rx) = mpsc::channel::<RecorderCommand>();
         let (init_tx, init_rx) = mpsc::channel::<Result<(), String>>();
-        let samples = self.samples.clone();
+        let sink = CaptureSink {
+            samples: self.samples.clone(),
+            keep_latest: spool.is_some().then_some(LONG_FORM_RING_SAMPLES),
+            spool,
+            is_paused: self.is_paused.clone(),
+            input_level: self.input_level.clone(),
+            auto_stop: self.silence_auto_stop,
+            stopped_on_silence: self.stopped_on_silence.clone(),
+            preroll_filling: self.preroll.as_ref().map(|preroll| preroll.filling.clone()),
+        };
+        self.reset_pause_state();
+        self.input_level.store(0, Ordering::Relaxed);
+        self.stopped_on_silence.store(false, Ordering::SeqCst);
         let is_recording = self.is_recording.clone();
-        let input_device_name = self.input_device_name.clone();
+        let input_device_name = self.input_device_for_recording();
         let output_device_name = self.output_device_name.clone();
         let capture_source = self.capture_source;
 
@@ -198,7 +556,7 @@ impl AudioRecorder {
             if let Err(e) = run_recording_thread(
                 cmd_rx,
                 init_tx,
-                samples,
+                sink,
                 is_recording.clone(),
                 input_device_name,
                 output_device_name,
@@ -213,7 +571,11 @@ impl AudioRecorder {
         self.thread_handle = Some(handle);
 
         match init_rx.recv_timeout(Duration::from_secs(3)) {
-            Ok(Ok(())) => Ok(()),
+            Ok(Ok(())) => {
+                self.started_at = Some(Instant::now());
+                self.prepend_preroll(append);
+                Ok(())
+            }
             Ok(Err(error)) => {
                 self.cleanup_failed_start();
                 Err(error)
@@ -235,11 +597,92 @@ impl AudioRecorder {
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
+
+        self.discard_spool();
     }
 
-    pub fn stop_recording(&mut self) -> Result<Vec<f32>, String> {
-        self.is_recording.store(false, Ordering::SeqCst);
+    /// Finalizes and deletes the long-form spool file, if any.
+    fn discard_spool(&mut self) {
+        if let Some((path, spool)) = self.spool.take() {
+            let _ = spool.lock().unwrap().finalize();
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Finalizes the long-form spool file, reads it back and deletes it.
+    fn read_back_spool(path: &Path, spool: &Mutex<AudioSpool>) -> Result<Vec<f32>, String> {
+        let result = spool.lock().unwrap().finalize().and_then(|_| {
+            let reader = hound::WavReader::open(path)
+                .map_err(|e| format!("Failed to open recording file: {}", e))?;
+            reader
+                .into_samples::<i16>()
+                .map(|sample| {
+                    sample
+                        .map(|s| s as f32 / 32768.0)
+                        .map_err(|e| format!("Failed to read recording file: {}", e))
+                })
+                .collect()
+        });
+
+        let _ = std::fs::remove_file(path);
+        result
+    }
+
+    /// Stop appending samples without tearing down the audio stream
+    pub fn pause_recording(&mut self) -> Result<(), String> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            return Err("Not recording".to_string());
+        }
 
+        if !self.is_paused.swap(true, Ordering::SeqCst) {
+            self.paused_at = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    pub fn resume_recording(&mut self) -> Result<(), String> {
+        if !self.is_recording.load(Ordering::SeqCst) {
+            return Err("Not recording".to_string());
+        }
+
+        if self.is_paused.swap(false, Ordering::SeqCst) {
+            if let Some(paused_at) = self.paused_at.take() {
+                self.paused_total += paused_at.elapsed();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.load(Ordering::SeqCst)
+    }
+
+    /// Time spent recording so far, excluding time spent paused
+    pub fn elapsed(&self) -> Duration {
+        let Some(started_at) = self.started_at else {
+            return Duration::ZERO;
+        };
+
+        let current_pause = self
+            .paused_at
+            .map(|paused_at| paused_at.elapsed())
+            .unwrap_or_default();
+        started_at
+            .elapsed()
+            .saturating_sub(self.paused_total + current_pause)
+    }
+
+    fn reset_pause_state(&mut self) {
+        self.is_paused.store(false, Ordering::SeqCst);
+        self.started_at = None;
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
+    }
+
+    /// Stop the capture thread and release the microphone. Samples stay
+    /// buffered, so a take that ended itself can still be collected with
+    /// `stop_recording`.
+    pub fn close_stream(&mut self) {
         // Signal thread to stop
         if let Some(sender) = self.command_sender.take() {
             let _ = sender.send(RecorderCommand::Stop);
@@ -249,16 +692,63 @@ impl AudioRecorder {
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
+        self.resume_preroll();
+    }
+
+    pub fn stop_recording(&mut self) -> Result<Vec<f32>, String> {
+        self.is_recording.store(false, Ordering::SeqCst);
+        self.reset_pause_state();
+        self.close_stream();
 
         // No delay needed - samples are already collected via mutex
         // The stream is already stopped at this point
 
-        let samples = self.samples.lock().unwrap().clone();
+        let long_form = self.spool.is_some();
+        let samples = match self.spool.take() {
+            Some((path, spool)) => Self::read_back_spool(&path, &spool)?,
+            None => self.samples.lock().unwrap().clone(),
+        };
 
         if samples.is_empty() {
             return Err("No audio recorded".to_string());
         }
 
+        if long_form {
+            self.last_recording = Vec::new();
+        } else {
+            self.last_recording.clone_from(&samples);
+        }
+        Ok(samples)
+    }
+
+    /// Samples of the last stopped recording; empty before the first one and
+    /// after a long-form take
+    pub fn last_recording(&self) -> &[f32] {
+        &self.last_recording
+    }
+
+    /// RMS waveform of the last stopped recording, see [`waveform`]. `None`
+    /// when nothing has been recorded yet.
+    pub fn last_waveform(&self, bucket_count: usize) -> Option<Vec<f32>> {
+        if self.last_recording.is_empty() {
+            return None;
+        }
+        Some(waveform(&self.last_recording, bucket_count))
+    }
+
+    /// Stop the current take, if any, and return every sample recorded since
+    /// the last fresh start. The buffer is cleared so the next take starts over.
+    pub fn finish_takes(&mut self) -> Result<Vec<f32>, String> {
+        let samples = if self.thread_handle.is_some() {
+            self.stop_recording()?
+        } else {
+            self.samples.lock().unwrap().clone()
+        };
+        self.samples.lock().unwrap().clear();
+
+        if samples.is_empty() {
+            return Err("No audio recorded".to_string());
+        }
         Ok(samples)
     }
 
@@ -266,25 +756,125 @@ impl AudioRecorder {
         self.is_recording.load(Ordering::SeqCst)
     }
 
+    /// Normalized 0.0-1.0 input level, see [`meter_level`]. Zero while
+    /// stopped or paused.
+    pub fn input_level(&self) -> f32 {
+        if !self.is_recording() || self.is_paused() {
+            return 0.0;
+        }
+        f32::from_bits(self.input_level.load(Ordering::Relaxed))
+    }
+
     pub fn cancel_recording(&mut self) {
         self.is_recording.store(false, Ordering::SeqCst);
+        self.reset_pause_state();
+        self.close_stream();
 
-        if let Some(sender) = self.command_sender.take() {
-            let _ = sender.send(RecorderCommand::Stop);
+        self.discard_spool();
+        self.samples.lock().unwrap().clear();
+    }
+}
+
+/// Microphone stream kept open between recordings that holds only the most
+/// recent audio, see `AudioRecorder::set_preroll`
+struct PrerollStream {
+    ring: Arc<Mutex<Vec<f32>>>,
+    // Cleared while a recording has the microphone, so the ring stays as it
+    // was when the recording started
+    filling: Arc<AtomicBool>,
+    command_sender: mpsc::Sender<RecorderCommand>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl PrerollStream {
+    fn start(device_name: Option<String>, capacity: usize) -> Result<Self, String> {
+        let ring = Arc::new(Mutex::new(Vec::with_capacity(capacity * 2)));
+        let filling = Arc::new(AtomicBool::new(true));
+        let sink = CaptureSink {
+            samples: ring.clone(),
+            keep_latest: Some(capacity),
+            spool: None,
+            is_paused: Arc::new(AtomicBool::new(false)),
+            input_level: Arc::new(AtomicU32::new(0)),
+            auto_stop: None,
+            stopped_on_silence: Arc::new(AtomicBool::new(false)),
+            preroll_filling: None,
+        };
+
+        let (cmd_tx, cmd_rx) = mpsc::channel::<RecorderCommand>();
+        let (init_tx, init_rx) = mpsc::channel::<Result<(), String>>();
+        let stream_filling = filling.clone();
+        let handle = thread::spawn(move || {
+            if let Err(e) = run_preroll_thread(cmd_rx, &init_tx, sink, stream_filling, device_name)
+            {
+                eprintln!("Pre-roll thread error: {}", e);
+                let _ = init_tx.send(Err(e));
+            }
+        });
+
+        // Dropping the stream on failure stops the thread
+        let preroll = Self {
+            ring,
+            filling,
+            command_sender: cmd_tx,
+            thread_handle: Some(handle),
+        };
+        match init_rx.recv_timeout(Duration::from_secs(3)) {
+            Ok(Ok(())) => Ok(preroll),
+            Ok(Err(error)) => Err(error),
+            Err(_) => Err("Timed out while starting pre-roll audio input".to_string()),
         }
+    }
+
+    /// Stop filling and return the buffered audio, oldest first
+    fn take(&self) -> Vec<f32> {
+        self.filling.store(false, Ordering::SeqCst);
+        self.ring.lock().unwrap().drain(..).collect()
+    }
 
+    /// Fill again from empty, so the next pre-roll never spans a recording
+    fn resume(&self) {
+        self.ring.lock().unwrap().clear();
+        self.filling.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for PrerollStream {
+    fn drop(&mut self) {
+        let _ = self.command_sender.send(RecorderCommand::Stop);
         if let Some(handle) = self.thread_handle.take() {
             let _ = handle.join();
         }
-
-        self.samples.lock().unwrap().clear();
     }
 }
 
+fn run_preroll_thread(
+    cmd_rx: mpsc::Receiver<RecorderCommand>,
+    init_tx: &mpsc::Sender<Result<(), String>>,
+    sink: CaptureSink,
+    filling: Arc<AtomicBool>,
+    device_name: Option<String>,
+) -> Result<(), String> {
+    let host = cpal::default_host();
+    let device = select_input_device(&host, device_name.as_deref())?;
+    // The callback skips everything while `filling` is cleared, so the idle
+    // stream costs little during recordings
+    let stream = build_capture_stream(device, CaptureDeviceKind::Input, sink, filling)?;
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start pre-roll stream: {}", e))?;
+    let _ = init_tx.send(Ok(()));
+
+    // Blocks until stopped or the stream handle is dropped
+    let _ = cmd_rx.recv();
+    drop(stream);
+    Ok(())
+}
+
 fn run_recording_thread(
     cmd_rx: mpsc::Receiver<RecorderCommand>,
     init_tx: mpsc::Sender<Result<(), String>>,
-    samples: Arc<Mutex<Vec<f32>>>,
+    sink: CaptureSink,
     is_recording: Arc<AtomicBool>,
     input_device_name: Option<String>,
     output_device_name: Option<String>,
@@ -305,9 +895,9 @@ fn run_recording_thread(
     ) {
         let device = select_input_device(&host, input_device_name.as_deref())?;
         let target = if capture_source == AudioCaptureSource::Mic {
-            samples.clone()
+            sink.clone()
         } else {
-            mic_samples.clone()
+            sink.memory(mic_samples.clone())
         };
         streams.push(build_capture_stream(
             device,
@@ -323,9 +913,9 @@ fn run_recording_thread(
     ) {
         let device = select_output_device(&host, output_device_name.as_deref())?;
         let target = if capture_source == AudioCaptureSource::System {
-            samples.clone()
+            sink.clone()
         } else {
-            system_samples.clone()
+            sink.memory(system_samples.clone())
         };
         streams.push(build_capture_stream(
             device,
@@ -371,7 +961,7 @@ fn run_recording_thread(
         let mic = mic_samples.lock().unwrap().clone();
         let system = system_samples.lock().unwrap().clone();
         let mixed = mix_audio_sources(&mic, &system);
-        *samples.lock().unwrap() = mixed;
+        *sink.samples.lock().unwrap() = mixed;
     }
 
     Ok(())
@@ -385,7 +975,7 @@ enum CaptureDeviceKind {
 fn build_capture_stream(
     device: cpal::Device,
     kind: CaptureDeviceKind,
-    samples: Arc<Mutex<Vec<f32>>>,
+    sink: CaptureSink,
     is_recording: Arc<AtomicBool>,
 ) -> Result<cpal::Stream, String> {
     let device_name = device
@@ -414,24 +1004,27 @@ fn build_capture_stream(
         config.sample_format()
     );
 
-    build_stream_for_config(device, config, samples, is_recording)
+    build_stream_for_config(device, config, sink, is_recording)
 }
 
 fn build_stream_for_config(
     device: cpal::Device,
     config: SupportedStreamConfig,
-    samples: Arc<Mutex<Vec<f32>>>,
+    sink: CaptureSink,
     is_recording: Arc<AtomicBool>,
 ) -> Result<cpal::Stream, String> {
     let sample_rate = config.sample_rate().0;
     let channels = config.channels() as usize;
-    let target_sample_rate = TARGET_SAMPLE_RATE;
+    // The stream keeps its filter state across callbacks
+    let mut resampler = (sample_rate != TARGET_SAMPLE_RATE)
+        .then(|| Resampler::new(sample_rate, TARGET_SAMPLE_RATE));
+    let mut silence = sink.auto_stop.map(SilenceDetector::new);
     let err_fn = |err| eprintln!("[AUDIO ERROR] Audio stream error: {}", err);
 
     let stream = match config.sample_format() {
         SampleFormat::F32 => {
             let is_recording = is_recording.clone();
-            let samples = samples.clone();
+            let sink = sink.clone();
             device.build_input_stream(
                 &config.into(),
                 move |data: &[f32], _: &_| {
@@ -439,9 +1032,9 @@ fn build_stream_for_config(
                         process_audio_data(
                             data,
                             channels,
-                            sample_rate,
-                            target_sample_rate,
-                            &samples,
+                            &mut resampler,
+                            &mut silence,
+                            &sink,
                             &is_recording,
                         );
                     }
@@ -452,7 +1045,7 @@ fn build_stream_for_config(
         }
         SampleFormat::I16 => {
             let is_recording = is_recording.clone();
-            let samples = samples.clone();
+            let sink = sink.clone();
             device.build_input_stream(
                 &config.into(),
                 move |data: &[i16], _: &_| {
@@ -462,9 +1055,9 @@ fn build_stream_for_config(
                         process_audio_data(
                             &float_data,
                             channels,
-                            sample_rate,
-                            target_sample_rate,
-                            &samples,
+                            &mut resampler,
+                            &mut silence,
+                            &sink,
                             &is_recording,
                         );
                     }
@@ -475,7 +1068,7 @@ fn build_stream_for_config(
         }
         SampleFormat::U16 => {
             let is_recording = is_recording.clone();
-            let samples = samples.clone();
+            let sink = sink.clone();
             device.build_input_stream(
                 &config.into(),
                 move |data: &[u16], _: &_| {
@@ -485,9 +1078,9 @@ fn build_stream_for_config(
                         process_audio_data(
                             &float_data,
                             channels,
-                            sample_rate,
-                            target_sample_rate,
-                            &samples,
+                            &mut resampler,
+                            &mut silence,
+                            &sink,
                             &is_recording,
                         );
                     }
@@ -505,20 +1098,19 @@ fn build_stream_for_config(
 
 fn select_input_device(host: &cpal::Host, name: Option<&str>) -> Result<cpal::Device, String> {
     if let Some(name) = name {
-        let mut devices = host
+        let (mut devices, names): (Vec<cpal::Device>, Vec<String>) = host
             .input_devices()
-            .map_err(|e| format!("Failed to list input devices: {}", e))?;
-
-        if let Some(device) = devices.find(|device| {
-            device
-                .name()
-                .map(|device_name| device_name == name)
-                .unwrap_or(false)
-        }) {
-            return Ok(device);
-        }
+            .map_err(|e| format!("Failed to list input devices: {}", e))?
+            .filter_map(|device| {
+                let device_name = device.name().ok()?;
+                Some((device, device_name))
+            })
+            .unzip();
 
-        return Err(format!("Input device not found: {}", name));
+        return match resolve_input_device(&names, name) {
+            Some(index) => Ok(devices.swap_remove(index)),
+            None => Err(format!("Input device not found: {}", name)),
+        };
     }
 
     // If no explicit input device is provided, prefer a real microphone over
@@ -590,11 +1182,15 @@ fn mix_audio_sources(primary: &[f32], secondary: &[f32]) -> Vec<f32> {
 fn process_audio_data(
     data: &[f32],
     channels: usize,
-    source_rate: u32,
-    target_rate: u32,
-    samples: &Arc<Mutex<Vec<f32>>>,
+    resampler: &mut Option<Resampler>,
+    silence: &mut Option<SilenceDetector>,
+    sink: &CaptureSink,
     is_recording: &Arc<AtomicBool>,
 ) {
+    if sink.is_paused.load(Ordering::SeqCst) {
+        return;
+    }
+
     // Convert to mono if stereo
     let mono: Vec<f32> = if channels > 1 {
         data.chunks(channels)
@@ -604,52 +1200,245 @@ fn process_audio_data(
         data.to_vec()
     };
 
-    // Simple resampling (linear interpolation)
-    let resampled = if source_rate != target_rate {
-        resample(&mono, source_rate, target_rate)
-    } else {
-        mono
+    let resampled = match resampler {
+        Some(resampler) => resampler.process(&mono),
+        None => mono,
     };
+    if !resampled.is_empty() {
+        let level = meter_level(measure_level(&resampled).rms);
+        sink.input_level.store(level.to_bits(), Ordering::Relaxed);
+    }
+    // The block that completes the silence is still kept below
+    if let Some(detector) = silence {
+        if detector.push(&resampled) {
+            sink.stopped_on_silence.store(true, Ordering::SeqCst);
+            is_recording.store(false, Ordering::SeqCst);
+        }
+    }
+
+    if let Some(ref filling) = sink.preroll_filling {
+        filling.store(false, Ordering::SeqCst);
+    }
+
+    if let Some(ref spool) = sink.spool {
+        if !spool.lock().unwrap().write(&resampled) {
+            is_recording.store(false, Ordering::SeqCst);
+        }
+    }
+
+    if let Some(keep) = sink.keep_latest {
+        let mut ring = sink.samples.lock().unwrap();
+        ring.extend_from_slice(&resampled);
+        if ring.len() > keep {
+            let excess = ring.len() - keep;
+            ring.drain(..excess);
+        }
+        return;
+    }
 
-    let mut samples = samples.lock().unwrap();
+    let mut samples = sink.samples.lock().unwrap();
     let remaining = MAX_RECORDING_SAMPLES.saturating_sub(samples.len());
     if remaining == 0 {
         is_recording.store(false, Ordering::SeqCst);
         return;
     }
 
-    if resampled.len() >= remaining {
-        samples.extend_from_slice(&resampled[..remaining]);
+    if !append_capped(&mut samples, &resampled, MAX_RECORDING_SAMPLES) {
         is_recording.store(false, Ordering::SeqCst);
-    } else {
-        samples.extend(resampled);
     }
 }
 
-fn resample(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
-    let ratio = source_rate as f64 / target_rate as f64;
-    let output_len = (samples.len() as f64 / ratio) as usize;
-    let mut output = Vec::with_capacity(output_len);
+/// Appends `chunk` to `samples` without growing past `cap`, returning false
+/// once the cap is reached. Growth is reserved up front in doubling steps
+/// clamped to `cap`, so a capture reallocates a handful of times at most and
+/// never over-allocates past the max recording length.
+fn append_capped(samples: &mut Vec<f32>, chunk: &[f32], cap: usize) -> bool {
+    let take = chunk.len().min(cap.saturating_sub(samples.len()));
+    let needed = samples.len() + take;
+    if needed > samples.capacity() {
+        let target = needed.max(samples.capacity() * 2).min(cap);
+        samples.reserve_exact(target - samples.len());
+    }
 
-    for i in 0..output_len {
-        let src_idx = i as f64 * ratio;
-        let idx = src_idx as usize;
-        let frac = src_idx - idx as f64;
+    samples.extend_from_slice(&chunk[..take]);
+    samples.len() < cap
+}
 
-        let sample = if idx + 1 < samples.len() {
-            samples[idx] * (1.0 - frac as f32) + samples[idx + 1] * frac as f32
-        } else if idx < samples.len() {
-            samples[idx]
+/// Zero crossings of the sinc kernel on each side of the output sample
+const SINC_ZERO_CROSSINGS: usize = 16;
+/// Kaiser window shape; 8.0 gives roughly 80 dB of stopband attenuation
+const KAISER_BETA: f64 = 8.0;
+/// Cutoff as a fraction of the lower Nyquist frequency, leaving room for the
+/// filter's transition band
+const RESAMPLE_ROLLOFF: f64 = 0.95;
+/// Kernel table entries per input sample; taps in between are interpolated
+const KERNEL_TABLE_RESOLUTION: usize = 256;
+
+/// Streaming windowed-sinc resampler. Input can arrive in chunks of any
+/// size; filter state carries over so chunk boundaries leave no seams.
+pub struct Resampler {
+    /// Input samples per output sample
+    step: f64,
+    /// Kernel reach on each side, in input samples
+    half_width: usize,
+    /// Kernel half sampled every `1 / KERNEL_TABLE_RESOLUTION` input samples
+    kernel: Vec<f32>,
+    /// Input not yet fully consumed, led by `half_width` samples of history
+    buffer: Vec<f32>,
+    /// Where the next output sample falls in `buffer`
+    position: f64,
+    input_total: usize,
+    output_total: usize,
+}
+
+impl Resampler {
+    pub fn new(source_rate: u32, target_rate: u32) -> Self {
+        let step = f64::from(source_rate) / f64::from(target_rate);
+        let cutoff = RESAMPLE_ROLLOFF * (1.0 / step).min(1.0);
+        let half_width = (SINC_ZERO_CROSSINGS as f64 / cutoff).ceil() as usize;
+
+        let table_len = half_width * KERNEL_TABLE_RESOLUTION + 1;
+        let kaiser_norm = bessel_i0(KAISER_BETA);
+        let kernel = (0..table_len)
+            .map(|index| {
+                let x = index as f64 / KERNEL_TABLE_RESOLUTION as f64;
+                let ratio = x / half_width as f64;
+                let window =
+                    bessel_i0(KAISER_BETA * (1.0 - ratio * ratio).max(0.0).sqrt()) / kaiser_norm;
+                (cutoff * sinc(cutoff * x) * window) as f32
+            })
+            .collect();
+
+        Self {
+            step,
+            half_width,
+            kernel,
+            // Leading silence so the first output lines up with the first input
+            buffer: vec![0.0; half_width],
+            position: half_width as f64,
+            input_total: 0,
+            output_total: 0,
+        }
+    }
+
+    /// Resample the next chunk of input. Output lags the input by the
+    /// kernel's reach until `flush`.
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.input_total += input.len();
+        let output = self.run(input);
+        self.output_total += output.len();
+        output
+    }
+
+    /// Output still held back waiting for input that will never come
+    pub fn flush(&mut self) -> Vec<f32> {
+        // One output per `step` of real input; the zero padding only lets the
+        // kernel reach past the end
+        let expected = (self.input_total as f64 / self.step).ceil() as usize;
+        let mut output = self.run(&vec![0.0; self.half_width + 1]);
+        output.truncate(expected.saturating_sub(self.output_total));
+        self.output_total += output.len();
+        output
+    }
+
+    fn run(&mut self, input: &[f32]) -> Vec<f32> {
+        self.buffer.extend_from_slice(input);
+
+        let mut output = Vec::with_capacity((input.len() as f64 / self.step).ceil() as usize + 1);
+        while (self.position as usize) + self.half_width < self.buffer.len() {
+            output.push(self.interpolate(self.position));
+            self.position += self.step;
+        }
+
+        // Drop input that no later output sample can reach
+        let consumed = (self.position as usize).saturating_sub(self.half_width);
+        let consumed = consumed.min(self.buffer.len());
+        self.buffer.drain(..consumed);
+        self.position -= consumed as f64;
+
+        output
+    }
+
+    fn interpolate(&self, position: f64) -> f32 {
+        let center = position as usize;
+        let frac = position - center as f64;
+        let first = center + 1 - self.half_width;
+        let last = center + self.half_width;
+
+        let mut sum = 0.0f32;
+        let mut weight_sum = 0.0f32;
+        for index in first..=last {
+            let weight = self.kernel_at((index as f64 - center as f64 - frac).abs());
+            sum += self.buffer[index] * weight;
+            weight_sum += weight;
+        }
+
+        // Normalizing by the tap sum keeps DC gain at exactly one
+        let value = if weight_sum.abs() > f32::EPSILON {
+            sum / weight_sum
         } else {
             0.0
         };
+        value.clamp(-1.0, 1.0)
+    }
+
+    fn kernel_at(&self, distance: f64) -> f32 {
+        let scaled = distance * KERNEL_TABLE_RESOLUTION as f64;
+        let index = scaled as usize;
+        if index + 1 >= self.kernel.len() {
+            return 0.0;
+        }
+        let frac = (scaled - index as f64) as f32;
+        self.kernel[index] * (1.0 - frac) + self.kernel[index + 1] * frac
+    }
+}
 
-        output.push(sample);
+/// Resample a whole clip with a Kaiser-windowed sinc filter, which keeps
+/// content above the new Nyquist frequency from aliasing into speech.
+/// The result has `len * target / source` samples, rounded. Capture and file
+/// decoding stream through `Resampler` directly; the tests check it against
+/// this.
+#[cfg(test)]
+fn resample_high_quality(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
+    if source_rate == target_rate || samples.is_empty() {
+        return samples.to_vec();
     }
 
+    let mut resampler = Resampler::new(source_rate, target_rate);
+    let mut output = resampler.process(samples);
+    output.extend(resampler.flush());
+
+    let expected =
+        (samples.len() as f64 * f64::from(target_rate) / f64::from(source_rate)).round() as usize;
+    output.resize(expected, 0.0);
     output
 }
 
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        let x = std::f64::consts::PI * x;
+        x.sin() / x
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind, for the Kaiser
+/// window
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half = x / 2.0;
+    for k in 1..50 {
+        term *= (half / k as f64) * (half / k as f64);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
 fn is_probable_loopback_input(device_name: &str) -> bool {
     let name = device_name.to_ascii_lowercase();
     [
@@ -663,24 +1452,37 @@ fn is_probable_loopback_input(device_name: &str) -> bool {
     .any(|pattern| name.contains(pattern))
 }
 
-// Save audio to WAV file for debugging
+/// Sample encoding used when saving a recording as WAV
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WavFormat {
+    /// 16-bit PCM, half the size of float
+    #[default]
+    Int16,
+    /// 32-bit float, keeps the captured samples exactly for later
+    /// re-transcription
+    Float32,
+}
+
 // Save audio to WAV file
-pub fn save_wav(samples: &[f32], path: &str) -> Result<(), String> {
-    let spec = hound::WavSpec {
-        channels: 1,
-        sample_rate: 16000,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
+pub fn save_wav(samples: &[f32], path: &str, format: WavFormat) -> Result<(), String> {
+    let spec = match format {
+        WavFormat::Int16 => wav_spec(),
+        WavFormat::Float32 => hound::WavSpec {
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+            ..wav_spec()
+        },
     };
-
     let mut writer = hound::WavWriter::create(path, spec)
         .map_err(|e| format!("Failed to create WAV file: {}", e))?;
 
     for &sample in samples {
-        let amplitude = (sample * 32767.0) as i16;
-        writer
-            .write_sample(amplitude)
-            .map_err(|e| format!("Failed to write sample: {}", e))?;
+        let written = match format {
+            WavFormat::Int16 => writer.write_sample(sample_to_i16(sample)),
+            WavFormat::Float32 => writer.write_sample(sample),
+        };
+        written.map_err(|e| format!("Failed to write sample: {}", e))?;
     }
 
     writer
@@ -689,3 +1491,892 @@ pub fn save_wav(samples: &[f32], path: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+fn wav_spec() -> hound::WavSpec {
+    hound::WavSpec {
+        channels: 1,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+fn sample_to_i16(sample: f32) -> i16 {
+    (sample * 32767.0) as i16
+}
+
+// ==================== Levels & Calibration ====================
+
+/// Length of the analysis frames used for level measurements
+const LEVEL_FRAME_SAMPLES: usize = TARGET_SAMPLE_RATE as usize / 50;
+/// Leading audio treated as the room's noise floor during calibration
+const CALIBRATION_NOISE_SAMPLES: usize = TARGET_SAMPLE_RATE as usize / 2;
+/// Lowest level reported, used in place of -inf for digital silence
+const MIN_DBFS: f32 = -100.0;
+const CLIP_THRESHOLD: f32 = 0.99;
+/// Level shown as an empty meter; quieter input reads as 0.0
+const METER_FLOOR_DBFS: f32 = -60.0;
+
+/// Peak and RMS amplitude of a block of samples
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AudioLevel {
+    pub peak: f32,
+    pub rms: f32,
+}
+
+pub fn measure_level(samples: &[f32]) -> AudioLevel {
+    if samples.is_empty() {
+        return AudioLevel::default();
+    }
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    AudioLevel { peak, rms }
+}
+
+pub fn amplitude_to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 0.0 {
+        return MIN_DBFS;
+    }
+    (20.0 * amplitude.log10()).max(MIN_DBFS)
+}
+
+/// Map an RMS amplitude onto a 0.0-1.0 meter scale that is linear in dB
+/// between `METER_FLOOR_DBFS` and full scale
+pub fn meter_level(rms: f32) -> f32 {
+    let dbfs = amplitude_to_dbfs(rms);
+    ((dbfs - METER_FLOOR_DBFS) / -METER_FLOOR_DBFS).clamp(0.0, 1.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalibrationVerdict {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalibrationResult {
+    pub peak_dbfs: f32,
+    pub rms_dbfs: f32,
+    pub noise_floor_dbfs: f32,
+    pub speech_dbfs: f32,
+    pub snr_db: f32,
+    /// Fraction of samples at or near full scale
+    pub clipped_ratio: f32,
+    pub recommend_normalization: bool,
+    pub verdict: CalibrationVerdict,
+    pub recommendations: Vec<String>,
+}
+
+/// Analyze a short calibration recording. The first half second is taken as
+/// the noise floor, so the user should start speaking after a brief pause;
+/// the speech level is the loudest tenth of the remaining 20 ms frames.
+pub fn analyze_calibration(samples: &[f32]) -> CalibrationResult {
+    let overall = measure_level(samples);
+    let noise_end = CALIBRATION_NOISE_SAMPLES.min(samples.len());
+    let noise = measure_level(&samples[..noise_end]);
+
+    let mut frame_rms: Vec<f32> = samples[noise_end..]
+        .chunks(LEVEL_FRAME_SAMPLES)
+        .map(|frame| measure_level(frame).rms)
+        .collect();
+    frame_rms.sort_by(|a, b| b.total_cmp(a));
+    let loud_frames = &frame_rms[..frame_rms.len().div_ceil(10)];
+    let speech_rms = if loud_frames.is_empty() {
+        0.0
+    } else {
+        loud_frames.iter().sum::<f32>() / loud_frames.len() as f32
+    };
+
+    let clipped = samples.iter().filter(|s| s.abs() >= CLIP_THRESHOLD).count();
+    let clipped_ratio = if samples.is_empty() {
+        0.0
+    } else {
+        clipped as f32 / samples.len() as f32
+    };
+
+    let peak_dbfs = amplitude_to_dbfs(overall.peak);
+    let noise_floor_dbfs = amplitude_to_dbfs(noise.rms);
+    let speech_dbfs = amplitude_to_dbfs(speech_rms);
+    let snr_db = speech_dbfs - noise_floor_dbfs;
+
+    let mut verdict = CalibrationVerdict::Pass;
+    let mut recommendations = Vec::new();
+    let mut flag = |message: &str, severity: CalibrationVerdict| {
+        recommendations.push(message.to_string());
+        if severity == CalibrationVerdict::Fail || verdict == CalibrationVerdict::Pass {
+            verdict = severity;
+        }
+    };
+
+    if peak_dbfs < -50.0 {
+        flag(
+            "No signal detected. Check that the right microphone is selected and not muted.",
+            CalibrationVerdict::Fail,
+        );
+    } else if clipped_ratio > 0.001 {
+        flag(
+            "The input is clipping. Lower the microphone gain or move further away.",
+            CalibrationVerdict::Warn,
+        );
+    }
+
+    let recommend_normalization = peak_dbfs >= -50.0 && speech_dbfs < -30.0;
+    if recommend_normalization {
+        flag(
+            "Speech is quiet. Enable normalization, raise the gain or move closer to the microphone.",
+            CalibrationVerdict::Warn,
+        );
+    }
+
+    if peak_dbfs >= -50.0 && snr_db < 10.0 {
+        flag(
+            "Speech is barely louder than the background noise. Move closer to the microphone or to a quieter spot.",
+            CalibrationVerdict::Fail,
+        );
+    } else if peak_dbfs >= -50.0 && snr_db < 20.0 {
+        flag(
+            "Background noise is noticeable. Moving closer to the microphone will improve accuracy.",
+            CalibrationVerdict::Warn,
+        );
+    }
+
+    CalibrationResult {
+        peak_dbfs,
+        rms_dbfs: amplitude_to_dbfs(overall.rms),
+        noise_floor_dbfs,
+        speech_dbfs,
+        snr_db,
+        clipped_ratio,
+        recommend_normalization,
+        verdict,
+        recommendations,
+    }
+}
+
+/// Upper bound on the buckets `waveform` returns, far more than any display
+/// can draw
+pub const MAX_WAVEFORM_BUCKETS: usize = 4096;
+
+/// Downsample `samples` into at most `bucket_count` RMS values for drawing a
+/// waveform, scaled so the loudest bucket is 1.0. A single pass over the
+/// samples; recordings shorter than `bucket_count` get one bucket per sample.
+pub fn waveform(samples: &[f32], bucket_count: usize) -> Vec<f32> {
+    let bucket_count = bucket_count.min(MAX_WAVEFORM_BUCKETS).min(samples.len());
+    if bucket_count == 0 {
+        return Vec::new();
+    }
+
+    let mut buckets: Vec<f32> = (0..bucket_count)
+        .map(|i| {
+            let start = i * samples.len() / bucket_count;
+            let end = (i + 1) * samples.len() / bucket_count;
+            let bucket = &samples[start..end];
+            let sum_squares: f64 = bucket.iter().map(|&s| f64::from(s) * f64::from(s)).sum();
+            (sum_squares / bucket.len() as f64).sqrt() as f32
+        })
+        .collect();
+
+    let peak = buckets.iter().copied().fold(0.0f32, f32::max);
+    if peak > 0.0 {
+        for value in &mut buckets {
+            *value /= peak;
+        }
+    }
+    buckets
+}
+
+/// Margin above the room's noise floor where speech detection starts
+const VAD_MARGIN_DB: f32 = 6.0;
+/// Range the recommended speech threshold is kept within. Below the floor
+/// any hiss counts as speech; above the ceiling quiet speakers are missed.
+pub const MIN_VAD_THRESHOLD_DBFS: f32 = -70.0;
+pub const MAX_VAD_THRESHOLD_DBFS: f32 = -20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct NoiseFloor {
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    /// Level of the loudest tenth of 20 ms frames, so brief bumps count
+    /// without a single click dominating
+    pub noise_floor_dbfs: f32,
+    /// Suggested energy threshold for voice activity detection
+    pub recommended_vad_threshold_dbfs: f32,
+}
+
+/// Analyze a recording of the room with nobody speaking
+pub fn analyze_noise_floor(samples: &[f32]) -> NoiseFloor {
+    let overall = measure_level(samples);
+
+    let mut frame_rms: Vec<f32> = samples
+        .chunks(LEVEL_FRAME_SAMPLES)
+        .map(|frame| measure_level(frame).rms)
+        .collect();
+    frame_rms.sort_by(|a, b| b.total_cmp(a));
+    let noisy_frame = frame_rms.get(frame_rms.len() / 10).copied().unwrap_or(0.0);
+    let noise_floor_dbfs = amplitude_to_dbfs(noisy_frame);
+
+    NoiseFloor {
+        rms_dbfs: amplitude_to_dbfs(overall.rms),
+        peak_dbfs: amplitude_to_dbfs(overall.peak),
+        noise_floor_dbfs,
+        recommended_vad_threshold_dbfs: (noise_floor_dbfs + VAD_MARGIN_DB)
+            .clamp(MIN_VAD_THRESHOLD_DBFS, MAX_VAD_THRESHOLD_DBFS),
+    }
+}
+
+/// Silence is only counted once this much of a recording has passed, so the
+/// pause before the user starts speaking never ends it
+const SILENCE_GRACE_PERIOD: Duration = Duration::from_millis(1500);
+
+/// When a recording should end on its own
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SilenceAutoStop {
+    /// How long the input has to stay quiet
+    pub timeout: Duration,
+    /// Frames with an RMS level below this count as silence
+    pub threshold_dbfs: f32,
+}
+
+/// Follows the RMS of consecutive 20 ms frames of 16kHz audio and reports
+/// when it has stayed below the threshold for the whole timeout
+pub struct SilenceDetector {
+    threshold: f32,
+    timeout_samples: usize,
+    grace_samples: usize,
+    seen_samples: usize,
+    silent_samples: usize,
+    frame_energy: f32,
+    frame_len: usize,
+}
+
+impl SilenceDetector {
+    pub fn new(config: SilenceAutoStop) -> Self {
+        let samples_for =
+            |duration: Duration| (duration.as_secs_f64() * TARGET_SAMPLE_RATE as f64) as usize;
+        Self {
+            threshold: 10f32.powf(config.threshold_dbfs / 20.0),
+            timeout_samples: samples_for(config.timeout).max(1),
+            grace_samples: samples_for(SILENCE_GRACE_PERIOD),
+            seen_samples: 0,
+            silent_samples: 0,
+            frame_energy: 0.0,
+            frame_len: 0,
+        }
+    }
+
+    /// Feed the next block of samples. Returns true once the silence has
+    /// lasted the timeout.
+    pub fn push(&mut self, samples: &[f32]) -> bool {
+        for sample in samples {
+            self.frame_energy += sample * sample;
+            self.frame_len += 1;
+            if self.frame_len < LEVEL_FRAME_SAMPLES {
+                continue;
+            }
+
+            let rms = (self.frame_energy / LEVEL_FRAME_SAMPLES as f32).sqrt();
+            self.seen_samples += LEVEL_FRAME_SAMPLES;
+            if rms >= self.threshold {
+                self.silent_samples = 0;
+            } else if self.seen_samples > self.grace_samples {
+                self.silent_samples += LEVEL_FRAME_SAMPLES;
+            }
+            self.frame_energy = 0.0;
+            self.frame_len = 0;
+        }
+        self.silent_samples >= self.timeout_samples
+    }
+}
+
+/// Frame used to look for a quiet point when splitting long audio
+const SPLIT_FRAME_SAMPLES: usize = TARGET_SAMPLE_RATE as usize / 10;
+
+/// Split 16kHz audio into consecutive ranges of at most `max_samples`. Each
+/// cut is placed in the quietest frame of the last third of the chunk, so a
+/// word is only split when there is no pause to cut at.
+pub fn split_on_silence(samples: &[f32], max_samples: usize) -> Vec<std::ops::Range<usize>> {
+    let max_samples = max_samples.max(1);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while samples.len() - start > max_samples {
+        let end = start + max_samples;
+        let search_start = start + max_samples * 2 / 3;
+        let cut = if end - search_start < SPLIT_FRAME_SAMPLES {
+            end
+        } else {
+            (search_start..=end - SPLIT_FRAME_SAMPLES)
+                .step_by(SPLIT_FRAME_SAMPLES / 2)
+                .map(|frame| {
+                    let energy: f32 = samples[frame..frame + SPLIT_FRAME_SAMPLES]
+                        .iter()
+                        .map(|s| s * s)
+                        .sum();
+                    (frame, energy)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(end, |(frame, _)| frame + SPLIT_FRAME_SAMPLES / 2)
+        };
+        ranges.push(start..cut);
+        start = cut;
+    }
+
+    if start < samples.len() {
+        ranges.push(start..samples.len());
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finish_takes_returns_accumulated_samples_and_clears() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        recorder
+            .samples
+            .lock()
+            .unwrap()
+            .extend_from_slice(&[0.1, 0.2, 0.3]);
+
+        assert_eq!(recorder.finish_takes().unwrap(), vec![0.1, 0.2, 0.3]);
+        assert!(recorder.finish_takes().is_err());
+    }
+
+    #[test]
+    fn continue_recording_refuses_a_full_buffer() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        recorder
+            .samples
+            .lock()
+            .unwrap()
+            .resize(MAX_RECORDING_SAMPLES, 0.0);
+
+        assert_eq!(
+            recorder.continue_recording(),
+            Err("Recording has reached the maximum length".to_string())
+        );
+        assert!(!recorder.is_recording());
+    }
+
+    /// Put the recorder in the recording state without opening a device and
+    /// return a sink that feeds it like the capture stream would
+    fn start_without_device(recorder: &mut AudioRecorder) -> CaptureSink {
+        recorder.reset_pause_state();
+        recorder.started_at = Some(Instant::now());
+        recorder.is_recording.store(true, Ordering::SeqCst);
+        CaptureSink {
+            samples: recorder.samples.clone(),
+            keep_latest: None,
+            spool: None,
+            is_paused: recorder.is_paused.clone(),
+            input_level: recorder.input_level.clone(),
+            auto_stop: None,
+            stopped_on_silence: recorder.stopped_on_silence.clone(),
+            preroll_filling: None,
+        }
+    }
+
+    fn capture(recorder: &AudioRecorder, sink: &CaptureSink, block: &[f32]) {
+        process_audio_data(block, 1, &mut None, &mut None, sink, &recorder.is_recording);
+    }
+
+    #[test]
+    fn pause_then_stop_returns_only_unpaused_audio() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        let sink = start_without_device(&mut recorder);
+
+        capture(&recorder, &sink, &[0.1, 0.2]);
+        recorder.pause_recording().unwrap();
+        assert!(recorder.is_paused());
+        assert_eq!(recorder.input_level(), 0.0);
+        capture(&recorder, &sink, &[0.9, 0.9]);
+
+        recorder.resume_recording().unwrap();
+        assert!(!recorder.is_paused());
+        capture(&recorder, &sink, &[0.3]);
+
+        // Stopping while paused keeps what was captured before the pause
+        recorder.pause_recording().unwrap();
+        capture(&recorder, &sink, &[0.9]);
+        assert_eq!(recorder.stop_recording().unwrap(), vec![0.1, 0.2, 0.3]);
+        assert!(!recorder.is_recording());
+        assert!(!recorder.is_paused());
+        assert_eq!(recorder.elapsed(), Duration::ZERO);
+        assert_eq!(recorder.last_recording(), &[0.1, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn long_form_takes_are_not_kept_as_last_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut recorder = AudioRecorder::new().unwrap();
+        let sink = start_without_device(&mut recorder);
+        capture(&recorder, &sink, &[0.1, 0.2]);
+        recorder.stop_recording().unwrap();
+        assert_eq!(recorder.last_recording(), &[0.1, 0.2]);
+
+        let path = dir.path().join("long_form.wav");
+        let spool = Arc::new(Mutex::new(AudioSpool::create(&path).unwrap()));
+        recorder.spool = Some((path, spool.clone()));
+        let mut sink = start_without_device(&mut recorder);
+        sink.spool = Some(spool);
+        capture(&recorder, &sink, &[0.5, 0.5]);
+
+        assert_eq!(recorder.stop_recording().unwrap().len(), 2);
+        assert!(recorder.last_recording().is_empty());
+        assert!(recorder.last_waveform(8).is_none());
+    }
+
+    #[test]
+    fn pause_then_cancel_discards_the_take() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        let sink = start_without_device(&mut recorder);
+
+        capture(&recorder, &sink, &[0.1, 0.2]);
+        recorder.pause_recording().unwrap();
+        recorder.cancel_recording();
+
+        assert!(!recorder.is_recording());
+        assert!(!recorder.is_paused());
+        assert!(recorder.samples.lock().unwrap().is_empty());
+        assert!(recorder.last_recording().is_empty());
+        assert_eq!(
+            recorder.resume_recording(),
+            Err("Not recording".to_string())
+        );
+        assert_eq!(recorder.pause_recording(), Err("Not recording".to_string()));
+        assert!(recorder.stop_recording().is_err());
+    }
+
+    fn tone(amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| {
+                let t = i as f32 / TARGET_SAMPLE_RATE as f32;
+                amplitude * (2.0 * std::f32::consts::PI * 220.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn noise(amplitude: f32, samples: usize) -> Vec<f32> {
+        // Deterministic pseudo-random noise
+        let mut state = 0x1234_5678u32;
+        (0..samples)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                amplitude * ((state as f32 / u32::MAX as f32) * 2.0 - 1.0)
+            })
+            .collect()
+    }
+
+    fn recording(noise_amplitude: f32, speech_amplitude: f32) -> Vec<f32> {
+        let background = noise(noise_amplitude, TARGET_SAMPLE_RATE as usize * 2);
+        let speech = tone(speech_amplitude, TARGET_SAMPLE_RATE as usize * 3 / 2);
+        background
+            .iter()
+            .enumerate()
+            .map(|(i, n)| {
+                let offset = i.checked_sub(CALIBRATION_NOISE_SAMPLES);
+                n + offset.and_then(|o| speech.get(o)).copied().unwrap_or(0.0)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn measure_level_reports_peak_and_rms() {
+        let level = measure_level(&[0.5, -0.5, 0.5, -0.5]);
+        assert_eq!(level.peak, 0.5);
+        assert!((level.rms - 0.5).abs() < 1e-6);
+        assert_eq!(measure_level(&[]), AudioLevel::default());
+        assert_eq!(amplitude_to_dbfs(0.0), MIN_DBFS);
+        assert!((amplitude_to_dbfs(0.5) + 6.02).abs() < 0.01);
+    }
+
+    #[test]
+    fn meter_level_spans_floor_to_full_scale() {
+        assert_eq!(meter_level(0.0), 0.0);
+        assert_eq!(meter_level(0.0005), 0.0);
+        assert_eq!(meter_level(1.0), 1.0);
+        assert!((meter_level(0.1) - 2.0 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn clean_speech_passes() {
+        let result = analyze_calibration(&recording(0.001, 0.3));
+        assert_eq!(result.verdict, CalibrationVerdict::Pass);
+        assert!(result.snr_db > 40.0);
+        assert!(!result.recommend_normalization);
+    }
+
+    #[test]
+    fn quiet_speech_recommends_normalization() {
+        let result = analyze_calibration(&recording(0.0001, 0.02));
+        assert_eq!(result.verdict, CalibrationVerdict::Warn);
+        assert!(result.recommend_normalization);
+    }
+
+    #[test]
+    fn noisy_room_fails() {
+        let result = analyze_calibration(&recording(0.1, 0.1));
+        assert_eq!(result.verdict, CalibrationVerdict::Fail);
+    }
+
+    #[test]
+    fn silence_fails() {
+        let result = analyze_calibration(&vec![0.0; TARGET_SAMPLE_RATE as usize * 2]);
+        assert_eq!(result.verdict, CalibrationVerdict::Fail);
+        assert_eq!(result.peak_dbfs, MIN_DBFS);
+    }
+
+    #[test]
+    fn silence_detector_waits_out_the_grace_period() {
+        let second = TARGET_SAMPLE_RATE as usize;
+        let mut detector = SilenceDetector::new(SilenceAutoStop {
+            timeout: Duration::from_secs(1),
+            threshold_dbfs: -45.0,
+        });
+
+        // Silence inside the grace period is not counted
+        assert!(!detector.push(&vec![0.0; second * 3 / 2]));
+        assert!(!detector.push(&vec![0.0; second * 9 / 10]));
+        // Speech restarts the count
+        assert!(!detector.push(&tone(0.3, second / 2)));
+        assert!(!detector.push(&vec![0.0; second * 9 / 10]));
+        assert!(detector.push(&vec![0.0; second / 10]));
+    }
+
+    #[test]
+    fn split_on_silence_cuts_at_the_quiet_frame() {
+        let second = TARGET_SAMPLE_RATE as usize;
+        let mut samples = vec![0.5; 10 * second];
+        let pause = 8 * second..8 * second + SPLIT_FRAME_SAMPLES * 2;
+        samples[pause.clone()].fill(0.0);
+
+        let ranges = split_on_silence(&samples, 9 * second);
+        assert_eq!(ranges.len(), 2);
+        assert!(pause.contains(&ranges[0].end));
+        assert_eq!(ranges[1], ranges[0].end..samples.len());
+    }
+
+    #[test]
+    fn split_on_silence_covers_all_samples() {
+        let samples: Vec<f32> = (0..95_000).map(|i| (i as f32 * 0.01).sin()).collect();
+        let ranges = split_on_silence(&samples, 20_000);
+
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, samples.len());
+        assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+        assert!(ranges.iter().all(|r| !r.is_empty() && r.len() <= 20_000));
+        assert_eq!(split_on_silence(&samples[..100], 20_000), vec![0..100]);
+        assert!(split_on_silence(&[], 20_000).is_empty());
+    }
+
+    #[test]
+    fn noise_floor_threshold_sits_above_the_room() {
+        // Steady hiss at about -40 dBFS with one click
+        let mut samples: Vec<f32> = (0..TARGET_SAMPLE_RATE as usize)
+            .map(|i| if i % 2 == 0 { 0.01 } else { -0.01 })
+            .collect();
+        samples[100] = 0.9;
+
+        let floor = analyze_noise_floor(&samples);
+        assert!((floor.noise_floor_dbfs + 40.0).abs() < 0.5);
+        assert!(
+            (floor.recommended_vad_threshold_dbfs - floor.noise_floor_dbfs - VAD_MARGIN_DB).abs()
+                < 0.01
+        );
+        assert!(floor.peak_dbfs > -1.0);
+
+        let silent = analyze_noise_floor(&vec![0.0; 1600]);
+        assert_eq!(
+            silent.recommended_vad_threshold_dbfs,
+            MIN_VAD_THRESHOLD_DBFS
+        );
+        assert_eq!(analyze_noise_floor(&[]).noise_floor_dbfs, MIN_DBFS);
+    }
+
+    /// Feeds a full-length capture through `append_capped` in 10 ms callbacks
+    /// and counts how often the buffer had to move.
+    fn count_reallocations(mut samples: Vec<f32>) -> (usize, Vec<f32>) {
+        let callback = vec![0.1; TARGET_SAMPLE_RATE as usize / 100];
+        let mut reallocations = 0;
+        loop {
+            let capacity = samples.capacity();
+            let open = append_capped(&mut samples, &callback, MAX_RECORDING_SAMPLES);
+            if samples.capacity() != capacity {
+                reallocations += 1;
+            }
+            if !open {
+                return (reallocations, samples);
+            }
+        }
+    }
+
+    #[test]
+    fn append_capped_amortizes_growth_up_to_the_cap() {
+        let (unreserved, samples) = count_reallocations(Vec::new());
+        assert_eq!(samples.len(), MAX_RECORDING_SAMPLES);
+        assert_eq!(samples.capacity(), MAX_RECORDING_SAMPLES);
+        assert!(unreserved <= 16, "{} reallocations", unreserved);
+
+        let (default_reserve, samples) =
+            count_reallocations(Vec::with_capacity(INITIAL_BUFFER_CAPACITY));
+        assert!(default_reserve <= 4, "{} reallocations", default_reserve);
+        assert_eq!(samples.capacity(), MAX_RECORDING_SAMPLES);
+
+        let (full_reserve, _) = count_reallocations(Vec::with_capacity(MAX_RECORDING_SAMPLES));
+        assert_eq!(full_reserve, 0);
+    }
+
+    #[test]
+    fn keep_latest_sink_holds_only_the_newest_samples() {
+        let ring = Arc::new(Mutex::new(Vec::new()));
+        let sink = CaptureSink {
+            samples: ring.clone(),
+            keep_latest: Some(4),
+            spool: None,
+            is_paused: Arc::new(AtomicBool::new(false)),
+            input_level: Arc::new(AtomicU32::new(0)),
+            auto_stop: None,
+            stopped_on_silence: Arc::new(AtomicBool::new(false)),
+            preroll_filling: None,
+        };
+        let recording = Arc::new(AtomicBool::new(true));
+
+        for block in [[0.1, 0.2, 0.3], [0.4, 0.5, 0.6]] {
+            process_audio_data(&block, 1, &mut None, &mut None, &sink, &recording);
+        }
+        assert_eq!(*ring.lock().unwrap(), vec![0.3, 0.4, 0.5, 0.6]);
+        assert!(recording.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn first_recorded_audio_stops_the_preroll_ring() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        let preroll_filling = Arc::new(AtomicBool::new(true));
+        let sink = CaptureSink {
+            preroll_filling: Some(preroll_filling.clone()),
+            ..start_without_device(&mut recorder)
+        };
+
+        capture(&recorder, &sink, &[0.1, 0.2]);
+        assert!(!preroll_filling.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn append_capped_truncates_at_the_cap() {
+        let mut samples = vec![0.0; 90];
+        assert!(append_capped(&mut samples, &[1.0; 5], 100));
+        assert!(!append_capped(&mut samples, &[1.0; 10], 100));
+        assert_eq!(samples.len(), 100);
+        assert!(!append_capped(&mut samples, &[1.0; 10], 100));
+        assert_eq!(samples.len(), 100);
+    }
+
+    #[test]
+    fn waveform_normalizes_bucket_rms() {
+        let mut samples = vec![0.0f32; 100];
+        samples.extend_from_slice(&[0.5; 100]);
+        samples.extend([0.25, -0.25].iter().cycle().take(100));
+
+        let buckets = waveform(&samples, 3);
+        assert_eq!(buckets, vec![0.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn waveform_is_bounded_by_the_requested_buckets() {
+        let samples = vec![0.1f32; 10_000];
+
+        assert_eq!(waveform(&samples, 64).len(), 64);
+        assert_eq!(waveform(&samples[..10], 64).len(), 10);
+        assert_eq!(waveform(&samples, usize::MAX).len(), MAX_WAVEFORM_BUCKETS);
+        assert!(waveform(&samples, 0).is_empty());
+        assert!(waveform(&[], 64).is_empty());
+        assert_eq!(waveform(&[0.0; 8], 4), vec![0.0; 4]);
+    }
+
+    #[test]
+    fn float_wav_round_trips_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let samples: Vec<f32> = (0..1600).map(|i| (i as f32 * 0.01).sin() * 0.8).collect();
+
+        let float_path = dir.path().join("float.wav");
+        save_wav(&samples, float_path.to_str().unwrap(), WavFormat::Float32).unwrap();
+        let mut reader = hound::WavReader::open(&float_path).unwrap();
+        assert_eq!(reader.spec().sample_format, hound::SampleFormat::Float);
+        let read_back: Vec<f32> = reader.samples::<f32>().map(Result::unwrap).collect();
+        assert_eq!(read_back.len(), samples.len());
+        for (original, read) in samples.iter().zip(&read_back) {
+            assert!((original - read).abs() < 1e-6);
+        }
+
+        let int_path = dir.path().join("int.wav");
+        save_wav(&samples, int_path.to_str().unwrap(), WavFormat::default()).unwrap();
+        let reader = hound::WavReader::open(&int_path).unwrap();
+        assert_eq!(reader.spec().bits_per_sample, 16);
+        assert!(
+            std::fs::metadata(&int_path).unwrap().len()
+                < std::fs::metadata(&float_path).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn duplicate_device_names_get_indexed_ids() {
+        let names: Vec<String> = ["USB Audio", "Built-in Mic", "USB Audio"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(
+            input_device_ids(&names),
+            vec!["USB Audio#1", "Built-in Mic", "USB Audio#2"]
+        );
+        assert_eq!(resolve_input_device(&names, "USB Audio#2"), Some(2));
+        assert_eq!(resolve_input_device(&names, "Built-in Mic"), Some(1));
+        // A plain name saved before the duplicate appeared picks the first
+        assert_eq!(resolve_input_device(&names, "USB Audio"), Some(0));
+        assert_eq!(resolve_input_device(&names, "USB Audio#3"), None);
+    }
+
+    /// The linear interpolation the recorder used before the sinc filter
+    fn resample_linear(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
+        let ratio = source_rate as f64 / target_rate as f64;
+        let output_len = (samples.len() as f64 / ratio) as usize;
+        (0..output_len)
+            .map(|i| {
+                let src_idx = i as f64 * ratio;
+                let idx = src_idx as usize;
+                let frac = (src_idx - idx as f64) as f32;
+                match (samples.get(idx), samples.get(idx + 1)) {
+                    (Some(&a), Some(&b)) => a * (1.0 - frac) + b * frac,
+                    (Some(&a), None) => a,
+                    _ => 0.0,
+                }
+            })
+            .collect()
+    }
+
+    fn sine(frequency: f64, rate: u32, seconds: f64, amplitude: f64) -> Vec<f32> {
+        let len = (f64::from(rate) * seconds) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f64 / f64::from(rate);
+                (amplitude * (2.0 * std::f64::consts::PI * frequency * t).sin()) as f32
+            })
+            .collect()
+    }
+
+    /// Power left after removing the best-fitting `frequency` sinusoid,
+    /// relative to that sinusoid, in dB (THD+N). The edges are skipped.
+    fn thd_plus_noise_db(samples: &[f32], frequency: f64, rate: u32) -> f64 {
+        let body = &samples[samples.len() / 10..samples.len() * 9 / 10];
+        let offset = samples.len() / 10;
+        let omega = 2.0 * std::f64::consts::PI * frequency / f64::from(rate);
+        let (mut sin_sum, mut cos_sum) = (0.0, 0.0);
+        for (i, &x) in body.iter().enumerate() {
+            let phase = omega * (i + offset) as f64;
+            sin_sum += f64::from(x) * phase.sin();
+            cos_sum += f64::from(x) * phase.cos();
+        }
+        let (a, b) = (
+            2.0 * sin_sum / body.len() as f64,
+            2.0 * cos_sum / body.len() as f64,
+        );
+
+        let (mut signal, mut residual) = (0.0, 0.0);
+        for (i, &x) in body.iter().enumerate() {
+            let phase = omega * (i + offset) as f64;
+            let fit = a * phase.sin() + b * phase.cos();
+            signal += fit * fit;
+            residual += (f64::from(x) - fit).powi(2);
+        }
+        10.0 * (residual / signal).log10()
+    }
+
+    #[test]
+    fn sinc_resampler_keeps_a_1khz_sine_clean() {
+        let input = sine(1_000.0, 48_000, 1.0, 0.5);
+
+        let sinc = resample_high_quality(&input, 48_000, 16_000);
+        let linear = resample_linear(&input, 48_000, 16_000);
+
+        let sinc_thd = thd_plus_noise_db(&sinc, 1_000.0, 16_000);
+        let linear_thd = thd_plus_noise_db(&linear, 1_000.0, 16_000);
+        assert!(sinc_thd < -60.0, "sinc THD+N {:.1} dB", sinc_thd);
+        // 48k to 16k is an exact 3:1, so linear interpolation only drops
+        // samples here; the filter must not do worse than that
+        assert!(
+            sinc_thd < linear_thd.max(-60.0) + 1.0,
+            "sinc {:.1} dB vs linear {:.1} dB",
+            sinc_thd,
+            linear_thd
+        );
+    }
+
+    #[test]
+    fn sinc_resampler_beats_linear_at_fractional_ratios() {
+        let input = sine(1_000.0, 44_100, 1.0, 0.5);
+
+        let sinc_thd = thd_plus_noise_db(
+            &resample_high_quality(&input, 44_100, 16_000),
+            1_000.0,
+            16_000,
+        );
+        let linear_thd =
+            thd_plus_noise_db(&resample_linear(&input, 44_100, 16_000), 1_000.0, 16_000);
+
+        assert!(
+            sinc_thd < linear_thd - 20.0,
+            "sinc {:.1} dB vs linear {:.1} dB",
+            sinc_thd,
+            linear_thd
+        );
+    }
+
+    #[test]
+    fn sinc_resampler_rejects_content_above_nyquist() {
+        // A 10 kHz tone can't be represented at 16 kHz; linear decimation
+        // folds it down to 6 kHz at full strength
+        let input = sine(10_000.0, 48_000, 1.0, 0.5);
+        let rms = |samples: &[f32]| {
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+
+        let sinc = resample_high_quality(&input, 48_000, 16_000);
+        let linear = resample_linear(&input, 48_000, 16_000);
+
+        assert!(rms(&linear[1_000..15_000]) > 0.3);
+        assert!(rms(&sinc[1_000..15_000]) < 0.001);
+    }
+
+    #[test]
+    fn sinc_resampler_preserves_length_and_stream_continuity() {
+        let input = sine(440.0, 44_100, 0.5, 0.9);
+
+        for (source, target) in [(44_100, 16_000), (48_000, 16_000), (8_000, 16_000)] {
+            let output = resample_high_quality(&input, source, target);
+            let expected = input.len() as f64 * target as f64 / source as f64;
+            assert!((output.len() as f64 - expected).abs() <= 1.0);
+            assert!(output.iter().all(|s| s.abs() <= 1.0));
+        }
+
+        // Feeding the same audio in uneven chunks gives the same result
+        let whole = resample_high_quality(&input, 44_100, 16_000);
+        let mut resampler = Resampler::new(44_100, 16_000);
+        let mut chunked = Vec::new();
+        for chunk in input.chunks(441) {
+            chunked.extend(resampler.process(chunk));
+        }
+        chunked.extend(resampler.flush());
+        chunked.resize(whole.len(), 0.0);
+        assert!(whole
+            .iter()
+            .zip(&chunked)
+            .all(|(a, b)| (a - b).abs() < 1e-5));
+    }
+}
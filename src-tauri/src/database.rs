@@ -1,8 +1,13 @@
-use rusqlite::{params, Connection, Result};
+use crate::post_process::PostProcessingConfig;
+use crate::transcription::WhisperTuningOverrides;
+use rusqlite::{ffi, params, Connection, ErrorCode, OptionalExtension, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
+const DB_FILE_NAME: &str = "Wavee.db";
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 // Types for database operations
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppSettings {
@@ -19,8 +24,62 @@ pub struct AppSettings {
     pub post_processing_enabled: bool,
     pub voice_commands_enabled: bool,
     pub clipboard_mode: bool,
+    #[serde(default)]
+    pub long_form_recording: bool,
+    #[serde(default = "default_empty_transcription_behavior")]
+    pub empty_transcription_behavior: String,
+    #[serde(default = "default_min_recording_ms")]
+    pub min_recording_ms: i64,
+    #[serde(default = "default_cancel_key")]
+    pub cancel_key: String,
+    #[serde(default = "default_overlay_position")]
+    pub overlay_position: String,
+    #[serde(default = "default_injection_blocklist")]
+    pub injection_blocklist: String,
+    #[serde(default = "default_warm_model_cache_size")]
+    pub warm_model_cache_size: i64,
+    #[serde(default = "default_tray_menu_items")]
+    pub tray_menu_items: String,
+    #[serde(default = "default_silence_pad_ms")]
+    pub silence_pad_ms: i64,
+    #[serde(default = "default_injection_trailing")]
+    pub injection_trailing: String,
+    #[serde(default)]
+    pub models_dir: Option<String>,
+    #[serde(default = "default_min_hold_ms")]
+    pub min_hold_ms: i64,
+    #[serde(default = "default_vad_threshold_dbfs")]
+    pub vad_threshold_dbfs: i64,
+    #[serde(default = "default_preallocate_recording_seconds")]
+    pub preallocate_recording_seconds: i64,
+    #[serde(default = "default_download_mirrors")]
+    pub download_mirrors: String,
+    #[serde(default)]
+    pub confirm_destructive_commands: bool,
+    #[serde(default = "default_release_notes_url")]
+    pub release_notes_url: String,
+    #[serde(default)]
+    pub input_device_name: Option<String>,
+    #[serde(default = "default_silence_timeout_ms")]
+    pub silence_timeout_ms: i64,
+    #[serde(default = "default_preroll_ms")]
+    pub preroll_ms: i64,
+    #[serde(default = "default_decoding_strategy")]
+    pub decoding_strategy: String,
+    #[serde(default = "default_cpu_threads")]
+    pub cpu_threads: i64,
+    #[serde(default = "default_injection_method")]
+    pub injection_method: String,
+    #[serde(default)]
+    pub restore_clipboard: bool,
+    #[serde(default = "default_typing_delay_ms")]
+    pub typing_delay_ms: i64,
 }
 
+/// Optional tray menu items shown by default, in menu order
+pub const DEFAULT_TRAY_MENU_ITEMS: &str =
+    "transcribe,start_recording,stop_recording,copy_last,history,models,settings,help";
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -37,10 +96,115 @@ impl Default for AppSettings {
             post_processing_enabled: true,
             voice_commands_enabled: false,
             clipboard_mode: false,
+            long_form_recording: false,
+            empty_transcription_behavior: "empty".to_string(),
+            min_recording_ms: 250,
+            cancel_key: "Escape".to_string(),
+            overlay_position: "fullscreen".to_string(),
+            injection_blocklist: String::new(),
+            warm_model_cache_size: 1,
+            tray_menu_items: DEFAULT_TRAY_MENU_ITEMS.to_string(),
+            silence_pad_ms: 1000,
+            injection_trailing: "none".to_string(),
+            models_dir: None,
+            min_hold_ms: 120,
+            vad_threshold_dbfs: -45,
+            preallocate_recording_seconds: 60,
+            download_mirrors: String::new(),
+            confirm_destructive_commands: false,
+            release_notes_url: crate::release_notes::DEFAULT_RELEASE_NOTES_URL.to_string(),
+            input_device_name: None,
+            silence_timeout_ms: 0,
+            preroll_ms: 0,
+            decoding_strategy: "auto".to_string(),
+            cpu_threads: 0,
+            injection_method: "paste".to_string(),
+            restore_clipboard: false,
+            typing_delay_ms: 0,
         }
     }
 }
 
+fn default_typing_delay_ms() -> i64 {
+    AppSettings::default().typing_delay_ms
+}
+
+fn default_injection_method() -> String {
+    AppSettings::default().injection_method
+}
+
+fn default_cpu_threads() -> i64 {
+    AppSettings::default().cpu_threads
+}
+
+fn default_decoding_strategy() -> String {
+    AppSettings::default().decoding_strategy
+}
+
+fn default_preroll_ms() -> i64 {
+    AppSettings::default().preroll_ms
+}
+
+fn default_silence_timeout_ms() -> i64 {
+    AppSettings::default().silence_timeout_ms
+}
+
+fn default_release_notes_url() -> String {
+    AppSettings::default().release_notes_url
+}
+
+fn default_download_mirrors() -> String {
+    AppSettings::default().download_mirrors
+}
+
+fn default_preallocate_recording_seconds() -> i64 {
+    AppSettings::default().preallocate_recording_seconds
+}
+
+fn default_vad_threshold_dbfs() -> i64 {
+    AppSettings::default().vad_threshold_dbfs
+}
+
+fn default_min_hold_ms() -> i64 {
+    AppSettings::default().min_hold_ms
+}
+
+fn default_injection_trailing() -> String {
+    AppSettings::default().injection_trailing
+}
+
+fn default_silence_pad_ms() -> i64 {
+    AppSettings::default().silence_pad_ms
+}
+
+fn default_tray_menu_items() -> String {
+    AppSettings::default().tray_menu_items
+}
+
+fn default_warm_model_cache_size() -> i64 {
+    AppSettings::default().warm_model_cache_size
+}
+
+fn default_injection_blocklist() -> String {
+    AppSettings::default().injection_blocklist
+}
+
+fn default_overlay_position() -> String {
+    AppSettings::default().overlay_position
+}
+
+fn default_cancel_key() -> String {
+    AppSettings::default().cancel_key
+}
+
+fn default_min_recording_ms() -> i64 {
+    AppSettings::default().min_recording_ms
+}
+
+fn default_empty_transcription_behavior() -> String {
+    AppSettings::default().empty_transcription_behavior
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct WhisperModel {
     pub id: String,
@@ -53,6 +217,29 @@ pub struct WhisperModel {
     pub download_path: Option<String>,
 }
 
+/// A user-registered ggml model, downloaded from its own URL
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CustomModel {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub filename: String,
+    /// 0 when the size is unknown
+    pub size_bytes: i64,
+}
+
+/// Human-readable size for the model catalog
+fn format_model_size(size_bytes: i64) -> String {
+    const MB: i64 = 1024 * 1024;
+    if size_bytes <= 0 {
+        "Unknown".to_string()
+    } else if size_bytes >= 1024 * MB {
+        format!("{:.1} GB", size_bytes as f64 / (1024 * MB) as f64)
+    } else {
+        format!("{} MB", (size_bytes + MB / 2) / MB)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LicenseData {
     pub license_key: Option<String>,
@@ -103,21 +290,157 @@ pub struct TranscriptionHistory {
     pub model_id: String,
     pub language: String,
     pub duration_ms: i64,
+    /// `transcription_params_hash` of the settings that produced the text;
+    /// `None` for entries saved before it was recorded
+    pub params_hash: Option<String>,
+    /// Transcript before post-processing, kept so the entry can be
+    /// reprocessed; `None` for entries saved before it was recorded
+    pub raw_text: Option<String>,
     pub created_at: String,
 }
 
+/// History entries that fall on one local calendar day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranscriptionHistoryDay {
+    /// Local date as `YYYY-MM-DD`
+    pub date: String,
+    /// 0 for today, 1 for yesterday; `None` when the timestamp was unreadable
+    pub days_ago: Option<i64>,
+    pub entries: Vec<TranscriptionHistory>,
+}
+
+/// Parse a stored `created_at`, which SQLite writes as UTC
+fn parse_history_timestamp(created_at: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S")
+        .map(|naive| naive.and_utc())
+        .or_else(|_| {
+            chrono::DateTime::parse_from_rfc3339(created_at)
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+        })
+        .ok()
+}
+
+/// Group newest-first history into local days, keeping the order. `offset`
+/// is the local time zone's offset from UTC.
+pub fn group_history_by_day(
+    entries: Vec<TranscriptionHistory>,
+    offset: chrono::FixedOffset,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<TranscriptionHistoryDay> {
+    let today = now.with_timezone(&offset).date_naive();
+    let mut days: Vec<TranscriptionHistoryDay> = Vec::new();
+
+    for entry in entries {
+        let local_date = parse_history_timestamp(&entry.created_at)
+            .map(|created| created.with_timezone(&offset).date_naive());
+        let date = match local_date {
+            Some(date) => date.format("%Y-%m-%d").to_string(),
+            None => entry
+                .created_at
+                .split([' ', 'T'])
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        };
+
+        match days.last_mut() {
+            Some(day) if day.date == date => day.entries.push(entry),
+            _ => days.push(TranscriptionHistoryDay {
+                date,
+                days_ago: local_date.map(|date| (today - date).num_days()),
+                entries: vec![entry],
+            }),
+        }
+    }
+    days
+}
+
+/// Describes how the database was recovered at startup, so the UI can tell
+/// the user their settings were reset.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DatabaseRecovery {
+    pub reason: String,
+    pub backup_path: Option<String>,
+}
+
 pub struct Database {
     conn: Mutex<Connection>,
+    recovery: Option<DatabaseRecovery>,
 }
 
 impl Database {
+    /// Open the app database. A corrupt database file is moved aside to
+    /// `Wavee.db.corrupt-<timestamp>` and replaced with a fresh one. Any
+    /// other failure (locked, permissions, disk full) is reported as an error
+    /// and the file is left untouched, as is a corrupt file that cannot be
+    /// moved aside.
     pub fn new(app_data_dir: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&app_data_dir).ok();
-        let db_path = app_data_dir.join("Wavee.db");
+        let db_path = app_data_dir.join(DB_FILE_NAME);
+
+        match Self::open(&db_path) {
+            Ok(db) => Ok(db),
+            Err(e) if !is_corruption_error(&e) => Err(e),
+            Err(e) => {
+                log::error!("Database at {:?} is corrupt: {}", db_path, e);
+                let backup_path = match backup_corrupt_database(&db_path) {
+                    Ok(backup_path) => backup_path,
+                    Err(backup_error) => {
+                        log::error!("Failed to back up corrupt database: {}", backup_error);
+                        return Err(e);
+                    }
+                };
+                let mut db = Self::open(&db_path)?;
+                log::warn!("Recreated database; corrupt copy at {:?}", backup_path);
+
+                db.recovery = Some(DatabaseRecovery {
+                    reason: e.to_string(),
+                    backup_path: Some(backup_path.to_string_lossy().to_string()),
+                });
+                Ok(db)
+            }
+        }
+    }
+
+    /// Open a throwaway in-memory database, used when the on-disk database
+    /// cannot be opened at all so the app can still start.
+    pub fn in_memory(reason: String) -> Result<Self> {
+        let db = Self {
+            conn: Mutex::new(Connection::open_in_memory()?),
+            recovery: Some(DatabaseRecovery {
+                reason,
+                backup_path: None,
+            }),
+        };
+
+        db.init_tables()?;
+        db.init_default_data()?;
+
+        Ok(db)
+    }
+
+    fn open(db_path: &Path) -> Result<Self> {
         let conn = Connection::open(db_path)?;
 
+        // Wait for other connections instead of failing with "database is
+        // locked", and use WAL so readers don't block writers.
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let journal_mode: String =
+            conn.query_row("PRAGMA journal_mode=WAL", [], |row| row.get(0))?;
+        log::debug!("Database journal mode: {}", journal_mode);
+
+        let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))?;
+        if integrity != "ok" {
+            return Err(rusqlite::Error::SqliteFailure(
+                ffi::Error::new(ffi::SQLITE_CORRUPT),
+                Some(format!("Integrity check failed: {}", integrity)),
+            ));
+        }
+        log::info!("Database integrity check passed");
+
         let db = Self {
             conn: Mutex::new(conn),
+            recovery: None,
         };
 
         db.init_tables()?;
@@ -126,6 +449,11 @@ impl Database {
         Ok(db)
     }
 
+    /// Details of a startup recovery, if one happened
+    pub fn recovery(&self) -> Option<&DatabaseRecovery> {
+        self.recovery.as_ref()
+    }
+
     fn init_tables(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
 
@@ -146,6 +474,31 @@ impl Database {
                 post_processing_enabled INTEGER NOT NULL DEFAULT 1,
                 voice_commands_enabled INTEGER NOT NULL DEFAULT 0,
                 clipboard_mode INTEGER NOT NULL DEFAULT 0,
+                long_form_recording INTEGER NOT NULL DEFAULT 0,
+                empty_transcription_behavior TEXT NOT NULL DEFAULT 'empty',
+                min_recording_ms INTEGER NOT NULL DEFAULT 250,
+                cancel_key TEXT NOT NULL DEFAULT 'Escape',
+                overlay_position TEXT NOT NULL DEFAULT 'fullscreen',
+                injection_blocklist TEXT NOT NULL DEFAULT '',
+                warm_model_cache_size INTEGER NOT NULL DEFAULT 1,
+                tray_menu_items TEXT NOT NULL DEFAULT 'transcribe,start_recording,stop_recording,copy_last,history,models,settings,help',
+                silence_pad_ms INTEGER NOT NULL DEFAULT 1000,
+                injection_trailing TEXT NOT NULL DEFAULT 'none',
+                models_dir TEXT,
+                min_hold_ms INTEGER NOT NULL DEFAULT 120,
+                vad_threshold_dbfs INTEGER NOT NULL DEFAULT -45,
+                preallocate_recording_seconds INTEGER NOT NULL DEFAULT 60,
+                download_mirrors TEXT NOT NULL DEFAULT '',
+                confirm_destructive_commands INTEGER NOT NULL DEFAULT 0,
+                release_notes_url TEXT NOT NULL DEFAULT 'https://raw.githubusercontent.com/johuniq/wavee/main/CHANGELOG.md',
+                input_device_name TEXT,
+                silence_timeout_ms INTEGER NOT NULL DEFAULT 0,
+                preroll_ms INTEGER NOT NULL DEFAULT 0,
+                decoding_strategy TEXT NOT NULL DEFAULT 'auto',
+                cpu_threads INTEGER NOT NULL DEFAULT 0,
+                injection_method TEXT NOT NULL DEFAULT 'paste',
+                restore_clipboard INTEGER NOT NULL DEFAULT 0,
+                typing_delay_ms INTEGER NOT NULL DEFAULT 0,
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP
             )",
             [],
@@ -177,6 +530,169 @@ impl Database {
             [],
         );
 
+        // Add long_form_recording column if it doesn't exist (migration for existing DBs)
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN long_form_recording INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add empty_transcription_behavior column if it doesn't exist. "empty" returns
+        // an empty string for silent recordings, "error" fails with "No speech detected".
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN empty_transcription_behavior TEXT NOT NULL DEFAULT 'empty'",
+            [],
+        );
+
+        // Add min_recording_ms column if it doesn't exist. Shorter recordings are
+        // rejected before inference (accidental hotkey taps).
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN min_recording_ms INTEGER NOT NULL DEFAULT 250",
+            [],
+        );
+
+        // Shortcut that cancels an in-progress recording; empty disables it.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN cancel_key TEXT NOT NULL DEFAULT 'Escape'",
+            [],
+        );
+
+        // Add overlay_position column if it doesn't exist (migration for existing DBs)
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN overlay_position TEXT NOT NULL DEFAULT 'fullscreen'",
+            [],
+        );
+
+        // Add injection_blocklist column if it doesn't exist (migration for existing DBs)
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN injection_blocklist TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add warm_model_cache_size column if it doesn't exist (migration for existing DBs)
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN warm_model_cache_size INTEGER NOT NULL DEFAULT 1",
+            [],
+        );
+
+        // Add tray_menu_items column if it doesn't exist (migration for existing DBs)
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN tray_menu_items TEXT NOT NULL DEFAULT 'transcribe,start_recording,stop_recording,copy_last,history,models,settings,help'",
+            [],
+        );
+
+        // Add silence_pad_ms column if it doesn't exist. Short clips are padded
+        // with trailing silence up to this length; 0 disables padding.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN silence_pad_ms INTEGER NOT NULL DEFAULT 1000",
+            [],
+        );
+
+        // Add injection_trailing column if it doesn't exist. Controls what is
+        // typed after injected text: none, space, newline or smart.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN injection_trailing TEXT NOT NULL DEFAULT 'none'",
+            [],
+        );
+
+        // Add models_dir column if it doesn't exist. Overrides where models are
+        // stored; NULL keeps them in the app data directory.
+        let _ = conn.execute("ALTER TABLE settings ADD COLUMN models_dir TEXT", []);
+
+        // Add min_hold_ms column if it doesn't exist. Push-to-talk presses
+        // shorter than this are treated as accidental taps and ignored.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN min_hold_ms INTEGER NOT NULL DEFAULT 120",
+            [],
+        );
+
+        // Add vad_threshold_dbfs column if it doesn't exist. Frames quieter than
+        // this are treated as silence; measure_noise_floor adapts it to the room.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN vad_threshold_dbfs INTEGER NOT NULL DEFAULT -45",
+            [],
+        );
+
+        // Add preallocate_recording_seconds column if it doesn't exist. Recordings
+        // reserve this much buffer up front so capture callbacks rarely reallocate.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN preallocate_recording_seconds INTEGER NOT NULL DEFAULT 60",
+            [],
+        );
+
+        // Add download_mirrors column if it doesn't exist. Base URLs, one per
+        // line, tried in order when a Hugging Face download fails.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN download_mirrors TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add confirm_destructive_commands column if it doesn't exist. Destructive
+        // voice commands wait for confirm_action before the frontend runs them.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN confirm_destructive_commands INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add release_notes_url column if it doesn't exist. The update prompt
+        // reads release notes from the changelog at this URL.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN release_notes_url TEXT NOT NULL DEFAULT 'https://raw.githubusercontent.com/johuniq/wavee/main/CHANGELOG.md'",
+            [],
+        );
+
+        // Add input_device_name column if it doesn't exist. NULL records from
+        // the default microphone.
+        let _ = conn.execute("ALTER TABLE settings ADD COLUMN input_device_name TEXT", []);
+
+        // Add silence_timeout_ms column if it doesn't exist. Recording stops on
+        // its own after this much silence; 0 disables auto-stop.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN silence_timeout_ms INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add preroll_ms column if it doesn't exist. This much audio from just
+        // before a recording starts is kept; 0 leaves the microphone closed
+        // between recordings.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN preroll_ms INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add decoding_strategy column if it doesn't exist. "auto" follows each
+        // model's tuning; "greedy" and "beam-search" force one for every model.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN decoding_strategy TEXT NOT NULL DEFAULT 'auto'",
+            [],
+        );
+
+        // Add cpu_threads column if it doesn't exist. Caps the threads Whisper
+        // decodes with; 0 uses every available core.
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN cpu_threads INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // How dictation reaches the focused app: "paste" via the clipboard or
+        // "type" key by key
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN injection_method TEXT NOT NULL DEFAULT 'paste'",
+            [],
+        );
+
+        // Put the previous clipboard text back after a paste injection
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN restore_clipboard INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Pause between typed characters, for apps that drop fast synthetic
+        // input
+        let _ = conn.execute(
+            "ALTER TABLE settings ADD COLUMN typing_delay_ms INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
         // Migration: Update default hotkeys if they are still the old ones
         // This ensures existing users get the new non-conflicting defaults
         let _ = conn.execute(
@@ -226,11 +742,60 @@ impl Database {
                 model_id TEXT NOT NULL,
                 language TEXT NOT NULL,
                 duration_ms INTEGER NOT NULL,
+                params_hash TEXT,
+                raw_text TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Migration: add params_hash column if it doesn't exist. Rows saved
+        // before it was added keep NULL.
+        let _ = conn.execute(
+            "ALTER TABLE transcription_history ADD COLUMN params_hash TEXT",
+            [],
+        );
+
+        // Migration: add raw_text column if it doesn't exist. Older rows
+        // have no unprocessed transcript and can't be reprocessed.
+        let _ = conn.execute(
+            "ALTER TABLE transcription_history ADD COLUMN raw_text TEXT",
+            [],
+        );
+
+        // User-registered ggml models; each also has a row in `models`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS custom_models (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                url TEXT NOT NULL,
+                filename TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP
             )",
             [],
         )?;
 
+        // Post-processing config table (single JSON document)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS post_processing_config (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                config TEXT NOT NULL,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
+        // Per-model decoding overrides (JSON), keyed by model id
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS model_tuning (
+                model_id TEXT PRIMARY KEY,
+                config TEXT NOT NULL,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+            [],
+        )?;
+
         // License table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS license (
@@ -332,6 +897,14 @@ impl Database {
                 "High-accuracy multilingual transcription for demanding audio.",
                 "[\"multilingual\"]",
             ),
+            (
+                "large-v2",
+                "Whisper Large v2",
+                "2.9 GB",
+                2951_i64 * 1024 * 1024,
+                "Previous large Whisper model, still strong on noisy multilingual audio.",
+                "[\"multilingual\"]",
+            ),
             (
                 "large-v3",
                 "Whisper Large v3",
@@ -439,7 +1012,32 @@ impl Database {
         conn.query_row(
             "SELECT push_to_talk_key, toggle_key, hotkey_mode, language, selected_model_id,
                     show_recording_indicator, show_recording_overlay, play_audio_feedback, auto_start_on_boot, minimize_to_tray,
-                    post_processing_enabled, voice_commands_enabled, clipboard_mode
+                    post_processing_enabled, voice_commands_enabled, clipboard_mode,
+                    long_form_recording,
+                    empty_transcription_behavior,
+                    min_recording_ms,
+                    cancel_key,
+                    overlay_position,
+                    injection_blocklist,
+                    warm_model_cache_size,
+                    tray_menu_items,
+                    silence_pad_ms,
+                    injection_trailing,
+                    models_dir,
+                    min_hold_ms,
+                    vad_threshold_dbfs,
+                    preallocate_recording_seconds,
+                    download_mirrors,
+                    confirm_destructive_commands,
+                    release_notes_url,
+                    input_device_name,
+                    silence_timeout_ms,
+                    preroll_ms,
+                    decoding_strategy,
+                    cpu_threads,
+                    injection_method,
+                    restore_clipboard,
+                    typing_delay_ms
              FROM settings WHERE id = 1",
             [],
             |row| {
@@ -457,11 +1055,38 @@ impl Database {
                     post_processing_enabled: row.get::<_, i32>(10)? == 1,
                     voice_commands_enabled: row.get::<_, i32>(11)? == 1,
                     clipboard_mode: row.get::<_, i32>(12)? == 1,
+                    long_form_recording: row.get::<_, i32>(13)? == 1,
+                    empty_transcription_behavior: row.get(14)?,
+                    min_recording_ms: row.get(15)?,
+                    cancel_key: row.get(16)?,
+                    overlay_position: row.get(17)?,
+                    injection_blocklist: row.get(18)?,
+                    warm_model_cache_size: row.get(19)?,
+                    tray_menu_items: row.get(20)?,
+                    silence_pad_ms: row.get(21)?,
+                    injection_trailing: row.get(22)?,
+                    models_dir: row.get(23)?,
+                    min_hold_ms: row.get(24)?,
+                    vad_threshold_dbfs: row.get(25)?,
+                    preallocate_recording_seconds: row.get(26)?,
+                    download_mirrors: row.get(27)?,
+                    confirm_destructive_commands: row.get::<_, i32>(28)? == 1,
+                    release_notes_url: row.get(29)?,
+                    input_device_name: row.get(30)?,
+                    silence_timeout_ms: row.get(31)?,
+                    preroll_ms: row.get(32)?,
+                    decoding_strategy: row.get(33)?,
+                    cpu_threads: row.get(34)?,
+                    injection_method: row.get(35)?,
+                    restore_clipboard: row.get::<_, i32>(36)? == 1,
+                    typing_delay_ms: row.get(37)?,
                 })
             },
         )
     }
 
+    /// Write every setting except `models_dir`. The models move with that
+    /// one, so only `set_models_dir` changes it.
     pub fn update_settings(&self, settings: &AppSettings) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
@@ -479,6 +1104,30 @@ impl Database {
                 post_processing_enabled = ?11,
                 voice_commands_enabled = ?12,
                 clipboard_mode = ?13,
+                long_form_recording = ?14,
+                empty_transcription_behavior = ?15,
+                min_recording_ms = ?16,
+                cancel_key = ?17,
+                overlay_position = ?18,
+                injection_blocklist = ?19,
+                warm_model_cache_size = ?20,
+                tray_menu_items = ?21,
+                silence_pad_ms = ?22,
+                injection_trailing = ?23,
+                min_hold_ms = ?24,
+                vad_threshold_dbfs = ?25,
+                preallocate_recording_seconds = ?26,
+                download_mirrors = ?27,
+                confirm_destructive_commands = ?28,
+                release_notes_url = ?29,
+                input_device_name = ?30,
+                silence_timeout_ms = ?31,
+                preroll_ms = ?32,
+                decoding_strategy = ?33,
+                cpu_threads = ?34,
+                injection_method = ?35,
+                restore_clipboard = ?36,
+                typing_delay_ms = ?37,
                 updated_at = CURRENT_TIMESTAMP
              WHERE id = 1",
             params![
@@ -495,11 +1144,68 @@ impl Database {
                 settings.post_processing_enabled as i32,
                 settings.voice_commands_enabled as i32,
                 settings.clipboard_mode as i32,
+                settings.long_form_recording as i32,
+                settings.empty_transcription_behavior,
+                settings.min_recording_ms,
+                settings.cancel_key,
+                settings.overlay_position,
+                settings.injection_blocklist,
+                settings.warm_model_cache_size,
+                settings.tray_menu_items,
+                settings.silence_pad_ms,
+                settings.injection_trailing,
+                settings.min_hold_ms,
+                settings.vad_threshold_dbfs,
+                settings.preallocate_recording_seconds,
+                settings.download_mirrors,
+                settings.confirm_destructive_commands as i32,
+                settings.release_notes_url,
+                settings.input_device_name,
+                settings.silence_timeout_ms,
+                settings.preroll_ms,
+                settings.decoding_strategy,
+                settings.cpu_threads,
+                settings.injection_method,
+                settings.restore_clipboard as i32,
+                settings.typing_delay_ms,
             ],
         )?;
         Ok(())
     }
 
+    /// Restore settings to their defaults. When `keys` is given, only those
+    /// settings are reset and the rest are left as they are.
+    pub fn reset_settings(&self, keys: Option<&[String]>) -> Result<AppSettings> {
+        let defaults = AppSettings::default();
+        let settings = match keys {
+            None => defaults,
+            Some(keys) => {
+                let to_value = |settings: &AppSettings| {
+                    serde_json::to_value(settings)
+                        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+                };
+                let defaults = to_value(&defaults)?;
+                let mut current = to_value(&self.get_settings()?)?;
+
+                for key in keys {
+                    let default = defaults.get(key).ok_or_else(|| {
+                        rusqlite::Error::InvalidParameterName(format!(
+                            "Invalid setting key: {}",
+                            key
+                        ))
+                    })?;
+                    current[key] = default.clone();
+                }
+
+                serde_json::from_value(current)
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?
+            }
+        };
+
+        self.update_settings(&settings)?;
+        self.get_settings()
+    }
+
     pub fn update_setting(&self, key: &str, value: &str) -> Result<()> {
         // Security: Whitelist allowed column names to prevent SQL injection
         const ALLOWED_KEYS: &[&str] = &[
@@ -516,6 +1222,30 @@ impl Database {
             "post_processing_enabled",
             "voice_commands_enabled",
             "clipboard_mode",
+            "long_form_recording",
+            "empty_transcription_behavior",
+            "min_recording_ms",
+            "cancel_key",
+            "overlay_position",
+            "injection_blocklist",
+            "warm_model_cache_size",
+            "tray_menu_items",
+            "silence_pad_ms",
+            "injection_trailing",
+            "min_hold_ms",
+            "vad_threshold_dbfs",
+            "preallocate_recording_seconds",
+            "download_mirrors",
+            "confirm_destructive_commands",
+            "release_notes_url",
+            "input_device_name",
+            "silence_timeout_ms",
+            "preroll_ms",
+            "decoding_strategy",
+            "cpu_threads",
+            "injection_method",
+            "restore_clipboard",
+            "typing_delay_ms",
         ];
 
         if !ALLOWED_KEYS.contains(&key) {
@@ -534,6 +1264,103 @@ impl Database {
         Ok(())
     }
 
+    /// Models move with this setting, so it is changed on its own rather than
+    /// through `update_setting`
+    pub fn set_models_dir(&self, models_dir: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE settings SET models_dir = ?1, updated_at = CURRENT_TIMESTAMP WHERE id = 1",
+            params![models_dir],
+        )?;
+        Ok(())
+    }
+
+    // Post-processing config operations
+    pub fn get_post_processing_config(&self) -> Result<PostProcessingConfig> {
+        let conn = self.conn.lock().unwrap();
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT config FROM post_processing_config WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let Some(json) = stored else {
+            return Ok(PostProcessingConfig::default());
+        };
+
+        // A config written by a newer version may not parse; fall back to
+        // defaults rather than breaking post-processing entirely
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable post-processing config: {}", e);
+            PostProcessingConfig::default()
+        }))
+    }
+
+    pub fn save_post_processing_config(&self, config: &PostProcessingConfig) -> Result<()> {
+        let json = serde_json::to_string(config)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO post_processing_config (id, config) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET
+                config = excluded.config,
+                updated_at = CURRENT_TIMESTAMP",
+            params![json],
+        )?;
+        Ok(())
+    }
+
+    // Model tuning operations
+    pub fn get_model_tuning(&self, model_id: &str) -> Result<WhisperTuningOverrides> {
+        let conn = self.conn.lock().unwrap();
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT config FROM model_tuning WHERE model_id = ?1",
+                params![model_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let Some(json) = stored else {
+            return Ok(WhisperTuningOverrides::default());
+        };
+
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring unreadable tuning for model {}: {}", model_id, e);
+            WhisperTuningOverrides::default()
+        }))
+    }
+
+    /// Store a model's overrides; empty overrides restore the defaults
+    pub fn save_model_tuning(
+        &self,
+        model_id: &str,
+        overrides: &WhisperTuningOverrides,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        if overrides.is_empty() {
+            conn.execute(
+                "DELETE FROM model_tuning WHERE model_id = ?1",
+                params![model_id],
+            )?;
+            return Ok(());
+        }
+
+        let json = serde_json::to_string(overrides)
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        conn.execute(
+            "INSERT INTO model_tuning (model_id, config) VALUES (?1, ?2)
+             ON CONFLICT(model_id) DO UPDATE SET
+                config = excluded.config,
+                updated_at = CURRENT_TIMESTAMP",
+            params![model_id, json],
+        )?;
+        Ok(())
+    }
+
     // App state operations
     pub fn get_app_state(&self) -> Result<AppState> {
         let conn = self.conn.lock().unwrap();
@@ -641,6 +1468,89 @@ impl Database {
         Ok(model)
     }
 
+    /// Register a custom model and add it to the model catalog
+    pub fn add_custom_model(&self, model: &CustomModel) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO custom_models (id, name, url, filename, size_bytes)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                model.id,
+                model.name,
+                model.url,
+                model.filename,
+                model.size_bytes
+            ],
+        )?;
+        tx.execute(
+            "INSERT INTO models (id, name, size, size_bytes, description, languages)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                model.id,
+                model.name,
+                format_model_size(model.size_bytes),
+                model.size_bytes,
+                "Custom whisper.cpp model.",
+                "[\"multilingual\"]"
+            ],
+        )?;
+        tx.commit()
+    }
+
+    /// Remove a custom model from the catalog. Returns false when no custom
+    /// model has this id; built-in models are never removed.
+    pub fn remove_custom_model(&self, id: &str) -> Result<bool> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let removed = tx.execute("DELETE FROM custom_models WHERE id = ?1", params![id])?;
+        if removed > 0 {
+            tx.execute("DELETE FROM models WHERE id = ?1", params![id])?;
+            tx.execute("DELETE FROM model_tuning WHERE model_id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(removed > 0)
+    }
+
+    pub fn get_custom_models(&self) -> Result<Vec<CustomModel>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, url, filename, size_bytes FROM custom_models ORDER BY created_at ASC",
+        )?;
+
+        let models = stmt
+            .query_map([], |row| {
+                Ok(CustomModel {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    url: row.get(2)?,
+                    filename: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(models)
+    }
+
+    pub fn get_custom_model(&self, id: &str) -> Result<Option<CustomModel>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, url, filename, size_bytes FROM custom_models WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(CustomModel {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    url: row.get(2)?,
+                    filename: row.get(3)?,
+                    size_bytes: row.get(4)?,
+                })
+            },
+        )
+        .optional()
+    }
+
     pub fn set_model_downloaded(
         &self,
         id: &str,
@@ -685,16 +1595,68 @@ impl Database {
         model_id: &str,
         language: &str,
         duration_ms: i64,
+    ) -> Result<i64> {
+        self.add_transcription_entry(text, model_id, language, duration_ms, None, None)
+    }
+
+    /// Save a transcription with the settings hash and the transcript as it
+    /// was before post-processing
+    pub fn add_transcription_entry(
+        &self,
+        text: &str,
+        model_id: &str,
+        language: &str,
+        duration_ms: i64,
+        params_hash: Option<&str>,
+        raw_text: Option<&str>,
     ) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO transcription_history (text, model_id, language, duration_ms)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![text, model_id, language, duration_ms],
+            "INSERT INTO transcription_history
+                (text, model_id, language, duration_ms, params_hash, raw_text)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![text, model_id, language, duration_ms, params_hash, raw_text],
         )?;
         Ok(conn.last_insert_rowid())
     }
 
+    /// History entry by id, if it exists
+    pub fn get_transcription(&self, id: i64) -> Result<Option<TranscriptionHistory>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, text, model_id, language, duration_ms, params_hash, raw_text, created_at
+             FROM transcription_history
+             WHERE id = ?1",
+            params![id],
+            Self::history_from_row,
+        )
+        .optional()
+    }
+
+    /// Replace the displayed text of a history entry, leaving its raw text
+    /// alone. Returns false if no entry has that id.
+    pub fn update_transcription_text(&self, id: i64, text: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE transcription_history SET text = ?2 WHERE id = ?1",
+            params![id, text],
+        )?;
+        Ok(updated > 0)
+    }
+
+    fn history_from_row(row: &rusqlite::Row) -> Result<TranscriptionHistory> {
+        Ok(TranscriptionHistory {
+            id: row.get(0)?,
+            text: row.get(1)?,
+            model_id: row.get(2)?,
+            language: row.get(3)?,
+            duration_ms: row.get(4)?,
+            params_hash: row.get(5)?,
+            raw_text: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    }
+
     pub fn get_transcription_history(
         &self,
         limit: i32,
@@ -705,7 +1667,7 @@ impl Database {
         let search = search.unwrap_or("").trim();
         let pattern = format!("%{}%", Self::escape_like_pattern(search));
         let mut stmt = conn.prepare(
-            "SELECT id, text, model_id, language, duration_ms, created_at
+            "SELECT id, text, model_id, language, duration_ms, params_hash, raw_text, created_at
              FROM transcription_history
              WHERE (?3 = '' OR text LIKE ?4 ESCAPE '\\')
              ORDER BY created_at DESC
@@ -713,21 +1675,29 @@ impl Database {
         )?;
 
         let history = stmt
-            .query_map(params![limit, offset, search, pattern], |row| {
-                Ok(TranscriptionHistory {
-                    id: row.get(0)?,
-                    text: row.get(1)?,
-                    model_id: row.get(2)?,
-                    language: row.get(3)?,
-                    duration_ms: row.get(4)?,
-                    created_at: row.get(5)?,
-                })
-            })?
+            .query_map(
+                params![limit, offset, search, pattern],
+                Self::history_from_row,
+            )?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(history)
     }
 
+    /// Most recent transcription, if any
+    pub fn get_latest_transcription(&self) -> Result<Option<TranscriptionHistory>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, text, model_id, language, duration_ms, params_hash, raw_text, created_at
+             FROM transcription_history
+             ORDER BY created_at DESC, id DESC
+             LIMIT 1",
+            [],
+            Self::history_from_row,
+        )
+        .optional()
+    }
+
     pub fn get_transcription_history_count(&self, search: Option<&str>) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         let search = search.unwrap_or("").trim();
@@ -847,3 +1817,42 @@ impl Database {
         Ok(())
     }
 }
+
+/// Whether opening failed because the file is damaged or not a database at
+/// all, as opposed to locks, permissions or a full disk
+fn is_corruption_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(ErrorCode::DatabaseCorrupt) | Some(ErrorCode::NotADatabase)
+    )
+}
+
+/// Move a corrupt database and its WAL/SHM sidecars out of the way. Nothing
+/// is deleted; if the main file cannot be moved it stays where it is.
+fn backup_corrupt_database(db_path: &Path) -> std::io::Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let file_name = db_path
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("Database path has no file name"))?
+        .to_string_lossy()
+        .to_string();
+    let backup_name = format!("{}.corrupt-{}", file_name, timestamp);
+    let backup_path = db_path.with_file_name(&backup_name);
+
+    std::fs::rename(db_path, &backup_path)?;
+    // Stale sidecars would be replayed into the fresh database, so they go
+    // along with the backup
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = db_path.with_file_name(format!("{}{}", file_name, suffix));
+        if sidecar.exists() {
+            if let Err(e) = std::fs::rename(
+                &sidecar,
+                db_path.with_file_name(format!("{}{}", backup_name, suffix)),
+            ) {
+                log::warn!("Failed to move {:?} aside: {}", sidecar, e);
+            }
+        }
+    }
+
+    Ok(backup_path)
+}
@@ -1,18 +1,40 @@
 use futures_util::StreamExt;
 use reqwest::Client;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+    Arc, Mutex, RwLock,
 };
+use std::time::{Duration, Instant};
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Error returned by a download stopped with `cancel_download`, kept apart
+/// from failure messages so callers can tell the two apart
+pub const DOWNLOAD_CANCELLED: &str = "cancelled";
+
+/// SHA256 digests of built-in single-file models, keyed by model id, as
+/// published in the LFS metadata of the model repository. Models without an
+/// entry are saved unchecked; custom models never have one unless set with
+/// `set_expected_sha256`.
+const MODEL_SHA256: &[(&str, &str)] = &[];
+
+/// Where a user-registered model is downloaded from and stored
+struct CustomModelSource {
+    url: String,
+    filename: String,
+}
 
 pub struct ModelDownloader {
     client: Client,
-    models_dir: PathBuf,
+    models_dir: RwLock<PathBuf>,
     cancel_tokens: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    custom_models: Mutex<HashMap<String, CustomModelSource>>,
+    mirrors: RwLock<Vec<String>>,
+    failed_mirrors: Mutex<HashSet<String>>,
+    expected_sha256: RwLock<HashMap<String, String>>,
     pub test_url_override: Option<String>,
 }
 
@@ -22,14 +44,87 @@ pub struct DownloadProgress {
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
     pub percentage: f32,
+    /// Host the bytes are coming from, which changes when a mirror takes over
+    pub source: String,
+    /// Speed over roughly the last second
+    pub bytes_per_second: f64,
+    /// Time left at the current speed; `None` while the size or speed is unknown
+    pub eta_seconds: Option<u64>,
+}
+
+/// How far back `DownloadRate` looks when averaging
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Download speed averaged over the last `RATE_WINDOW` of progress, which is
+/// far steadier than the size of the latest chunk
+#[derive(Default)]
+pub struct DownloadRate {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl DownloadRate {
+    /// Record `bytes_downloaded` so far at `now` and return the speed in
+    /// bytes per second
+    pub fn record(&mut self, now: Instant, bytes_downloaded: u64) -> f64 {
+        self.samples.push_back((now, bytes_downloaded));
+        // Keep the newest sample at or before the window start so the
+        // average always covers the full window once it has elapsed
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= RATE_WINDOW {
+            self.samples.pop_front();
+        }
+
+        let (start, start_bytes) = self.samples[0];
+        let elapsed = now.duration_since(start).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        bytes_downloaded.saturating_sub(start_bytes) as f64 / elapsed
+    }
+}
+
+/// Seconds left to fetch the rest of `total_bytes`, 0 once complete
+pub fn download_eta(bytes_downloaded: u64, total_bytes: u64, bytes_per_second: f64) -> Option<u64> {
+    if total_bytes > 0 && bytes_downloaded >= total_bytes {
+        return Some(0);
+    }
+    estimate_download_seconds(
+        total_bytes.saturating_sub(bytes_downloaded),
+        bytes_per_second.round() as u64,
+    )
+}
+
+/// Bytes fetched by `test_download_speed`
+pub const SPEED_TEST_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Measured throughput for a model's download source
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DownloadSpeedTest {
+    pub model_id: String,
+    pub bytes_downloaded: u64,
+    pub elapsed_ms: u64,
+    pub bytes_per_second: u64,
+    /// Size of the full model; 0 when neither the caller nor the server knew it
+    pub total_bytes: u64,
+    /// Time the full download would take at the measured speed
+    pub estimated_seconds: Option<u64>,
+    pub source: String,
 }
 
 impl ModelDownloader {
     pub fn new(models_dir: PathBuf) -> Self {
         Self {
             client: Client::new(),
-            models_dir,
+            models_dir: RwLock::new(models_dir),
             cancel_tokens: Mutex::new(HashMap::new()),
+            custom_models: Mutex::new(HashMap::new()),
+            mirrors: RwLock::new(Vec::new()),
+            failed_mirrors: Mutex::new(HashSet::new()),
+            expected_sha256: RwLock::new(
+                MODEL_SHA256
+                    .iter()
+                    .map(|(id, digest)| (id.to_string(), digest.to_string()))
+                    .collect(),
+            ),
             test_url_override: None,
         }
     }
@@ -60,9 +155,136 @@ impl ModelDownloader {
         }
     }
 
+    pub fn models_dir(&self) -> PathBuf {
+        self.models_dir.read().unwrap().clone()
+    }
+
+    pub fn set_models_dir(&self, models_dir: PathBuf) {
+        *self.models_dir.write().unwrap() = models_dir;
+    }
+
+    pub fn has_active_downloads(&self) -> bool {
+        !self.cancel_tokens.lock().unwrap().is_empty()
+    }
+
+    /// Make a user-registered ggml model downloadable under `model_id`
+    pub fn register_custom_model(&self, model_id: &str, url: &str, filename: &str) {
+        self.custom_models.lock().unwrap().insert(
+            model_id.to_string(),
+            CustomModelSource {
+                url: url.to_string(),
+                filename: filename.to_string(),
+            },
+        );
+    }
+
+    pub fn unregister_custom_model(&self, model_id: &str) {
+        self.custom_models.lock().unwrap().remove(model_id);
+    }
+
+    /// Digest a download of `model_id` must match before it is saved, or
+    /// `None` to skip the check
+    pub fn set_expected_sha256(&self, model_id: &str, digest: Option<&str>) {
+        let mut expected = self.expected_sha256.write().unwrap();
+        match digest {
+            Some(digest) => expected.insert(model_id.to_string(), digest.to_ascii_lowercase()),
+            None => expected.remove(model_id),
+        };
+    }
+
+    pub fn expected_sha256(&self, model_id: &str) -> Option<String> {
+        self.expected_sha256.read().unwrap().get(model_id).cloned()
+    }
+
+    /// Base URLs tried in order when a built-in model's primary URL fails
+    pub fn set_mirrors(&self, mirrors: Vec<String>) {
+        *self.mirrors.write().unwrap() = mirrors;
+    }
+
+    /// Candidate URLs for `url`: the original first, then each configured
+    /// mirror that has not already failed this session
+    fn download_sources(&self, url: &str, use_mirrors: bool) -> Vec<(Option<String>, String)> {
+        let mut sources = vec![(None, url.to_string())];
+        if !use_mirrors {
+            return sources;
+        }
+
+        let failed = self.failed_mirrors.lock().unwrap();
+        for mirror in self.mirrors.read().unwrap().iter() {
+            if failed.contains(mirror) {
+                continue;
+            }
+            if let Some(mirrored) = mirror_url(url, mirror) {
+                sources.push((Some(mirror.clone()), mirrored));
+            }
+        }
+        sources
+    }
+
+    /// Start a GET for `url`, falling back through the mirrors when the
+    /// request fails or returns an error status. Mirrors that fail are
+    /// skipped for the rest of the session. Returns the response and the host
+    /// serving it, or an error listing every attempt. With `range_end` only
+    /// bytes up to that offset are requested; servers may still send more.
+    async fn open_download(
+        &self,
+        url: &str,
+        use_mirrors: bool,
+        range_end: Option<u64>,
+    ) -> Result<(reqwest::Response, String), String> {
+        let mut failures = Vec::new();
+
+        for (mirror, source_url) in self.download_sources(url, use_mirrors) {
+            let source = url_host(&source_url).to_string();
+
+            // Security: Enforce HTTPS only
+            if !source_url.starts_with("https://") && self.test_url_override.is_none() {
+                failures.push((
+                    source,
+                    "Security error: Only HTTPS URLs are allowed for downloads".to_string(),
+                ));
+                continue;
+            }
+
+            let mut request = self.client.get(&source_url);
+            if let Some(end) = range_end {
+                request = request.header(reqwest::header::RANGE, format!("bytes=0-{}", end));
+            }
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok((response, source)),
+                Ok(response) => format!("Download failed with status: {}", response.status()),
+                Err(e) => format!("Failed to start download: {}", e),
+            };
+
+            if let Some(mirror) = mirror {
+                self.failed_mirrors.lock().unwrap().insert(mirror);
+            }
+            failures.push((source, error));
+        }
+
+        if failures.len() == 1 {
+            return Err(failures.remove(0).1);
+        }
+        Err(format!(
+            "Download failed from every source: {}",
+            failures
+                .iter()
+                .map(|(source, error)| format!("{}: {}", source, error))
+                .collect::<Vec<_>>()
+                .join("; ")
+        ))
+    }
+
     pub fn get_model_path(&self, model_id: &str) -> PathBuf {
-        self.models_dir
-            .join(crate::transcription::get_model_filename(model_id))
+        self.model_path_in(&self.models_dir(), model_id)
+    }
+
+    /// Where `model_id` lives when `models_dir` is the models directory
+    fn model_path_in(&self, models_dir: &Path, model_id: &str) -> PathBuf {
+        if let Some(source) = self.custom_models.lock().unwrap().get(model_id) {
+            return models_dir.join(&source.filename);
+        }
+        models_dir.join(crate::transcription::get_model_filename(model_id))
     }
 
     pub fn is_model_downloaded(&self, model_id: &str) -> bool {
@@ -128,13 +350,19 @@ impl ModelDownloader {
                 .await;
         }
 
-        let original_url = crate::transcription::get_model_url(model_id)
+        let custom_url = self
+            .custom_models
+            .lock()
+            .unwrap()
+            .get(model_id)
+            .map(|source| source.url.clone());
+        // Mirrors only carry the built-in Hugging Face models
+        let use_mirrors = custom_url.is_none();
+        let original_url = custom_url
+            .or_else(|| crate::transcription::get_model_url(model_id))
             .ok_or_else(|| format!("Unknown model: {}", model_id))?;
 
-        let url = self
-            .test_url_override
-            .clone()
-            .unwrap_or_else(|| original_url.to_string());
+        let url = self.test_url_override.clone().unwrap_or(original_url);
 
         // Security: Enforce HTTPS only
         if !url.starts_with("https://") && self.test_url_override.is_none() {
@@ -142,30 +370,19 @@ impl ModelDownloader {
         }
 
         // Create models directory if it doesn't exist
-        tokio::fs::create_dir_all(&self.models_dir)
+        tokio::fs::create_dir_all(self.models_dir())
             .await
             .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
         let model_path = self.get_model_path(model_id);
         let temp_path = model_path.with_extension("bin.tmp");
 
-        // Start download
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to start download: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!(
-                "Download failed with status: {}",
-                response.status()
-            ));
-        }
+        // Start download, falling back to mirrors if the primary URL fails
+        let (response, source) = self.open_download(&url, use_mirrors, None).await?;
 
         let total_size = response.content_length().unwrap_or(0);
         let mut downloaded: u64 = 0;
+        let mut rate = DownloadRate::default();
 
         let mut file = File::create(&temp_path)
             .await
@@ -177,7 +394,7 @@ impl ModelDownloader {
             if Self::is_cancelled(&cancel_token) {
                 drop(file);
                 let _ = tokio::fs::remove_file(&temp_path).await;
-                return Err("Download cancelled".to_string());
+                return Err(DOWNLOAD_CANCELLED.to_string());
             }
 
             let chunk = chunk.map_err(|e| format!("Download error: {}", e))?;
@@ -194,17 +411,40 @@ impl ModelDownloader {
                 0.0
             };
 
+            let bytes_per_second = rate.record(Instant::now(), downloaded);
             progress_callback(DownloadProgress {
                 model_id: model_id.to_string(),
                 bytes_downloaded: downloaded,
                 total_bytes: total_size,
                 percentage,
+                source: source.clone(),
+                bytes_per_second,
+                eta_seconds: download_eta(downloaded, total_size, bytes_per_second),
             });
         }
 
         file.flush()
             .await
             .map_err(|e| format!("Failed to flush file: {}", e))?;
+        drop(file);
+
+        // A truncated or corrupted file would only fail later, when loaded
+        if let Some(expected) = self.expected_sha256(model_id) {
+            let actual = match sha256_file(&temp_path).await {
+                Ok(actual) => actual,
+                Err(e) => {
+                    let _ = tokio::fs::remove_file(&temp_path).await;
+                    return Err(e);
+                }
+            };
+            if actual != expected {
+                let _ = tokio::fs::remove_file(&temp_path).await;
+                return Err(format!(
+                    "Checksum mismatch for {}: expected {}, got {}",
+                    model_id, expected, actual
+                ));
+            }
+        }
 
         // Rename temp file to final path
         tokio::fs::rename(&temp_path, &model_path)
@@ -228,7 +468,7 @@ impl ModelDownloader {
             .or_else(|| crate::transcription::get_qwen3_asr_files(model_id))
             .ok_or_else(|| format!("Unknown {} model: {}", model_name, model_id))?;
 
-        tokio::fs::create_dir_all(&self.models_dir)
+        tokio::fs::create_dir_all(self.models_dir())
             .await
             .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
@@ -256,28 +496,24 @@ impl ModelDownloader {
         }
 
         let mut total_downloaded = 0u64;
+        let mut source = String::new();
+        let mut rate = DownloadRate::default();
+        let mut bytes_per_second = 0.0;
 
         for file in files {
             if Self::is_cancelled(&cancel_token) {
                 let _ = tokio::fs::remove_dir_all(&model_dir).await;
-                return Err("Download cancelled".to_string());
+                return Err(DOWNLOAD_CANCELLED.to_string());
             }
 
             let final_path = model_dir.join(file.filename);
-            let temp_path = final_path.with_extension("tmp");
+            let temp_path = directory_file_temp_path(&final_path);
 
-            let response =
-                self.client.get(file.url).send().await.map_err(|e| {
-                    format!("Failed to start download for {}: {}", file.filename, e)
-                })?;
-
-            if !response.status().is_success() {
-                return Err(format!(
-                    "Download failed for {} with status: {}",
-                    file.filename,
-                    response.status()
-                ));
-            }
+            let (response, file_source) = self
+                .open_download(file.url, true, None)
+                .await
+                .map_err(|e| format!("Failed to download {}: {}", file.filename, e))?;
+            source = file_source;
 
             let file_size = response.content_length().unwrap_or(0);
             if total_size == 0 {
@@ -294,7 +530,7 @@ impl ModelDownloader {
                     drop(output);
                     let _ = tokio::fs::remove_file(&temp_path).await;
                     let _ = tokio::fs::remove_dir_all(&model_dir).await;
-                    return Err("Download cancelled".to_string());
+                    return Err(DOWNLOAD_CANCELLED.to_string());
                 }
 
                 let chunk =
@@ -313,11 +549,15 @@ impl ModelDownloader {
                     0.0
                 };
 
+                bytes_per_second = rate.record(Instant::now(), total_downloaded);
                 progress_callback(DownloadProgress {
                     model_id: model_id.to_string(),
                     bytes_downloaded: total_downloaded,
                     total_bytes: total_size,
                     percentage,
+                    source: source.clone(),
+                    bytes_per_second,
+                    eta_seconds: download_eta(total_downloaded, total_size, bytes_per_second),
                 });
             }
 
@@ -342,11 +582,107 @@ impl ModelDownloader {
             bytes_downloaded: total_downloaded,
             total_bytes: total_size,
             percentage: 100.0,
+            source,
+            bytes_per_second,
+            eta_seconds: Some(0),
         });
 
         Ok(model_dir)
     }
 
+    /// Download the first `SPEED_TEST_BYTES` of a model and estimate how long
+    /// the whole download would take. `total_bytes` is the model's catalog
+    /// size, or 0 to use the size the server reports. Nothing is written to
+    /// disk and the connection is dropped once enough bytes have arrived.
+    pub async fn test_download_speed(
+        &self,
+        model_id: &str,
+        total_bytes: u64,
+    ) -> Result<DownloadSpeedTest, String> {
+        let directory_files = crate::transcription::get_parakeet_files(model_id)
+            .or_else(|| crate::transcription::get_qwen3_asr_files(model_id));
+        let (url, use_mirrors, total_bytes) = match directory_files {
+            Some(files) => {
+                // Time the weights; the small config files finish before the
+                // connection gets up to speed
+                let file = files
+                    .iter()
+                    .find(|file| {
+                        file.filename.ends_with(".onnx") || file.filename.ends_with(".safetensors")
+                    })
+                    .or_else(|| files.first())
+                    .ok_or_else(|| format!("Unknown model: {}", model_id))?;
+                let total_bytes = match total_bytes {
+                    0 => expected_directory_model_size(model_id).unwrap_or(0),
+                    size => size,
+                };
+                (file.url.to_string(), true, total_bytes)
+            }
+            None => {
+                let custom_url = self
+                    .custom_models
+                    .lock()
+                    .unwrap()
+                    .get(model_id)
+                    .map(|source| source.url.clone());
+                let use_mirrors = custom_url.is_none();
+                let url = custom_url
+                    .or_else(|| crate::transcription::get_model_url(model_id))
+                    .ok_or_else(|| format!("Unknown model: {}", model_id))?;
+                (url, use_mirrors, total_bytes)
+            }
+        };
+        let url = self.test_url_override.clone().unwrap_or(url);
+
+        let started = std::time::Instant::now();
+        let (response, source) = self
+            .open_download(&url, use_mirrors, Some(SPEED_TEST_BYTES - 1))
+            .await?;
+
+        let reported_total = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+            response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(content_range_total)
+        } else {
+            response.content_length()
+        };
+        let total_bytes = match total_bytes {
+            0 => reported_total.unwrap_or(0),
+            size => size,
+        };
+
+        // Servers that ignore the range send the whole file, so stop reading
+        // once the sample is in
+        let mut downloaded = 0u64;
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Download error: {}", e))?;
+            downloaded += chunk.len() as u64;
+            if downloaded >= SPEED_TEST_BYTES {
+                break;
+            }
+        }
+        drop(stream);
+        let elapsed = started.elapsed();
+
+        if downloaded == 0 {
+            return Err("Speed test received no data".to_string());
+        }
+        let bytes_per_second = (downloaded as f64 / elapsed.as_secs_f64().max(0.001)) as u64;
+
+        Ok(DownloadSpeedTest {
+            model_id: model_id.to_string(),
+            bytes_downloaded: downloaded,
+            elapsed_ms: elapsed.as_millis() as u64,
+            bytes_per_second,
+            total_bytes,
+            estimated_seconds: estimate_download_seconds(total_bytes, bytes_per_second),
+            source,
+        })
+    }
+
     async fn generate_qwen3_asr_tokenizer(
         &self,
         model_dir: &std::path::Path,
@@ -388,29 +724,240 @@ impl ModelDownloader {
     }
 
     pub fn get_downloaded_models(&self) -> Vec<String> {
-        let models = [
-            "tiny",
-            "base",
-            "small",
-            "medium",
-            "large",
-            "large-v3",
-            "large-v3-turbo",
-            "tiny.en",
-            "base.en",
-            "small.en",
-            "medium.en",
-            "distil-small.en",
-            "parakeet-v2",
-            "parakeet-v3",
-            "qwen3-asr-0.6b",
-        ];
-        models
+        let custom_ids: Vec<String> = self.custom_models.lock().unwrap().keys().cloned().collect();
+        crate::transcription::BUILTIN_MODEL_IDS
             .iter()
-            .filter(|&&id| self.is_model_downloaded(id))
-            .map(|&s| s.to_string())
+            .map(|id| id.to_string())
+            .chain(custom_ids)
+            .filter(|id| self.is_model_downloaded(id))
+            .collect()
+    }
+
+    /// Temp files that will be renamed or replaced by downloads in progress
+    fn active_download_paths(&self) -> Vec<PathBuf> {
+        self.cancel_tokens
+            .lock()
+            .unwrap()
+            .keys()
+            .flat_map(|model_id| {
+                let path = self.get_model_path(model_id);
+                [path.with_extension("bin.tmp"), path]
+            })
             .collect()
     }
+
+    /// Leftover `.tmp` files from downloads that never finished, including
+    /// those inside directory models. Only the models directory itself and
+    /// the folders of directory models are searched, since the models
+    /// directory may be a folder the user also keeps other files in.
+    /// Downloads still running are skipped.
+    pub fn find_partial_downloads(&self) -> Vec<PartialDownload> {
+        let active = self.active_download_paths();
+        let is_active = |path: &Path| active.iter().any(|active| path.starts_with(active));
+
+        let mut dirs = vec![(self.models_dir(), false)];
+        dirs.extend(
+            crate::transcription::BUILTIN_MODEL_IDS
+                .iter()
+                .filter(|id| {
+                    crate::transcription::get_parakeet_files(id)
+                        .or_else(|| crate::transcription::get_qwen3_asr_files(id))
+                        .is_some()
+                })
+                .map(|id| (self.get_model_path(id), true)),
+        );
+
+        let mut partials = Vec::new();
+        for (dir, in_model_folder) in dirs {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_file()
+                    && is_partial_download(&path, in_model_folder)
+                    && !is_active(&path)
+                {
+                    partials.push(PartialDownload {
+                        path,
+                        size_bytes: metadata.len(),
+                    });
+                }
+            }
+        }
+
+        partials.sort_by(|a, b| a.path.cmp(&b.path));
+        partials
+    }
+
+    /// Delete leftover partial downloads. Files that cannot be removed stay
+    /// listed but do not count towards `freed_bytes`.
+    pub fn cleanup_partial_downloads(&self) -> PartialDownloadCleanup {
+        let files = self.find_partial_downloads();
+        let freed_bytes = files
+            .iter()
+            .filter(|file| std::fs::remove_file(&file.path).is_ok())
+            .map(|file| file.size_bytes)
+            .sum();
+        PartialDownloadCleanup { files, freed_bytes }
+    }
+
+    /// Move every downloaded model into `new_dir`, which becomes the models
+    /// directory once all of them are there. Models already present in
+    /// `new_dir` are left where they are. If a move fails, the models moved
+    /// so far are moved back and the models directory is unchanged. Returns
+    /// the ids of the models that were moved.
+    pub fn move_models_to(&self, new_dir: &Path) -> std::io::Result<Vec<String>> {
+        std::fs::create_dir_all(new_dir)?;
+
+        let mut moved: Vec<(String, PathBuf, PathBuf)> = Vec::new();
+        for model_id in self.get_downloaded_models() {
+            let old_path = self.get_model_path(&model_id);
+            let new_path = self.model_path_in(new_dir, &model_id);
+            if new_path == old_path || new_path.exists() {
+                continue;
+            }
+            if let Err(e) = move_path(&old_path, &new_path) {
+                for (model_id, old_path, new_path) in moved.into_iter().rev() {
+                    if let Err(e) = move_path(&new_path, &old_path) {
+                        log::error!("Failed to move {} back to {:?}: {}", model_id, old_path, e);
+                    }
+                }
+                return Err(e);
+            }
+            moved.push((model_id, old_path, new_path));
+        }
+
+        self.set_models_dir(new_dir.to_path_buf());
+        Ok(moved.into_iter().map(|(model_id, _, _)| model_id).collect())
+    }
+}
+
+/// Check that `dir` exists (creating it if needed) and accepts new files
+pub fn check_dir_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".write-test-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Parse the `download_mirrors` setting: one base URL per line, blank lines
+/// ignored
+pub fn parse_mirror_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim().trim_end_matches('/'))
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Rewrite `url` to be served from `mirror_base`, keeping its path. For
+/// example `https://huggingface.co/org/repo/resolve/main/model.bin` with the
+/// mirror `https://hf-mirror.com` becomes
+/// `https://hf-mirror.com/org/repo/resolve/main/model.bin`.
+pub fn mirror_url(url: &str, mirror_base: &str) -> Option<String> {
+    let after_scheme = url.find("://")? + 3;
+    let path_start = url[after_scheme..].find('/')? + after_scheme;
+    let base = mirror_base.trim_end_matches('/');
+    if base.is_empty() || base == &url[..path_start] {
+        return None;
+    }
+    Some(format!("{}{}", base, &url[path_start..]))
+}
+
+/// Host part of `url`, used to label where a download is coming from
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
+
+/// Seconds to download `total_bytes` at `bytes_per_second`, rounded up.
+/// `None` when either is unknown.
+pub fn estimate_download_seconds(total_bytes: u64, bytes_per_second: u64) -> Option<u64> {
+    if total_bytes == 0 || bytes_per_second == 0 {
+        return None;
+    }
+    Some(total_bytes.div_ceil(bytes_per_second))
+}
+
+/// Full size from a `Content-Range: bytes 0-99/1234` header
+fn content_range_total(value: &str) -> Option<u64> {
+    value.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Rename `from` to `to`, copying when they are on different filesystems
+fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if from.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            move_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        std::fs::remove_dir(from)
+    } else {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PartialDownload {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct PartialDownloadCleanup {
+    pub files: Vec<PartialDownload>,
+    pub freed_bytes: u64,
+}
+
+/// Hex SHA256 of a file, read in chunks so large models are not held in memory
+pub async fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path)
+        .await
+        .map_err(|e| format!("Failed to open file for checksum: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to read file for checksum: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Temp file a directory model's file is written to until it is complete
+fn directory_file_temp_path(final_path: &Path) -> PathBuf {
+    let mut name = final_path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// Single-file downloads leave `.bin.tmp` files in the models directory,
+/// which may also hold the user's own files. A directory model's folder
+/// only holds files the app wrote, so any `.tmp` file there is partial.
+fn is_partial_download(path: &Path, in_model_folder: bool) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    if in_model_folder {
+        name.ends_with(".tmp")
+    } else {
+        name.ends_with(".bin.tmp")
+    }
 }
 
 fn expected_directory_model_size(model_id: &str) -> Option<u64> {
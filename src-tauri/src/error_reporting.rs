@@ -10,16 +10,46 @@
 use chrono::{DateTime, Utc};
 use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::backtrace::Backtrace;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
 use std::panic::{self, PanicHookInfo};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
 static ERROR_REPORTER: OnceLock<Arc<ErrorReporter>> = OnceLock::new();
 
+/// Whether the installed panic hook writes crash reports. While off, panics
+/// go straight to the hook that was installed before ours.
+static PANIC_HOOK_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// How `ErrorReporter::init_with_panic_hook` treats a panic hook that is
+/// already installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicHookMode {
+    /// Only write the crash report; the previous hook runs only while crash
+    /// reporting is switched off
+    Replace,
+    /// Write the crash report, then run the previous hook so the panic is
+    /// still printed (and seen by test harnesses) as usual
+    Chain,
+}
+
+/// Short hash of the commit this build came from, injected by build.rs
+pub const GIT_HASH: &str = match option_env!("GIT_HASH") {
+    Some(hash) => hash,
+    None => "unknown",
+};
+
+/// When this build was made (RFC 3339 UTC), injected by build.rs
+pub const BUILD_TIME: &str = match option_env!("BUILD_TIME") {
+    Some(time) => time,
+    None => "unknown",
+};
+
 /// Error severity levels
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -118,7 +148,8 @@ pub struct ErrorReport {
 }
 
 impl ErrorReport {
-    /// Create a new error report
+    /// Create a new error report. The build's git hash and build time are
+    /// added to the context so reports can be traced to an exact build.
     pub fn new(
         severity: ErrorSeverity,
         category: ErrorCategory,
@@ -132,7 +163,10 @@ impl ErrorReport {
             message: message.into(),
             details: None,
             backtrace: None,
-            context: HashMap::new(),
+            context: HashMap::from([
+                ("git_hash".to_string(), GIT_HASH.to_string()),
+                ("build_time".to_string(), BUILD_TIME.to_string()),
+            ]),
             occurrence_count: 1,
             app_version: env!("CARGO_PKG_VERSION").to_string(),
             os_info: get_os_info(),
@@ -168,14 +202,82 @@ impl ErrorReport {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrashReport {
     pub id: String,
+    /// When the crash first occurred
     pub timestamp: DateTime<Utc>,
     pub panic_message: String,
+    /// Backtrace of the first occurrence
     pub backtrace: String,
     pub app_version: String,
+    /// Commit and build time of the build that crashed
+    #[serde(default = "unknown_build_value")]
+    pub git_hash: String,
+    #[serde(default = "unknown_build_value")]
+    pub build_time: String,
     pub os_info: String,
     pub thread_name: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// Hash of the panic message and location, used to merge repeats
+    #[serde(default)]
+    pub fingerprint: String,
+    #[serde(default = "default_occurrence_count")]
+    pub occurrence_count: u32,
+    /// When the crash most recently occurred
+    #[serde(default)]
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+fn default_occurrence_count() -> u32 {
+    1
+}
+
+fn unknown_build_value() -> String {
+    "unknown".to_string()
+}
+
+impl CrashReport {
+    /// Create a crash report for a panic at `location`
+    pub fn new(
+        panic_message: impl Into<String>,
+        location: Option<String>,
+        backtrace: impl Into<String>,
+    ) -> Self {
+        let panic_message = panic_message.into();
+        let fingerprint = crash_fingerprint(&panic_message, location.as_deref());
+
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            timestamp: Utc::now(),
+            panic_message,
+            backtrace: backtrace.into(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: GIT_HASH.to_string(),
+            build_time: BUILD_TIME.to_string(),
+            os_info: get_os_info(),
+            thread_name: std::thread::current().name().map(String::from),
+            location,
+            fingerprint,
+            occurrence_count: 1,
+            last_seen: None,
+        }
+    }
+}
+
+/// Stable hash of a panic message and location
+fn crash_fingerprint(panic_message: &str, location: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(panic_message.as_bytes());
+    hasher.update(b"\0");
+    hasher.update(location.unwrap_or("").as_bytes());
+    hex::encode(&hasher.finalize()[..8])
 }
 
+/// A repeat of the same crash within this window updates the existing report
+const CRASH_DEDUP_WINDOW_HOURS: i64 = 24;
+
+/// Most crash reports kept on disk; the oldest are removed first
+const MAX_CRASH_REPORTS: usize = 20;
+
 /// Error reporter with aggregation and persistence
 pub struct ErrorReporter {
     /// Directory for storing error logs
@@ -208,20 +310,50 @@ impl ErrorReporter {
 
     /// Initialize the global error reporter
     pub fn init(log_dir: PathBuf) {
+        Self::init_with_panic_hook(log_dir, PanicHookMode::Replace);
+    }
+
+    /// Initialize the global error reporter, installing its crash-reporting
+    /// panic hook in front of the existing one. Only the first call has any
+    /// effect.
+    pub fn init_with_panic_hook(log_dir: PathBuf, mode: PanicHookMode) {
         let _ = ERROR_REPORTER.get_or_init(|| {
             let reporter = Arc::new(ErrorReporter::new(log_dir));
 
-            // Set up panic hook for crash reporting
+            // Set up panic hook for crash reporting, keeping the previous
+            // one to defer to
             let reporter_clone = reporter.clone();
+            let previous_hook = panic::take_hook();
             panic::set_hook(Box::new(move |info| {
-                reporter_clone.handle_panic(info);
+                let active = PANIC_HOOK_ACTIVE.load(Ordering::SeqCst);
+                if active {
+                    reporter_clone.handle_panic(info);
+                }
+                if !active || mode == PanicHookMode::Chain {
+                    previous_hook(info);
+                }
             }));
+            PANIC_HOOK_ACTIVE.store(true, Ordering::SeqCst);
 
             info!("Error reporter initialized");
             reporter
         });
     }
 
+    /// Whether panics are currently written as crash reports
+    pub fn panic_hook_active() -> bool {
+        PANIC_HOOK_ACTIVE.load(Ordering::SeqCst)
+    }
+
+    /// Switch crash reporting for panics on or off. Has no effect before
+    /// `init`, since there is no hook to switch. Returns the new state.
+    pub fn set_panic_hook_active(active: bool) -> bool {
+        if ERROR_REPORTER.get().is_some() {
+            PANIC_HOOK_ACTIVE.store(active, Ordering::SeqCst);
+        }
+        Self::panic_hook_active()
+    }
+
     /// Get the global error reporter
     pub fn global() -> Option<Arc<ErrorReporter>> {
         ERROR_REPORTER.get().cloned()
@@ -293,15 +425,11 @@ impl ErrorReporter {
             .location()
             .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
 
-        let crash_report = CrashReport {
-            id: uuid::Uuid::new_v4().to_string(),
-            timestamp: Utc::now(),
-            panic_message: panic_message.clone(),
-            backtrace: format!("{:?}", Backtrace::force_capture()),
-            app_version: env!("CARGO_PKG_VERSION").to_string(),
-            os_info: get_os_info(),
-            thread_name: std::thread::current().name().map(String::from),
-        };
+        let crash_report = CrashReport::new(
+            panic_message.clone(),
+            location.clone(),
+            format!("{:?}", Backtrace::force_capture()),
+        );
 
         // Log the crash
         error!("=== CRASH DETECTED ===");
@@ -312,7 +440,68 @@ impl ErrorReporter {
         error!("Thread: {:?}", crash_report.thread_name);
 
         // Write crash report to file
-        self.write_crash_report(&crash_report);
+        self.record_crash(&crash_report);
+    }
+
+    /// Persist a crash, merging it into a recent report with the same
+    /// fingerprint so a crash loop does not fill the log directory
+    fn record_crash(&self, crash: &CrashReport) {
+        let cutoff = crash.timestamp - chrono::Duration::hours(CRASH_DEDUP_WINDOW_HOURS);
+        let existing = self.crash_report_files().into_iter().find_map(|path| {
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                return None;
+            }
+            let report: CrashReport =
+                serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            let last_seen = report.last_seen.unwrap_or(report.timestamp);
+            (report.fingerprint == crash.fingerprint && last_seen >= cutoff)
+                .then_some((path, report))
+        });
+
+        match existing {
+            Some((path, mut report)) => {
+                // Keep the first occurrence's backtrace and timestamp
+                report.occurrence_count += 1;
+                report.last_seen = Some(crash.timestamp);
+                self.write_crash_files(&path, &report);
+            }
+            None => {
+                let filename = format!(
+                    "crash-{}-{}.json",
+                    crash.timestamp.format("%Y%m%d-%H%M%S"),
+                    crash.fingerprint
+                );
+                self.write_crash_files(&self.log_dir.join(filename), crash);
+                self.prune_crash_reports();
+            }
+        }
+    }
+
+    /// Remove the oldest crash reports beyond `MAX_CRASH_REPORTS`
+    fn prune_crash_reports(&self) {
+        let mut reports: Vec<(PathBuf, DateTime<Utc>)> = self
+            .crash_report_files()
+            .into_iter()
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+            .map(|path| {
+                let seen = fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<CrashReport>(&json).ok())
+                    .map(|report| report.last_seen.unwrap_or(report.timestamp))
+                    .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                (path, seen)
+            })
+            .collect();
+
+        if reports.len() <= MAX_CRASH_REPORTS {
+            return;
+        }
+
+        reports.sort_by_key(|(_, seen)| std::cmp::Reverse(*seen));
+        for (path, _) in reports.drain(MAX_CRASH_REPORTS..) {
+            let _ = fs::remove_file(path.with_extension("txt"));
+            let _ = fs::remove_file(path);
+        }
     }
 
     /// Write error to log file
@@ -333,35 +522,96 @@ impl ErrorReporter {
         }
     }
 
-    /// Write crash report to file
-    fn write_crash_report(&self, crash: &CrashReport) {
-        let filename = format!("crash-{}.json", crash.timestamp.format("%Y%m%d-%H%M%S"));
-        let filepath = self.log_dir.join(filename);
-
+    /// Write a crash report as JSON at `json_path`, with a human-readable
+    /// copy next to it
+    fn write_crash_files(&self, json_path: &std::path::Path, crash: &CrashReport) {
         if let Ok(json) = serde_json::to_string_pretty(crash) {
-            let _ = fs::write(&filepath, json);
+            let _ = fs::write(json_path, json);
         }
 
-        // Also write a human-readable version
-        let txt_filename = format!("crash-{}.txt", crash.timestamp.format("%Y%m%d-%H%M%S"));
-        let txt_filepath = self.log_dir.join(txt_filename);
-
         let report = format!(
             "=== Wavee Crash Report ===\n\
             Time: {}\n\
+            Last seen: {}\n\
+            Occurrences: {}\n\
             Version: {}\n\
+            Build: {} ({})\n\
             OS: {}\n\
-            Thread: {:?}\n\n\
+            Thread: {:?}\n\
+            Location: {}\n\n\
             Error: {}\n\n\
             Backtrace:\n{}\n",
             crash.timestamp,
+            crash.last_seen.unwrap_or(crash.timestamp),
+            crash.occurrence_count,
             crash.app_version,
+            crash.git_hash,
+            crash.build_time,
             crash.os_info,
             crash.thread_name,
+            crash.location.as_deref().unwrap_or("unknown"),
             crash.panic_message,
             crash.backtrace
         );
-        let _ = fs::write(&txt_filepath, report);
+        let _ = fs::write(json_path.with_extension("txt"), report);
+    }
+
+    /// Crash report files (`crash-*.json` and `crash-*.txt`) in the log directory
+    fn crash_report_files(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.log_dir) else {
+            return Vec::new();
+        };
+
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                name.starts_with("crash-") && (name.ends_with(".json") || name.ends_with(".txt"))
+            })
+            .collect()
+    }
+
+    /// Crash reports written by the panic hook, most recent first.
+    /// Files that fail to parse are skipped.
+    pub fn get_crash_reports(&self, limit: Option<usize>) -> Vec<CrashReport> {
+        let mut crashes: Vec<CrashReport> = self
+            .crash_report_files()
+            .into_iter()
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+            .filter_map(|path| fs::read_to_string(path).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+
+        crashes.sort_by_key(|crash| std::cmp::Reverse(crash.timestamp));
+        if let Some(n) = limit {
+            crashes.truncate(n);
+        }
+        crashes
+    }
+
+    /// Crashes recorded by reports last seen at or after `since`, counting
+    /// every merged occurrence. Used to spot a crash loop at startup.
+    pub fn recent_crash_count(&self, since: DateTime<Utc>) -> u32 {
+        self.get_crash_reports(None)
+            .iter()
+            .filter(|crash| crash.last_seen.unwrap_or(crash.timestamp) >= since)
+            .map(|crash| crash.occurrence_count)
+            .sum()
+    }
+
+    /// Delete every crash report file, returning how many crashes were removed
+    pub fn clear_crash_reports(&self) -> Result<usize, std::io::Error> {
+        let mut removed = 0;
+        for path in self.crash_report_files() {
+            if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                removed += 1;
+            }
+            fs::remove_file(path)?;
+        }
+
+        info!("Cleared {} crash reports", removed);
+        Ok(removed)
     }
 
     /// Get recent errors
@@ -413,6 +663,7 @@ impl ErrorReporter {
         output.push_str("=== Wavee Error Export ===\n\n");
         output.push_str(&format!("Generated: {}\n", Utc::now()));
         output.push_str(&format!("Version: {}\n", env!("CARGO_PKG_VERSION")));
+        output.push_str(&format!("Build: {} ({})\n", GIT_HASH, BUILD_TIME));
         output.push_str(&format!("OS: {}\n\n", get_os_info()));
 
         output.push_str("=== Recent Errors ===\n\n");
@@ -466,6 +717,8 @@ impl ErrorReporter {
         let export = serde_json::json!({
             "generated_at": Utc::now().to_rfc3339(),
             "app_version": env!("CARGO_PKG_VERSION"),
+            "git_hash": GIT_HASH,
+            "build_time": BUILD_TIME,
             "os_info": get_os_info(),
             "errors": errors,
             "stats": stats,
@@ -485,6 +738,7 @@ impl ErrorReporter {
             Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         ));
         md.push_str(&format!("**Version:** {}\n\n", env!("CARGO_PKG_VERSION")));
+        md.push_str(&format!("**Build:** {} ({})\n\n", GIT_HASH, BUILD_TIME));
         md.push_str(&format!("**OS:** {}\n\n", get_os_info()));
 
         md.push_str("## Statistics\n\n");
@@ -527,6 +781,39 @@ impl ErrorReporter {
         info!("Error reports cleared from memory");
     }
 
+    /// Remove the reports in `category` (any category when `None`) that
+    /// occurred before `older_than` (any time when `None`), returning how many
+    /// were removed. Fingerprints left with no report are dropped from the
+    /// occurrence counts, so a repeat is counted from one again.
+    pub fn clear_filtered(
+        &self,
+        category: Option<&ErrorCategory>,
+        older_than: Option<DateTime<Utc>>,
+    ) -> usize {
+        let mut errors = self.recent_errors.lock().unwrap();
+        let (removed, kept): (Vec<ErrorReport>, Vec<ErrorReport>) =
+            errors.drain(..).partition(|error| {
+                let before_cutoff = match older_than {
+                    Some(cutoff) => error.timestamp < cutoff,
+                    None => true,
+                };
+                before_cutoff && (category.is_none() || category == Some(&error.category))
+            });
+        *errors = kept;
+
+        let remaining: HashSet<String> = errors.iter().map(ErrorReport::fingerprint).collect();
+        let mut counts = self.error_counts.lock().unwrap();
+        for error in &removed {
+            let fingerprint = error.fingerprint();
+            if !remaining.contains(&fingerprint) {
+                counts.remove(&fingerprint);
+            }
+        }
+
+        info!("Cleared {} error reports", removed.len());
+        removed.len()
+    }
+
     /// Persist errors to a file
     pub fn persist_to_file(&self, app_dir: &std::path::Path) -> Result<(), std::io::Error> {
         let errors_dir = app_dir.join("errors");
@@ -594,7 +881,6 @@ pub fn report_error(severity: ErrorSeverity, category: ErrorCategory, message: i
 }
 
 /// Report an error with details
-#[allow(dead_code)]
 pub fn report_error_with_details(
     severity: ErrorSeverity,
     category: ErrorCategory,
@@ -650,6 +936,22 @@ mod tests {
         assert!(report.context.contains_key("url"));
     }
 
+    #[test]
+    fn test_reports_carry_build_info() {
+        let report = ErrorReport::new(ErrorSeverity::Error, ErrorCategory::Audio, "Test error");
+        assert_eq!(report.context.get("git_hash").unwrap(), GIT_HASH);
+        assert_eq!(report.context.get("build_time").unwrap(), BUILD_TIME);
+
+        let crash = CrashReport::new("boom", None, "");
+        assert_eq!(crash.git_hash, GIT_HASH);
+
+        // Crash reports written before build info was recorded still load
+        let mut json = serde_json::to_value(&crash).unwrap();
+        json.as_object_mut().unwrap().remove("git_hash");
+        let loaded: CrashReport = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.git_hash, "unknown");
+    }
+
     #[test]
     fn test_error_reporter() {
         let dir = tempdir().unwrap();
@@ -690,6 +992,64 @@ mod tests {
         assert_eq!(stats.total_errors, 3);
     }
 
+    #[test]
+    fn test_clear_filtered_keeps_other_categories() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        for message in ["Timed out", "DNS lookup failed"] {
+            reporter.report(ErrorReport::new(
+                ErrorSeverity::Warning,
+                ErrorCategory::Network,
+                message,
+            ));
+        }
+        reporter.report(ErrorReport::new(
+            ErrorSeverity::Error,
+            ErrorCategory::Transcription,
+            "Decoder failed",
+        ));
+
+        assert_eq!(
+            reporter.clear_filtered(Some(&ErrorCategory::Network), None),
+            2
+        );
+
+        let remaining = reporter.get_reports(None);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].category, ErrorCategory::Transcription);
+        assert_eq!(reporter.error_counts.lock().unwrap().len(), 1);
+
+        // A repeat of a cleared error is counted from one again
+        reporter.report(ErrorReport::new(
+            ErrorSeverity::Warning,
+            ErrorCategory::Network,
+            "Timed out",
+        ));
+        assert_eq!(reporter.get_reports(Some(1))[0].occurrence_count, 1);
+    }
+
+    #[test]
+    fn test_clear_filtered_by_age() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let mut old = ErrorReport::new(ErrorSeverity::Error, ErrorCategory::Audio, "Old");
+        old.timestamp = Utc::now() - chrono::Duration::days(3);
+        reporter.report(old);
+        reporter.report(ErrorReport::new(
+            ErrorSeverity::Error,
+            ErrorCategory::Audio,
+            "New",
+        ));
+
+        let cutoff = Utc::now() - chrono::Duration::days(1);
+        assert_eq!(reporter.clear_filtered(None, Some(cutoff)), 1);
+        assert_eq!(reporter.get_reports(None)[0].message, "New");
+        assert_eq!(
+            reporter.clear_filtered(Some(&ErrorCategory::Model), None),
+            0
+        );
+    }
+
     #[test]
     fn test_fingerprint_deduplication() {
         let error1 = ErrorReport::new(ErrorSeverity::Error, ErrorCategory::Audio, "Same error");
@@ -697,4 +1057,134 @@ mod tests {
 
         assert_eq!(error1.fingerprint(), error2.fingerprint());
     }
+
+    fn crash_at(timestamp: DateTime<Utc>, message: &str) -> CrashReport {
+        CrashReport {
+            timestamp,
+            ..CrashReport::new(message, Some("src/lib.rs:1:1".to_string()), "")
+        }
+    }
+
+    #[test]
+    fn test_crash_reports_are_listed_newest_first() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let now = Utc::now();
+
+        reporter.record_crash(&crash_at(now - chrono::Duration::hours(2), "older"));
+        reporter.record_crash(&crash_at(now, "newer"));
+        fs::write(dir.path().join("crash-garbage.json"), "not json").unwrap();
+
+        let crashes = reporter.get_crash_reports(None);
+        assert_eq!(crashes.len(), 2);
+        assert_eq!(crashes[0].panic_message, "newer");
+        assert_eq!(reporter.get_crash_reports(Some(1)).len(), 1);
+    }
+
+    #[test]
+    fn test_clear_crash_reports_keeps_other_logs() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        reporter.record_crash(&crash_at(Utc::now(), "boom"));
+        reporter.report(ErrorReport::new(
+            ErrorSeverity::Error,
+            ErrorCategory::Database,
+            "Logged error",
+        ));
+
+        assert_eq!(reporter.clear_crash_reports().unwrap(), 1);
+        assert!(reporter.get_crash_reports(None).is_empty());
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_repeated_crash_updates_one_report() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let first = Utc::now();
+
+        let mut original = crash_at(first, "startup failure");
+        original.backtrace = "first backtrace".to_string();
+        reporter.record_crash(&original);
+        for minutes in 1..5 {
+            let mut repeat = crash_at(
+                first + chrono::Duration::minutes(minutes),
+                "startup failure",
+            );
+            repeat.backtrace = "later backtrace".to_string();
+            reporter.record_crash(&repeat);
+        }
+
+        let crashes = reporter.get_crash_reports(None);
+        assert_eq!(crashes.len(), 1);
+        assert_eq!(crashes[0].occurrence_count, 5);
+        assert_eq!(crashes[0].backtrace, "first backtrace");
+        assert_eq!(
+            crashes[0].last_seen,
+            Some(first + chrono::Duration::minutes(4))
+        );
+        // One JSON report plus its text copy
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_recent_crash_count_sums_occurrences() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let now = Utc::now();
+
+        reporter.record_crash(&crash_at(now - chrono::Duration::hours(3), "old crash"));
+        for seconds in 0..3 {
+            reporter.record_crash(&crash_at(
+                now + chrono::Duration::seconds(seconds),
+                "startup failure",
+            ));
+        }
+
+        assert_eq!(
+            reporter.recent_crash_count(now - chrono::Duration::minutes(10)),
+            3
+        );
+        assert_eq!(
+            reporter.recent_crash_count(now - chrono::Duration::days(1)),
+            4
+        );
+    }
+
+    #[test]
+    fn test_crash_outside_window_gets_new_report() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let first = Utc::now() - chrono::Duration::days(2);
+
+        reporter.record_crash(&crash_at(first, "startup failure"));
+        reporter.record_crash(&crash_at(Utc::now(), "startup failure"));
+
+        assert_eq!(reporter.get_crash_reports(None).len(), 2);
+    }
+
+    #[test]
+    fn test_crash_reports_are_capped() {
+        let dir = tempdir().unwrap();
+        let reporter = ErrorReporter::new(dir.path().to_path_buf());
+        let now = Utc::now();
+
+        for i in 0..(MAX_CRASH_REPORTS as i64 + 5) {
+            reporter.record_crash(&crash_at(
+                now - chrono::Duration::minutes(100 - i),
+                &format!("crash {}", i),
+            ));
+        }
+
+        let crashes = reporter.get_crash_reports(None);
+        assert_eq!(crashes.len(), MAX_CRASH_REPORTS);
+        assert_eq!(
+            crashes[0].panic_message,
+            format!("crash {}", MAX_CRASH_REPORTS + 4)
+        );
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            MAX_CRASH_REPORTS * 2
+        );
+    }
 }
@@ -0,0 +1,176 @@
+//! Stage timings of the most recent dictation, from hotkey to injected text
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// A hotkey release older than this did not start the current run
+const MAX_HOTKEY_GAP: Duration = Duration::from_secs(10);
+
+/// Post-processing and injection only count towards a run that finished
+/// transcribing this recently; later calls belong to something else
+const RUN_WINDOW: Duration = Duration::from_secs(30);
+
+/// Millisecond breakdown of one dictation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LatencyBreakdown {
+    pub recorded_at: String,
+    /// Hotkey release until the frontend asked for the transcription; unset
+    /// when the run was not started from the hotkey
+    pub hotkey_to_stop_ms: Option<u64>,
+    pub stop_recording_ms: u64,
+    pub transcription_ms: u64,
+    pub post_process_ms: Option<u64>,
+    pub injection_ms: Option<u64>,
+    pub total_ms: u64,
+}
+
+impl LatencyBreakdown {
+    fn update_total(&mut self) {
+        self.total_ms = self.hotkey_to_stop_ms.unwrap_or(0)
+            + self.stop_recording_ms
+            + self.transcription_ms
+            + self.post_process_ms.unwrap_or(0)
+            + self.injection_ms.unwrap_or(0);
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// Collects timings as the separate pipeline commands report them
+#[derive(Debug, Default)]
+pub struct LatencyTracker {
+    hotkey_released_at: Option<Instant>,
+    transcribed_at: Option<Instant>,
+    last: Option<LatencyBreakdown>,
+}
+
+impl LatencyTracker {
+    pub fn hotkey_released(&mut self, at: Instant) {
+        self.hotkey_released_at = Some(at);
+    }
+
+    /// Start a new run once recording has stopped and been transcribed
+    pub fn record_transcription(
+        &mut self,
+        started: Instant,
+        stop_recording: Duration,
+        transcription: Duration,
+    ) -> LatencyBreakdown {
+        let hotkey_to_stop = self
+            .hotkey_released_at
+            .take()
+            .and_then(|released| started.checked_duration_since(released))
+            .filter(|gap| *gap <= MAX_HOTKEY_GAP);
+
+        let mut breakdown = LatencyBreakdown {
+            recorded_at: chrono::Utc::now().to_rfc3339(),
+            hotkey_to_stop_ms: hotkey_to_stop.map(millis),
+            stop_recording_ms: millis(stop_recording),
+            transcription_ms: millis(transcription),
+            post_process_ms: None,
+            injection_ms: None,
+            total_ms: 0,
+        };
+        breakdown.update_total();
+
+        self.transcribed_at = Some(started + stop_recording + transcription);
+        self.last = Some(breakdown.clone());
+        breakdown
+    }
+
+    fn open_run(&mut self, now: Instant) -> Option<&mut LatencyBreakdown> {
+        let transcribed_at = self.transcribed_at?;
+        if now.saturating_duration_since(transcribed_at) > RUN_WINDOW {
+            return None;
+        }
+        self.last.as_mut()
+    }
+
+    /// Add a post-processing pass to the open run. The frontend may run
+    /// several, so they accumulate.
+    pub fn record_post_process(&mut self, duration: Duration, now: Instant) {
+        if let Some(breakdown) = self.open_run(now) {
+            let total = breakdown.post_process_ms.unwrap_or(0) + millis(duration);
+            breakdown.post_process_ms = Some(total);
+            breakdown.update_total();
+        }
+    }
+
+    /// Record injection, which closes the open run. Returns the finished
+    /// breakdown, or `None` when no run was open.
+    pub fn record_injection(
+        &mut self,
+        duration: Duration,
+        now: Instant,
+    ) -> Option<LatencyBreakdown> {
+        let breakdown = self.open_run(now)?;
+        breakdown.injection_ms = Some(millis(duration));
+        breakdown.update_total();
+        let finished = breakdown.clone();
+        self.transcribed_at = None;
+        Some(finished)
+    }
+
+    pub fn last(&self) -> Option<LatencyBreakdown> {
+        self.last.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[test]
+    fn breakdown_covers_every_stage() {
+        let mut tracker = LatencyTracker::default();
+        let released = Instant::now();
+        let started = released + 40 * MS;
+
+        tracker.hotkey_released(released);
+        let breakdown = tracker.record_transcription(started, 15 * MS, 600 * MS);
+        assert_eq!(breakdown.hotkey_to_stop_ms, Some(40));
+        assert_eq!(breakdown.total_ms, 655);
+
+        let transcribed = started + 615 * MS;
+        tracker.record_post_process(5 * MS, transcribed + 10 * MS);
+        tracker.record_post_process(3 * MS, transcribed + 20 * MS);
+        let finished = tracker
+            .record_injection(30 * MS, transcribed + 30 * MS)
+            .unwrap();
+
+        assert_eq!(finished.post_process_ms, Some(8));
+        assert_eq!(finished.injection_ms, Some(30));
+        assert_eq!(finished.total_ms, 693);
+        assert_eq!(tracker.last(), Some(finished));
+    }
+
+    #[test]
+    fn stale_hotkey_and_late_stages_are_ignored() {
+        let mut tracker = LatencyTracker::default();
+        let released = Instant::now();
+        let started = released + MAX_HOTKEY_GAP + MS;
+
+        tracker.hotkey_released(released);
+        let breakdown = tracker.record_transcription(started, MS, MS);
+        assert_eq!(breakdown.hotkey_to_stop_ms, None);
+
+        let late = started + RUN_WINDOW + 10 * MS;
+        tracker.record_post_process(5 * MS, late);
+        assert!(tracker.record_injection(5 * MS, late).is_none());
+        assert_eq!(tracker.last(), Some(breakdown));
+    }
+
+    #[test]
+    fn injection_closes_the_run() {
+        let mut tracker = LatencyTracker::default();
+        let started = Instant::now();
+        tracker.record_transcription(started, MS, MS);
+
+        assert!(tracker.record_injection(MS, started + 5 * MS).is_some());
+        assert!(tracker.record_injection(MS, started + 6 * MS).is_none());
+    }
+}
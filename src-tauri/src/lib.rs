@@ -3,25 +3,42 @@
 mod audio;
 pub mod database;
 pub mod downloader;
-mod error_reporting;
+pub mod error_reporting;
+mod latency;
 pub mod license;
+mod logging;
+mod model_cache;
 pub mod post_process;
+mod release_notes;
 pub mod security;
+mod system_info;
 mod text_inject;
 pub mod transcription;
+mod transcription_queue;
 
 use audio::{AudioCaptureSource, AudioInputDevice, AudioOutputDevice, AudioRecorder};
-use database::{AppSettings, AppState, Database, LicenseData, TranscriptionHistory, WhisperModel};
-use downloader::{DownloadProgress, ModelDownloader};
-use error_reporting::{ErrorCategory, ErrorReport, ErrorReporter, ErrorSeverity, ErrorStats};
+use database::{
+    AppSettings, AppState, Database, DatabaseRecovery, LicenseData, TranscriptionHistory,
+    TranscriptionHistoryDay, WhisperModel,
+};
+use downloader::{DownloadProgress, DownloadSpeedTest, ModelDownloader, PartialDownloadCleanup};
+use error_reporting::{
+    CrashReport, ErrorCategory, ErrorReport, ErrorReporter, ErrorSeverity, ErrorStats,
+};
+use futures_util::future::{AbortHandle, Abortable};
+use latency::{LatencyBreakdown, LatencyTracker};
 use license::{
-    clear_cache, get_device_id, get_device_label, load_cache, LicenseInfo, LicenseManager,
-    LicenseStatus,
+    clear_cache, get_device_id, get_device_label, load_cache, load_trial_record,
+    store_trial_record, DeviceFingerprintDebug, LicenseInfo, LicenseManager, LicenseStatus,
+    TrialRecord,
 };
 use log::{debug, error, info, warn};
-use post_process::PostProcessor;
+use model_cache::{ModelCache, WarmModelInfo};
+use post_process::{PostProcessingConfig, PostProcessor, ProcessingStage};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tauri::{
@@ -30,17 +47,37 @@ use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager, State, WindowEvent,
 };
+use tauri_plugin_autostart::ManagerExt as AutostartExt;
+use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
-use transcription::Transcriber;
+use tauri_plugin_opener::OpenerExt;
+use text_inject::{InjectionMethod, InjectionOptions, InjectionPermission};
+use transcription::{
+    append_chunk_transcript, DecodingStrategy, Segment, Transcriber, TranscriptionEngine,
+    WhisperTuning, WhisperTuningOverrides, TRANSCRIPTION_CANCELLED,
+};
+use transcription_queue::TranscriptionQueue;
 
 // Application version from Cargo.toml
 const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 const APP_NAME: &str = "Wavee";
+
+/// Start without hotkeys, model auto-load or the full tray menu so a setting
+/// that breaks startup can be fixed
+const SAFE_MODE_FLAG: &str = "--safe-mode";
+/// Crashes within `SAFE_MODE_CRASH_WINDOW_MINUTES` that make the app suggest
+/// restarting in safe mode
+const SAFE_MODE_CRASH_THRESHOLD: u32 = 3;
+const SAFE_MODE_CRASH_WINDOW_MINUTES: i64 = 10;
 const APP_ICON_BYTES: &[u8] = include_bytes!("../icons/icon.png");
 const AUDIO_TARGET_SAMPLE_RATE: u32 = 16_000;
 const MAX_FILE_TRANSCRIPTION_SECONDS: usize = 30 * 60;
+const MAX_AUDIO_FILE_BYTES: u64 = 500 * 1024 * 1024;
 const MAX_FILE_AUDIO_SAMPLES: usize =
     AUDIO_TARGET_SAMPLE_RATE as usize * MAX_FILE_TRANSCRIPTION_SECONDS;
+/// Dictations waiting for or running transcription before new ones are
+/// turned away
+const TRANSCRIPTION_QUEUE_CAPACITY: usize = 4;
 
 // Rate limiter for preventing abuse
 pub struct RateLimiter {
@@ -58,7 +95,9 @@ impl RateLimiter {
         }
     }
 
-    pub fn check(&self, key: &str) -> bool {
+    /// Record a request for `key`, or return how long to wait before the
+    /// next one will be accepted.
+    pub fn check(&self, key: &str) -> Result<(), Duration> {
         let mut requests = self.requests.lock().unwrap();
         let now = Instant::now();
 
@@ -68,18 +107,83 @@ impl RateLimiter {
         timestamps.retain(|&t| now.duration_since(t) < self.window);
 
         if timestamps.len() >= self.max_requests {
-            warn!("Rate limit exceeded for action: {}", key);
-            false
+            // A slot frees up once the oldest request leaves the window
+            let retry_after = timestamps
+                .first()
+                .map(|&oldest| self.window.saturating_sub(now.duration_since(oldest)))
+                .unwrap_or(self.window);
+            warn!(
+                "Rate limit exceeded for action: {} (retry after {:?})",
+                key, retry_after
+            );
+            Err(retry_after)
         } else {
             timestamps.push(now);
-            true
+            Ok(())
         }
     }
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimitedPayload {
+    action: String,
+    retry_after_ms: u64,
+}
+
+/// Check `rate_limiter` for `action`, telling the frontend how long to back
+/// off when the request is rejected.
+fn enforce_rate_limit(
+    app: &tauri::AppHandle,
+    rate_limiter: &RateLimiter,
+    action: &str,
+    message: &str,
+) -> CommandResult<()> {
+    rate_limiter.check(action).map_err(|retry_after| {
+        let retry_after_ms = retry_after.as_millis() as u64;
+        let _ = app.emit(
+            "rate-limited",
+            RateLimitedPayload {
+                action: action.to_string(),
+                retry_after_ms,
+            },
+        );
+        CommandError::RateLimited {
+            message: message.to_string(),
+            retry_after_ms,
+        }
+    })
+}
+
 pub struct RateLimiterState(pub Arc<RateLimiter>);
 
-const AUDIO_FILE_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "ogg", "flac", "aac", "webm", "mkv"];
+/// Audio file extensions `transcribe_file` may accept, each with the codecs
+/// usually found in it. Containers are fixed by the symphonia features in
+/// Cargo.toml; an extension is only offered when one of its codecs is
+/// actually registered (WebM recordings are almost always Opus).
+const AUDIO_FILE_FORMATS: &[(&str, &[symphonia::core::codecs::CodecType])] = {
+    use symphonia::core::codecs::*;
+    &[
+        (
+            "wav",
+            &[
+                CODEC_TYPE_PCM_S16LE,
+                CODEC_TYPE_PCM_S24LE,
+                CODEC_TYPE_PCM_F32LE,
+            ],
+        ),
+        ("mp3", &[CODEC_TYPE_MP3]),
+        ("m4a", &[CODEC_TYPE_AAC, CODEC_TYPE_ALAC]),
+        (
+            "ogg",
+            &[CODEC_TYPE_VORBIS, CODEC_TYPE_OPUS, CODEC_TYPE_FLAC],
+        ),
+        ("flac", &[CODEC_TYPE_FLAC]),
+        ("aac", &[CODEC_TYPE_AAC]),
+        ("webm", &[CODEC_TYPE_OPUS]),
+        ("mkv", &[CODEC_TYPE_OPUS, CODEC_TYPE_AAC, CODEC_TYPE_VORBIS]),
+    ]
+};
 const EXPORT_FILE_EXTENSIONS: &[&str] = &["json", "md", "markdown"];
 const MAX_EXPORT_BYTES: usize = 10 * 1024 * 1024;
 
@@ -115,29 +219,48 @@ fn path_has_extension(path: &std::path::Path, allowed: &[&str]) -> bool {
 }
 
 fn validate_export_path(path: &str) -> Result<std::path::PathBuf, String> {
+    validate_output_path(
+        path,
+        "Export",
+        EXPORT_FILE_EXTENSIONS,
+        ".json, .md, or .markdown",
+    )
+}
+
+/// Check a user-chosen destination: it must carry one of `allowed` extensions
+/// and sit in an existing directory. `label` prefixes the error messages.
+fn validate_output_path(
+    path: &str,
+    label: &str,
+    allowed: &[&str],
+    allowed_description: &str,
+) -> Result<std::path::PathBuf, String> {
     if path.trim().is_empty() || path.contains('\0') {
-        return Err("Invalid export path".to_string());
+        return Err(format!("Invalid {} path", label.to_lowercase()));
     }
 
     let path = std::path::Path::new(path);
-    if !path_has_extension(path, EXPORT_FILE_EXTENSIONS) {
-        return Err("Export path must end in .json, .md, or .markdown".to_string());
+    if !path_has_extension(path, allowed) {
+        return Err(format!(
+            "{} path must end in {}",
+            label, allowed_description
+        ));
     }
 
     let parent = path
         .parent()
-        .ok_or_else(|| "Export path must include a parent directory".to_string())?;
+        .ok_or_else(|| format!("{} path must include a parent directory", label))?;
     let parent = parent
         .canonicalize()
-        .map_err(|e| format!("Cannot access export directory: {}", e))?;
+        .map_err(|e| format!("Cannot access {} directory: {}", label.to_lowercase(), e))?;
 
     if !parent.is_dir() {
-        return Err("Export directory is not a directory".to_string());
+        return Err(format!("{} directory is not a directory", label));
     }
 
     let file_name = path
         .file_name()
-        .ok_or_else(|| "Export path must include a file name".to_string())?;
+        .ok_or_else(|| format!("{} path must include a file name", label))?;
 
     Ok(parent.join(file_name))
 }
@@ -236,20 +359,132 @@ fn is_model_language_supported(model_id: &str, language: &str) -> bool {
         ),
 
         // Multilingual Whisper models
-        _ => language == "auto" || is_valid_language_code(language),
+        _ => language == "auto" || transcription::WHISPER_LANGUAGES.contains(&language),
+    }
+}
+
+/// Checks a language against a model's `languages` column (a JSON array).
+/// `["multilingual"]` means any Whisper language; an explicit list of more than
+/// one language also permits auto-detection.
+fn model_languages_allow(languages_json: &str, language: &str) -> bool {
+    let languages: Vec<String> = match serde_json::from_str(languages_json) {
+        Ok(languages) => languages,
+        Err(_) => return false,
+    };
+
+    if languages.iter().any(|l| l == "multilingual") {
+        return language == "auto" || transcription::WHISPER_LANGUAGES.contains(&language);
+    }
+
+    if language == "auto" {
+        return languages.len() > 1;
+    }
+
+    languages.iter().any(|l| l == language)
+}
+
+/// A language a model can be loaded with and the name shown for it
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct LanguageOption {
+    code: String,
+    name: String,
+}
+
+/// Languages a model can be loaded with, "auto" first when the model can
+/// detect the language itself
+fn model_language_options(model_id: &str, languages_json: &str) -> Vec<LanguageOption> {
+    let languages: Vec<String> = serde_json::from_str(languages_json).unwrap_or_default();
+    let codes: Vec<&str> = if languages.iter().any(|l| l == "multilingual") {
+        transcription::WHISPER_LANGUAGES.to_vec()
+    } else {
+        languages.iter().map(String::as_str).collect()
+    };
+
+    std::iter::once("auto")
+        .chain(codes)
+        .filter(|code| {
+            model_languages_allow(languages_json, code)
+                && is_model_language_supported(model_id, code)
+        })
+        .map(|code| LanguageOption {
+            code: code.to_string(),
+            name: if code == "auto" {
+                "Auto detect".to_string()
+            } else {
+                transcription::language_display_name(code)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| code.to_uppercase())
+            },
+        })
+        .collect()
+}
+
+/// Validates a language for a model using the built-in rules and, when the
+/// model is known to the database, its stored language list.
+fn validate_model_language(db: &Database, model_id: &str, language: &str) -> Result<(), String> {
+    if !is_valid_language_code(language) {
+        return Err(format!("Invalid language code: {}", language));
+    }
+
+    let allowed_by_db = match db.get_model(model_id) {
+        Ok(Some(model)) => model_languages_allow(&model.languages, language),
+        _ => true,
+    };
+
+    if !allowed_by_db || !is_model_language_supported(model_id, language) {
+        return Err(format!(
+            "Language '{}' is not supported by model '{}'",
+            language, model_id
+        ));
     }
+
+    Ok(())
+}
+
+/// Like `validate_model_language`, but also accepts any Whisper language for
+/// models that can be loaded with "auto", since that is what detection can
+/// report
+fn validate_history_language(db: &Database, model_id: &str, language: &str) -> Result<(), String> {
+    validate_model_language(db, model_id, language).or_else(|e| {
+        let detectable = transcription::WHISPER_LANGUAGES.contains(&language)
+            && validate_model_language(db, model_id, "auto").is_ok();
+        if detectable {
+            Ok(())
+        } else {
+            Err(e)
+        }
+    })
 }
 
 // State wrappers
 pub struct DbState(pub Arc<Database>);
 pub struct RecorderState(pub Arc<Mutex<Option<AudioRecorder>>>);
 pub struct TranscriberState(pub Arc<Mutex<Option<Transcriber>>>);
+pub struct LoadedModelState(pub Arc<Mutex<Option<LoadedModelInfo>>>);
+// Set by `cancel_transcription` to stop the running transcription
+pub struct TranscriptionAbortState(pub Arc<AtomicBool>);
+// Orders `record_and_transcribe` requests so they run one after another
+pub struct TranscriptionQueueState(pub Arc<TranscriptionQueue>);
+// Stage timings of the last dictation, filled in by each pipeline command
+pub struct LatencyState(pub Arc<Mutex<LatencyTracker>>);
+// Recently used models kept loaded so switching back skips the disk load
+pub struct WarmModelCacheState(pub Arc<Mutex<ModelCache<Transcriber>>>);
 pub struct DownloaderState(pub Arc<ModelDownloader>);
 pub struct LicenseManagerState(pub Arc<LicenseManager>);
+// Aborts the in-flight `activate_license` request, if any
+pub struct LicenseActivationState(pub Mutex<Option<AbortHandle>>);
 pub struct TextInjectorState(pub Arc<Mutex<text_inject::TextInjector>>);
 // Rate limiter: 100 requests per minute per action
 pub struct RecordingRateLimiter(pub Arc<RateLimiter>);
 pub struct TranscriptionRateLimiter(pub Arc<RateLimiter>);
+// Recording shortcut, replaced without touching the cancel shortcut
+pub struct RecordingHotkeyState(pub Mutex<Option<Shortcut>>);
+// Cancel shortcut, registered only while a recording is in progress
+pub struct CancelHotkeyState(pub Mutex<Option<Shortcut>>);
+// Destructive voice commands waiting for `confirm_action`
+pub struct PendingActionsState(pub Mutex<PendingActions>);
+// Whether the app was started with `--safe-mode`
+pub struct SafeModeState(pub bool);
 
 // Error type for commands
 #[derive(Debug, thiserror::Error)]
@@ -270,6 +505,13 @@ pub enum CommandError {
     License(String),
     #[error("Post-processing error: {0}")]
     PostProcessing(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
+    #[error("{message} Try again in {}s.", retry_after_ms.div_ceil(1000))]
+    RateLimited {
+        message: String,
+        retry_after_ms: u64,
+    },
 }
 
 fn user_facing_license_error(error: &str) -> &'static str {
@@ -352,7 +594,38 @@ fn has_active_trial(db: &Database) -> bool {
         return false;
     };
 
-    has_active_trial_core(&license, chrono::Utc::now())
+    let now = chrono::Utc::now();
+    has_active_trial_core(&license, now)
+        && license
+            .trial_started_at
+            .as_deref()
+            .is_some_and(|started| trial_clock_allows_usage(started, now))
+}
+
+/// Check the device-bound trial record for clock rollback and advance its
+/// last-seen time. Best-effort: a missing or unreadable record is re-seeded,
+/// but a clock that obviously moved backward fails closed.
+fn trial_clock_allows_usage(trial_started: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+    let (mut record, seeded) = match load_trial_record() {
+        Some(record) if record.trial_started_at == trial_started => (record, false),
+        _ => (TrialRecord::new(trial_started, now), true),
+    };
+
+    let advanced = match record.observe(now) {
+        Ok(advanced) => advanced,
+        Err(e) => {
+            warn!("Refusing trial: {}", e);
+            return false;
+        }
+    };
+
+    // Only write when something changed; this runs on every access check
+    if seeded || advanced {
+        if let Err(e) = store_trial_record(&record) {
+            warn!("Failed to store trial record: {}", e);
+        }
+    }
+    true
 }
 
 pub fn has_active_trial_core(
@@ -383,8 +656,50 @@ pub fn has_active_trial_core(
         return false;
     }
 
-    let days_since_start = (now - start_date).num_days();
-    (0..7).contains(&days_since_start)
+    trial_time_remaining(trial_started, now)
+        .is_some_and(|remaining| remaining.seconds_remaining > 0)
+}
+
+/// Length of the free trial
+pub const TRIAL_DURATION_DAYS: i64 = 7;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Time left on a trial, measured to the second
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrialTimeRemaining {
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub seconds_remaining: u64,
+}
+
+impl TrialTimeRemaining {
+    /// Whole days left, rounded up so a trial with hours to go still reports a day
+    pub fn days_remaining(&self) -> u64 {
+        self.seconds_remaining.div_ceil(SECONDS_PER_DAY)
+    }
+}
+
+/// Remaining trial time for a trial started at `trial_started` (RFC 3339).
+/// A start date in the future is treated as an exhausted trial.
+pub fn trial_time_remaining(
+    trial_started: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<TrialTimeRemaining> {
+    let start_date = chrono::DateTime::parse_from_rfc3339(trial_started)
+        .ok()?
+        .with_timezone(&chrono::Utc);
+    let expires_at = start_date + chrono::Duration::days(TRIAL_DURATION_DAYS);
+
+    let seconds_remaining = if start_date > now {
+        0
+    } else {
+        (expires_at - now).num_seconds().max(0) as u64
+    };
+
+    Some(TrialTimeRemaining {
+        expires_at,
+        seconds_remaining,
+    })
 }
 
 pub fn db_license_allows_usage(license: &database::LicenseData) -> bool {
@@ -448,13 +763,442 @@ fn get_settings(db: State<DbState>) -> CommandResult<AppSettings> {
 }
 
 #[tauri::command]
-fn update_settings(db: State<DbState>, settings: AppSettings) -> CommandResult<()> {
-    db.0.update_settings(&settings).map_err(Into::into)
+fn update_settings(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    settings: AppSettings,
+) -> CommandResult<()> {
+    parse_tray_menu_items(&settings.tray_menu_items)
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
+    check_hotkey_collisions(&configured_hotkeys(&settings))
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
+    check_empty_transcription_behavior(&settings.empty_transcription_behavior)
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
+    db.0.update_settings(&settings)?;
+    apply_tray_menu_items(&app, &settings.tray_menu_items);
+    apply_preroll(&app, &settings);
+    apply_decoding_strategy(&app);
+    apply_cpu_threads(&app, &settings);
+    Ok(())
+}
+
+#[tauri::command]
+fn update_setting(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    key: String,
+    value: String,
+) -> CommandResult<()> {
+    if key == "tray_menu_items" {
+        parse_tray_menu_items(&value)
+            .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
+    }
+    if matches!(
+        key.as_str(),
+        "push_to_talk_key" | "toggle_key" | "cancel_key"
+    ) {
+        let mut settings = db.0.get_settings()?;
+        match key.as_str() {
+            "push_to_talk_key" => settings.push_to_talk_key = value.clone(),
+            "toggle_key" => settings.toggle_key = value.clone(),
+            _ => settings.cancel_key = value.clone(),
+        }
+        check_hotkey_collisions(&configured_hotkeys(&settings))
+            .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
+    }
+    db.0.update_setting(&key, &value)?;
+    if key == "tray_menu_items" {
+        apply_tray_menu_items(&app, &value);
+    }
+    if key == "preroll_ms" {
+        apply_preroll(&app, &db.0.get_settings()?);
+    }
+    if key == "decoding_strategy" {
+        apply_decoding_strategy(&app);
+    }
+    if key == "cpu_threads" {
+        apply_cpu_threads(&app, &db.0.get_settings()?);
+    }
+    Ok(())
+}
+
+fn autostart_error(error: tauri_plugin_autostart::Error) -> CommandError {
+    CommandError::Io(std::io::Error::other(format!("Autostart error: {}", error)))
+}
+
+fn save_autostart_setting(db: &Database, enabled: bool) -> CommandResult<()> {
+    db.update_setting("auto_start_on_boot", if enabled { "1" } else { "0" })?;
+    Ok(())
+}
+
+/// Register or remove the OS login item and keep `auto_start_on_boot` in
+/// step with it
+#[tauri::command]
+fn set_autostart(app: tauri::AppHandle, db: State<DbState>, enabled: bool) -> CommandResult<bool> {
+    apply_autostart(&app, &db.0, enabled)
+}
+
+/// Returns what the OS reports after the change, which is also what gets
+/// saved
+fn apply_autostart(app: &tauri::AppHandle, db: &Database, enabled: bool) -> CommandResult<bool> {
+    let autolaunch = app.autolaunch();
+    if enabled {
+        autolaunch.enable().map_err(autostart_error)?;
+    } else {
+        autolaunch.disable().map_err(autostart_error)?;
+    }
+
+    let actual = autolaunch.is_enabled().map_err(autostart_error)?;
+    if actual != enabled {
+        warn!(
+            "Autostart did not change (requested {}, OS reports {})",
+            enabled, actual
+        );
+    }
+    save_autostart_setting(db, actual)?;
+    Ok(actual)
+}
+
+/// Whether the OS will launch the app at login. The user can remove the
+/// login item outside the app, so the setting is updated to match.
+#[tauri::command]
+fn get_autostart_status(app: tauri::AppHandle, db: State<DbState>) -> CommandResult<bool> {
+    let enabled = app.autolaunch().is_enabled().map_err(autostart_error)?;
+    if db.0.get_settings()?.auto_start_on_boot != enabled {
+        save_autostart_setting(&db.0, enabled)?;
+    }
+    Ok(enabled)
+}
+
+/// Bring `auto_start_on_boot` in line with the OS login item at startup
+fn reconcile_autostart(app: &tauri::AppHandle, db: &Database) {
+    let enabled = match app.autolaunch().is_enabled() {
+        Ok(enabled) => enabled,
+        Err(e) => {
+            warn!("Failed to read autostart state: {}", e);
+            return;
+        }
+    };
+    match db.get_settings() {
+        Ok(settings) if settings.auto_start_on_boot != enabled => {
+            info!(
+                "Autostart setting was {}, OS reports {}; updating setting",
+                settings.auto_start_on_boot, enabled
+            );
+            if let Err(e) = save_autostart_setting(db, enabled) {
+                warn!("Failed to update autostart setting: {}", e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("Failed to read settings for autostart: {}", e),
+    }
+}
+
+#[tauri::command]
+fn reset_settings(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    keys: Option<Vec<String>>,
+) -> CommandResult<AppSettings> {
+    let settings = db.0.reset_settings(keys.as_deref())?;
+
+    // Hotkeys may have changed; the frontend re-registers them on reload
+    unregister_recording_hotkey(&app);
+    apply_tray_menu_items(&app, &settings.tray_menu_items);
+    apply_preroll(&app, &settings);
+    apply_decoding_strategy(&app);
+    apply_cpu_threads(&app, &settings);
+    let _ = app.emit("settings-reset", &settings);
+
+    Ok(settings)
+}
+
+const SETTINGS_EXPORT_FORMAT: &str = "wavee-settings";
+const SETTINGS_EXPORT_VERSION: u32 = 1;
+
+/// Checksum over the exported settings and post-processing config.
+/// `serde_json::Value` keeps keys sorted, so the serialization is stable
+/// across exports. Files without a post-processing config hash the
+/// settings alone.
+fn settings_checksum(
+    settings: &serde_json::Value,
+    post_processing: Option<&serde_json::Value>,
+) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(settings.to_string().as_bytes());
+    if let Some(post_processing) = post_processing {
+        hasher.update(post_processing.to_string().as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// A verified settings file. `post_processing` is `None` for files exported
+/// before the post-processing config was included.
+#[derive(Debug)]
+struct SettingsImport {
+    settings: AppSettings,
+    post_processing: Option<PostProcessingConfig>,
+}
+
+/// Settings that only make sense on the machine that wrote them: a models
+/// folder path and an audio device name
+const MACHINE_LOCAL_SETTINGS: &[&str] = &["models_dir", "input_device_name"];
+
+fn build_settings_export(
+    settings: &AppSettings,
+    post_processing: &PostProcessingConfig,
+) -> Result<String, String> {
+    let mut settings = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    if let Some(object) = settings.as_object_mut() {
+        for key in MACHINE_LOCAL_SETTINGS {
+            object.remove(*key);
+        }
+    }
+    let post_processing = serde_json::to_value(post_processing).map_err(|e| e.to_string())?;
+    let export = serde_json::json!({
+        "format": SETTINGS_EXPORT_FORMAT,
+        "version": SETTINGS_EXPORT_VERSION,
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "checksum": settings_checksum(&settings, Some(&post_processing)),
+        "settings": settings,
+        "postProcessing": post_processing,
+    });
+    serde_json::to_string_pretty(&export).map_err(|e| e.to_string())
+}
+
+/// Parse and verify an exported settings blob. Only the `settings` and
+/// `postProcessing` objects are read; anything else in the blob is ignored.
+fn parse_settings_import(json: &str) -> Result<SettingsImport, String> {
+    let blob: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid settings file: {}", e))?;
+
+    if blob.get("format").and_then(|v| v.as_str()) != Some(SETTINGS_EXPORT_FORMAT) {
+        return Err("Not a Wavee settings file".to_string());
+    }
+
+    match blob.get("version").and_then(|v| v.as_u64()) {
+        Some(version) if version == SETTINGS_EXPORT_VERSION as u64 => {}
+        Some(version) => {
+            return Err(format!(
+                "Settings file version {} is not supported (expected {})",
+                version, SETTINGS_EXPORT_VERSION
+            ))
+        }
+        None => return Err("Settings file is missing a version".to_string()),
+    }
+
+    let settings = blob
+        .get("settings")
+        .filter(|v| v.is_object())
+        .ok_or_else(|| "Settings file has no settings".to_string())?;
+    let post_processing = blob.get("postProcessing");
+    let checksum = blob.get("checksum").and_then(|v| v.as_str());
+    if checksum != Some(settings_checksum(settings, post_processing).as_str()) {
+        return Err("Settings file checksum does not match; it may be corrupted".to_string());
+    }
+
+    let settings =
+        serde_json::from_value(settings.clone()).map_err(|e| format!("Invalid settings: {}", e))?;
+    let post_processing = post_processing
+        .map(|config| {
+            serde_json::from_value(config.clone())
+                .map_err(|e| format!("Invalid post-processing config: {}", e))
+        })
+        .transpose()?;
+    Ok(SettingsImport {
+        settings,
+        post_processing,
+    })
+}
+
+/// Check every imported field before anything is written.
+fn validate_settings(settings: &AppSettings, known_model_ids: &[String]) -> Result<(), String> {
+    check_hotkey_collisions(&configured_hotkeys(settings))?;
+
+    if !matches!(settings.hotkey_mode.as_str(), "push-to-talk" | "toggle") {
+        return Err(format!("Invalid hotkey mode: {}", settings.hotkey_mode));
+    }
+    if !known_model_ids.contains(&settings.selected_model_id) {
+        return Err(format!("Unknown model: {}", settings.selected_model_id));
+    }
+    if !is_model_language_supported(&settings.selected_model_id, &settings.language) {
+        return Err(format!(
+            "Language '{}' is not supported by model '{}'",
+            settings.language, settings.selected_model_id
+        ));
+    }
+    check_empty_transcription_behavior(&settings.empty_transcription_behavior)?;
+    if !(0..=10_000).contains(&settings.min_recording_ms) {
+        return Err("Minimum recording length must be between 0 and 10000 ms".to_string());
+    }
+    if !matches!(
+        settings.injection_trailing.as_str(),
+        "none" | "space" | "newline" | "smart"
+    ) {
+        return Err(format!(
+            "Invalid injection trailing behavior: {}",
+            settings.injection_trailing
+        ));
+    }
+    if InjectionMethod::parse(&settings.injection_method).is_none() {
+        return Err(format!(
+            "Invalid injection method: {}",
+            settings.injection_method
+        ));
+    }
+    if !(0..=MAX_TYPING_DELAY_MS).contains(&settings.typing_delay_ms) {
+        return Err(format!(
+            "Typing delay must be between 0 and {} ms",
+            MAX_TYPING_DELAY_MS
+        ));
+    }
+    let vad_range = audio::MIN_VAD_THRESHOLD_DBFS as i64..=audio::MAX_VAD_THRESHOLD_DBFS as i64;
+    if !vad_range.contains(&settings.vad_threshold_dbfs) {
+        return Err(format!(
+            "VAD threshold must be between {} and {} dBFS",
+            vad_range.start(),
+            vad_range.end()
+        ));
+    }
+    if !(0..=audio::MAX_RECORDING_SECONDS as i64).contains(&settings.preallocate_recording_seconds)
+    {
+        return Err(format!(
+            "Recording pre-allocation must be between 0 and {} seconds",
+            audio::MAX_RECORDING_SECONDS
+        ));
+    }
+    if !settings.release_notes_url.starts_with("https://") {
+        return Err("Release notes URL must use HTTPS".to_string());
+    }
+    if let Some(mirror) = downloader::parse_mirror_list(&settings.download_mirrors)
+        .into_iter()
+        .find(|mirror| !mirror.starts_with("https://"))
+    {
+        return Err(format!("Download mirror must use HTTPS: {}", mirror));
+    }
+    if !(0..=MAX_MIN_HOLD_MS).contains(&settings.min_hold_ms) {
+        return Err(format!(
+            "Minimum hold time must be between 0 and {} ms",
+            MAX_MIN_HOLD_MS
+        ));
+    }
+    if !(0..=MAX_SILENCE_PAD_MS).contains(&settings.silence_pad_ms) {
+        return Err(format!(
+            "Silence padding must be between 0 and {} ms",
+            MAX_SILENCE_PAD_MS
+        ));
+    }
+    if !(0..=MAX_SILENCE_TIMEOUT_MS).contains(&settings.silence_timeout_ms) {
+        return Err(format!(
+            "Silence timeout must be between 0 and {} ms",
+            MAX_SILENCE_TIMEOUT_MS
+        ));
+    }
+    if !(0..=MAX_PREROLL_MS).contains(&settings.preroll_ms) {
+        return Err(format!(
+            "Pre-roll must be between 0 and {} ms",
+            MAX_PREROLL_MS
+        ));
+    }
+    parse_tray_menu_items(&settings.tray_menu_items)?;
+    if !(0..=MAX_WARM_MODELS).contains(&settings.warm_model_cache_size) {
+        return Err(format!(
+            "Warm model cache size must be between 0 and {}",
+            MAX_WARM_MODELS
+        ));
+    }
+    if !(0..=MAX_CPU_THREADS).contains(&settings.cpu_threads) {
+        return Err(format!(
+            "CPU threads must be between 0 and {}",
+            MAX_CPU_THREADS
+        ));
+    }
+    if DecodingStrategy::parse(&settings.decoding_strategy).is_none() {
+        return Err(format!(
+            "Invalid decoding strategy: {}",
+            settings.decoding_strategy
+        ));
+    }
+    if OverlayPosition::parse(&settings.overlay_position).is_none() {
+        return Err(format!(
+            "Invalid overlay position: {}",
+            settings.overlay_position
+        ));
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+fn export_settings(db: State<DbState>) -> CommandResult<String> {
+    let settings = db.0.get_settings()?;
+    let post_processing = db.0.get_post_processing_config()?;
+    build_settings_export(&settings, &post_processing)
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))
 }
 
+/// Import settings exported by `export_settings`. The blob is fully validated
+/// first and then written in a single update, so nothing changes on error.
 #[tauri::command]
-fn update_setting(db: State<DbState>, key: String, value: String) -> CommandResult<()> {
-    db.0.update_setting(&key, &value).map_err(Into::into)
+fn import_settings(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    json: String,
+) -> CommandResult<AppSettings> {
+    let to_error = |e: String| CommandError::Database(rusqlite::Error::InvalidParameterName(e));
+
+    let SettingsImport {
+        mut settings,
+        post_processing,
+    } = parse_settings_import(&json).map_err(to_error)?;
+    let known_model_ids: Vec<String> = db.0.get_models()?.into_iter().map(|m| m.id).collect();
+    validate_settings(&settings, &known_model_ids).map_err(to_error)?;
+    if let Some(config) = &post_processing {
+        PostProcessor::from_config(config).map_err(CommandError::PostProcessing)?;
+    }
+
+    // Machine-local fields keep their current values. `update_settings`
+    // never writes `models_dir`, so only the input device needs carrying over.
+    settings.input_device_name = db.0.get_settings()?.input_device_name;
+    db.0.update_settings(&settings)?;
+    if let Some(config) = &post_processing {
+        db.0.save_post_processing_config(config)?;
+    }
+    let settings = db.0.get_settings()?;
+    apply_tray_menu_items(&app, &settings.tray_menu_items);
+    apply_preroll(&app, &settings);
+    apply_decoding_strategy(&app);
+    apply_cpu_threads(&app, &settings);
+    apply_imported_hotkey(&app, &settings);
+    if let Err(e) = apply_autostart(&app, &db.0, settings.auto_start_on_boot) {
+        warn!("Failed to apply imported autostart setting: {}", e);
+    }
+    // The OS may have refused the autostart change
+    let settings = db.0.get_settings()?;
+    let _ = app.emit("settings-imported", &settings);
+
+    Ok(settings)
+}
+
+/// Register the recording hotkey for the imported mode. The settings were
+/// validated, so the key parses; a taken shortcut is logged rather than
+/// failing an import that has already been written.
+fn apply_imported_hotkey(app: &tauri::AppHandle, settings: &AppSettings) {
+    if in_safe_mode(app) {
+        return;
+    }
+    let hotkey = match settings.hotkey_mode.as_str() {
+        "toggle" => &settings.toggle_key,
+        _ => &settings.push_to_talk_key,
+    };
+    let result = parse_hotkey(hotkey)
+        .map_err(|e| CommandError::Recording(format!("Invalid hotkey: {}", e)))
+        .and_then(|shortcut| {
+            register_recording_hotkey(app, hotkey, shortcut, &settings.hotkey_mode)
+        });
+    if let Err(e) = result {
+        warn!("Failed to register imported hotkey: {}", e);
+    }
 }
 
 // ==================== App State Commands ====================
@@ -479,6 +1223,12 @@ fn set_current_setup_step(db: State<DbState>, step: i32) -> CommandResult<()> {
     db.0.set_current_setup_step(step).map_err(Into::into)
 }
 
+/// Reports whether the database had to be recreated or replaced at startup
+#[tauri::command]
+fn get_database_recovery(db: State<DbState>) -> Option<DatabaseRecovery> {
+    db.0.recovery().cloned()
+}
+
 // ==================== Model Commands ====================
 
 #[tauri::command]
@@ -563,33 +1313,241 @@ fn set_selected_model(db: State<DbState>, model_id: Option<String>) -> CommandRe
         .map_err(Into::into)
 }
 
-// ==================== Recording Commands ====================
-
-#[tauri::command]
-fn get_audio_input_devices() -> CommandResult<Vec<AudioInputDevice>> {
-    AudioRecorder::list_input_devices().map_err(CommandError::Recording)
+/// Inference needs roughly 1.5x the model size in memory
+fn model_inference_bytes(size_bytes: u64) -> u64 {
+    size_bytes + size_bytes / 2
 }
 
-#[tauri::command]
-fn get_audio_output_devices() -> CommandResult<Vec<AudioOutputDevice>> {
-    AudioRecorder::list_output_devices().map_err(CommandError::Recording)
+/// Fixed whisper.cpp allocations on top of the weights: compute buffers,
+/// the KV cache and the mel spectrogram for a 30 second window
+const WHISPER_OVERHEAD_BYTES: u64 = 300 * 1024 * 1024;
+
+/// Approximate memory needed to load and run a model. Deliberately
+/// conservative, since underestimating means the machine starts swapping.
+fn estimate_model_memory_bytes(size_bytes: u64) -> u64 {
+    model_inference_bytes(size_bytes) + WHISPER_OVERHEAD_BYTES
 }
 
-#[tauri::command]
-fn set_audio_input_device(
-    recorder: State<RecorderState>,
-    device_name: Option<String>,
-) -> CommandResult<()> {
-    let mut recorder_guard = recorder.0.lock().unwrap();
+/// Approximate memory cost of loading a model, for warning before it is loaded.
+#[derive(Debug, Clone, serde::Serialize)]
+struct ModelMemoryEstimate {
+    model_id: String,
+    model_size_bytes: u64,
+    estimated_bytes: u64,
+    available_bytes: Option<u64>,
+    total_bytes: Option<u64>,
+    /// Whether the estimate fits in available memory; unknown when it
+    /// cannot be read
+    fits: Option<bool>,
+}
 
-    if recorder_guard.is_none() {
-        *recorder_guard = Some(AudioRecorder::new().map_err(CommandError::Recording)?);
+fn model_memory_estimate(
+    model_id: &str,
+    size_bytes: u64,
+    available_bytes: Option<u64>,
+    total_bytes: Option<u64>,
+) -> ModelMemoryEstimate {
+    let estimated_bytes = estimate_model_memory_bytes(size_bytes);
+    ModelMemoryEstimate {
+        model_id: model_id.to_string(),
+        model_size_bytes: size_bytes,
+        estimated_bytes,
+        available_bytes,
+        total_bytes,
+        fits: available_bytes.map(|available| estimated_bytes <= available),
     }
+}
 
-    if let Some(ref mut rec) = *recorder_guard {
-        rec.set_input_device(device_name)
-            .map_err(CommandError::Recording)?;
-    }
+/// Memory currently used by the app.
+#[derive(Debug, Clone, serde::Serialize)]
+struct MemoryUsage {
+    /// Resident set size of this process
+    process_bytes: Option<u64>,
+    available_bytes: Option<u64>,
+    total_bytes: Option<u64>,
+}
+
+/// A model suggested for a language, with the reasons behind its ranking.
+#[derive(Debug, Clone, serde::Serialize)]
+struct ModelRecommendation {
+    model_id: String,
+    name: String,
+    score: i32,
+    reasons: Vec<String>,
+}
+
+/// Rank models that can decode `language`, best first. Hardware limits are
+/// optional; when unknown, models are ranked on language fit and speed alone.
+fn rank_models_for_language(
+    models: &[WhisperModel],
+    language: &str,
+    available_memory_bytes: Option<u64>,
+    available_disk_bytes: Option<u64>,
+) -> Vec<ModelRecommendation> {
+    let mut ranked: Vec<(i64, ModelRecommendation)> = models
+        .iter()
+        .filter(|model| {
+            model_languages_allow(&model.languages, language)
+                && is_model_language_supported(&model.id, language)
+        })
+        .map(|model| {
+            let size = model.size_bytes.max(0) as u64;
+            let mut score = 0;
+            let mut reasons = Vec::new();
+
+            if model.downloaded {
+                score += 30;
+                reasons.push("Already downloaded".to_string());
+            } else if let Some(disk) = available_disk_bytes {
+                if size > disk {
+                    score -= 100;
+                    reasons.push("Not enough free disk space to download".to_string());
+                }
+            }
+
+            if model.id.starts_with("distil-") {
+                score += 20;
+                reasons.push("Distilled for faster transcription".to_string());
+            }
+
+            if language == "en" && model.languages == "[\"en\"]" {
+                score += 10;
+                reasons.push("Optimized for English".to_string());
+            }
+
+            if let Some(memory) = available_memory_bytes {
+                let required = model_inference_bytes(size);
+                if required * 2 <= memory {
+                    score += 20;
+                    reasons.push("Fits comfortably in available memory".to_string());
+                } else if required <= memory {
+                    score += 5;
+                    reasons.push("Fits in available memory".to_string());
+                } else {
+                    score -= 50;
+                    reasons.push("Likely exceeds available memory".to_string());
+                }
+            }
+
+            const MB: u64 = 1024 * 1024;
+            if size < 200 * MB {
+                score += 15;
+                reasons.push("Fast on most hardware".to_string());
+            } else if size < 1024 * MB {
+                score += 10;
+                reasons.push("Balanced speed and accuracy".to_string());
+            } else {
+                score += 5;
+                reasons.push("Highest accuracy, but slower".to_string());
+            }
+
+            (
+                model.size_bytes,
+                ModelRecommendation {
+                    model_id: model.id.clone(),
+                    name: model.name.clone(),
+                    score,
+                    reasons,
+                },
+            )
+        })
+        .collect();
+
+    // Prefer higher scores, then smaller models
+    ranked.sort_by(|(a_size, a), (b_size, b)| b.score.cmp(&a.score).then(a_size.cmp(b_size)));
+    ranked.into_iter().map(|(_, rec)| rec).collect()
+}
+
+#[tauri::command]
+fn recommend_model(
+    db: State<DbState>,
+    downloader: State<DownloaderState>,
+    language: String,
+) -> CommandResult<Vec<ModelRecommendation>> {
+    if !is_valid_language_code(&language) {
+        return Err(CommandError::Transcription(format!(
+            "Invalid language code: {}",
+            language
+        )));
+    }
+
+    let mut models = db.0.get_models().map_err(CommandError::Database)?;
+    for model in models.iter_mut() {
+        model.downloaded = downloader.0.is_model_downloaded(&model.id);
+    }
+
+    let memory = system_info::available_memory_bytes();
+    let disk = system_info::available_disk_bytes(&downloader.0.models_dir());
+
+    Ok(rank_models_for_language(&models, &language, memory, disk))
+}
+
+/// Estimate how much memory loading `model_id` would take. The estimate is
+/// approximate; it is meant for warnings, not hard limits.
+#[tauri::command]
+fn get_model_memory_estimate(
+    db: State<DbState>,
+    model_id: String,
+) -> CommandResult<ModelMemoryEstimate> {
+    let model =
+        db.0.get_model(&model_id)
+            .map_err(CommandError::Database)?
+            .ok_or_else(|| CommandError::Transcription(format!("Unknown model: {}", model_id)))?;
+
+    Ok(model_memory_estimate(
+        &model.id,
+        model.size_bytes.max(0) as u64,
+        system_info::available_memory_bytes(),
+        system_info::total_memory_bytes(),
+    ))
+}
+
+#[tauri::command]
+fn get_current_memory_usage() -> MemoryUsage {
+    MemoryUsage {
+        process_bytes: system_info::process_memory_bytes(),
+        available_bytes: system_info::available_memory_bytes(),
+        total_bytes: system_info::total_memory_bytes(),
+    }
+}
+
+// ==================== Recording Commands ====================
+
+#[tauri::command]
+fn get_audio_input_devices() -> CommandResult<Vec<AudioInputDevice>> {
+    AudioRecorder::list_input_devices().map_err(CommandError::Recording)
+}
+
+#[tauri::command]
+fn get_audio_output_devices() -> CommandResult<Vec<AudioOutputDevice>> {
+    AudioRecorder::list_output_devices().map_err(CommandError::Recording)
+}
+
+/// Record from `device_name` (an id from `get_audio_input_devices`), or the
+/// default microphone for `None`. The choice is saved and used again on the
+/// next launch while that device is connected.
+#[tauri::command]
+fn set_audio_input_device(
+    db: State<DbState>,
+    recorder: State<RecorderState>,
+    device_name: Option<String>,
+) -> CommandResult<()> {
+    let mut recorder_guard = recorder.0.lock().unwrap();
+
+    if recorder_guard.is_none() {
+        *recorder_guard = Some(AudioRecorder::new().map_err(CommandError::Recording)?);
+    }
+
+    if let Some(ref mut rec) = *recorder_guard {
+        rec.set_input_device(device_name.clone())
+            .map_err(CommandError::Recording)?;
+        rec.set_preferred_input_device(device_name.clone());
+    }
+    drop(recorder_guard);
+
+    let mut settings = db.0.get_settings()?;
+    settings.input_device_name = device_name;
+    db.0.update_settings(&settings)?;
 
     Ok(())
 }
@@ -617,10 +1575,12 @@ fn set_audio_capture_config(
 
 #[tauri::command]
 async fn start_recording(
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
     recorder: State<'_, RecorderState>,
     rate_limiter: State<'_, RecordingRateLimiter>,
+    append: Option<bool>,
 ) -> CommandResult<()> {
     let db = db.0.clone();
     let license_manager = license_manager.0.clone();
@@ -630,13 +1590,29 @@ async fn start_recording(
     ensure_app_access_verified(&db, &license_manager).await?;
 
     // Rate limiting check
-    if !rate_limiter.check("start_recording") {
-        return Err(CommandError::Recording(
-            "Rate limit exceeded. Please wait before starting another recording.".to_string(),
-        ));
-    }
+    enforce_rate_limit(
+        &app,
+        &rate_limiter,
+        "start_recording",
+        "Rate limit exceeded. Please wait before starting another recording.",
+    )?;
 
     debug!("start_recording called");
+
+    let settings = db.get_settings()?;
+    let preallocate_seconds = settings.preallocate_recording_seconds.max(0) as usize;
+
+    // Long-form mode spools audio to the cache dir instead of holding it in memory
+    let long_form_dir = if settings.long_form_recording {
+        Some(
+            app.path()
+                .app_cache_dir()
+                .map_err(|e| CommandError::Io(std::io::Error::other(e.to_string())))?,
+        )
+    } else {
+        None
+    };
+
     let mut recorder_guard = recorder.lock().unwrap();
 
     if recorder_guard.is_none() {
@@ -648,22 +1624,203 @@ async fn start_recording(
     }
 
     if let Some(ref mut rec) = *recorder_guard {
-        debug!("Starting recording...");
-        rec.start_recording().map_err(|e| {
+        rec.set_long_form_dir(long_form_dir)
+            .map_err(CommandError::Recording)?;
+        rec.set_preallocated_seconds(preallocate_seconds)
+            .map_err(CommandError::Recording)?;
+        rec.set_preferred_input_device(settings.input_device_name.clone());
+        rec.set_silence_auto_stop(silence_auto_stop(&settings));
+        // Appending keeps earlier takes so `finalize_recording` can
+        // transcribe them together
+        let started = if append.unwrap_or(false) {
+            debug!("Continuing recording...");
+            rec.continue_recording()
+        } else {
+            debug!("Starting recording...");
+            rec.start_recording()
+        };
+        started.map_err(|e| {
             error!("Failed to start recording: {}", e);
             CommandError::Recording(e)
         })?;
         debug!("Recording started successfully");
     }
+    drop(recorder_guard);
+    refresh_tray_items(&app);
+    spawn_recording_monitor(&app, recorder);
+
+    arm_cancel_hotkey(&app, &db.get_settings()?.cancel_key);
 
     Ok(())
 }
 
-#[tauri::command]
-fn stop_recording(recorder: State<RecorderState>) -> CommandResult<Vec<f32>> {
+/// Longest silence the `silence_timeout_ms` setting may wait for
+const MAX_SILENCE_TIMEOUT_MS: i64 = 60_000;
+
+/// Auto-stop configured by settings; the silence threshold is the saved VAD
+/// threshold
+fn silence_auto_stop(settings: &AppSettings) -> Option<audio::SilenceAutoStop> {
+    (settings.silence_timeout_ms > 0).then(|| audio::SilenceAutoStop {
+        timeout: Duration::from_millis(settings.silence_timeout_ms as u64),
+        threshold_dbfs: settings.vad_threshold_dbfs as f32,
+    })
+}
+
+/// Longest pre-roll kept ahead of a recording. The ring is trimmed on every
+/// callback, so it is kept short.
+const MAX_PREROLL_MS: i64 = 2_000;
+
+/// Open, resize or close the idle pre-roll stream to match settings. The
+/// recorder is only created here when pre-roll is on.
+fn apply_preroll(app: &tauri::AppHandle, settings: &AppSettings) {
+    if in_safe_mode(app) {
+        return;
+    }
+    let Some(recorder) = app.try_state::<RecorderState>() else {
+        return;
+    };
     let mut recorder_guard = recorder.0.lock().unwrap();
+    if recorder_guard.is_none() && settings.preroll_ms <= 0 {
+        return;
+    }
+    if recorder_guard.is_none() {
+        match AudioRecorder::new() {
+            Ok(rec) => *recorder_guard = Some(rec),
+            Err(e) => {
+                warn!("Failed to create recorder for pre-roll: {}", e);
+                return;
+            }
+        }
+    }
 
-    if let Some(ref mut rec) = *recorder_guard {
+    let rec = recorder_guard.as_mut().unwrap();
+    rec.set_preferred_input_device(settings.input_device_name.clone());
+    let preroll = Duration::from_millis(settings.preroll_ms.clamp(0, MAX_PREROLL_MS) as u64);
+    if let Err(e) = rec.set_preroll(preroll) {
+        warn!("Failed to start pre-roll: {}", e);
+    }
+}
+
+/// How often `audio-level` is emitted while recording
+const LEVEL_METER_INTERVAL: Duration = Duration::from_millis(50);
+static RECORDING_MONITOR_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Emit the recorder's input level as `audio-level` until recording stops,
+/// then refresh the tray and emit `recording-auto-stopped` if it ended itself
+/// after silence so the frontend can collect it with `stop_recording`. Only
+/// one monitor runs at a time, so appended takes reuse the running one.
+fn spawn_recording_monitor(app: &tauri::AppHandle, recorder: Arc<Mutex<Option<AudioRecorder>>>) {
+    if RECORDING_MONITOR_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(LEVEL_METER_INTERVAL);
+        let (level, stopped_on_silence) = match recorder.lock().unwrap().as_ref() {
+            Some(rec) if rec.is_recording() => (Some(rec.input_level()), false),
+            Some(rec) => (None, rec.stopped_on_silence()),
+            None => (None, false),
+        };
+
+        match level {
+            Some(level) => {
+                let _ = app.emit("audio-level", level);
+            }
+            None => {
+                RECORDING_MONITOR_RUNNING.store(false, Ordering::SeqCst);
+                refresh_tray_items(&app);
+                if stopped_on_silence {
+                    info!("Recording stopped after silence");
+                    // Release the microphone now; the frontend collects the
+                    // take when it handles the event
+                    if let Some(ref mut rec) = *recorder.lock().unwrap() {
+                        rec.close_stream();
+                    }
+                    let _ = app.emit("recording-auto-stopped", ());
+                }
+                break;
+            }
+        }
+    });
+}
+
+/// Register the cancel shortcut for the duration of a recording. Failures are
+/// logged rather than returned so a taken shortcut never blocks recording.
+fn arm_cancel_hotkey(app: &tauri::AppHandle, cancel_key: &str) {
+    if in_safe_mode(app) || cancel_key.trim().is_empty() {
+        return;
+    }
+
+    let shortcut = match parse_hotkey(cancel_key) {
+        Ok(shortcut) => shortcut,
+        Err(e) => {
+            warn!("Invalid cancel hotkey '{}': {}", cancel_key, e);
+            return;
+        }
+    };
+
+    let state = app.state::<CancelHotkeyState>();
+    let mut armed = state.0.lock().unwrap();
+    if armed.is_some() || app.global_shortcut().is_registered(shortcut) {
+        return;
+    }
+
+    let result = app
+        .global_shortcut()
+        .on_shortcut(shortcut, |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                cancel_active_recording(app);
+            }
+        });
+
+    match result {
+        Ok(()) => *armed = Some(shortcut),
+        Err(e) => warn!("Failed to register cancel hotkey: {}", e),
+    }
+}
+
+/// Unregister the cancel shortcut so the key is not hijacked while idle
+fn release_cancel_hotkey(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<CancelHotkeyState>() else {
+        return;
+    };
+    let Some(shortcut) = state.0.lock().unwrap().take() else {
+        return;
+    };
+
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        warn!("Failed to unregister cancel hotkey: {}", e);
+    }
+}
+
+/// Discard the in-progress recording and tell the frontend
+fn cancel_active_recording(app: &tauri::AppHandle) {
+    if let Some(recorder) = app.try_state::<RecorderState>() {
+        if let Some(ref mut rec) = *recorder.0.lock().unwrap() {
+            rec.cancel_recording();
+        }
+    }
+    refresh_tray_items(app);
+
+    // Called from the shortcut handler, which must not unregister itself
+    // synchronously
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        release_cancel_hotkey(&handle);
+    });
+
+    let _ = app.emit("recording-cancelled", ());
+}
+
+#[tauri::command]
+fn stop_recording(
+    app: tauri::AppHandle,
+    recorder: State<RecorderState>,
+) -> CommandResult<Vec<f32>> {
+    release_cancel_hotkey(&app);
+
+    let result = if let Some(ref mut rec) = *recorder.0.lock().unwrap() {
         rec.stop_recording().map_err(|e| {
             error!("Failed to stop recording: {}", e);
             CommandError::Recording(e)
@@ -672,7 +1829,21 @@ fn stop_recording(recorder: State<RecorderState>) -> CommandResult<Vec<f32>> {
         Err(CommandError::Recording(
             "No recorder initialized".to_string(),
         ))
-    }
+    };
+    refresh_tray_items(&app);
+    result
+}
+
+/// RMS waveform of the last recording, `bucket_count` values between 0 and 1
+#[tauri::command]
+fn get_waveform(recorder: State<RecorderState>, bucket_count: usize) -> CommandResult<Vec<f32>> {
+    recorder
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|rec| rec.last_waveform(bucket_count))
+        .ok_or_else(|| CommandError::Recording("No recording available".to_string()))
 }
 
 #[tauri::command]
@@ -698,22 +1869,142 @@ async fn save_temp_audio(
         .to_str()
         .ok_or_else(|| CommandError::Io(std::io::Error::other("Invalid path")))?;
 
-    audio::save_wav(&samples, path_str).map_err(CommandError::Recording)?;
+    audio::save_wav(&samples, path_str, audio::WavFormat::default())
+        .map_err(CommandError::Recording)?;
 
     Ok(path_str.to_string())
 }
 
+/// Write the most recent recording to `path` as a WAV file. `format` picks
+/// the sample encoding and defaults to 16-bit PCM.
 #[tauri::command]
-fn cancel_recording(recorder: State<RecorderState>) -> CommandResult<()> {
-    let mut recorder_guard = recorder.0.lock().unwrap();
+fn save_last_recording(
+    recorder: State<'_, RecorderState>,
+    path: String,
+    format: Option<audio::WavFormat>,
+) -> CommandResult<String> {
+    let path = validate_output_path(&path, "Recording", &["wav"], ".wav")
+        .map_err(|e| CommandError::Io(std::io::Error::other(e)))?;
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| CommandError::Io(std::io::Error::other("Invalid path")))?;
 
-    if let Some(ref mut rec) = *recorder_guard {
-        rec.cancel_recording();
-    }
+    let guard = recorder.0.lock().unwrap();
+    let samples = guard
+        .as_ref()
+        .map(|rec| rec.last_recording())
+        .filter(|samples| !samples.is_empty())
+        .ok_or_else(|| CommandError::Recording("No recording available".to_string()))?;
+
+    audio::save_wav(samples, path_str, format.unwrap_or_default())
+        .map_err(CommandError::Recording)?;
+
+    Ok(path_str.to_string())
+}
 
+#[tauri::command]
+fn cancel_recording(app: tauri::AppHandle) -> CommandResult<()> {
+    cancel_active_recording(&app);
     Ok(())
 }
 
+const CALIBRATION_DURATION: Duration = Duration::from_secs(2);
+
+/// Record a short clip and report levels, SNR and a pass/warn/fail verdict
+/// for the onboarding wizard. Nothing is transcribed or stored.
+#[tauri::command]
+async fn calibrate_microphone(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    recorder: State<'_, RecorderState>,
+) -> CommandResult<audio::CalibrationResult> {
+    let samples = record_clip(
+        &app,
+        &db.0,
+        recorder.0.clone(),
+        CALIBRATION_DURATION,
+        "calibrate",
+    )
+    .await?;
+    Ok(audio::analyze_calibration(&samples))
+}
+
+/// Default and allowed lengths of a noise floor measurement
+const NOISE_FLOOR_DEFAULT_MS: u64 = 3_000;
+const NOISE_FLOOR_MIN_MS: u64 = 500;
+const NOISE_FLOOR_MAX_MS: u64 = 10_000;
+
+/// Record the room with nobody speaking and save a voice activity threshold
+/// a few dB above its noise floor. Run again after moving somewhere quieter
+/// or louder.
+#[tauri::command]
+async fn measure_noise_floor(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    recorder: State<'_, RecorderState>,
+    duration_ms: Option<u64>,
+) -> CommandResult<audio::NoiseFloor> {
+    let duration = Duration::from_millis(
+        duration_ms
+            .unwrap_or(NOISE_FLOOR_DEFAULT_MS)
+            .clamp(NOISE_FLOOR_MIN_MS, NOISE_FLOOR_MAX_MS),
+    );
+    let samples = record_clip(&app, &db.0, recorder.0.clone(), duration, "measure noise").await?;
+    let floor = audio::analyze_noise_floor(&samples);
+
+    let threshold = floor.recommended_vad_threshold_dbfs.round() as i64;
+    db.0.update_setting("vad_threshold_dbfs", &threshold.to_string())?;
+    info!(
+        "Noise floor {:.1} dBFS, VAD threshold set to {} dBFS",
+        floor.noise_floor_dbfs, threshold
+    );
+    Ok(floor)
+}
+
+/// Record a clip of `duration` for analysis. Nothing is transcribed or
+/// stored. `action` names the caller in the error shown when a recording is
+/// already running.
+async fn record_clip(
+    app: &tauri::AppHandle,
+    db: &Database,
+    recorder: Arc<Mutex<Option<AudioRecorder>>>,
+    duration: Duration,
+    action: &str,
+) -> CommandResult<Vec<f32>> {
+    let preferred_input = db.get_settings()?.input_device_name;
+    {
+        let mut recorder_guard = recorder.lock().unwrap();
+        if recorder_guard.is_none() {
+            *recorder_guard = Some(AudioRecorder::new().map_err(CommandError::Recording)?);
+        }
+        let rec = recorder_guard.as_mut().unwrap();
+        if rec.is_recording() {
+            return Err(CommandError::Recording(format!(
+                "Cannot {} while a recording is in progress",
+                action
+            )));
+        }
+        rec.set_long_form_dir(None)
+            .map_err(CommandError::Recording)?;
+        rec.set_preferred_input_device(preferred_input);
+        rec.set_silence_auto_stop(None);
+        rec.start_recording().map_err(CommandError::Recording)?;
+    }
+    refresh_tray_items(app);
+
+    let slept = tauri::async_runtime::spawn_blocking(move || std::thread::sleep(duration)).await;
+
+    let samples = match recorder.lock().unwrap().as_mut() {
+        Some(rec) => rec.stop_recording().map_err(CommandError::Recording),
+        None => Err(CommandError::Recording(
+            "No recorder initialized".to_string(),
+        )),
+    };
+    refresh_tray_items(app);
+    slept.map_err(|e| CommandError::Recording(format!("Recording interrupted: {}", e)))?;
+    samples
+}
+
 #[tauri::command]
 fn is_recording(recorder: State<RecorderState>) -> bool {
     let recorder_guard = recorder.0.lock().unwrap();
@@ -723,130 +2014,689 @@ fn is_recording(recorder: State<RecorderState>) -> bool {
         .unwrap_or(false)
 }
 
-// ==================== Recording Overlay Commands ====================
-
+/// Current input level from 0.0 (silence) to 1.0 (full scale)
 #[tauri::command]
-async fn show_recording_overlay(app: tauri::AppHandle) -> CommandResult<()> {
-    use tauri::Manager;
+fn get_input_level(recorder: State<RecorderState>) -> f32 {
+    let recorder_guard = recorder.0.lock().unwrap();
+    recorder_guard
+        .as_ref()
+        .map(|r| r.input_level())
+        .unwrap_or(0.0)
+}
 
-    if let Some(overlay_window) = app.get_webview_window("recording-overlay") {
-        // Show the overlay window
-        overlay_window.show().map_err(|e| {
-            error!("Failed to show overlay window: {}", e);
-            CommandError::Recording(format!("Failed to show overlay: {}", e))
-        })?;
+#[tauri::command]
+fn pause_recording(app: tauri::AppHandle, recorder: State<RecorderState>) -> CommandResult<()> {
+    let mut recorder_guard = recorder.0.lock().unwrap();
 
-        // Set it to fullscreen and always on top
-        overlay_window.set_fullscreen(true).map_err(|e| {
-            warn!("Failed to set fullscreen: {}", e);
-            CommandError::Recording(format!("Failed to set fullscreen: {}", e))
-        })?;
+    if let Some(ref mut rec) = *recorder_guard {
+        rec.pause_recording().map_err(CommandError::Recording)?;
+        let _ = app.emit("recording-paused", ());
+        Ok(())
+    } else {
+        Err(CommandError::Recording(
+            "No recorder initialized".to_string(),
+        ))
+    }
+}
 
-        overlay_window.set_always_on_top(true).map_err(|e| {
-            warn!("Failed to set always on top: {}", e);
-            CommandError::Recording(format!("Failed to set always on top: {}", e))
-        })?;
+#[tauri::command]
+fn resume_recording(app: tauri::AppHandle, recorder: State<RecorderState>) -> CommandResult<()> {
+    let mut recorder_guard = recorder.0.lock().unwrap();
 
-        debug!("Recording overlay shown");
+    if let Some(ref mut rec) = *recorder_guard {
+        rec.resume_recording().map_err(CommandError::Recording)?;
+        let _ = app.emit("recording-resumed", ());
+        Ok(())
     } else {
-        warn!("Recording overlay window not found");
+        Err(CommandError::Recording(
+            "No recorder initialized".to_string(),
+        ))
     }
-
-    Ok(())
 }
 
 #[tauri::command]
-async fn hide_recording_overlay(app: tauri::AppHandle) -> CommandResult<()> {
-    use tauri::Manager;
+fn is_recording_paused(recorder: State<RecorderState>) -> bool {
+    let recorder_guard = recorder.0.lock().unwrap();
+    recorder_guard
+        .as_ref()
+        .map(|r| r.is_paused())
+        .unwrap_or(false)
+}
 
-    if let Some(overlay_window) = app.get_webview_window("recording-overlay") {
-        // Hide the overlay window
-        overlay_window.hide().map_err(|e| {
-            error!("Failed to hide overlay window: {}", e);
-            CommandError::Recording(format!("Failed to hide overlay: {}", e))
-        })?;
+/// Elapsed recording time in milliseconds, excluding paused time
+#[tauri::command]
+fn get_recording_elapsed_ms(recorder: State<RecorderState>) -> u64 {
+    let recorder_guard = recorder.0.lock().unwrap();
+    recorder_guard
+        .as_ref()
+        .map(|r| r.elapsed().as_millis() as u64)
+        .unwrap_or(0)
+}
 
-        debug!("Recording overlay hidden");
-    }
+// ==================== Recording Overlay Commands ====================
 
-    Ok(())
+/// Where the recording overlay sits on the monitor under the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverlayPosition {
+    Fullscreen,
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl OverlayPosition {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "fullscreen" => Some(Self::Fullscreen),
+            "center" => Some(Self::Center),
+            "top_left" => Some(Self::TopLeft),
+            "top_right" => Some(Self::TopRight),
+            "bottom_left" => Some(Self::BottomLeft),
+            "bottom_right" => Some(Self::BottomRight),
+            _ => None,
+        }
+    }
+}
+
+/// Logical size of the overlay when it is not fullscreen
+const OVERLAY_WIDTH: f64 = 360.0;
+const OVERLAY_HEIGHT: f64 = 120.0;
+/// Logical gap between a corner overlay and the monitor edges
+const OVERLAY_MARGIN: f64 = 24.0;
+
+/// Physical top-left corner and size of the overlay on a monitor, or `None`
+/// when the overlay should cover the whole monitor
+fn overlay_frame(
+    position: OverlayPosition,
+    monitor_origin: (i32, i32),
+    monitor_size: (u32, u32),
+    scale_factor: f64,
+) -> Option<((i32, i32), (u32, u32))> {
+    let width = ((OVERLAY_WIDTH * scale_factor).round() as u32).min(monitor_size.0);
+    let height = ((OVERLAY_HEIGHT * scale_factor).round() as u32).min(monitor_size.1);
+    let margin = (OVERLAY_MARGIN * scale_factor).round() as i32;
+
+    let left = monitor_origin.0 + margin;
+    let right = monitor_origin.0 + monitor_size.0 as i32 - width as i32 - margin;
+    let top = monitor_origin.1 + margin;
+    let bottom = monitor_origin.1 + monitor_size.1 as i32 - height as i32 - margin;
+
+    let origin = match position {
+        OverlayPosition::Fullscreen => return None,
+        OverlayPosition::Center => (
+            monitor_origin.0 + (monitor_size.0 - width) as i32 / 2,
+            monitor_origin.1 + (monitor_size.1 - height) as i32 / 2,
+        ),
+        OverlayPosition::TopLeft => (left, top),
+        OverlayPosition::TopRight => (right, top),
+        OverlayPosition::BottomLeft => (left, bottom),
+        OverlayPosition::BottomRight => (right, bottom),
+    };
+
+    Some((origin, (width, height)))
+}
+
+/// The monitor under the mouse cursor, falling back to the overlay's current
+/// monitor and then the primary one
+fn monitor_under_cursor(
+    app: &tauri::AppHandle,
+    window: &tauri::WebviewWindow,
+) -> Option<tauri::Monitor> {
+    app.cursor_position()
+        .ok()
+        .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+#[tauri::command]
+async fn show_recording_overlay(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+) -> CommandResult<()> {
+    use tauri::Manager;
+
+    let overlay_window = app.get_webview_window("recording-overlay").ok_or_else(|| {
+        error!("Recording overlay window not found");
+        CommandError::Recording("Recording overlay window not found".to_string())
+    })?;
+
+    let position = OverlayPosition::parse(&db.0.get_settings()?.overlay_position)
+        .unwrap_or(OverlayPosition::Fullscreen);
+    let to_error = |action: &str, e: tauri::Error| {
+        warn!("Failed to {}: {}", action, e);
+        CommandError::Recording(format!("Failed to {}: {}", action, e))
+    };
+
+    // Show the overlay window
+    overlay_window.show().map_err(|e| {
+        error!("Failed to show overlay window: {}", e);
+        CommandError::Recording(format!("Failed to show overlay: {}", e))
+    })?;
+
+    let monitor = monitor_under_cursor(&app, &overlay_window);
+    let frame = monitor.as_ref().and_then(|monitor| {
+        let origin = monitor.position();
+        let size = monitor.size();
+        overlay_frame(
+            position,
+            (origin.x, origin.y),
+            (size.width, size.height),
+            monitor.scale_factor(),
+        )
+    });
+
+    match frame {
+        Some(((x, y), (width, height))) => {
+            overlay_window
+                .set_fullscreen(false)
+                .map_err(|e| to_error("leave fullscreen", e))?;
+            overlay_window
+                .set_size(tauri::PhysicalSize::new(width, height))
+                .map_err(|e| to_error("resize overlay", e))?;
+            overlay_window
+                .set_position(tauri::PhysicalPosition::new(x, y))
+                .map_err(|e| to_error("position overlay", e))?;
+        }
+        None => {
+            // Move onto the target monitor first so fullscreen covers it
+            if let Some(monitor) = &monitor {
+                overlay_window
+                    .set_position(*monitor.position())
+                    .map_err(|e| to_error("position overlay", e))?;
+            }
+            overlay_window
+                .set_fullscreen(true)
+                .map_err(|e| to_error("set fullscreen", e))?;
+        }
+    }
+
+    overlay_window
+        .set_always_on_top(true)
+        .map_err(|e| to_error("set always on top", e))?;
+
+    debug!("Recording overlay shown ({:?})", position);
+    Ok(())
+}
+
+#[tauri::command]
+async fn hide_recording_overlay(app: tauri::AppHandle) -> CommandResult<()> {
+    use tauri::Manager;
+
+    if let Some(overlay_window) = app.get_webview_window("recording-overlay") {
+        // Hide the overlay window
+        overlay_window.hide().map_err(|e| {
+            error!("Failed to hide overlay window: {}", e);
+            CommandError::Recording(format!("Failed to hide overlay: {}", e))
+        })?;
+
+        debug!("Recording overlay hidden");
+    }
+
+    Ok(())
 }
 
 // ==================== Transcription Commands ====================
 
+/// The model currently held by `TranscriberState`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LoadedModelInfo {
+    pub model_id: String,
+    pub engine: String,
+    pub language: String,
+    pub loaded_at: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ParakeetErrorPayload {
+    model_id: String,
+    message: String,
+}
+
+/// Parakeet runs in-process through ONNX Runtime, so its failures only show
+/// up as load errors. Record them with the error reporter (which deduplicates
+/// repeats) and tell the frontend.
+fn report_parakeet_error(app: &tauri::AppHandle, model_id: &str, error: &str) {
+    error_reporting::report_error_with_details(
+        ErrorSeverity::Error,
+        ErrorCategory::Model,
+        format!("Parakeet model {} failed to load", model_id),
+        error,
+    );
+
+    let _ = app.emit(
+        "parakeet-error",
+        ParakeetErrorPayload {
+            model_id: model_id.to_string(),
+            message: error.to_string(),
+        },
+    );
+}
+
+/// Delete a model file that cannot be loaded and mark it as not downloaded,
+/// so the UI offers a fresh download instead of failing again
+async fn discard_broken_model(
+    db: &Database,
+    downloader: &ModelDownloader,
+    model_id: &str,
+    problem: transcription::ModelFileProblem,
+) {
+    error_reporting::report_error_with_details(
+        ErrorSeverity::Error,
+        ErrorCategory::Model,
+        format!("Model {} failed to load", model_id),
+        format!("{:?}: {}", problem, problem.message()),
+    );
+
+    if let Err(e) = downloader.delete_model(model_id).await {
+        warn!("Failed to delete broken model {}: {}", model_id, e);
+    }
+    if let Err(e) = db.set_model_downloaded(model_id, false, None) {
+        warn!("Failed to mark model {} as not downloaded: {}", model_id, e);
+    }
+}
+
 #[tauri::command]
 async fn load_model(
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
     transcriber: State<'_, TranscriberState>,
+    loaded_model: State<'_, LoadedModelState>,
+    warm_cache: State<'_, WarmModelCacheState>,
     downloader: State<'_, DownloaderState>,
+    abort: State<'_, TranscriptionAbortState>,
     model_id: String,
     language: String,
-) -> CommandResult<()> {
+    strict: Option<bool>,
+) -> CommandResult<LoadedModelInfo> {
     let db = db.0.clone();
     let license_manager = license_manager.0.clone();
     let transcriber = transcriber.0.clone();
+    let loaded_model = loaded_model.0.clone();
+    let warm_cache = warm_cache.0.clone();
     let downloader = downloader.0.clone();
 
     ensure_app_access_verified(&db, &license_manager).await?;
 
-    if !is_valid_language_code(&language) {
-        return Err(CommandError::Transcription(format!(
-            "Invalid language code: {}",
-            language
-        )));
+    validate_model_language(&db, &model_id, &language).map_err(CommandError::Transcription)?;
+
+    let mut model_id = model_id;
+    let mut model_path = downloader.get_model_path(&model_id);
+
+    if !model_path.exists() {
+        // Unless the caller wants the error, fall back to another model
+        // rather than leaving the user with nothing loaded
+        let fallback = if strict.unwrap_or(false) {
+            None
+        } else {
+            pick_fallback_model(&db.get_models()?, &model_id, &language, |id| {
+                downloader.get_model_path(id).exists()
+            })
+        };
+        let Some(fallback) = fallback else {
+            return Err(CommandError::Transcription(format!(
+                "Model {} is not downloaded",
+                model_id
+            )));
+        };
+
+        warn!(
+            "Model {} is not downloaded, falling back to {}",
+            model_id, fallback
+        );
+        db.set_selected_model(Some(&fallback))?;
+        let _ = app.emit(
+            "model-fallback",
+            ModelFallback {
+                requested_model_id: model_id,
+                fallback_model_id: fallback.clone(),
+            },
+        );
+        model_path = downloader.get_model_path(&fallback);
+        model_id = fallback;
+    }
+
+    // Catch broken whisper.cpp files before the current model is dropped
+    if model_path.is_file() {
+        if let Ok(Some(problem)) = transcription::check_ggml_model_file(&model_path) {
+            discard_broken_model(&db, &downloader, &model_id, problem).await;
+            return Err(CommandError::Transcription(problem.message().to_string()));
+        }
+    }
+
+    // Move the current model into the warm cache, or drop it to free memory
+    // when it does not fit, before loading the new one
+    let previous = {
+        let mut transcriber_guard = transcriber.lock().unwrap();
+        let previous = transcriber_guard.take();
+        previous.zip(loaded_model.lock().unwrap().take())
+    };
+    let warm = {
+        let mut cache = warm_cache.lock().unwrap();
+        let mut evicted = cache.set_capacity(warm_cache_capacity(&db));
+        if let Some((previous, info)) = previous {
+            // Reloading the active model always starts fresh
+            if info.model_id != model_id {
+                let size_bytes = db
+                    .get_model(&info.model_id)
+                    .ok()
+                    .flatten()
+                    .map_or(0, |model| model.size_bytes.max(0) as u64);
+                evicted.extend(cache.insert(
+                    &info.model_id,
+                    size_bytes,
+                    previous,
+                    warm_cache_budget(),
+                ));
+            }
+        }
+        if !evicted.is_empty() {
+            info!("Released warm models: {}", evicted.join(", "));
+        }
+        cache.take(&model_id)
+    };
+
+    let mut new_transcriber = match warm {
+        Some(mut warm) => {
+            debug!("Reusing warm model {}", model_id);
+            warm.set_language(&language);
+            warm
+        }
+        None => {
+            Transcriber::new(&model_id, model_path.to_str().unwrap(), &language).map_err(|e| {
+                if model_id.starts_with("parakeet-") {
+                    report_parakeet_error(&app, &model_id, &e);
+                }
+                CommandError::Transcription(e)
+            })?
+        }
+    };
+
+    new_transcriber.set_whisper_tuning(effective_model_tuning(&db, &model_id));
+    new_transcriber.set_abort_flag(abort.0.clone());
+    new_transcriber.set_cpu_threads(cpu_threads(&db.get_settings().unwrap_or_default()));
+
+    let engine = new_transcriber.name();
+    let info = LoadedModelInfo {
+        model_id: model_id.clone(),
+        engine: engine.to_string(),
+        language: language.clone(),
+        loaded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    *transcriber.lock().unwrap() = Some(new_transcriber);
+    *loaded_model.lock().unwrap() = Some(info.clone());
+    refresh_tray_items(&app);
+
+    info!(
+        "Model loaded: {} (engine: {}, language: {})",
+        model_id, engine, language
+    );
+
+    Ok(info)
+}
+
+/// Sent as `model-fallback` when the requested model was missing and
+/// another one was loaded and selected in its place
+#[derive(Debug, Clone, serde::Serialize)]
+struct ModelFallback {
+    requested_model_id: String,
+    fallback_model_id: String,
+}
+
+/// Smallest downloaded model, other than `missing`, that can transcribe
+/// `language`. `is_present` checks the files really are on disk, since the
+/// downloaded flag can be stale.
+fn pick_fallback_model(
+    models: &[WhisperModel],
+    missing: &str,
+    language: &str,
+    is_present: impl Fn(&str) -> bool,
+) -> Option<String> {
+    models
+        .iter()
+        .filter(|model| model.downloaded && model.id != missing)
+        .filter(|model| is_model_language_supported(&model.id, language))
+        .filter(|model| is_present(&model.id))
+        .min_by_key(|model| model.size_bytes)
+        .map(|model| model.id.clone())
+}
+
+/// A model's default decoding settings with the user's overrides and the
+/// decoding strategy applied
+fn effective_model_tuning(db: &Database, model_id: &str) -> WhisperTuning {
+    let overrides = db.get_model_tuning(model_id).unwrap_or_else(|e| {
+        warn!("Failed to read tuning for model {}: {}", model_id, e);
+        WhisperTuningOverrides::default()
+    });
+    transcription::whisper_tuning_for(model_id)
+        .with_overrides(&overrides)
+        .with_strategy(decoding_strategy(db))
+}
+
+fn decoding_strategy(db: &Database) -> DecodingStrategy {
+    db.get_settings()
+        .ok()
+        .and_then(|settings| DecodingStrategy::parse(&settings.decoding_strategy))
+        .unwrap_or_default()
+}
+
+/// Give the loaded model its decoding settings again after
+/// `decoding_strategy` changed
+fn apply_decoding_strategy(app: &tauri::AppHandle) {
+    let (Some(db), Some(loaded_model), Some(transcriber)) = (
+        app.try_state::<DbState>(),
+        app.try_state::<LoadedModelState>(),
+        app.try_state::<TranscriberState>(),
+    ) else {
+        return;
+    };
+    let Some(model_id) = loaded_model
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|info| info.model_id.clone())
+    else {
+        return;
+    };
+
+    let tuning = effective_model_tuning(&db.0, &model_id);
+    if let Some(t) = transcriber.0.lock().unwrap().as_mut() {
+        t.set_whisper_tuning(tuning);
+    }
+}
+
+/// Highest `cpu_threads` accepted; requests are clamped to the physical
+/// core count anyway
+const MAX_CPU_THREADS: i64 = 256;
+/// Thread cap for the `cpu_threads` setting
+fn cpu_threads(settings: &AppSettings) -> Option<usize> {
+    transcription::cpu_thread_limit(settings.cpu_threads, system_info::physical_core_count())
+}
+
+/// Give the loaded model the thread cap from `cpu_threads`
+fn apply_cpu_threads(app: &tauri::AppHandle, settings: &AppSettings) {
+    let Some(transcriber) = app.try_state::<TranscriberState>() else {
+        return;
+    };
+    if let Some(t) = transcriber.0.lock().unwrap().as_mut() {
+        t.set_cpu_threads(cpu_threads(settings));
     }
-    if !is_model_language_supported(&model_id, &language) {
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ModelTuning {
+    model_id: String,
+    defaults: WhisperTuning,
+    overrides: WhisperTuningOverrides,
+    effective: WhisperTuning,
+}
+
+fn model_tuning_info(db: &Database, model_id: &str) -> CommandResult<ModelTuning> {
+    let defaults = transcription::whisper_tuning_for(model_id);
+    let overrides = db.get_model_tuning(model_id)?;
+    Ok(ModelTuning {
+        model_id: model_id.to_string(),
+        defaults,
+        overrides,
+        effective: defaults
+            .with_overrides(&overrides)
+            .with_strategy(decoding_strategy(db)),
+    })
+}
+
+#[tauri::command]
+fn get_model_tuning(db: State<DbState>, model_id: String) -> CommandResult<ModelTuning> {
+    if !is_known_model_id(&db.0, &model_id) {
         return Err(CommandError::Transcription(format!(
-            "Language '{}' is not supported by model '{}'",
-            language, model_id
+            "Unknown model: {}",
+            model_id
         )));
     }
+    model_tuning_info(&db.0, &model_id)
+}
 
-    let model_path = downloader.get_model_path(&model_id);
-
-    if !model_path.exists() {
+/// Save decoding overrides for a model. Empty overrides restore the
+/// defaults. A loaded model picks the change up immediately.
+#[tauri::command]
+fn set_model_tuning(
+    db: State<DbState>,
+    transcriber: State<TranscriberState>,
+    loaded_model: State<LoadedModelState>,
+    model_id: String,
+    overrides: WhisperTuningOverrides,
+) -> CommandResult<ModelTuning> {
+    if !is_known_model_id(&db.0, &model_id) {
         return Err(CommandError::Transcription(format!(
-            "Model {} is not downloaded",
+            "Unknown model: {}",
             model_id
         )));
     }
+    overrides.validate().map_err(CommandError::Transcription)?;
+    db.0.save_model_tuning(&model_id, &overrides)?;
 
-    // Drop existing model first to free memory before loading new one
-    {
-        let mut transcriber_guard = transcriber.lock().unwrap();
-        *transcriber_guard = None;
-        // Force memory release by dropping the guard
-        drop(transcriber_guard);
+    let tuning = model_tuning_info(&db.0, &model_id)?;
+    let is_loaded = loaded_model
+        .0
+        .lock()
+        .unwrap()
+        .as_ref()
+        .is_some_and(|info| info.model_id == model_id);
+    if is_loaded {
+        if let Some(t) = transcriber.0.lock().unwrap().as_mut() {
+            t.set_whisper_tuning(tuning.effective);
+        }
     }
+    Ok(tuning)
+}
 
-    // Load new model
-    let new_transcriber = Transcriber::new(&model_id, model_path.to_str().unwrap(), &language)
-        .map_err(CommandError::Transcription)?;
+/// Most models the warm cache may hold, whatever the setting says
+const MAX_WARM_MODELS: i64 = 4;
 
-    let mut transcriber_guard = transcriber.lock().unwrap();
-    *transcriber_guard = Some(new_transcriber);
+/// Memory budget used when available memory cannot be read
+const WARM_CACHE_FALLBACK_BYTES: u64 = 2 * 1024 * 1024 * 1024;
 
-    info!("Model loaded: {} (language: {})", model_id, language);
+fn warm_cache_capacity(db: &Database) -> usize {
+    db.get_settings()
+        .map(|settings| settings.warm_model_cache_size)
+        .unwrap_or_else(|_| AppSettings::default().warm_model_cache_size)
+        .clamp(0, MAX_WARM_MODELS) as usize
+}
 
-    Ok(())
+/// Warm models may use at most half of the memory currently available, so
+/// large models are rarely kept alongside another one
+fn warm_cache_budget() -> u64 {
+    system_info::available_memory_bytes()
+        .map(|bytes| bytes / 2)
+        .unwrap_or(WARM_CACHE_FALLBACK_BYTES)
 }
 
+/// Unload the active model. Warm models are released as well, since
+/// unloading is how users free memory.
 #[tauri::command]
-fn unload_model(transcriber: State<TranscriberState>) -> CommandResult<()> {
-    let mut transcriber_guard = transcriber.0.lock().unwrap();
-    *transcriber_guard = None;
+fn unload_model(
+    app: tauri::AppHandle,
+    transcriber: State<TranscriberState>,
+    loaded_model: State<LoadedModelState>,
+    warm_cache: State<WarmModelCacheState>,
+) -> CommandResult<()> {
+    *transcriber.0.lock().unwrap() = None;
+    *loaded_model.0.lock().unwrap() = None;
+    warm_cache.0.lock().unwrap().clear();
+    refresh_tray_items(&app);
     info!("Model unloaded");
     Ok(())
 }
 
+#[tauri::command]
+fn get_warm_models(warm_cache: State<WarmModelCacheState>) -> Vec<WarmModelInfo> {
+    warm_cache.0.lock().unwrap().entries()
+}
+
+#[tauri::command]
+fn clear_warm_models(warm_cache: State<WarmModelCacheState>) -> usize {
+    let cleared = warm_cache.0.lock().unwrap().clear();
+    info!("Released {} warm models", cleared);
+    cleared
+}
+
+/// Switch the loaded model's language in place instead of reloading it
+#[tauri::command]
+fn set_transcription_language(
+    db: State<DbState>,
+    transcriber: State<TranscriberState>,
+    loaded_model: State<LoadedModelState>,
+    language: String,
+) -> CommandResult<LoadedModelInfo> {
+    let mut transcriber_guard = transcriber.0.lock().unwrap();
+    let mut loaded_guard = loaded_model.0.lock().unwrap();
+    let (Some(active), Some(info)) = (transcriber_guard.as_mut(), loaded_guard.as_mut()) else {
+        return Err(CommandError::Transcription("No model loaded".to_string()));
+    };
+
+    validate_model_language(&db.0, &info.model_id, &language)
+        .map_err(CommandError::Transcription)?;
+
+    active.set_language(&language);
+    info.language = language;
+    info!(
+        "Transcription language set to {} for {}",
+        info.language, info.model_id
+    );
+
+    Ok(info.clone())
+}
+
+#[tauri::command]
+fn get_loaded_model(
+    loaded_model: State<LoadedModelState>,
+) -> CommandResult<Option<LoadedModelInfo>> {
+    Ok(loaded_model.0.lock().unwrap().clone())
+}
+
+#[tauri::command]
+fn get_supported_languages(
+    db: State<DbState>,
+    model_id: String,
+) -> CommandResult<Vec<LanguageOption>> {
+    let model =
+        db.0.get_model(&model_id)?
+            .ok_or_else(|| CommandError::Transcription(format!("Unknown model: {}", model_id)))?;
+
+    Ok(model_language_options(&model.id, &model.languages))
+}
+
 #[tauri::command]
 async fn transcribe_audio(
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
     transcriber: State<'_, TranscriberState>,
     audio_samples: Vec<f32>,
-) -> CommandResult<String> {
+) -> CommandResult<Transcript> {
     let db = db.0.clone();
     let license_manager = license_manager.0.clone();
     let transcriber = transcriber.0.clone();
@@ -856,127 +2706,897 @@ async fn transcribe_audio(
     let mut transcriber_guard = transcriber.lock().unwrap();
 
     if let Some(ref mut t) = *transcriber_guard {
-        let text = t
-            .transcribe(&audio_samples)
-            .map_err(CommandError::Transcription)?;
-        Ok(text)
+        ensure_min_recording_length(audio_samples.len(), min_recording_samples(&db))?;
+        t.clear_abort();
+        let (text, detected_language) =
+            transcribe_samples(t, &audio_samples, silence_pad_samples(&db))?;
+        Ok(Transcript {
+            text: handle_empty_transcription(text, &empty_transcription_behavior(&db))?,
+            detected_language,
+        })
     } else {
         Err(CommandError::Transcription("No model loaded".to_string()))
     }
 }
 
+/// Transcribe `audio_samples` into timed segments, for subtitles or seeking
+/// through history. Slower than `transcribe_audio`, which dictation uses.
+#[tauri::command]
+async fn transcribe_audio_segments(
+    db: State<'_, DbState>,
+    license_manager: State<'_, LicenseManagerState>,
+    transcriber: State<'_, TranscriberState>,
+    audio_samples: Vec<f32>,
+) -> CommandResult<Vec<Segment>> {
+    let db = db.0.clone();
+    let license_manager = license_manager.0.clone();
+    let transcriber = transcriber.0.clone();
+
+    ensure_app_access_verified(&db, &license_manager).await?;
+
+    let mut transcriber_guard = transcriber.lock().unwrap();
+    let t = transcriber_guard
+        .as_mut()
+        .ok_or_else(|| CommandError::Transcription("No model loaded".to_string()))?;
+
+    ensure_min_recording_length(audio_samples.len(), min_recording_samples(&db))?;
+    let padded = transcription::pad_with_silence(&audio_samples, silence_pad_samples(&db));
+    t.clear_abort();
+    let segments = t
+        .transcribe_with_timestamps(&padded)
+        .map_err(CommandError::Transcription)?;
+    let segments = transcription::clamp_segments(segments, audio_samples.len());
+    if segments.is_empty() {
+        handle_empty_transcription(String::new(), &empty_transcription_behavior(&db))?;
+    }
+    Ok(segments)
+}
+
+/// Payload of the `transcription-queued` event
+#[derive(Debug, Clone, serde::Serialize)]
+struct TranscriptionQueued {
+    request_id: u64,
+    /// Requests ahead of this one, including the running one
+    ahead: usize,
+}
+
+/// Payload of the `transcription-started` and `transcription-done` events
+#[derive(Debug, Clone, serde::Serialize)]
+struct TranscriptionProgress {
+    request_id: u64,
+    /// Why the request failed; only set on `transcription-done`
+    error: Option<String>,
+}
+
+/// Stop recording and queue the clip for transcription. Requests run one at
+/// a time in arrival order on a blocking thread, reported through the
+/// `transcription-queued`, `transcription-started` and `transcription-done`
+/// events. A full queue is rejected with a retry-after hint.
 #[tauri::command]
 async fn record_and_transcribe(
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
     recorder: State<'_, RecorderState>,
     transcriber: State<'_, TranscriberState>,
-) -> CommandResult<String> {
+    latency: State<'_, LatencyState>,
+    queue: State<'_, TranscriptionQueueState>,
+) -> CommandResult<Transcript> {
     let db = db.0.clone();
     let license_manager = license_manager.0.clone();
     let recorder = recorder.0.clone();
     let transcriber = transcriber.0.clone();
+    let latency = latency.0.clone();
+    let queue = queue.0.clone();
 
     ensure_app_access_verified(&db, &license_manager).await?;
 
-    // Stop recording first
-    let samples = {
-        let mut recorder_guard = recorder.lock().unwrap();
-        if let Some(ref mut rec) = *recorder_guard {
-            rec.stop_recording().map_err(CommandError::Recording)?
-        } else {
-            return Err(CommandError::Recording(
-                "No recorder initialized".to_string(),
-            ));
+    // Reserve a queue slot before stopping, so a full queue leaves the
+    // recording running to be stopped again instead of losing it
+    let mut ticket = queue.enqueue().map_err(|retry_after| {
+        let retry_after_ms = retry_after.as_millis() as u64;
+        let _ = app.emit(
+            "rate-limited",
+            RateLimitedPayload {
+                action: "record_and_transcribe".to_string(),
+                retry_after_ms,
+            },
+        );
+        CommandError::RateLimited {
+            message: "Too many dictations are waiting to be transcribed. Still recording; \
+                      stop again in a moment."
+                .to_string(),
+            retry_after_ms,
         }
-    };
+    })?;
 
-    // Transcribe
-    let mut transcriber_guard = transcriber.lock().unwrap();
-    if let Some(ref mut t) = *transcriber_guard {
-        let text = t
-            .transcribe(&samples)
-            .map_err(CommandError::Transcription)?;
-        Ok(text)
-    } else {
-        Err(CommandError::Transcription("No model loaded".to_string()))
-    }
+    release_cancel_hotkey(&app);
+    let started = Instant::now();
+    let samples = stop_recording_samples(&recorder);
+    let stop_recording = started.elapsed();
+    refresh_tray_items(&app);
+    let samples = samples?;
+    ensure_min_recording_length(samples.len(), min_recording_samples(&db))?;
+
+    let request_id = ticket.id();
+    let _ = app.emit(
+        "transcription-queued",
+        TranscriptionQueued {
+            request_id,
+            ahead: ticket.ahead(),
+        },
+    );
+
+    let pad_samples = silence_pad_samples(&db);
+    let worker_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        ticket.wait_turn();
+        let _ = worker_app.emit(
+            "transcription-started",
+            TranscriptionProgress {
+                request_id,
+                error: None,
+            },
+        );
+        let transcribe_started = Instant::now();
+        let transcript = transcribe_with_loaded_model(&transcriber, &samples, pad_samples)?;
+        Ok((transcript, transcribe_started.elapsed()))
+    })
+    .await
+    .map_err(|e| CommandError::Transcription(format!("Transcription worker failed: {}", e)))
+    .and_then(|result| result);
+
+    let _ = app.emit(
+        "transcription-done",
+        TranscriptionProgress {
+            request_id,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        },
+    );
+    let ((text, detected_language), transcribe_time) = result?;
+
+    let breakdown =
+        latency
+            .lock()
+            .unwrap()
+            .record_transcription(started, stop_recording, transcribe_time);
+    let _ = app.emit("latency-breakdown", &breakdown);
+
+    Ok(Transcript {
+        text: handle_empty_transcription(text, &empty_transcription_behavior(&db))?,
+        detected_language,
+    })
 }
 
+/// Stage timings of the last `record_and_transcribe` run, including the
+/// post-processing and injection that followed it
 #[tauri::command]
-async fn transcribe_file(
+fn get_last_latency_breakdown(latency: State<LatencyState>) -> Option<LatencyBreakdown> {
+    latency.0.lock().unwrap().last()
+}
+
+/// Transcribe every take recorded since the last fresh `start_recording`,
+/// stopping the current take if one is running
+#[tauri::command]
+async fn finalize_recording(
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
+    recorder: State<'_, RecorderState>,
     transcriber: State<'_, TranscriberState>,
-    rate_limiter: State<'_, TranscriptionRateLimiter>,
-    file_path: String,
-) -> CommandResult<String> {
+) -> CommandResult<Transcript> {
     let db = db.0.clone();
     let license_manager = license_manager.0.clone();
+    let recorder = recorder.0.clone();
     let transcriber = transcriber.0.clone();
-    let rate_limiter = rate_limiter.0.clone();
 
     ensure_app_access_verified(&db, &license_manager).await?;
 
-    // Rate limiting check
-    if !rate_limiter.check("transcribe_file") {
-        return Err(CommandError::Transcription(
-            "Rate limit exceeded. Please wait before transcribing another file.".to_string(),
-        ));
-    }
+    release_cancel_hotkey(&app);
+    let samples = {
+        let mut recorder_guard = recorder.lock().unwrap();
+        match recorder_guard.as_mut() {
+            Some(rec) => rec.finish_takes().map_err(CommandError::Recording)?,
+            None => {
+                return Err(CommandError::Recording(
+                    "No recorder initialized".to_string(),
+                ))
+            }
+        }
+    };
+    refresh_tray_items(&app);
 
-    let safe_path =
-        canonicalize_existing_file_path(&file_path).map_err(CommandError::Transcription)?;
+    ensure_min_recording_length(samples.len(), min_recording_samples(&db))?;
 
-    if !path_has_extension(&safe_path, AUDIO_FILE_EXTENSIONS) {
-        return Err(CommandError::Transcription(
-            "Unsupported audio format. Please use WAV, MP3, M4A, OGG, FLAC, AAC, or WebM."
-                .to_string(),
-        ));
+    let (text, detected_language) = {
+        let mut transcriber_guard = transcriber.lock().unwrap();
+        let t = transcriber_guard
+            .as_mut()
+            .ok_or_else(|| CommandError::Transcription("No model loaded".to_string()))?;
+        t.clear_abort();
+        transcribe_samples(t, &samples, silence_pad_samples(&db))?
+    };
+    Ok(Transcript {
+        text: handle_empty_transcription(text, &empty_transcription_behavior(&db))?,
+        detected_language,
+    })
+}
+
+const NO_SPEECH_DETECTED: &str = "No speech detected";
+
+/// Apply the `empty_transcription_behavior` setting to a transcription result:
+/// `"error"` turns a blank result into a distinct error, anything else
+/// returns an empty string.
+fn handle_empty_transcription(text: String, behavior: &str) -> CommandResult<String> {
+    if !text.trim().is_empty() {
+        return Ok(text);
     }
 
-    // Check file size (max 500MB)
-    let metadata = std::fs::metadata(&safe_path)
-        .map_err(|e| CommandError::Transcription(format!("Cannot read file: {}", e)))?;
-    if metadata.len() > 500 * 1024 * 1024 {
-        return Err(CommandError::Transcription(
-            "File too large. Maximum size is 500MB.".to_string(),
-        ));
+    if behavior == "error" {
+        Err(CommandError::Transcription(NO_SPEECH_DETECTED.to_string()))
+    } else {
+        Ok(String::new())
     }
+}
 
-    // Read audio file and convert to capped 16kHz mono samples.
-    let samples = read_audio_file(&safe_path)
-        .map_err(|e| CommandError::Transcription(format!("Failed to read audio file: {}", e)))?;
+/// Reject `empty_transcription_behavior` values other than `"empty"` and
+/// `"error"`, which `handle_empty_transcription` would quietly treat as
+/// `"empty"`
+fn check_empty_transcription_behavior(behavior: &str) -> Result<(), String> {
+    if matches!(behavior, "empty" | "error") {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid empty transcription behavior: {}",
+            behavior
+        ))
+    }
+}
+
+const RECORDING_TOO_SHORT: &str = "Recording too short";
+
+/// Padding past Whisper's 30 second window would only add work
+const MAX_SILENCE_PAD_MS: i64 = 30_000;
+
+/// Reject clips shorter than the `min_recording_ms` setting before running
+/// inference. This is checked against the raw capture, before any silence
+/// trimming, so short speech inside a longer clip still transcribes.
+fn ensure_min_recording_length(sample_count: usize, min_samples: usize) -> CommandResult<()> {
+    if sample_count < min_samples {
+        return Err(CommandError::Recording(RECORDING_TOO_SHORT.to_string()));
+    }
+    Ok(())
+}
+
+fn min_recording_samples(db: &Database) -> usize {
+    let min_ms = db
+        .get_settings()
+        .map(|settings| settings.min_recording_ms)
+        .unwrap_or_else(|_| AppSettings::default().min_recording_ms)
+        .max(0) as usize;
+    min_ms * AUDIO_TARGET_SAMPLE_RATE as usize / 1000
+}
+
+fn silence_pad_samples(db: &Database) -> usize {
+    let pad_ms = db
+        .get_settings()
+        .map(|settings| settings.silence_pad_ms)
+        .unwrap_or_else(|_| AppSettings::default().silence_pad_ms)
+        .max(0) as usize;
+    pad_ms * AUDIO_TARGET_SAMPLE_RATE as usize / 1000
+}
+
+/// Run the model on `samples`, padding clips shorter than `pad_samples` with
+/// trailing silence first. Decoding loops are stripped from padded results,
+/// since the added silence is where Whisper tends to produce them. Returns
+/// the text and the language it was detected in, see `detected_language`.
+fn transcribe_samples<T: TranscriptionEngine + ?Sized>(
+    engine: &mut T,
+    samples: &[f32],
+    pad_samples: usize,
+) -> CommandResult<(String, Option<String>)> {
+    let padded = transcription::pad_with_silence(samples, pad_samples);
+    let (text, language) = engine
+        .transcribe_detecting_language(&padded)
+        .map_err(CommandError::Transcription)?;
+    let text = if padded.len() > samples.len() {
+        transcription::suppress_repeated_phrases(&transcription::strip_trailing_loop(&text))
+    } else {
+        text
+    };
+    Ok((text, detected_language(language)))
+}
+
+/// Language an engine reported, or `None` for "auto" when it couldn't tell
+fn detected_language(language: String) -> Option<String> {
+    (language != "auto").then_some(language)
+}
+
+/// Language to store with a history entry: a requested "auto" becomes the
+/// detected language when the caller passes one
+fn history_language(language: &str, detected_language: Option<&str>) -> String {
+    match detected_language {
+        Some(detected) if language == "auto" => detected.to_string(),
+        _ => language.to_string(),
+    }
+}
+
+/// Text of a dictation and the language it was detected in, so the caller
+/// can pass that on when saving it to history
+#[derive(Debug, Clone, serde::Serialize)]
+struct Transcript {
+    text: String,
+    /// `None` unless the model ran with "auto" and reported a language
+    detected_language: Option<String>,
+}
+
+fn empty_transcription_behavior(db: &Database) -> String {
+    db.get_settings()
+        .map(|settings| settings.empty_transcription_behavior)
+        .unwrap_or_default()
+}
+
+/// Transcription result with speaking-rate statistics, returned by
+/// `record_and_transcribe_scored`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+struct ScoredTranscription {
+    text: String,
+    word_count: usize,
+    /// Words per minute; `None` when the recording has no measurable duration.
+    wpm: Option<f64>,
+    duration_ms: u64,
+    /// Set when the transcription came back empty
+    no_speech: bool,
+}
+
+/// Duration of `sample_count` samples at the transcription sample rate
+fn samples_to_duration_ms(sample_count: usize) -> u64 {
+    (sample_count as u64 * 1000) / AUDIO_TARGET_SAMPLE_RATE as u64
+}
+
+fn score_transcription(text: String, sample_count: usize) -> ScoredTranscription {
+    let word_count = text.split_whitespace().count();
+    let duration_ms = samples_to_duration_ms(sample_count);
+    let wpm = if duration_ms == 0 {
+        None
+    } else {
+        Some(word_count as f64 / (duration_ms as f64 / 60_000.0))
+    };
+
+    ScoredTranscription {
+        text,
+        word_count,
+        wpm,
+        duration_ms,
+        no_speech: word_count == 0,
+    }
+}
+
+/// Same as `record_and_transcribe`, but also reports word count and speaking rate.
+#[tauri::command]
+async fn record_and_transcribe_scored(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    license_manager: State<'_, LicenseManagerState>,
+    recorder: State<'_, RecorderState>,
+    transcriber: State<'_, TranscriberState>,
+) -> CommandResult<ScoredTranscription> {
+    let db = db.0.clone();
+    let license_manager = license_manager.0.clone();
+    let recorder = recorder.0.clone();
+    let transcriber = transcriber.0.clone();
+
+    ensure_app_access_verified(&db, &license_manager).await?;
+
+    release_cancel_hotkey(&app);
+    let (text, sample_count) = stop_and_transcribe(
+        &app,
+        &recorder,
+        &transcriber,
+        min_recording_samples(&db),
+        silence_pad_samples(&db),
+    )?;
+    let text = handle_empty_transcription(text, &empty_transcription_behavior(&db))?;
+    Ok(score_transcription(text, sample_count))
+}
+
+/// Stops the active recording and transcribes it, returning the text, the
+/// number of captured samples and how long stopping the recorder took.
+fn stop_and_transcribe(
+    app: &tauri::AppHandle,
+    recorder: &Mutex<Option<AudioRecorder>>,
+    transcriber: &Mutex<Option<Transcriber>>,
+    min_samples: usize,
+    pad_samples: usize,
+) -> CommandResult<(String, usize)> {
+    // Stop recording first
+    let samples = stop_recording_samples(recorder);
+    refresh_tray_items(app);
+    let samples = samples?;
+
+    ensure_min_recording_length(samples.len(), min_samples)?;
+
+    let (text, _) = transcribe_with_loaded_model(transcriber, &samples, pad_samples)?;
+    Ok((text, samples.len()))
+}
+
+fn stop_recording_samples(recorder: &Mutex<Option<AudioRecorder>>) -> CommandResult<Vec<f32>> {
+    let mut recorder_guard = recorder.lock().unwrap();
+    if let Some(ref mut rec) = *recorder_guard {
+        rec.stop_recording().map_err(CommandError::Recording)
+    } else {
+        Err(CommandError::Recording(
+            "No recorder initialized".to_string(),
+        ))
+    }
+}
 
-    // Transcribe
+fn transcribe_with_loaded_model(
+    transcriber: &Mutex<Option<Transcriber>>,
+    samples: &[f32],
+    pad_samples: usize,
+) -> CommandResult<(String, Option<String>)> {
     let mut transcriber_guard = transcriber.lock().unwrap();
     if let Some(ref mut t) = *transcriber_guard {
-        let text = t
-            .transcribe(&samples)
-            .map_err(CommandError::Transcription)?;
-        Ok(text)
+        t.clear_abort();
+        transcribe_samples(t, samples, pad_samples)
     } else {
         Err(CommandError::Transcription("No model loaded".to_string()))
     }
 }
 
-fn read_audio_file(file_path: &std::path::Path) -> Result<Vec<f32>, String> {
-    use std::fs::File;
-    use symphonia::core::audio::SampleBuffer;
-    use symphonia::core::codecs::DecoderOptions;
+/// Result of `transcribe_file`
+#[derive(Debug, Clone, serde::Serialize)]
+struct FileTranscriptionResult {
+    text: String,
+    /// Length of the decoded audio
+    duration_ms: u64,
+    /// Language of the transcription; `None` when the model auto-detected it
+    /// without reporting which
+    detected_language: Option<String>,
+    /// Decoded samples per channel at the transcription sample rate
+    sample_count: usize,
+}
+
+/// Transcribe an audio file of any length, in windows cut at pauses so long
+/// recordings aren't truncated to their first segment
+#[tauri::command]
+async fn transcribe_file(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    license_manager: State<'_, LicenseManagerState>,
+    transcriber: State<'_, TranscriberState>,
+    rate_limiter: State<'_, TranscriptionRateLimiter>,
+    loaded_model: State<'_, LoadedModelState>,
+    file_path: String,
+    split_channels: Option<bool>,
+) -> CommandResult<FileTranscriptionResult> {
+    let db = db.0.clone();
+    let license_manager = license_manager.0.clone();
+    let transcriber = transcriber.0.clone();
+    let rate_limiter = rate_limiter.0.clone();
+
+    ensure_app_access_verified(&db, &license_manager).await?;
+
+    // Rate limiting check
+    enforce_rate_limit(
+        &app,
+        &rate_limiter,
+        "transcribe_file",
+        "Rate limit exceeded. Please wait before transcribing another file.",
+    )?;
+
+    let safe_path = validate_audio_file_path(&file_path)?;
+
+    // Read audio file and convert to capped 16kHz samples: mono by default,
+    // or one buffer per side for stereo files when splitting is requested.
+    let channels = read_audio_channels(&safe_path, split_channels.unwrap_or(false))
+        .map_err(|e| CommandError::Transcription(format!("Failed to read audio file: {}", e)))?;
+
+    transcribe_decoded_channels(&db, &transcriber, &loaded_model.0, &channels)
+}
+
+/// Language to report for a file transcription: the configured one, or the
+/// detected one when the model was loaded with "auto"
+fn reported_language(
+    loaded_model: &Mutex<Option<LoadedModelInfo>>,
+    detected_language: Option<String>,
+) -> Option<String> {
+    loaded_model
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|info| info.language.clone())
+        .filter(|language| language != "auto")
+        .or(detected_language)
+}
+
+/// Resolve a user-picked audio file, rejecting unsupported formats and files
+/// over the size limit
+fn validate_audio_file_path(file_path: &str) -> CommandResult<std::path::PathBuf> {
+    let safe_path =
+        canonicalize_existing_file_path(file_path).map_err(CommandError::Transcription)?;
+
+    let supported = supported_audio_extensions();
+    if !path_has_extension(&safe_path, &supported) {
+        return Err(CommandError::Transcription(format!(
+            "Unsupported audio format. Please use {}.",
+            supported.join(", ").to_uppercase()
+        )));
+    }
+
+    let metadata = std::fs::metadata(&safe_path)
+        .map_err(|e| CommandError::Transcription(format!("Cannot read file: {}", e)))?;
+    ensure_audio_size_allowed(metadata.len())?;
+    Ok(safe_path)
+}
+
+fn ensure_audio_size_allowed(size_bytes: u64) -> CommandResult<()> {
+    if size_bytes > MAX_AUDIO_FILE_BYTES {
+        return Err(CommandError::Transcription(
+            "File too large. Maximum size is 500MB.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Transcribe decoded 16 kHz buffers, labelling the transcripts when there
+/// is one buffer per stereo channel
+fn transcribe_decoded_channels(
+    db: &Database,
+    transcriber: &Mutex<Option<Transcriber>>,
+    loaded_model: &Mutex<Option<LoadedModelInfo>>,
+    channels: &[Vec<f32>],
+) -> CommandResult<FileTranscriptionResult> {
+    let mut transcriber_guard = transcriber.lock().unwrap();
+    if let Some(ref mut t) = *transcriber_guard {
+        // Cleared once, not per window, so a cancel between windows holds
+        t.clear_abort();
+        let (transcripts, languages): (Vec<_>, Vec<_>) = channels
+            .iter()
+            .map(|samples| t.transcribe_long(samples))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(CommandError::Transcription)?
+            .into_iter()
+            .unzip();
+        // Split channels share one language setting, so the first speaks for all
+        let detected = languages.into_iter().next().and_then(detected_language);
+
+        let text = if transcripts.len() > 1 {
+            label_channel_transcripts(&transcripts)
+        } else {
+            transcripts.into_iter().next().unwrap_or_default()
+        };
+        let text = handle_empty_transcription(text, &empty_transcription_behavior(db))?;
+
+        // Split channels all cover the same span of the file
+        let sample_count = channels.first().map_or(0, Vec::len);
+        let detected_language = reported_language(loaded_model, detected);
+
+        Ok(FileTranscriptionResult {
+            text,
+            duration_ms: samples_to_duration_ms(sample_count),
+            detected_language,
+            sample_count,
+        })
+    } else {
+        Err(CommandError::Transcription("No model loaded".to_string()))
+    }
+}
+
+/// File extension symphonia should probe for an audio MIME type. Parameters
+/// such as `;codecs=opus` are ignored.
+fn audio_extension_for_mime(mime: &str) -> Option<&'static str> {
+    let essence = mime.split(';').next()?.trim().to_ascii_lowercase();
+    let extension = match essence.as_str() {
+        "audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave" => "wav",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        "audio/mp4" | "audio/m4a" | "audio/x-m4a" => "m4a",
+        "audio/flac" | "audio/x-flac" => "flac",
+        "audio/ogg" | "application/ogg" => "ogg",
+        "audio/aac" | "audio/x-aac" => "aac",
+        "audio/webm" => "webm",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+/// Transcribe audio the frontend holds in memory, such as a dropped file or
+/// a browser recording, without writing it to disk first
+#[tauri::command]
+async fn transcribe_bytes(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    license_manager: State<'_, LicenseManagerState>,
+    transcriber: State<'_, TranscriberState>,
+    rate_limiter: State<'_, TranscriptionRateLimiter>,
+    loaded_model: State<'_, LoadedModelState>,
+    data: Vec<u8>,
+    mime: String,
+) -> CommandResult<FileTranscriptionResult> {
+    let db = db.0.clone();
+    let license_manager = license_manager.0.clone();
+    let transcriber = transcriber.0.clone();
+    let rate_limiter = rate_limiter.0.clone();
+
+    ensure_app_access_verified(&db, &license_manager).await?;
+
+    enforce_rate_limit(
+        &app,
+        &rate_limiter,
+        "transcribe_bytes",
+        "Rate limit exceeded. Please wait before transcribing more audio.",
+    )?;
+
+    let supported = supported_audio_extensions();
+    let extension = audio_extension_for_mime(&mime)
+        .filter(|extension| supported.contains(extension))
+        .ok_or_else(|| {
+            CommandError::Transcription(format!(
+                "Unsupported audio format. Please use {}.",
+                supported.join(", ").to_uppercase()
+            ))
+        })?;
+    ensure_audio_size_allowed(data.len() as u64)?;
+
+    let samples = read_audio_bytes(data, extension)
+        .map_err(|e| CommandError::Transcription(format!("Failed to read audio: {}", e)))?;
+
+    transcribe_decoded_channels(&db, &transcriber, &loaded_model.0, &[samples])
+}
+
+/// Progress of `transcribe_file_streaming`, emitted after every chunk
+#[derive(Debug, Clone, serde::Serialize)]
+struct FileTranscriptionProgress {
+    file_path: String,
+    /// Transcript of the chunks finished so far
+    text: String,
+    chunks_done: usize,
+    /// Estimated from the file's length, since the audio is decoded as it
+    /// is transcribed; exact once the last chunk is done
+    chunk_count: usize,
+    percent: f32,
+}
+
+/// Transcribes a file's audio as it is decoded, one silence-split window at
+/// a time, so only about one window of audio is held in memory
+struct StreamingFileTranscription<'a> {
+    app: &'a tauri::AppHandle,
+    file_path: &'a str,
+    transcriber: &'a Mutex<Option<Transcriber>>,
+    abort: &'a AtomicBool,
+    /// Language of the last chunk with speech in it
+    detected_language: Option<String>,
+    /// Length of the file from its headers, 0 when unknown
+    estimated_samples: usize,
+    /// Decoded audio not yet transcribed
+    pending: Vec<f32>,
+    transcribed_samples: usize,
+    chunks_done: usize,
+    text: String,
+}
+
+impl StreamingFileTranscription<'_> {
+    /// Add decoded audio, transcribing every window that can no longer move.
+    /// A window's cut only depends on the window itself, so the chunks match
+    /// splitting the whole file at once.
+    fn push(&mut self, samples: &[f32]) -> CommandResult<()> {
+        self.pending.extend_from_slice(samples);
+        while self.pending.len() > transcription::LONG_FORM_WINDOW_SAMPLES {
+            let cut =
+                audio::split_on_silence(&self.pending, transcription::LONG_FORM_WINDOW_SAMPLES)[0]
+                    .end;
+            let chunk: Vec<f32> = self.pending.drain(..cut).collect();
+            self.transcribe_chunk(&chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Transcribe what is left once decoding has finished
+    fn finish(&mut self) -> CommandResult<()> {
+        let rest = std::mem::take(&mut self.pending);
+        for range in audio::split_on_silence(&rest, transcription::LONG_FORM_WINDOW_SAMPLES) {
+            self.transcribe_chunk(&rest[range])?;
+        }
+        Ok(())
+    }
+
+    fn transcribe_chunk(&mut self, chunk: &[f32]) -> CommandResult<()> {
+        if self.abort.swap(false, Ordering::SeqCst) {
+            info!(
+                "File transcription cancelled after {} chunks",
+                self.chunks_done
+            );
+            return Err(CommandError::Transcription(
+                TRANSCRIPTION_CANCELLED.to_string(),
+            ));
+        }
+
+        let chunk_text = {
+            let mut transcriber_guard = self.transcriber.lock().unwrap();
+            let t = transcriber_guard
+                .as_mut()
+                .ok_or_else(|| CommandError::Transcription("No model loaded".to_string()))?;
+            let (chunk_text, language) = t
+                .transcribe_window(chunk)
+                .map_err(CommandError::Transcription)?;
+            // Silent chunks detect nothing useful, so keep the last spoken one
+            if !chunk_text.trim().is_empty() {
+                self.detected_language = detected_language(language);
+            }
+            chunk_text
+        };
+        append_chunk_transcript(&mut self.text, &chunk_text);
+        self.transcribed_samples += chunk.len();
+        self.chunks_done += 1;
+
+        let decoded = self.transcribed_samples + self.pending.len();
+        let total = self.estimated_samples.max(decoded);
+        let remaining = total - self.transcribed_samples;
+        let _ = self.app.emit(
+            "file-transcription-progress",
+            FileTranscriptionProgress {
+                file_path: self.file_path.to_string(),
+                text: self.text.clone(),
+                chunks_done: self.chunks_done,
+                chunk_count: self.chunks_done
+                    + remaining.div_ceil(transcription::LONG_FORM_WINDOW_SAMPLES),
+                percent: self.transcribed_samples as f32 / total.max(1) as f32 * 100.0,
+            },
+        );
+        Ok(())
+    }
+}
+
+/// Transcribe a long file chunk by chunk, cutting at pauses, and emit
+/// `file-transcription-progress` with the partial transcript after each
+/// chunk. The file is decoded as it is transcribed, so unlike
+/// `transcribe_file` its length is only limited by the file size cap.
+/// `cancel_transcription` stops the run, mid-chunk on Whisper models.
+#[tauri::command]
+async fn transcribe_file_streaming(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    license_manager: State<'_, LicenseManagerState>,
+    transcriber: State<'_, TranscriberState>,
+    rate_limiter: State<'_, TranscriptionRateLimiter>,
+    loaded_model: State<'_, LoadedModelState>,
+    abort: State<'_, TranscriptionAbortState>,
+    file_path: String,
+) -> CommandResult<FileTranscriptionResult> {
+    let db = db.0.clone();
+    let license_manager = license_manager.0.clone();
+    let transcriber = transcriber.0.clone();
+    let rate_limiter = rate_limiter.0.clone();
+    let abort = abort.0.clone();
+
+    ensure_app_access_verified(&db, &license_manager).await?;
+
+    enforce_rate_limit(
+        &app,
+        &rate_limiter,
+        "transcribe_file",
+        "Rate limit exceeded. Please wait before transcribing another file.",
+    )?;
+
+    let safe_path = validate_audio_file_path(&file_path)?;
+    let extension = safe_path.extension().and_then(|e| e.to_str());
+    let open = || {
+        std::fs::File::open(&safe_path)
+            .map_err(|e| CommandError::Transcription(format!("Failed to read audio file: {}", e)))
+    };
+    let estimated_samples = probe_audio_info(Box::new(open()?), extension)
+        .map(|info| (info.duration_ms * AUDIO_TARGET_SAMPLE_RATE as u64 / 1000) as usize)
+        .unwrap_or(0);
+
+    abort.store(false, Ordering::SeqCst);
+    let mut stream = StreamingFileTranscription {
+        app: &app,
+        file_path: &file_path,
+        transcriber: &transcriber,
+        abort: &abort,
+        detected_language: None,
+        estimated_samples,
+        pending: Vec::new(),
+        transcribed_samples: 0,
+        chunks_done: 0,
+        text: String::new(),
+    };
+
+    // Errors from transcribing a chunk are kept aside so they are not
+    // reported as decoding failures
+    let mut chunk_error = None;
+    let decoded = decode_audio_with(Box::new(open()?), extension, false, |_, samples| {
+        stream.push(samples).map_err(|e| {
+            let message = e.to_string();
+            chunk_error = Some(e);
+            message
+        })
+    });
+    if let Some(e) = chunk_error {
+        return Err(e);
+    }
+    decoded
+        .map_err(|e| CommandError::Transcription(format!("Failed to read audio file: {}", e)))?;
+    stream.finish()?;
+
+    let sample_count = stream.transcribed_samples;
+    let text = handle_empty_transcription(stream.text, &empty_transcription_behavior(&db))?;
+    let detected_language = reported_language(&loaded_model.0, stream.detected_language);
+
+    Ok(FileTranscriptionResult {
+        text,
+        duration_ms: samples_to_duration_ms(sample_count),
+        detected_language,
+        sample_count,
+    })
+}
+
+/// Stop the running transcription. Whisper bails out mid-decode; other
+/// engines finish their current pass, and a streamed file stops before its
+/// next chunk. The interrupted command fails with `TRANSCRIPTION_CANCELLED`.
+#[tauri::command]
+fn cancel_transcription(abort: State<'_, TranscriptionAbortState>) {
+    abort.0.store(true, Ordering::SeqCst);
+}
+
+/// Extensions from `AUDIO_FILE_FORMATS` that this build can decode
+fn supported_audio_extensions() -> Vec<&'static str> {
+    let codecs = symphonia::default::get_codecs();
+    AUDIO_FILE_FORMATS
+        .iter()
+        .filter(|(_, codec_types)| {
+            codec_types
+                .iter()
+                .any(|&codec_type| codecs.get_codec(codec_type).is_some())
+        })
+        .map(|&(extension, _)| extension)
+        .collect()
+}
+
+/// Audio file extensions that can be transcribed, for file picker filters
+#[tauri::command]
+fn supported_audio_formats() -> Vec<String> {
+    supported_audio_extensions()
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+/// Decode an audio file to 16kHz buffers. With `split_stereo`, a 2-channel
+/// file yields separate left and right buffers; anything else is downmixed
+/// to a single mono buffer.
+fn read_audio_channels(
+    file_path: &std::path::Path,
+    split_stereo: bool,
+) -> Result<Vec<Vec<f32>>, String> {
+    let file = std::fs::File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
+    let extension = file_path.extension().and_then(|e| e.to_str());
+    decode_audio_channels(Box::new(file), extension, split_stereo)
+}
+
+/// Decode in-memory audio the same way as a file with `extension`
+fn read_audio_bytes(data: Vec<u8>, extension: &str) -> Result<Vec<f32>, String> {
+    decode_audio_channels(Box::new(std::io::Cursor::new(data)), Some(extension), false)
+        .map(|mut channels| channels.remove(0))
+}
+
+/// Probe the container format of `source` without decoding any audio
+fn probe_audio_format(
+    source: Box<dyn symphonia::core::io::MediaSource>,
+    extension: Option<&str>,
+) -> Result<Box<dyn symphonia::core::formats::FormatReader>, String> {
     use symphonia::core::formats::FormatOptions;
     use symphonia::core::io::MediaSourceStream;
     use symphonia::core::meta::MetadataOptions;
     use symphonia::core::probe::Hint;
 
-    let file = File::open(file_path).map_err(|e| format!("Failed to open file: {}", e))?;
-
     // Create a media source stream
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mss = MediaSourceStream::new(source, Default::default());
 
     // Create a hint to help the format registry guess what format reader is appropriate
     let mut hint = Hint::new();
-    if let Some(ext) = file_path.extension().and_then(|e| e.to_str()) {
+    if let Some(ext) = extension {
         hint.with_extension(ext);
     }
 
@@ -990,14 +3610,129 @@ fn read_audio_file(file_path: &std::path::Path) -> Result<Vec<f32>, String> {
         )
         .map_err(|e| format!("Failed to probe audio format: {}", e))?;
 
-    let mut format = probed.format;
+    Ok(probed.format)
+}
 
-    // Find the first audio track
-    let track = format
+/// The first track of `format` that carries audio
+fn first_audio_track(
+    format: &dyn symphonia::core::formats::FormatReader,
+) -> Result<&symphonia::core::formats::Track, String> {
+    format
         .tracks()
         .iter()
         .find(|t| t.codec_params.codec != symphonia::core::codecs::CODEC_TYPE_NULL)
-        .ok_or_else(|| "No audio track found".to_string())?;
+        .ok_or_else(|| "No audio track found".to_string())
+}
+
+/// Length and layout of an audio file, from `get_audio_duration`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct AudioFileInfo {
+    duration_ms: u64,
+    sample_rate: u32,
+    channels: usize,
+}
+
+/// Read an audio file's length from its headers. Containers that don't
+/// record the frame count are measured by walking packet timestamps, still
+/// without decoding any audio.
+fn probe_audio_info(
+    source: Box<dyn symphonia::core::io::MediaSource>,
+    extension: Option<&str>,
+) -> Result<AudioFileInfo, String> {
+    let mut format = probe_audio_format(source, extension)?;
+    let track = first_audio_track(format.as_ref())?;
+    let track_id = track.id;
+    let params = track.codec_params.clone();
+    let sample_rate = params
+        .sample_rate
+        .ok_or_else(|| "Audio file does not report a sample rate".to_string())?;
+    let channels = params.channels.map(|c| c.count()).unwrap_or(1).max(1);
+
+    let frames = match params.n_frames {
+        Some(frames) => frames,
+        None => {
+            let mut end = 0;
+            loop {
+                match format.next_packet() {
+                    Ok(packet) if packet.track_id() == track_id => {
+                        end = end.max(packet.ts() + packet.dur());
+                    }
+                    Ok(_) => {}
+                    Err(symphonia::core::errors::Error::IoError(ref e))
+                        if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                    {
+                        break
+                    }
+                    Err(e) => return Err(format!("Failed to read packet: {}", e)),
+                }
+            }
+            end
+        }
+    };
+
+    // Timestamps are in the track's time base, which is usually one tick
+    // per frame
+    let duration_ms = match params.time_base {
+        Some(time_base) => {
+            let time = time_base.calc_time(frames);
+            time.seconds * 1000 + (time.frac * 1000.0).round() as u64
+        }
+        None => frames * 1000 / u64::from(sample_rate),
+    };
+
+    Ok(AudioFileInfo {
+        duration_ms,
+        sample_rate,
+        channels,
+    })
+}
+
+/// Length, sample rate and channel count of an audio file, read from its
+/// headers without transcribing it
+#[tauri::command]
+fn get_audio_duration(path: String) -> CommandResult<AudioFileInfo> {
+    let safe_path = validate_audio_file_path(&path)?;
+    let file = std::fs::File::open(&safe_path)
+        .map_err(|e| CommandError::Transcription(format!("Failed to open file: {}", e)))?;
+    let extension = safe_path.extension().and_then(|e| e.to_str());
+    probe_audio_info(Box::new(file), extension).map_err(CommandError::Transcription)
+}
+
+fn decode_audio_channels(
+    source: Box<dyn symphonia::core::io::MediaSource>,
+    extension: Option<&str>,
+    split_stereo: bool,
+) -> Result<Vec<Vec<f32>>, String> {
+    let mut outputs: Vec<Vec<f32>> = Vec::new();
+    let output_channels = decode_audio_with(source, extension, split_stereo, |index, samples| {
+        if outputs.len() <= index {
+            outputs.resize_with(index + 1, || {
+                Vec::with_capacity(
+                    (AUDIO_TARGET_SAMPLE_RATE as usize * 60).min(MAX_FILE_AUDIO_SAMPLES),
+                )
+            });
+        }
+        append_audio_samples_with_limit(&mut outputs[index], samples, MAX_FILE_AUDIO_SAMPLES)
+    })?;
+    outputs.resize_with(output_channels, Vec::new);
+    Ok(outputs)
+}
+
+/// Decode `source` to 16kHz and hand each decoded block to `on_samples`
+/// along with its output channel index, without keeping the audio. Output
+/// channels are as for `read_audio_channels`, and their count is returned.
+/// An error from `on_samples` stops decoding and is returned as is.
+fn decode_audio_with(
+    source: Box<dyn symphonia::core::io::MediaSource>,
+    extension: Option<&str>,
+    split_stereo: bool,
+    mut on_samples: impl FnMut(usize, &[f32]) -> Result<(), String>,
+) -> Result<usize, String> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::DecoderOptions;
+
+    let mut format = probe_audio_format(source, extension)?;
+    let track = first_audio_track(format.as_ref())?;
 
     let track_id = track.id;
     let sample_rate = track.codec_params.sample_rate.unwrap_or(44100);
@@ -1013,8 +3748,14 @@ fn read_audio_file(file_path: &std::path::Path) -> Result<Vec<f32>, String> {
         .make(&track.codec_params, &DecoderOptions::default())
         .map_err(|e| format!("Failed to create decoder: {}", e))?;
 
-    let mut samples =
-        Vec::with_capacity((AUDIO_TARGET_SAMPLE_RATE as usize * 60).min(MAX_FILE_AUDIO_SAMPLES));
+    let output_channels = if split_stereo && channels == 2 { 2 } else { 1 };
+    // One filter per output so state carries across packets
+    let mut resamplers: Vec<Option<audio::Resampler>> = (0..output_channels)
+        .map(|_| {
+            (sample_rate != AUDIO_TARGET_SAMPLE_RATE)
+                .then(|| audio::Resampler::new(sample_rate, AUDIO_TARGET_SAMPLE_RATE))
+        })
+        .collect();
 
     // Decode all packets
     loop {
@@ -1044,26 +3785,36 @@ fn read_audio_file(file_path: &std::path::Path) -> Result<Vec<f32>, String> {
             Err(e) => return Err(format!("Failed to decode: {}", e)),
         };
 
-        // Convert the current packet to f32 samples, then immediately fold it
-        // into the capped mono 16kHz buffer. This avoids retaining the full
-        // decoded source stream in memory.
+        // Convert the current packet to f32 samples and pass it on right
+        // away, so the full decoded source stream is never held in memory.
         let spec = *decoded.spec();
         let duration = decoded.capacity() as u64;
         let mut sample_buf = SampleBuffer::<f32>::new(duration, spec);
         sample_buf.copy_interleaved_ref(decoded);
 
-        let mono = interleaved_to_mono(sample_buf.samples(), channels);
+        for (index, resampler) in resamplers.iter_mut().enumerate() {
+            let channel = if output_channels == 1 {
+                interleaved_to_mono(sample_buf.samples(), channels)
+            } else {
+                deinterleave_channel(sample_buf.samples(), channels, index)
+            };
 
-        let normalized = if sample_rate != AUDIO_TARGET_SAMPLE_RATE {
-            resample_audio(&mono, sample_rate, AUDIO_TARGET_SAMPLE_RATE)
-        } else {
-            mono
-        };
+            let normalized = match resampler {
+                Some(resampler) => resampler.process(&channel),
+                None => channel,
+            };
+
+            on_samples(index, &normalized)?;
+        }
+    }
 
-        append_audio_samples_with_limit(&mut samples, &normalized, MAX_FILE_AUDIO_SAMPLES)?;
+    for (index, resampler) in resamplers.iter_mut().enumerate() {
+        if let Some(resampler) = resampler {
+            on_samples(index, &resampler.flush())?;
+        }
     }
 
-    Ok(samples)
+    Ok(output_channels)
 }
 
 fn append_audio_samples_with_limit(
@@ -1094,28 +3845,24 @@ fn interleaved_to_mono(samples: &[f32], channels: usize) -> Vec<f32> {
     }
 }
 
-fn resample_audio(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<f32> {
-    let ratio = source_rate as f64 / target_rate as f64;
-    let output_len = (samples.len() as f64 / ratio) as usize;
-    let mut output = Vec::with_capacity(output_len);
-
-    for i in 0..output_len {
-        let src_idx = i as f64 * ratio;
-        let idx = src_idx as usize;
-        let frac = src_idx - idx as f64;
-
-        let sample = if idx + 1 < samples.len() {
-            samples[idx] * (1.0 - frac as f32) + samples[idx + 1] * frac as f32
-        } else if idx < samples.len() {
-            samples[idx]
-        } else {
-            0.0
-        };
+fn deinterleave_channel(samples: &[f32], channels: usize, index: usize) -> Vec<f32> {
+    samples
+        .chunks(channels)
+        .filter_map(|frame| frame.get(index).copied())
+        .collect()
+}
 
-        output.push(sample);
-    }
+/// Join per-channel transcripts as `[L]`/`[R]` lines, skipping silent channels
+fn label_channel_transcripts(transcripts: &[String]) -> String {
+    const LABELS: [&str; 2] = ["[L]", "[R]"];
 
-    output
+    transcripts
+        .iter()
+        .zip(LABELS)
+        .filter(|(text, _)| !text.trim().is_empty())
+        .map(|(text, label)| format!("{} {}", label, text.trim()))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 // ==================== Download Commands ====================
@@ -1133,6 +3880,10 @@ async fn download_model(
     let downloader = downloader.0.clone();
 
     ensure_app_access_verified(&db, &license_manager).await?;
+    ensure_models_dir_writable(&downloader.models_dir())?;
+    downloader.set_mirrors(downloader::parse_mirror_list(
+        &db.get_settings()?.download_mirrors,
+    ));
 
     let app_clone = app.clone();
     let model_id_clone = model_id.clone();
@@ -1153,12 +3904,37 @@ async fn download_model(
     Ok(path_str)
 }
 
+/// Time a short sample of a model's download to estimate how long the full
+/// download will take
+#[tauri::command]
+async fn test_download_speed(
+    downloader: State<'_, DownloaderState>,
+    db: State<'_, DbState>,
+    model_id: String,
+) -> CommandResult<DownloadSpeedTest> {
+    let db = db.0.clone();
+    let downloader = downloader.0.clone();
+
+    let model = db
+        .get_model(&model_id)?
+        .ok_or_else(|| CommandError::Download(format!("Unknown model: {}", model_id)))?;
+    downloader.set_mirrors(downloader::parse_mirror_list(
+        &db.get_settings()?.download_mirrors,
+    ));
+
+    downloader
+        .test_download_speed(&model_id, model.size_bytes.max(0) as u64)
+        .await
+        .map_err(CommandError::Download)
+}
+
 #[tauri::command]
 async fn delete_model(
     downloader: State<'_, DownloaderState>,
     db: State<'_, DbState>,
     model_id: String,
 ) -> CommandResult<()> {
+    ensure_models_dir_writable(&downloader.0.models_dir())?;
     downloader
         .0
         .delete_model(&model_id)
@@ -1169,6 +3945,137 @@ async fn delete_model(
     Ok(())
 }
 
+fn is_known_model_id(db: &Database, model_id: &str) -> bool {
+    transcription::BUILTIN_MODEL_IDS.contains(&model_id)
+        || db.get_custom_model(model_id).ok().flatten().is_some()
+}
+
+/// Custom model ids are namespaced so they never collide with built-in ids
+/// and always load through whisper.cpp
+const CUSTOM_MODEL_ID_PREFIX: &str = "custom-";
+const MAX_CUSTOM_MODEL_ID_LEN: usize = 64;
+
+fn validate_custom_model_id(model_id: &str) -> Result<(), String> {
+    let Some(name) = model_id.strip_prefix(CUSTOM_MODEL_ID_PREFIX) else {
+        return Err(format!(
+            "Custom model ids must start with \"{}\"",
+            CUSTOM_MODEL_ID_PREFIX
+        ));
+    };
+    let valid = !name.is_empty()
+        && model_id.len() <= MAX_CUSTOM_MODEL_ID_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(
+            "Custom model ids may only contain lowercase letters, digits, '-', '_' and '.'"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn validate_custom_model_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("Invalid model URL: {}", e))?;
+    if parsed.scheme() != "https" {
+        return Err("Only HTTPS URLs are allowed for downloads".to_string());
+    }
+    if matches!(parsed.host_str(), None | Some("")) {
+        return Err("Model URL has no host".to_string());
+    }
+    Ok(())
+}
+
+/// Register a fine-tuned or community ggml model. It is downloaded like a
+/// built-in model and stored as `<id>.bin`.
+#[tauri::command]
+fn register_custom_model(
+    db: State<DbState>,
+    downloader: State<DownloaderState>,
+    id: String,
+    name: String,
+    url: String,
+    size_bytes: Option<i64>,
+) -> CommandResult<WhisperModel> {
+    let to_error = |e: String| CommandError::Download(e);
+    validate_custom_model_id(&id).map_err(to_error)?;
+    validate_custom_model_url(&url).map_err(to_error)?;
+    let name = sanitize_text(name.trim(), 100).map_err(to_error)?;
+    if name.is_empty() {
+        return Err(to_error("Model name cannot be empty".to_string()));
+    }
+    if is_known_model_id(&db.0, &id) {
+        return Err(to_error(format!("Model {} already exists", id)));
+    }
+
+    let model = database::CustomModel {
+        filename: format!("{}.bin", id),
+        id,
+        name,
+        url,
+        size_bytes: size_bytes.unwrap_or(0).max(0),
+    };
+    db.0.add_custom_model(&model)?;
+    downloader
+        .0
+        .register_custom_model(&model.id, &model.url, &model.filename);
+    info!("Registered custom model {}", model.id);
+
+    db.0.get_model(&model.id)?
+        .ok_or_else(|| to_error(format!("Model {} was not saved", model.id)))
+}
+
+/// Remove a custom model and its downloaded file
+#[tauri::command]
+async fn remove_custom_model(
+    db: State<'_, DbState>,
+    downloader: State<'_, DownloaderState>,
+    id: String,
+) -> CommandResult<()> {
+    if db.0.get_custom_model(&id)?.is_none() {
+        return Err(CommandError::Download(format!(
+            "Unknown custom model: {}",
+            id
+        )));
+    }
+
+    downloader
+        .0
+        .delete_model(&id)
+        .await
+        .map_err(CommandError::Download)?;
+    downloader.0.unregister_custom_model(&id);
+    db.0.remove_custom_model(&id)?;
+    info!("Removed custom model {}", id);
+    Ok(())
+}
+
+/// List temp files left behind by interrupted downloads, deleting them
+/// unless `dry_run` is set
+#[tauri::command]
+fn cleanup_partial_downloads(
+    downloader: State<DownloaderState>,
+    dry_run: bool,
+) -> CommandResult<PartialDownloadCleanup> {
+    if dry_run {
+        return Ok(PartialDownloadCleanup {
+            files: downloader.0.find_partial_downloads(),
+            freed_bytes: 0,
+        });
+    }
+
+    let cleanup = downloader.0.cleanup_partial_downloads();
+    info!(
+        "Removed {} partial download(s), freed {} bytes",
+        cleanup.files.len(),
+        cleanup.freed_bytes
+    );
+    Ok(cleanup)
+}
+
+/// Stop a download in progress. Its temp file is removed and
+/// `download_model` fails with `Download("cancelled")`.
 #[tauri::command]
 fn cancel_model_download(downloader: State<'_, DownloaderState>, model_id: String) -> bool {
     downloader.0.cancel_download(&model_id)
@@ -1176,29 +4083,12 @@ fn cancel_model_download(downloader: State<'_, DownloaderState>, model_id: Strin
 
 #[tauri::command]
 fn is_model_downloaded(
-    _db: State<DbState>,
+    db: State<DbState>,
     downloader: State<DownloaderState>,
     model_id: String,
 ) -> CommandResult<bool> {
-    // Validate model_id against allowed values
-    const VALID_MODEL_IDS: &[&str] = &[
-        "tiny",
-        "base",
-        "small",
-        "medium",
-        "large",
-        "large-v3",
-        "large-v3-turbo",
-        "tiny.en",
-        "base.en",
-        "small.en",
-        "medium.en",
-        "distil-small.en",
-        "parakeet-v2",
-        "parakeet-v3",
-        "qwen3-asr-0.6b",
-    ];
-    if !VALID_MODEL_IDS.contains(&model_id.as_str()) {
+    // Validate model_id against built-in and registered custom models
+    if !is_known_model_id(&db.0, &model_id) {
         return Err(CommandError::Database(
             rusqlite::Error::InvalidParameterName("Invalid model ID".to_string()),
         ));
@@ -1212,26 +4102,13 @@ fn get_downloaded_models(downloader: State<DownloaderState>) -> Vec<String> {
 }
 
 #[tauri::command]
-fn get_model_path(downloader: State<DownloaderState>, model_id: String) -> CommandResult<String> {
-    // Validate model_id against allowed values
-    const VALID_MODEL_IDS: &[&str] = &[
-        "tiny",
-        "base",
-        "small",
-        "medium",
-        "large",
-        "large-v3",
-        "large-v3-turbo",
-        "tiny.en",
-        "base.en",
-        "small.en",
-        "medium.en",
-        "distil-small.en",
-        "parakeet-v2",
-        "parakeet-v3",
-        "qwen3-asr-0.6b",
-    ];
-    if !VALID_MODEL_IDS.contains(&model_id.as_str()) {
+fn get_model_path(
+    db: State<DbState>,
+    downloader: State<DownloaderState>,
+    model_id: String,
+) -> CommandResult<String> {
+    // Validate model_id against built-in and registered custom models
+    if !is_known_model_id(&db.0, &model_id) {
         return Err(CommandError::Database(
             rusqlite::Error::InvalidParameterName("Invalid model ID".to_string()),
         ));
@@ -1245,36 +4122,426 @@ fn get_model_path(downloader: State<DownloaderState>, model_id: String) -> Comma
 
 // ==================== Post-Processing Commands ====================
 
+/// Post-processor built from the stored config, falling back to the
+/// default pipeline if the stored config cannot be loaded
+fn stored_post_processor(db: &Database) -> PostProcessor {
+    let config = db.get_post_processing_config().unwrap_or_else(|e| {
+        warn!("Failed to load post-processing config: {}", e);
+        PostProcessingConfig::default()
+    });
+
+    PostProcessor::from_config(&config).unwrap_or_else(|e| {
+        warn!("Stored post-processing config is invalid: {}", e);
+        PostProcessor::new()
+    })
+}
+
+/// Longest text accepted for post-processing outside of a recording
+const MAX_POST_PROCESS_TEXT_LEN: usize = 100_000;
+
+/// Processor running `stages` in place of the stored order; the stored user
+/// dictionary still applies
+fn post_processor_with_stages(
+    db: &Database,
+    stages: Vec<ProcessingStage>,
+) -> CommandResult<PostProcessor> {
+    let config = PostProcessingConfig {
+        stages,
+        ..db.get_post_processing_config()?
+    };
+    PostProcessor::from_config(&config).map_err(CommandError::PostProcessing)
+}
+
+/// How long a held dictation can still be confirmed
+const PENDING_ACTION_TTL: Duration = Duration::from_secs(30);
+
+/// How long to wait after hiding Wavee for the OS to hand focus back to the
+/// app the dictation was meant for
+const FOCUS_RESTORE_DELAY: Duration = Duration::from_millis(150);
+
+/// Dictations containing destructive action markers, held whole until the
+/// user confirms them so the text and commands still run in the order they
+/// were spoken. Entries expire so a late confirmation can't fire a command
+/// into whatever window has focus by then.
+#[derive(Default)]
+pub struct PendingActions {
+    next_id: u64,
+    actions: HashMap<u64, (String, Instant)>,
+}
+
+impl PendingActions {
+    /// Hold the dictation `text` and return the id that confirms it
+    fn hold(&mut self, text: String, now: Instant) -> u64 {
+        self.actions
+            .retain(|_, (_, held_at)| now.duration_since(*held_at) < PENDING_ACTION_TTL);
+        self.next_id += 1;
+        self.actions.insert(self.next_id, (text, now));
+        self.next_id
+    }
+
+    /// Release the dictation held under `id`, unless it has expired
+    fn take(&mut self, id: u64, now: Instant) -> Option<String> {
+        let (text, held_at) = self.actions.remove(&id)?;
+        (now.duration_since(held_at) < PENDING_ACTION_TTL).then_some(text)
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ConfirmActionRequest {
+    id: u64,
+    markers: Vec<String>,
+}
+
+/// Post-process `text`. When `execute_actions` is set the caller will run
+/// the action markers in the result; with `confirm_destructive_commands` on,
+/// a result containing destructive ones is held whole and announced through
+/// `confirm-action`, and an empty string is returned so nothing is typed
+/// until `confirm_action` releases it.
 #[tauri::command]
-fn post_process_text(text: String) -> CommandResult<String> {
-    let sanitized = sanitize_text(&text, 100_000).map_err(CommandError::PostProcessing)?;
+fn post_process_text(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    latency: State<LatencyState>,
+    pending: State<PendingActionsState>,
+    text: String,
+    stages: Option<Vec<ProcessingStage>>,
+    execute_actions: Option<bool>,
+) -> CommandResult<String> {
+    let started = Instant::now();
+    let sanitized =
+        sanitize_text(&text, MAX_POST_PROCESS_TEXT_LEN).map_err(CommandError::PostProcessing)?;
 
     if sanitized.is_empty() {
         return Ok(String::new());
     }
 
-    let processor = PostProcessor::new();
+    // Callers may override the stage order or disable stages
+    let processor = match stages {
+        Some(stages) => post_processor_with_stages(&db.0, stages)?,
+        None => stored_post_processor(&db.0),
+    };
     let processed = processor.process(&sanitized);
+    latency
+        .0
+        .lock()
+        .unwrap()
+        .record_post_process(started.elapsed(), Instant::now());
 
-    Ok(processed)
+    if !execute_actions.unwrap_or(false) || !db.0.get_settings()?.confirm_destructive_commands {
+        return Ok(processed);
+    }
+
+    let markers = post_process::destructive_markers(&processed);
+    if markers.is_empty() {
+        return Ok(processed);
+    }
+
+    let id = pending.0.lock().unwrap().hold(processed, Instant::now());
+    info!(
+        "Holding dictation with {} destructive voice command(s) for confirmation",
+        markers.len()
+    );
+    let _ = app.emit("confirm-action", ConfirmActionRequest { id, markers });
+    Ok(String::new())
+}
+
+/// Release the dictation held under `id` so the frontend can run it. The
+/// confirmation toast focuses Wavee, so the main window is hidden first and
+/// focus given time to return to the target app.
+#[tauri::command]
+async fn confirm_action(
+    app: tauri::AppHandle,
+    pending: State<'_, PendingActionsState>,
+    id: u64,
+) -> CommandResult<String> {
+    let text = pending
+        .0
+        .lock()
+        .unwrap()
+        .take(id, Instant::now())
+        .ok_or_else(|| {
+            CommandError::PostProcessing("This command has expired or was already run".to_string())
+        })?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        if window.is_focused().unwrap_or(false) {
+            let _ = window.hide();
+            let _ =
+                tauri::async_runtime::spawn_blocking(|| std::thread::sleep(FOCUS_RESTORE_DELAY))
+                    .await;
+        }
+    }
+
+    Ok(text)
+}
+
+/// Re-run a post-processing preset over the clipboard, which the frontend
+/// fills with the user's selection, and return the result for re-injection
+#[tauri::command]
+fn reprocess_clipboard(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    mode: String,
+) -> CommandResult<String> {
+    let stages = ProcessingStage::preset(&mode).ok_or_else(|| {
+        CommandError::PostProcessing(format!("Unknown post-processing mode: {}", mode))
+    })?;
+
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| CommandError::TextInjection(format!("Failed to read clipboard: {}", e)))?;
+    let sanitized =
+        sanitize_text(&text, MAX_POST_PROCESS_TEXT_LEN).map_err(CommandError::PostProcessing)?;
+
+    if sanitized.is_empty() {
+        return Ok(String::new());
+    }
+
+    let processor = post_processor_with_stages(&db.0, stages.to_vec())?;
+    Ok(processor.process(&sanitized))
+}
+
+#[tauri::command]
+fn get_post_processing_config(db: State<DbState>) -> CommandResult<PostProcessingConfig> {
+    Ok(db.0.get_post_processing_config()?)
+}
+
+#[tauri::command]
+fn set_post_processing_config(
+    db: State<DbState>,
+    config: PostProcessingConfig,
+) -> CommandResult<()> {
+    // Compile every dictionary entry before anything is stored
+    PostProcessor::from_config(&config).map_err(CommandError::PostProcessing)?;
+    db.0.save_post_processing_config(&config)?;
+    Ok(())
+}
+
+/// Preview a user regex replacement on sample text without touching any
+/// stored transcription
+#[tauri::command]
+fn test_replacement(pattern: String, replacement: String, sample: String) -> CommandResult<String> {
+    let sample = sanitize_text(&sample, post_process::MAX_REPLACEMENT_SAMPLE_LEN)
+        .map_err(CommandError::PostProcessing)?;
+
+    post_process::apply_user_replacement(&pattern, &replacement, &sample)
+        .map_err(CommandError::PostProcessing)
 }
 
 // ==================== Text Injection Commands ====================
 
+const INJECTION_PERMISSION_DENIED: &str = "Wavee needs Accessibility permission to type into other apps. Grant it in System Settings > Privacy & Security > Accessibility.";
+
+#[tauri::command]
+fn injection_permission_status() -> InjectionPermission {
+    text_inject::injection_permission_status()
+}
+
+#[tauri::command]
+fn request_injection_permission(app: tauri::AppHandle) -> CommandResult<()> {
+    let Some(url) = text_inject::injection_permission_settings_url() else {
+        debug!("No injection permission required on this platform");
+        return Ok(());
+    };
+
+    app.opener()
+        .open_url(url, None::<&str>)
+        .map_err(|e| CommandError::TextInjection(format!("Failed to open System Settings: {}", e)))
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InjectionBlockedPayload {
+    rule: String,
+    app: text_inject::ForegroundApp,
+}
+
+/// Split the stored blocklist into one rule per non-blank line
+fn parse_injection_blocklist(blocklist: &str) -> Vec<String> {
+    blocklist
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Append the `injection_trailing` separator so consecutive takes don't run
+/// together. `"smart"` adds a space unless the text already ends in a break.
+/// Text still ending in an unprocessed `[[...]]` command marker is left alone
+/// so the marker stays last.
+fn with_trailing_separator<'a>(text: &'a str, trailing: &str) -> Cow<'a, str> {
+    if text.ends_with("]]") {
+        return Cow::Borrowed(text);
+    }
+    let separator = match trailing {
+        "space" => " ",
+        "newline" => "\n",
+        "smart" => {
+            let ends_in_break = text
+                .chars()
+                .last()
+                .is_some_and(|c| c.is_whitespace() || matches!(c, '(' | '[' | '{' | '/' | '-'));
+            if ends_in_break {
+                return Cow::Borrowed(text);
+            }
+            " "
+        }
+        _ => return Cow::Borrowed(text),
+    };
+    Cow::Owned(format!("{}{}", text, separator))
+}
+
+/// Highest `typing_delay_ms` accepted
+const MAX_TYPING_DELAY_MS: i64 = 200;
+
+/// Type `text` into the focused app. Returns false when the injection
+/// blocklist skipped it.
 #[tauri::command]
-fn inject_text(injector: State<TextInjectorState>, text: String) -> CommandResult<()> {
+fn inject_text(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    injector: State<TextInjectorState>,
+    latency: State<LatencyState>,
+    text: String,
+) -> CommandResult<bool> {
+    inject_into_foreground(&app, &db.0, &injector.0, &latency.0, &text)
+}
+
+/// Returns false, after emitting `injection-blocked`, when the focused app
+/// matches the blocklist and nothing was typed
+fn inject_into_foreground(
+    app: &tauri::AppHandle,
+    db: &Database,
+    injector: &Mutex<text_inject::TextInjector>,
+    latency: &Mutex<LatencyTracker>,
+    text: &str,
+) -> CommandResult<bool> {
+    let started = Instant::now();
     // Sanitize input - limit text length and remove control characters
-    let sanitized = sanitize_text(&text, 100_000).map_err(CommandError::TextInjection)?;
+    let sanitized = sanitize_text(text, 100_000).map_err(CommandError::TextInjection)?;
 
-    if sanitized.is_empty() {
+    if sanitized.trim().is_empty() {
         return Err(CommandError::TextInjection("No text to inject".to_string()));
     }
 
+    // Skip apps the user excluded; focus detection is best-effort, so an
+    // unknown foreground app is not blocked
+    let settings = db.get_settings()?;
+    let blocklist = parse_injection_blocklist(&settings.injection_blocklist);
+    if !blocklist.is_empty() {
+        if let Some(foreground) = text_inject::foreground_app() {
+            if let Some(rule) = text_inject::blocklist_match(&foreground, &blocklist) {
+                info!("Injection blocked by rule '{}'", rule);
+                let _ = app.emit(
+                    "injection-blocked",
+                    InjectionBlockedPayload {
+                        rule: rule.to_string(),
+                        app: foreground,
+                    },
+                );
+                return Ok(false);
+            }
+        }
+    }
+
+    // Injection silently does nothing without the grant, so fail loudly instead
+    if text_inject::injection_permission_status() == InjectionPermission::Denied {
+        return Err(CommandError::TextInjection(
+            INJECTION_PERMISSION_DENIED.to_string(),
+        ));
+    }
+
+    let options = InjectionOptions {
+        method: InjectionMethod::parse(&settings.injection_method).unwrap_or_default(),
+        restore_clipboard: settings.restore_clipboard,
+        typing_delay: Duration::from_millis(settings.typing_delay_ms.max(0) as u64),
+    };
+
     // Reuse injector instance for better performance (avoids recreating each time)
-    let mut injector_guard = injector.0.lock().unwrap();
+    let mut injector_guard = injector.lock().unwrap();
     injector_guard
-        .inject_text(&sanitized)
-        .map_err(CommandError::TextInjection)
+        .inject_text(
+            &with_trailing_separator(&sanitized, &settings.injection_trailing),
+            &options,
+        )
+        .map_err(CommandError::TextInjection)?;
+    drop(injector_guard);
+
+    let finished = latency
+        .lock()
+        .unwrap()
+        .record_injection(started.elapsed(), Instant::now());
+    if let Some(breakdown) = finished {
+        let _ = app.emit("latency-breakdown", &breakdown);
+    }
+    Ok(true)
+}
+
+/// Reason given when the injection blocklist skipped a dictation
+const INJECTION_BLOCKED: &str = "The focused app is on the injection blocklist";
+
+/// Result of `save_and_inject_text`
+#[derive(Debug, Clone, serde::Serialize)]
+struct DictationOutcome {
+    /// History row holding the text; `None` if saving failed
+    history_id: Option<i64>,
+    /// Hash of the model and decoding settings, stored with the history row
+    params_hash: Option<String>,
+    /// False when injection failed or the blocklist skipped it
+    injected: bool,
+    /// Why nothing was typed, so the UI can offer to copy the text instead
+    injection_error: Option<String>,
+}
+
+/// Save a finished dictation to history, then inject it. The text is saved
+/// first so it survives a failed injection (missing permission, lost focus)
+/// and can still be copied from history.
+#[tauri::command]
+fn save_and_inject_text(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    injector: State<TextInjectorState>,
+    latency: State<LatencyState>,
+    text: String,
+    model_id: String,
+    language: String,
+    duration_ms: i64,
+    raw_text: Option<String>,
+    detected_language: Option<String>,
+) -> CommandResult<DictationOutcome> {
+    let (history_id, params_hash) = match save_transcription(
+        &db.0,
+        &text,
+        raw_text.as_deref(),
+        &model_id,
+        &history_language(&language, detected_language.as_deref()),
+        duration_ms,
+    ) {
+        Ok((id, params_hash)) => (Some(id), Some(params_hash)),
+        Err(e) => {
+            warn!("Failed to save dictation to history: {}", e);
+            (None, None)
+        }
+    };
+
+    let injection = inject_into_foreground(&app, &db.0, &injector.0, &latency.0, &text);
+    let injection_error = match injection {
+        Ok(true) => None,
+        Ok(false) => Some(INJECTION_BLOCKED.to_string()),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(e) = &injection_error {
+        warn!("Injection failed, text kept in history: {}", e);
+    }
+
+    Ok(DictationOutcome {
+        history_id,
+        params_hash,
+        injected: injection_error.is_none(),
+        injection_error,
+    })
 }
 
 #[tauri::command]
@@ -1322,6 +4589,8 @@ fn execute_keyboard_shortcut(
 
 // ==================== Transcription History Commands ====================
 
+/// Save a transcription to history. A `language` of "auto" is stored as
+/// `detected_language`, the language the transcription reported, when given.
 #[tauri::command]
 fn add_transcription(
     db: State<DbState>,
@@ -1329,9 +4598,33 @@ fn add_transcription(
     model_id: String,
     language: String,
     duration_ms: i64,
+    raw_text: Option<String>,
+    detected_language: Option<String>,
 ) -> CommandResult<i64> {
+    save_transcription(
+        &db.0,
+        &text,
+        raw_text.as_deref(),
+        &model_id,
+        &history_language(&language, detected_language.as_deref()),
+        duration_ms,
+    )
+    .map(|(id, _)| id)
+}
+
+/// Save a transcription to history. `raw_text` is the transcript before
+/// post-processing, kept so the entry can be reprocessed later. Returns the
+/// new row id and the hash of the settings that produced the text.
+fn save_transcription(
+    db: &Database,
+    text: &str,
+    raw_text: Option<&str>,
+    model_id: &str,
+    language: &str,
+    duration_ms: i64,
+) -> CommandResult<(i64, String)> {
     // Sanitize and validate text input
-    let sanitized_text = sanitize_text(&text, 1_000_000)
+    let sanitized_text = sanitize_text(text, 1_000_000)
         .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
 
     if sanitized_text.is_empty() {
@@ -1340,37 +4633,16 @@ fn add_transcription(
         ));
     }
 
-    // Validate model_id against allowed values
-    const VALID_MODEL_IDS: &[&str] = &[
-        // Whisper models
-        "tiny",
-        "base",
-        "small",
-        "medium",
-        "large",
-        "large-v3",
-        "large-v3-turbo",
-        "tiny.en",
-        "base.en",
-        "small.en",
-        "medium.en",
-        "distil-small.en",
-        "parakeet-v2",
-        "parakeet-v3",
-        "qwen3-asr-0.6b",
-    ];
-    if !VALID_MODEL_IDS.contains(&model_id.as_str()) {
+    // Validate model_id against built-in and registered custom models
+    if !is_known_model_id(db, model_id) {
         return Err(CommandError::Database(
             rusqlite::Error::InvalidParameterName("Invalid model ID".to_string()),
         ));
     }
 
-    // Validate language code shape. The UI restricts this to the selected model's languages.
-    if !is_valid_language_code(&language) {
-        return Err(CommandError::Database(
-            rusqlite::Error::InvalidParameterName("Invalid language".to_string()),
-        ));
-    }
+    // Validate the language against what the model can actually decode
+    validate_history_language(db, model_id, language)
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?;
 
     // Validate duration range (0 to 1 hour in milliseconds)
     if !(0..=3_600_000).contains(&duration_ms) {
@@ -1379,8 +4651,26 @@ fn add_transcription(
         ));
     }
 
-    db.0.add_transcription(&sanitized_text, &model_id, &language, duration_ms)
-        .map_err(Into::into)
+    let params_hash = transcription::transcription_params_hash(
+        model_id,
+        language,
+        &effective_model_tuning(db, model_id),
+    );
+    let raw_text = raw_text
+        .map(|raw| sanitize_text(raw, 1_000_000))
+        .transpose()
+        .map_err(|e| CommandError::Database(rusqlite::Error::InvalidParameterName(e)))?
+        .filter(|raw| !raw.is_empty());
+
+    let id = db.add_transcription_entry(
+        &sanitized_text,
+        model_id,
+        language,
+        duration_ms,
+        Some(&params_hash),
+        raw_text.as_deref(),
+    )?;
+    Ok((id, params_hash))
 }
 
 #[tauri::command]
@@ -1407,6 +4697,37 @@ fn get_transcription_history(
         .map_err(Into::into)
 }
 
+/// History grouped into local calendar days, newest first.
+/// `utc_offset_minutes` is how far local time is ahead of UTC; the stored
+/// timestamps stay UTC.
+#[tauri::command]
+fn get_transcription_history_grouped(
+    db: State<DbState>,
+    limit: Option<i32>,
+    offset: Option<i32>,
+    utc_offset_minutes: Option<i32>,
+) -> CommandResult<Vec<TranscriptionHistoryDay>> {
+    let safe_limit = limit.unwrap_or(50).clamp(1, 1000);
+    let safe_offset = offset.unwrap_or(0).max(0);
+    let zone = utc_offset_minutes
+        .unwrap_or(0)
+        .checked_mul(60)
+        .and_then(chrono::FixedOffset::east_opt)
+        .ok_or_else(|| {
+            CommandError::Database(rusqlite::Error::InvalidParameterName(
+                "Invalid UTC offset".to_string(),
+            ))
+        })?;
+
+    let history =
+        db.0.get_transcription_history(safe_limit, safe_offset, None)?;
+    Ok(database::group_history_by_day(
+        history,
+        zone,
+        chrono::Utc::now(),
+    ))
+}
+
 #[tauri::command]
 fn get_transcription_history_count(
     db: State<DbState>,
@@ -1426,6 +4747,32 @@ fn get_transcription_history_count(
         .map_err(Into::into)
 }
 
+/// Put the most recent transcription on the clipboard and emit `copied`.
+/// Returns `None` when the history is empty.
+fn copy_latest_transcription(
+    app: &tauri::AppHandle,
+    db: &Database,
+) -> CommandResult<Option<TranscriptionHistory>> {
+    let Some(latest) = db.get_latest_transcription()? else {
+        return Ok(None);
+    };
+
+    app.clipboard()
+        .write_text(latest.text.clone())
+        .map_err(|e| CommandError::TextInjection(format!("Failed to write clipboard: {}", e)))?;
+    let _ = app.emit("copied", &latest);
+    Ok(Some(latest))
+}
+
+/// Copy the last transcription, e.g. after injection went to the wrong window
+#[tauri::command]
+fn copy_last_transcription(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+) -> CommandResult<Option<TranscriptionHistory>> {
+    copy_latest_transcription(&app, &db.0)
+}
+
 #[tauri::command]
 fn clear_transcription_history(db: State<DbState>) -> CommandResult<()> {
     db.0.clear_transcription_history().map_err(Into::into)
@@ -1436,6 +4783,41 @@ fn delete_transcription(db: State<DbState>, id: i64) -> CommandResult<()> {
     db.0.delete_transcription(id).map_err(Into::into)
 }
 
+/// Run the current post-processing config over a history entry's stored raw
+/// text and save the result as its text. Returns the new text.
+#[tauri::command]
+fn reprocess_history_entry(db: State<DbState>, id: i64) -> CommandResult<String> {
+    let entry = db
+        .0
+        .get_transcription(id)?
+        .ok_or_else(|| CommandError::PostProcessing(format!("History entry {} not found", id)))?;
+    let raw_text = entry
+        .raw_text
+        .filter(|raw| !raw.is_empty())
+        .ok_or_else(|| {
+            CommandError::PostProcessing(
+                "Original audio/raw text not available for this entry".to_string(),
+            )
+        })?;
+
+    // Nothing runs the actions of a history entry, so drop the command
+    // phrases instead of storing `[[...]]` markers
+    let config = PostProcessingConfig {
+        action_markers: false,
+        ..db.0.get_post_processing_config()?
+    };
+    let processed = PostProcessor::from_config(&config)
+        .map_err(CommandError::PostProcessing)?
+        .process(&raw_text);
+    if processed.trim().is_empty() {
+        return Err(CommandError::PostProcessing(
+            "Reprocessing produced empty text".to_string(),
+        ));
+    }
+    db.0.update_transcription_text(id, &processed)?;
+    Ok(processed)
+}
+
 // ==================== License Commands ====================
 
 // License response for frontend
@@ -1452,7 +4834,9 @@ struct LicenseResponse {
     is_activated: bool,
     last_validated_at: Option<String>,
     trial_started_at: Option<String>,
-    trial_days_remaining: Option<i64>,
+    trial_days_remaining: Option<u64>,
+    trial_seconds_remaining: Option<u64>,
+    trial_expires_at: Option<String>,
     device_id: String,
     device_label: String,
     limit_activations: Option<i32>,
@@ -1475,6 +4859,8 @@ impl From<LicenseInfo> for LicenseResponse {
             last_validated_at: info.last_validated_at,
             trial_started_at: None,
             trial_days_remaining: None,
+            trial_seconds_remaining: None,
+            trial_expires_at: None,
             device_id: info.device_id,
             device_label: info.device_label,
             limit_activations: info.limit_activations,
@@ -1486,17 +4872,10 @@ impl From<LicenseInfo> for LicenseResponse {
 
 impl From<LicenseData> for LicenseResponse {
     fn from(data: LicenseData) -> Self {
-        let trial_days_remaining = if let Some(ref trial_started) = data.trial_started_at {
-            if let Ok(start_date) = chrono::DateTime::parse_from_rfc3339(trial_started) {
-                let now = chrono::Utc::now();
-                let days_since_start = (now - start_date.with_timezone(&chrono::Utc)).num_days();
-                Some((7 - days_since_start).max(0))
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        let trial_remaining = data
+            .trial_started_at
+            .as_deref()
+            .and_then(|started| trial_time_remaining(started, chrono::Utc::now()));
 
         Self {
             license_key: data.license_key.as_deref().map(security::mask_license_key),
@@ -1510,7 +4889,9 @@ impl From<LicenseData> for LicenseResponse {
             is_activated: data.is_activated,
             last_validated_at: data.last_validated_at,
             trial_started_at: data.trial_started_at,
-            trial_days_remaining,
+            trial_days_remaining: trial_remaining.map(|remaining| remaining.days_remaining()),
+            trial_seconds_remaining: trial_remaining.map(|remaining| remaining.seconds_remaining),
+            trial_expires_at: trial_remaining.map(|remaining| remaining.expires_at.to_rfc3339()),
             device_id: get_device_id(),
             device_label: get_device_label(),
             limit_activations: None,
@@ -1537,18 +4918,38 @@ fn get_license(
 
 #[tauri::command]
 async fn activate_license(
+    app: tauri::AppHandle,
     db: State<'_, DbState>,
     license_manager: State<'_, LicenseManagerState>,
+    activation: State<'_, LicenseActivationState>,
     license_key: String,
 ) -> CommandResult<LicenseResponse> {
     info!("Activating license key...");
 
-    // Activate using the new LicenseManager
-    let license_info = license_manager
-        .0
-        .activate(&license_key)
-        .await
-        .map_err(CommandError::License)?;
+    // Activate using the new LicenseManager, reporting each network stage so
+    // the UI can show progress. `cancel_license_activation` aborts the request.
+    let (abort_handle, abort_registration) = AbortHandle::new_pair();
+    *activation.0.lock().unwrap() = Some(abort_handle);
+    let activated = Abortable::new(
+        license_manager
+            .0
+            .activate_with_progress(&license_key, |stage| {
+                let _ = app.emit("license-activation-stage", stage);
+            }),
+        abort_registration,
+    )
+    .await;
+    activation.0.lock().unwrap().take();
+
+    let license_info = match activated {
+        Ok(result) => result.map_err(CommandError::License)?,
+        Err(_) => {
+            info!("License activation cancelled");
+            return Err(CommandError::License(
+                "License activation cancelled".to_string(),
+            ));
+        }
+    };
 
     if !license_info.status.allows_usage() {
         let _ = clear_cache();
@@ -1634,6 +5035,18 @@ async fn validate_license(
     Ok(LicenseResponse::from(license_info))
 }
 
+/// Abort an in-flight license activation. Returns false if none was running.
+#[tauri::command]
+fn cancel_license_activation(activation: State<'_, LicenseActivationState>) -> bool {
+    match activation.0.lock().unwrap().take() {
+        Some(handle) => {
+            handle.abort();
+            true
+        }
+        None => false,
+    }
+}
+
 #[tauri::command]
 async fn deactivate_license(
     db: State<'_, DbState>,
@@ -1738,7 +5151,7 @@ fn start_trial(db: State<DbState>) -> CommandResult<LicenseResponse> {
                     ));
                 }
 
-                if days_since_start >= 7 {
+                if days_since_start >= 7 || !trial_clock_allows_usage(trial_started, now) {
                     license.status = "trial_expired".to_string();
                     db.0.save_license(&license)
                         .map_err(CommandError::Database)?;
@@ -1752,13 +5165,32 @@ fn start_trial(db: State<DbState>) -> CommandResult<LicenseResponse> {
         return Ok(LicenseResponse::from(license));
     }
 
-    // Start new trial
-    let trial_started_at = chrono::Utc::now().to_rfc3339();
+    // Start new trial, keeping the original start if this device already had one
+    let now = chrono::Utc::now();
+    let record = load_trial_record().unwrap_or_else(|| {
+        let record = TrialRecord::new(&now.to_rfc3339(), now);
+        if let Err(e) = store_trial_record(&record) {
+            warn!("Failed to store trial record: {}", e);
+        }
+        record
+    });
+    let trial_started_at = record.trial_started_at;
     license.status = "trial".to_string();
     license.trial_started_at = Some(trial_started_at.clone());
     license.trial_integrity_hash = Some(calculate_trial_integrity_hash(&trial_started_at));
     license.is_activated = false;
 
+    let active = trial_time_remaining(&trial_started_at, now)
+        .is_some_and(|remaining| remaining.seconds_remaining > 0);
+    if !active {
+        license.status = "trial_expired".to_string();
+        db.0.save_license(&license)
+            .map_err(CommandError::Database)?;
+        return Err(CommandError::License(
+            "Trial has expired. Please purchase a license.".to_string(),
+        ));
+    }
+
     db.0.save_license(&license)
         .map_err(CommandError::Database)?;
 
@@ -1777,6 +5209,27 @@ fn get_device_info() -> serde_json::Value {
     })
 }
 
+/// Set to `1` to allow `get_device_fingerprint_debug` in release builds
+const DEVICE_DEBUG_ENV: &str = "WAVEE_DEVICE_DEBUG";
+
+/// Break the device id down into its inputs so support can see why it
+/// changed. Only available in debug builds or with `WAVEE_DEVICE_DEBUG=1`.
+#[tauri::command]
+fn get_device_fingerprint_debug() -> CommandResult<DeviceFingerprintDebug> {
+    let enabled =
+        cfg!(debug_assertions) || std::env::var(DEVICE_DEBUG_ENV).is_ok_and(|value| value == "1");
+    if !enabled {
+        return Err(CommandError::Io(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!(
+                "Device fingerprint details are disabled. Restart the app with {}=1 to enable them.",
+                DEVICE_DEBUG_ENV
+            ),
+        )));
+    }
+    Ok(license::get_device_fingerprint_debug())
+}
+
 #[tauri::command]
 #[allow(unused_variables)]
 async fn get_trial_status(
@@ -1819,19 +5272,24 @@ async fn get_trial_status(
             }));
         }
 
-        if let Ok(start_date) = chrono::DateTime::parse_from_rfc3339(trial_started) {
-            let now = chrono::Utc::now();
-            let days_since_start = (now - start_date.with_timezone(&chrono::Utc)).num_days();
-            let days_remaining = if days_since_start < 0 {
-                0
-            } else {
-                (7 - days_since_start).max(0)
-            };
+        let now = chrono::Utc::now();
+        if !trial_clock_allows_usage(trial_started, now) {
+            return Ok(serde_json::json!({
+                "isInTrial": false,
+                "daysRemaining": 0,
+                "trialExpired": true,
+                "hasLicense": false
+            }));
+        }
 
+        if let Some(remaining) = trial_time_remaining(trial_started, now) {
+            let active = remaining.seconds_remaining > 0;
             return Ok(serde_json::json!({
-                "isInTrial": days_remaining > 0,
-                "daysRemaining": days_remaining,
-                "trialExpired": days_remaining <= 0,
+                "isInTrial": active,
+                "daysRemaining": remaining.days_remaining(),
+                "secondsRemaining": remaining.seconds_remaining,
+                "expiresAt": remaining.expires_at.to_rfc3339(),
+                "trialExpired": !active,
                 "hasLicense": false
             }));
         }
@@ -1886,20 +5344,22 @@ async fn can_use_app(
             }));
         }
 
-        if let Ok(start_date) = chrono::DateTime::parse_from_rfc3339(trial_started) {
-            let now = chrono::Utc::now();
-            let days_since_start = (now - start_date.with_timezone(&chrono::Utc)).num_days();
-            let days_remaining = if days_since_start < 0 {
-                0
-            } else {
-                (7 - days_since_start).max(0)
-            };
+        let now = chrono::Utc::now();
+        if !trial_clock_allows_usage(trial_started, now) {
+            return Ok(serde_json::json!({
+                "canUse": false,
+                "reason": "trial_expired",
+                "daysRemaining": 0
+            }));
+        }
 
-            if days_remaining > 0 {
+        if let Some(remaining) = trial_time_remaining(trial_started, now) {
+            if remaining.seconds_remaining > 0 {
                 return Ok(serde_json::json!({
                     "canUse": true,
                     "reason": "trial",
-                    "daysRemaining": days_remaining
+                    "daysRemaining": remaining.days_remaining(),
+                    "secondsRemaining": remaining.seconds_remaining
                 }));
             } else {
                 return Ok(serde_json::json!({
@@ -1930,27 +5390,273 @@ fn get_app_data_dir(app: tauri::AppHandle) -> CommandResult<String> {
 }
 
 #[tauri::command]
-fn get_models_dir(app: tauri::AppHandle) -> CommandResult<String> {
+fn get_models_dir(downloader: State<DownloaderState>) -> String {
+    downloader.0.models_dir().to_string_lossy().to_string()
+}
+
+fn default_models_dir(app: &tauri::AppHandle) -> CommandResult<std::path::PathBuf> {
     let path = app.path().app_data_dir().map_err(|e: tauri::Error| {
         std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string())
     })?;
-    Ok(path.join("models").to_string_lossy().to_string())
+    Ok(path.join("models"))
+}
+
+/// Fail early with a storage error instead of a raw IO error halfway
+/// through a download when the models folder cannot be written
+fn ensure_models_dir_writable(dir: &std::path::Path) -> CommandResult<()> {
+    downloader::check_dir_writable(dir).map_err(|e| {
+        let reason = if e.kind() == std::io::ErrorKind::PermissionDenied {
+            "is read-only".to_string()
+        } else {
+            format!("cannot be written ({})", e)
+        };
+        CommandError::Storage(format!(
+            "The models folder {} {}. Choose a folder you can write to, such as one in \
+             your home directory or on another drive, in Settings.",
+            dir.display(),
+            reason
+        ))
+    })
+}
+
+/// Store models in `path` (or the default folder when `None`), moving the
+/// models that are already downloaded
+#[tauri::command]
+async fn set_models_dir(
+    app: tauri::AppHandle,
+    db: State<'_, DbState>,
+    downloader: State<'_, DownloaderState>,
+    path: Option<String>,
+) -> CommandResult<String> {
+    let db = db.0.clone();
+    let downloader = downloader.0.clone();
+
+    let new_dir = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => {
+            let path = std::path::PathBuf::from(path);
+            if !path.is_absolute() {
+                return Err(CommandError::Storage(
+                    "The models folder must be an absolute path".to_string(),
+                ));
+            }
+            path
+        }
+        None => default_models_dir(&app)?,
+    };
+    ensure_models_dir_writable(&new_dir)?;
+    let new_dir = new_dir.canonicalize()?;
+
+    if downloader.has_active_downloads() {
+        return Err(CommandError::Storage(
+            "Wait for downloads to finish before moving the models folder".to_string(),
+        ));
+    }
+
+    let old_dir = downloader.models_dir();
+    let mover = downloader.clone();
+    let target = new_dir.clone();
+    let moved = tauri::async_runtime::spawn_blocking(move || mover.move_models_to(&target))
+        .await
+        .map_err(|e| CommandError::Storage(format!("Failed to move models: {}", e)))?
+        .map_err(|e| {
+            CommandError::Storage(format!(
+                "Failed to move models from {} to {}: {}",
+                old_dir.display(),
+                new_dir.display(),
+                e
+            ))
+        })?;
+    info!(
+        "Models folder changed to {} ({} model(s) moved)",
+        new_dir.display(),
+        moved.len()
+    );
+
+    let override_path = path
+        .is_some()
+        .then(|| new_dir.to_string_lossy().to_string());
+    db.set_models_dir(override_path.as_deref())?;
+    for model_id in downloader.get_downloaded_models() {
+        let model_path = downloader.get_model_path(&model_id);
+        db.set_model_downloaded(&model_id, true, Some(model_path.to_string_lossy().as_ref()))?;
+    }
+
+    Ok(new_dir.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn get_log_path() -> CommandResult<String> {
+    logging::current_log_path()
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| CommandError::Io(std::io::Error::other("File logging is not active")))
 }
 
 // ==================== Hotkey Commands ====================
 
 #[tauri::command]
-fn register_hotkey(app: tauri::AppHandle, hotkey: String) -> CommandResult<()> {
+fn register_hotkey(app: tauri::AppHandle, db: State<DbState>, hotkey: String) -> CommandResult<()> {
     let shortcut = parse_hotkey(&hotkey)
         .map_err(|e| CommandError::Recording(format!("Invalid hotkey: {}", e)))?;
 
-    println!("Registering hotkey: {} -> {:?}", hotkey, shortcut);
+    let settings = db.0.get_settings()?;
+    register_recording_hotkey(&app, &hotkey, shortcut, &settings.hotkey_mode)
+}
+
+/// The hotkey settings, as sent by the frontend
+#[derive(Debug, Clone, serde::Deserialize)]
+struct HotkeyConfig {
+    push_to_talk_key: String,
+    toggle_key: String,
+    #[serde(default)]
+    cancel_key: String,
+    hotkey_mode: String,
+}
+
+/// Configured hotkeys as (action, hotkey) pairs. An empty cancel key means
+/// cancelling has no shortcut, so it is left out.
+fn hotkey_set<'a>(
+    push_to_talk_key: &'a str,
+    toggle_key: &'a str,
+    cancel_key: &'a str,
+) -> Vec<(&'static str, &'a str)> {
+    let mut hotkeys = vec![("push-to-talk", push_to_talk_key), ("toggle", toggle_key)];
+    if !cancel_key.trim().is_empty() {
+        hotkeys.push(("cancel", cancel_key));
+    }
+    hotkeys
+}
+
+fn configured_hotkeys(settings: &AppSettings) -> Vec<(&'static str, &str)> {
+    hotkey_set(
+        &settings.push_to_talk_key,
+        &settings.toggle_key,
+        &settings.cancel_key,
+    )
+}
+
+/// Reject invalid hotkeys and two actions bound to the same combo, naming
+/// the pair
+fn check_hotkey_collisions(hotkeys: &[(&str, &str)]) -> Result<(), String> {
+    let mut parsed: Vec<(&str, &str, Shortcut)> = Vec::with_capacity(hotkeys.len());
+    for &(action, hotkey) in hotkeys {
+        let shortcut =
+            parse_hotkey(hotkey).map_err(|e| format!("Invalid {} hotkey: {}", action, e))?;
+        if let Some((other, other_hotkey, _)) =
+            parsed.iter().find(|(_, _, existing)| *existing == shortcut)
+        {
+            return Err(format!(
+                "The {} and {} hotkeys are both set to {}",
+                other, action, other_hotkey
+            ));
+        }
+        parsed.push((action, hotkey, shortcut));
+    }
+    Ok(())
+}
+
+/// Check the whole hotkey set for collisions before registering anything,
+/// then register the key for the active mode. The cancel key is only
+/// registered while recording.
+#[tauri::command]
+fn register_all_hotkeys(
+    app: tauri::AppHandle,
+    db: State<DbState>,
+    config: HotkeyConfig,
+) -> CommandResult<()> {
+    check_hotkey_collisions(&hotkey_set(
+        &config.push_to_talk_key,
+        &config.toggle_key,
+        &config.cancel_key,
+    ))
+    .map_err(CommandError::Recording)?;
+
+    let hotkey = match config.hotkey_mode.as_str() {
+        "push-to-talk" => &config.push_to_talk_key,
+        "toggle" => &config.toggle_key,
+        other => {
+            return Err(CommandError::Recording(format!(
+                "Invalid hotkey mode: {}",
+                other
+            )))
+        }
+    };
+    let shortcut = parse_hotkey(hotkey)
+        .map_err(|e| CommandError::Recording(format!("Invalid hotkey: {}", e)))?;
+
+    register_recording_hotkey(&app, hotkey, shortcut, &config.hotkey_mode)
+}
+
+/// Longest hold the `min_hold_ms` setting may require
+const MAX_MIN_HOLD_MS: i64 = 1_000;
+
+/// How long push-to-talk must be held before a press counts. Toggle mode is
+/// driven by taps, so it never waits.
+fn min_hold_for_mode(settings: &AppSettings, hotkey_mode: &str) -> Duration {
+    if hotkey_mode != "push-to-talk" {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(settings.min_hold_ms.clamp(0, MAX_MIN_HOLD_MS) as u64)
+}
+
+/// Tracks the recording hotkey so key auto-repeat doesn't restart a press
+/// and presses released before the minimum hold can be discarded
+#[derive(Debug, Default)]
+struct HotkeyHold {
+    pressed_at: Option<Instant>,
+}
+
+impl HotkeyHold {
+    /// Start a press. False while the key is already held, which is how
+    /// auto-repeat shows up.
+    fn press(&mut self, now: Instant) -> bool {
+        if self.pressed_at.is_some() {
+            return false;
+        }
+        self.pressed_at = Some(now);
+        true
+    }
+
+    /// End the press. `Some(true)` when it was held for at least `min_hold`,
+    /// `Some(false)` for a shorter tap and `None` when no press was active.
+    fn release(&mut self, now: Instant, min_hold: Duration) -> Option<bool> {
+        let pressed_at = self.pressed_at.take()?;
+        Some(now.duration_since(pressed_at) >= min_hold)
+    }
+}
+
+/// Minimum hold for the registered mode, read at release so a changed
+/// `min_hold_ms` applies without re-registering the hotkey
+fn current_min_hold(app: &tauri::AppHandle, hotkey_mode: &str) -> Duration {
+    app.try_state::<DbState>()
+        .and_then(|db| db.0.get_settings().ok())
+        .map(|settings| min_hold_for_mode(&settings, hotkey_mode))
+        .unwrap_or_default()
+}
 
-    // Unregister all existing shortcuts first
-    if let Err(e) = app.global_shortcut().unregister_all() {
-        println!("Warning: Failed to unregister existing shortcuts: {}", e);
+/// Replace any registered shortcuts with the recording hotkey. Capture
+/// starts on press so the first syllable isn't lost; a push-to-talk press
+/// released before `min_hold_ms` emits `hotkey-tap` instead of
+/// `hotkey-released` so the frontend discards the take.
+fn register_recording_hotkey(
+    app: &tauri::AppHandle,
+    hotkey: &str,
+    shortcut: Shortcut,
+    hotkey_mode: &str,
+) -> CommandResult<()> {
+    if in_safe_mode(app) {
+        return Err(CommandError::Recording(
+            "Hotkeys are disabled in safe mode. Restart normally to use them.".to_string(),
+        ));
     }
 
+    println!("Registering hotkey: {} -> {:?}", hotkey, shortcut);
+    let hold = Arc::new(Mutex::new(HotkeyHold::default()));
+    let hotkey_mode = hotkey_mode.to_string();
+
+    // Replace only the previous recording shortcut; an armed cancel
+    // shortcut has to survive a re-registration mid-recording
+    unregister_recording_hotkey(app);
+
     // Register the new shortcut with handler
     let result = app
         .global_shortcut()
@@ -1958,12 +5664,29 @@ fn register_hotkey(app: tauri::AppHandle, hotkey: String) -> CommandResult<()> {
             println!("Shortcut event: {:?}", event.state());
             match event.state() {
                 ShortcutState::Pressed => {
+                    if !hold.lock().unwrap().press(Instant::now()) {
+                        return;
+                    }
                     println!("Emitting hotkey-pressed");
                     if let Err(e) = app.emit("hotkey-pressed", ()) {
                         println!("Failed to emit hotkey-pressed: {}", e);
                     }
                 }
                 ShortcutState::Released => {
+                    let min_hold = current_min_hold(app, &hotkey_mode);
+                    let released = hold.lock().unwrap().release(Instant::now(), min_hold);
+                    match released {
+                        None => return,
+                        Some(false) => {
+                            debug!("Discarding hotkey tap shorter than {:?}", min_hold);
+                            let _ = app.emit("hotkey-tap", ());
+                            return;
+                        }
+                        Some(true) => {}
+                    }
+                    if let Some(latency) = app.try_state::<LatencyState>() {
+                        latency.0.lock().unwrap().hotkey_released(Instant::now());
+                    }
                     println!("Emitting hotkey-released");
                     if let Err(e) = app.emit("hotkey-released", ()) {
                         println!("Failed to emit hotkey-released: {}", e);
@@ -1975,6 +5698,9 @@ fn register_hotkey(app: tauri::AppHandle, hotkey: String) -> CommandResult<()> {
     match result {
         Ok(_) => {
             println!("Hotkey registered successfully");
+            if let Some(state) = app.try_state::<RecordingHotkeyState>() {
+                *state.0.lock().unwrap() = Some(shortcut);
+            }
             Ok(())
         }
         Err(e) => {
@@ -1987,14 +5713,27 @@ fn register_hotkey(app: tauri::AppHandle, hotkey: String) -> CommandResult<()> {
     }
 }
 
+/// Unregister the recording shortcut. The cancel shortcut is left to
+/// `release_cancel_hotkey`, which runs when the recording ends.
 #[tauri::command]
 fn unregister_hotkeys(app: tauri::AppHandle) -> CommandResult<()> {
-    app.global_shortcut()
-        .unregister_all()
-        .map_err(|e| CommandError::Recording(format!("Failed to unregister hotkeys: {}", e)))?;
+    unregister_recording_hotkey(&app);
     Ok(())
 }
 
+fn unregister_recording_hotkey(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<RecordingHotkeyState>() else {
+        return;
+    };
+    let Some(shortcut) = state.0.lock().unwrap().take() else {
+        return;
+    };
+
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        warn!("Failed to unregister recording hotkey: {}", e);
+    }
+}
+
 fn parse_hotkey(hotkey: &str) -> Result<Shortcut, String> {
     let parts: Vec<&str> = hotkey.split('+').map(|s| s.trim()).collect();
 
@@ -2091,8 +5830,99 @@ fn get_app_name() -> String {
     APP_NAME.to_string()
 }
 
+#[derive(Debug, Clone, serde::Serialize)]
+struct VersionInfo {
+    version: String,
+    build_time: String,
+    git_hash: String,
+}
+
+/// Version of the running build along with when and from which commit it
+/// was built
+#[tauri::command]
+fn get_current_version_info() -> VersionInfo {
+    VersionInfo {
+        version: APP_VERSION.to_string(),
+        build_time: error_reporting::BUILD_TIME.to_string(),
+        git_hash: error_reporting::GIT_HASH.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct BuildInfo {
+    version: String,
+    /// Short commit hash, or "unknown" when built outside a git checkout
+    git_hash: String,
+    build_time: String,
+    target: String,
+    debug: bool,
+}
+
+/// Exact build details for bug reports
+#[tauri::command]
+fn get_build_info() -> BuildInfo {
+    BuildInfo {
+        version: APP_VERSION.to_string(),
+        git_hash: error_reporting::GIT_HASH.to_string(),
+        build_time: error_reporting::BUILD_TIME.to_string(),
+        target: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        debug: cfg!(debug_assertions),
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct ReleaseNotesResult {
+    releases: Vec<release_notes::ReleaseNotes>,
+    /// True when the endpoint was unreachable and the changelog bundled with
+    /// this build was used instead, which may not cover newer releases
+    from_bundle: bool,
+}
+
+/// Changelog entries for releases after `from_version` up to `to_version`,
+/// newest first. `from_version` defaults to the running version.
+#[tauri::command]
+async fn get_release_notes(
+    db: State<'_, DbState>,
+    from_version: Option<String>,
+    to_version: String,
+) -> CommandResult<ReleaseNotesResult> {
+    let from_version = from_version.unwrap_or_else(|| APP_VERSION.to_string());
+    let url = db.0.get_settings()?.release_notes_url;
+
+    match release_notes::fetch_release_notes(&url, &from_version, &to_version).await {
+        Ok(releases) => Ok(ReleaseNotesResult {
+            releases,
+            from_bundle: false,
+        }),
+        Err(e) => {
+            warn!("Falling back to bundled release notes: {}", e);
+            Ok(ReleaseNotesResult {
+                releases: release_notes::bundled_release_notes(&from_version, &to_version),
+                from_bundle: true,
+            })
+        }
+    }
+}
+
 // Error Reporting Commands
 
+/// Category names as sent by the frontend; anything else is `Unknown`
+fn parse_error_category(category: &str) -> ErrorCategory {
+    match category.to_lowercase().as_str() {
+        "transcription" => ErrorCategory::Transcription,
+        "audio" => ErrorCategory::Audio,
+        "model" => ErrorCategory::Model,
+        "database" => ErrorCategory::Database,
+        "network" => ErrorCategory::Network,
+        "filesystem" => ErrorCategory::FileSystem,
+        "license" => ErrorCategory::License,
+        "ui" => ErrorCategory::Ui,
+        "system" => ErrorCategory::System,
+        "configuration" => ErrorCategory::Configuration,
+        _ => ErrorCategory::Unknown,
+    }
+}
+
 #[tauri::command]
 async fn report_error(
     app: tauri::AppHandle,
@@ -2113,19 +5943,7 @@ async fn report_error(
         _ => ErrorSeverity::Error,
     };
 
-    let category = match category.to_lowercase().as_str() {
-        "transcription" => ErrorCategory::Transcription,
-        "audio" => ErrorCategory::Audio,
-        "model" => ErrorCategory::Model,
-        "database" => ErrorCategory::Database,
-        "network" => ErrorCategory::Network,
-        "filesystem" => ErrorCategory::FileSystem,
-        "license" => ErrorCategory::License,
-        "ui" => ErrorCategory::Ui,
-        "system" => ErrorCategory::System,
-        "configuration" => ErrorCategory::Configuration,
-        _ => ErrorCategory::Unknown,
-    };
+    let category = parse_error_category(&category);
 
     if let Some(reporter) = ErrorReporter::global() {
         let mut report = ErrorReport::new(severity, category, message);
@@ -2237,6 +6055,102 @@ async fn clear_error_reports() -> Result<(), CommandError> {
     Ok(())
 }
 
+/// Clear only the error reports in `category` and/or older than
+/// `older_than`, in memory and in the persisted file. Returns how many were
+/// removed.
+#[tauri::command]
+async fn clear_error_reports_filtered(
+    app: tauri::AppHandle,
+    category: Option<String>,
+    older_than: Option<chrono::DateTime<chrono::Utc>>,
+) -> Result<usize, CommandError> {
+    let Some(reporter) = ErrorReporter::global() else {
+        return Ok(0);
+    };
+
+    let category = category.as_deref().map(parse_error_category);
+    let removed = reporter.clear_filtered(category.as_ref(), older_than);
+    if let Ok(app_data_dir) = app.path().app_data_dir() {
+        reporter.persist_to_file(&app_data_dir)?;
+    }
+    Ok(removed)
+}
+
+/// Whether panics are being written as crash reports
+#[tauri::command]
+fn get_panic_hook_status() -> bool {
+    ErrorReporter::panic_hook_active()
+}
+
+/// Switch crash reporting for panics on or off, returning the new state
+#[tauri::command]
+fn set_panic_hook_enabled(enabled: bool) -> bool {
+    let active = ErrorReporter::set_panic_hook_active(enabled);
+    info!(
+        "Crash reporting {}",
+        if active { "enabled" } else { "disabled" }
+    );
+    active
+}
+
+fn in_safe_mode(app: &tauri::AppHandle) -> bool {
+    app.try_state::<SafeModeState>()
+        .is_some_and(|state| state.0)
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+struct SafeModeStatus {
+    /// Started with `--safe-mode`
+    active: bool,
+    /// Crashes recorded in the last `SAFE_MODE_CRASH_WINDOW_MINUTES`
+    recent_crashes: u32,
+    /// The app keeps crashing, so the UI should offer a safe mode restart
+    suggested: bool,
+}
+
+#[tauri::command]
+fn get_safe_mode_status(safe_mode: State<SafeModeState>) -> SafeModeStatus {
+    let since = chrono::Utc::now() - chrono::Duration::minutes(SAFE_MODE_CRASH_WINDOW_MINUTES);
+    let recent_crashes = ErrorReporter::global()
+        .map(|reporter| reporter.recent_crash_count(since))
+        .unwrap_or(0);
+    SafeModeStatus {
+        active: safe_mode.0,
+        recent_crashes,
+        suggested: !safe_mode.0 && recent_crashes >= SAFE_MODE_CRASH_THRESHOLD,
+    }
+}
+
+/// Relaunch the app, in safe mode or normally
+#[tauri::command]
+fn restart_app(app: tauri::AppHandle, safe_mode: bool) -> CommandResult<()> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    if safe_mode {
+        command.arg(SAFE_MODE_FLAG);
+    }
+    command.spawn()?;
+    app.exit(0);
+    Ok(())
+}
+
+/// Crash reports left by earlier panics, most recent first
+#[tauri::command]
+async fn get_crash_reports(limit: Option<usize>) -> Result<Vec<CrashReport>, CommandError> {
+    if let Some(reporter) = ErrorReporter::global() {
+        Ok(reporter.get_crash_reports(limit))
+    } else {
+        Ok(vec![])
+    }
+}
+
+#[tauri::command]
+async fn clear_crash_reports() -> Result<usize, CommandError> {
+    match ErrorReporter::global() {
+        Some(reporter) => Ok(reporter.clear_crash_reports()?),
+        None => Ok(0),
+    }
+}
+
 #[tauri::command]
 async fn load_error_reports(app: tauri::AppHandle) -> Result<usize, CommandError> {
     if let Some(reporter) = ErrorReporter::global() {
@@ -2257,13 +6171,16 @@ async fn load_error_reports(app: tauri::AppHandle) -> Result<usize, CommandError
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logger
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-        .format_timestamp_millis()
-        .init();
+    // Initialize logger (console now, file sink once the app data dir is known)
+    logging::init();
 
     info!("Starting {} v{}", APP_NAME, APP_VERSION);
 
+    let safe_mode = std::env::args().any(|arg| arg == SAFE_MODE_FLAG);
+    if safe_mode {
+        warn!("Starting in safe mode: hotkeys and the full tray menu are disabled");
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
@@ -2279,8 +6196,9 @@ pub fn run() {
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--minimized"]),
         ))
-        .setup(|app| {
+        .setup(move |app| {
             info!("Initializing application...");
+            app.manage(SafeModeState(safe_mode));
 
             // Initialize database
             let app_data_dir = app
@@ -2292,23 +6210,90 @@ pub fn run() {
 
             // Initialize error reporter early for crash handling
             let error_log_dir = app_data_dir.join("logs");
+            match logging::attach_file_sink(error_log_dir.clone()) {
+                Ok(path) => info!("Logging to {:?}", path),
+                Err(e) => warn!("Failed to open log file: {}", e),
+            }
             ErrorReporter::init(error_log_dir);
 
-            let db = Database::new(app_data_dir.clone()).expect("Failed to initialize database");
+            // A broken database must not stop the app from starting; fall back
+            // to an in-memory database and let the UI report the problem.
+            let db = match Database::new(app_data_dir.clone()) {
+                Ok(db) => db,
+                Err(e) => {
+                    error!("Failed to open database: {}", e);
+                    Database::in_memory(format!(
+                        "The database could not be opened, so changes will not be saved: {}",
+                        e
+                    ))
+                    .expect("Failed to initialize in-memory database")
+                }
+            };
+            if let Some(recovery) = db.recovery() {
+                warn!("Database recovered at startup: {}", recovery.reason);
+            }
+            let warm_model_capacity = warm_cache_capacity(&db);
+            reconcile_autostart(app.handle(), &db);
             app.manage(DbState(Arc::new(db)));
 
             // Initialize recorder state
             app.manage(RecorderState(Arc::new(Mutex::new(None))));
+            app.manage(RecordingHotkeyState(Mutex::new(None)));
+            app.manage(CancelHotkeyState(Mutex::new(None)));
+            app.manage(PendingActionsState(Mutex::new(PendingActions::default())));
 
             // Initialize transcriber state
             app.manage(TranscriberState(Arc::new(Mutex::new(None))));
+            app.manage(LoadedModelState(Arc::new(Mutex::new(None))));
+            app.manage(TranscriptionAbortState(Arc::new(AtomicBool::new(false))));
+            app.manage(TranscriptionQueueState(Arc::new(TranscriptionQueue::new(
+                TRANSCRIPTION_QUEUE_CAPACITY,
+            ))));
+            app.manage(LatencyState(Arc::new(
+                Mutex::new(LatencyTracker::default()),
+            )));
+            app.manage(WarmModelCacheState(Arc::new(Mutex::new(ModelCache::new(
+                warm_model_capacity,
+            )))));
 
             // Initialize downloader
-            let models_dir = app_data_dir.join("models");
-            app.manage(DownloaderState(Arc::new(ModelDownloader::new(models_dir))));
+            let default_models_dir = app_data_dir.join("models");
+            let models_dir = match app.state::<DbState>().0.get_settings() {
+                Ok(settings) => match settings.models_dir.map(std::path::PathBuf::from) {
+                    Some(dir) if std::fs::create_dir_all(&dir).is_ok() => dir,
+                    Some(dir) => {
+                        warn!(
+                            "Models folder {} is not available, using {}",
+                            dir.display(),
+                            default_models_dir.display()
+                        );
+                        default_models_dir
+                    }
+                    None => default_models_dir,
+                },
+                Err(_) => default_models_dir,
+            };
+            let downloader = ModelDownloader::new(models_dir);
+            match app.state::<DbState>().0.get_custom_models() {
+                Ok(custom_models) => {
+                    for model in custom_models {
+                        downloader.register_custom_model(&model.id, &model.url, &model.filename);
+                    }
+                }
+                Err(e) => warn!("Failed to load custom models: {}", e),
+            }
+            for partial in downloader.find_partial_downloads() {
+                warn!(
+                    "Found orphaned partial download {} ({} bytes)",
+                    partial.path.display(),
+                    partial.size_bytes
+                );
+            }
+            app.manage(DownloaderState(Arc::new(downloader)));
 
             // Initialize license manager
             app.manage(LicenseManagerState(Arc::new(LicenseManager::new())));
+            app.manage(LicenseActivationState(Mutex::new(None)));
 
             // Initialize text injector (reused for better performance)
             let text_injector =
@@ -2319,6 +6304,10 @@ pub fn run() {
             app.manage(RecordingRateLimiter(Arc::new(RateLimiter::new(100, 60))));
             app.manage(TranscriptionRateLimiter(Arc::new(RateLimiter::new(50, 60))));
 
+            if let Ok(settings) = app.state::<DbState>().0.get_settings() {
+                apply_preroll(app.handle(), &settings);
+            }
+
             setup_window_icons(app)?;
 
             // Setup system tray
@@ -2345,16 +6334,23 @@ pub fn run() {
             get_settings,
             update_settings,
             update_setting,
+            set_autostart,
+            get_autostart_status,
+            reset_settings,
+            export_settings,
+            import_settings,
             // App state
             get_app_state,
             update_app_state,
             set_setup_complete,
             set_current_setup_step,
+            get_database_recovery,
             // Models
             get_models,
             get_model,
             set_model_downloaded,
             set_selected_model,
+            recommend_model,
             // Recording
             get_audio_input_devices,
             get_audio_output_devices,
@@ -2362,39 +6358,82 @@ pub fn run() {
             set_audio_capture_config,
             start_recording,
             stop_recording,
+            get_waveform,
             save_temp_audio,
+            save_last_recording,
             cancel_recording,
             is_recording,
+            get_input_level,
+            calibrate_microphone,
+            measure_noise_floor,
+            pause_recording,
+            resume_recording,
+            is_recording_paused,
+            get_recording_elapsed_ms,
             // Recording overlay
             show_recording_overlay,
             hide_recording_overlay,
             // Transcription
             load_model,
             unload_model,
+            get_loaded_model,
+            get_warm_models,
+            supported_audio_formats,
+            get_audio_duration,
+            get_model_memory_estimate,
+            get_current_memory_usage,
+            clear_warm_models,
+            set_transcription_language,
+            get_supported_languages,
             transcribe_audio,
+            transcribe_audio_segments,
             record_and_transcribe,
+            get_last_latency_breakdown,
+            finalize_recording,
+            record_and_transcribe_scored,
             transcribe_file,
+            transcribe_bytes,
+            transcribe_file_streaming,
+            get_model_tuning,
+            set_model_tuning,
+            cancel_transcription,
+            confirm_action,
             // Download
             download_model,
             cancel_model_download,
+            cleanup_partial_downloads,
+            register_custom_model,
+            remove_custom_model,
             delete_model,
+            test_download_speed,
             is_model_downloaded,
             get_downloaded_models,
             get_model_path,
             // Text injection
             inject_text,
+            save_and_inject_text,
+            injection_permission_status,
+            request_injection_permission,
             execute_keyboard_shortcut,
             // Post-processing
             post_process_text,
+            reprocess_clipboard,
+            get_post_processing_config,
+            set_post_processing_config,
+            test_replacement,
             // Transcription history
             add_transcription,
             get_transcription_history,
+            get_transcription_history_grouped,
             get_transcription_history_count,
+            copy_last_transcription,
             clear_transcription_history,
             delete_transcription,
+            reprocess_history_entry,
             // License
             get_license,
             activate_license,
+            cancel_license_activation,
             validate_license,
             deactivate_license,
             clear_stored_license,
@@ -2402,15 +6441,22 @@ pub fn run() {
             start_trial,
             get_trial_status,
             get_device_info,
+            get_device_fingerprint_debug,
             can_use_app,
             // Utility
             get_app_data_dir,
             get_models_dir,
+            set_models_dir,
+            get_log_path,
             // Hotkeys
             register_hotkey,
+            register_all_hotkeys,
             unregister_hotkeys,
             // App info
             get_app_version,
+            get_current_version_info,
+            get_build_info,
+            get_release_notes,
             get_app_name,
             // Error reporting
             report_error,
@@ -2419,7 +6465,14 @@ pub fn run() {
             export_error_reports,
             save_export_file,
             clear_error_reports,
+            clear_error_reports_filtered,
+            get_panic_hook_status,
+            set_panic_hook_enabled,
             load_error_reports,
+            get_crash_reports,
+            clear_crash_reports,
+            get_safe_mode_status,
+            restart_app,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2444,54 +6497,181 @@ fn setup_window_icons(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
-fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    // Create tray menu items
-    let title_item = MenuItem::with_id(app, "title", "Wavee", false, None::<&str>)?;
-    let show_item = MenuItem::with_id(app, "show", "Open Wavee", true, None::<&str>)?;
-    let start_recording_item = MenuItem::with_id(
-        app,
-        "start_recording",
-        "Start Recording",
-        true,
-        None::<&str>,
-    )?;
-    let stop_recording_item =
-        MenuItem::with_id(app, "stop_recording", "Stop Recording", true, None::<&str>)?;
-    let transcribe_file_item =
-        MenuItem::with_id(app, "transcribe", "Transcribe File...", true, None::<&str>)?;
-    let history_item = MenuItem::with_id(app, "history", "History", true, None::<&str>)?;
-    let models_item = MenuItem::with_id(app, "models", "Models", true, None::<&str>)?;
-    let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-    let help_item = MenuItem::with_id(app, "help", "Help & Support", true, None::<&str>)?;
-    let separator = PredefinedMenuItem::separator(app)?;
-    let separator_2 = PredefinedMenuItem::separator(app)?;
-    let separator_3 = PredefinedMenuItem::separator(app)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-    // Build menu
-    let menu = Menu::with_items(
-        app,
-        &[
-            &title_item,
-            &separator,
-            &show_item,
-            &transcribe_file_item,
-            &start_recording_item,
-            &stop_recording_item,
-            &separator_2,
-            &history_item,
-            &models_item,
-            &settings_item,
-            &help_item,
-            &separator_3,
-            &quit_item,
-        ],
-    )?;
-
-    let icon = app_icon()?;
-
+/// Id of the app's single tray icon
+const TRAY_ID: &str = "main";
+
+/// Tray items shown in safe mode, enough to reach the settings
+const SAFE_MODE_TRAY_MENU_ITEMS: &str = "settings";
+
+/// Optional tray items by group, in menu order. `tray_menu_items` picks which
+/// of these appear; the title, "Open Wavee" and "Quit" are always shown.
+const TRAY_MENU_GROUPS: &[&[(&str, &str)]] = &[
+    &[
+        ("transcribe", "Transcribe File..."),
+        ("start_recording", "Start Recording"),
+        ("stop_recording", "Stop Recording"),
+        ("copy_last", "Copy Last Transcription"),
+    ],
+    &[
+        ("history", "History"),
+        ("models", "Models"),
+        ("settings", "Settings"),
+        ("help", "Help & Support"),
+    ],
+];
+
+/// Parse the comma-separated `tray_menu_items` setting, rejecting unknown ids
+fn parse_tray_menu_items(value: &str) -> Result<Vec<&str>, String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            let known = TRAY_MENU_GROUPS
+                .iter()
+                .flat_map(|group| group.iter())
+                .any(|(known, _)| *known == id);
+            if known {
+                Ok(id)
+            } else {
+                Err(format!("Unknown tray menu item: {}", id))
+            }
+        })
+        .collect()
+}
+
+/// Recording items kept so their enabled state can follow the app state
+#[derive(Default)]
+struct TrayRecordingItems {
+    start: Option<MenuItem<tauri::Wry>>,
+    stop: Option<MenuItem<tauri::Wry>>,
+}
+
+pub struct TrayMenuState(Mutex<TrayRecordingItems>);
+
+/// Whether Start and Stop Recording can be clicked: starting needs a loaded
+/// model and an idle recorder, stopping needs a recording in progress
+fn tray_recording_items_enabled(recording: bool, model_loaded: bool) -> (bool, bool) {
+    (!recording && model_loaded, recording)
+}
+
+fn build_tray_menu<M: Manager<tauri::Wry>>(
+    app: &M,
+    items: &str,
+) -> tauri::Result<(Menu<tauri::Wry>, TrayRecordingItems)> {
+    // An invalid setting falls back to the default items rather than an
+    // empty menu
+    let selected = parse_tray_menu_items(items).unwrap_or_else(|e| {
+        warn!("{}", e);
+        parse_tray_menu_items(database::DEFAULT_TRAY_MENU_ITEMS).unwrap_or_default()
+    });
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, "title", "Wavee", false, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "show", "Open Wavee", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut recording_items = TrayRecordingItems::default();
+    for (index, group) in TRAY_MENU_GROUPS.iter().enumerate() {
+        let visible: Vec<_> = group
+            .iter()
+            .filter(|(id, _)| selected.contains(id))
+            .collect();
+        if visible.is_empty() {
+            continue;
+        }
+        if index > 0 {
+            menu.append(&PredefinedMenuItem::separator(app)?)?;
+        }
+        for (id, label) in visible {
+            let item = MenuItem::with_id(app, *id, *label, true, None::<&str>)?;
+            menu.append(&item)?;
+            match *id {
+                "start_recording" => recording_items.start = Some(item),
+                "stop_recording" => recording_items.stop = Some(item),
+                _ => {}
+            }
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?)?;
+
+    Ok((menu, recording_items))
+}
+
+/// Enable or disable the tray recording items to match the recorder and
+/// model state. Callers must not hold the recorder or transcriber lock.
+fn refresh_tray_items(app: &tauri::AppHandle) {
+    let Some(state) = app.try_state::<TrayMenuState>() else {
+        return;
+    };
+    let recording = app.try_state::<RecorderState>().is_some_and(|recorder| {
+        recorder
+            .0
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|rec| rec.is_recording())
+    });
+    let model_loaded = app
+        .try_state::<TranscriberState>()
+        .is_some_and(|transcriber| transcriber.0.lock().unwrap().is_some());
+
+    let (start_enabled, stop_enabled) = tray_recording_items_enabled(recording, model_loaded);
+    let items = state.0.lock().unwrap();
+    for (item, enabled) in [(&items.start, start_enabled), (&items.stop, stop_enabled)] {
+        if let Some(item) = item {
+            if let Err(e) = item.set_enabled(enabled) {
+                warn!("Failed to update tray menu: {}", e);
+            }
+        }
+    }
+}
+
+/// Rebuild the tray menu after `tray_menu_items` changed
+fn apply_tray_menu_items(app: &tauri::AppHandle, items: &str) {
+    if in_safe_mode(app) {
+        return;
+    }
+    let (Some(tray), Some(state)) = (app.tray_by_id(TRAY_ID), app.try_state::<TrayMenuState>())
+    else {
+        return;
+    };
+
+    match build_tray_menu(app, items) {
+        Ok((menu, recording_items)) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                warn!("Failed to update tray menu: {}", e);
+                return;
+            }
+            *state.0.lock().unwrap() = recording_items;
+            refresh_tray_items(app);
+        }
+        Err(e) => warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let items = if in_safe_mode(app.handle()) {
+        SAFE_MODE_TRAY_MENU_ITEMS.to_string()
+    } else {
+        app.state::<DbState>()
+            .0
+            .get_settings()
+            .map(|settings| settings.tray_menu_items)
+            .unwrap_or_else(|_| database::DEFAULT_TRAY_MENU_ITEMS.to_string())
+    };
+    let (menu, recording_items) = build_tray_menu(app, &items)?;
+
+    let icon = app_icon()?;
+
     // Build tray icon
-    let _tray = TrayIconBuilder::new()
+    let _tray = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .menu(&menu)
         .tooltip("Wavee - Voice to Text")
@@ -2506,6 +6686,14 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
             "stop_recording" => {
                 let _ = app.emit("tray-stop-recording", ());
             }
+            "copy_last" => {
+                let db = app.state::<DbState>();
+                match copy_latest_transcription(app, &db.0) {
+                    Ok(Some(_)) => {}
+                    Ok(None) => info!("No transcription to copy yet"),
+                    Err(e) => warn!("Failed to copy last transcription: {}", e),
+                }
+            }
             "transcribe" => {
                 show_main_window(app);
                 let _ = app.emit("tray-navigate", "transcribe");
@@ -2545,6 +6733,9 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
         })
         .build(app)?;
 
+    app.manage(TrayMenuState(Mutex::new(recording_items)));
+    refresh_tray_items(app.handle());
+
     Ok(())
 }
 
@@ -2595,6 +6786,164 @@ mod audio_ingestion_tests {
 
         assert_eq!(mono, vec![0.0, 0.375]);
     }
+
+    #[test]
+    fn file_duration_comes_from_target_rate_sample_count() {
+        assert_eq!(samples_to_duration_ms(24_000), 1_500);
+        assert_eq!(samples_to_duration_ms(0), 0);
+    }
+
+    #[test]
+    fn supported_formats_only_list_decodable_codecs() {
+        let supported = supported_audio_extensions();
+
+        assert!(supported.contains(&"wav"));
+        assert!(supported.contains(&"mp3"));
+        assert!(supported.contains(&"flac"));
+        // Symphonia has no Opus decoder, so WebM recordings cannot be read
+        assert!(!supported.contains(&"webm"));
+    }
+
+    #[test]
+    fn audio_mime_types_map_to_probe_extensions() {
+        assert_eq!(audio_extension_for_mime("audio/x-wav"), Some("wav"));
+        assert_eq!(audio_extension_for_mime("Audio/MPEG"), Some("mp3"));
+        assert_eq!(
+            audio_extension_for_mime("audio/webm;codecs=opus"),
+            Some("webm")
+        );
+        assert_eq!(audio_extension_for_mime("video/mp4"), None);
+    }
+
+    #[test]
+    fn in_memory_wav_is_decoded_to_target_rate_mono() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut data = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+            for _ in 0..8_000 {
+                writer.write_sample(i16::MAX / 2).unwrap();
+                writer.write_sample(i16::MAX / 2).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let samples = read_audio_bytes(data.into_inner(), "wav").unwrap();
+
+        assert_eq!(samples_to_duration_ms(samples.len()), 1_000);
+        assert!((samples[samples.len() / 2] - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn streamed_decode_hands_over_audio_in_blocks() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut data = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+            for _ in 0..48_000 {
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let mut blocks = 0;
+        let mut total = 0;
+        let channels = decode_audio_with(
+            Box::new(std::io::Cursor::new(data.into_inner())),
+            Some("wav"),
+            false,
+            |channel, samples| {
+                assert_eq!(channel, 0);
+                blocks += 1;
+                total += samples.len();
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(channels, 1);
+        assert_eq!(total, 48_000);
+        assert!(blocks > 1);
+    }
+
+    #[test]
+    fn audio_info_is_read_without_decoding() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut data = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut data, spec).unwrap();
+            for _ in 0..12_000 {
+                writer.write_sample(0i16).unwrap();
+                writer.write_sample(0i16).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let info = probe_audio_info(
+            Box::new(std::io::Cursor::new(data.into_inner())),
+            Some("wav"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            info,
+            AudioFileInfo {
+                duration_ms: 1_500,
+                sample_rate: 8_000,
+                channels: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn deinterleave_channel_picks_one_side() {
+        let samples = [1.0, -1.0, 0.5, 0.25];
+
+        assert_eq!(deinterleave_channel(&samples, 2, 0), vec![1.0, 0.5]);
+        assert_eq!(deinterleave_channel(&samples, 2, 1), vec![-1.0, 0.25]);
+    }
+
+    #[test]
+    fn channel_transcripts_are_labelled_and_silent_channels_skipped() {
+        let both = ["Hello there.".to_string(), " Hi! ".to_string()];
+        let left_only = ["Hello there.".to_string(), "  ".to_string()];
+
+        assert_eq!(
+            label_channel_transcripts(&both),
+            "[L] Hello there.\n[R] Hi!"
+        );
+        assert_eq!(label_channel_transcripts(&left_only), "[L] Hello there.");
+        assert_eq!(
+            label_channel_transcripts(&[String::new(), String::new()]),
+            ""
+        );
+    }
+
+    #[test]
+    fn chunk_transcripts_are_joined_without_silent_chunks() {
+        let mut text = String::new();
+        append_chunk_transcript(&mut text, "  ");
+        append_chunk_transcript(&mut text, " First part.");
+        append_chunk_transcript(&mut text, "");
+        append_chunk_transcript(&mut text, "Second part. ");
+
+        assert_eq!(text, "First part. Second part.");
+    }
 }
 
 #[cfg(test)]
@@ -2609,6 +6958,66 @@ mod text_injection_tests {
         assert_eq!(clean, "Hello\nWorld\t\r");
     }
 
+    #[test]
+    fn injection_blocklist_matches_app_names_and_titles() {
+        let blocklist =
+            parse_injection_blocklist("  1Password \n\ncom.bank.app\ntitle:Online Banking\n");
+        assert_eq!(blocklist.len(), 3);
+
+        let password_manager = text_inject::ForegroundApp {
+            app_name: Some("1password".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            text_inject::blocklist_match(&password_manager, &blocklist),
+            Some("1Password")
+        );
+
+        let browser = text_inject::ForegroundApp {
+            app_name: Some("Firefox".to_string()),
+            bundle_id: Some("org.mozilla.firefox".to_string()),
+            window_title: Some("My online banking - Firefox".to_string()),
+        };
+        assert_eq!(
+            text_inject::blocklist_match(&browser, &blocklist),
+            Some("title:Online Banking")
+        );
+
+        let editor = text_inject::ForegroundApp {
+            app_name: Some("Code".to_string()),
+            bundle_id: None,
+            window_title: Some("main.rs".to_string()),
+        };
+        assert_eq!(text_inject::blocklist_match(&editor, &blocklist), None);
+    }
+
+    #[test]
+    fn trailing_separator_follows_the_setting() {
+        assert_eq!(with_trailing_separator("Hello.", "none"), "Hello.");
+        assert_eq!(with_trailing_separator("Hello.", "space"), "Hello. ");
+        assert_eq!(with_trailing_separator("Hello.", "newline"), "Hello.\n");
+
+        assert_eq!(with_trailing_separator("Hello.", "smart"), "Hello. ");
+        assert_eq!(with_trailing_separator("Hello ", "smart"), "Hello ");
+        assert_eq!(
+            with_trailing_separator("Dear team,\n", "smart"),
+            "Dear team,\n"
+        );
+        assert_eq!(with_trailing_separator("see (", "smart"), "see (");
+    }
+
+    #[test]
+    fn trailing_separator_is_not_added_after_command_markers() {
+        assert_eq!(
+            with_trailing_separator("done [[ENTER]]", "space"),
+            "done [[ENTER]]"
+        );
+        assert_eq!(
+            with_trailing_separator("done [[ENTER]]", "smart"),
+            "done [[ENTER]]"
+        );
+    }
+
     #[test]
     fn sanitize_text_respects_byte_limits() {
         let text = "Exactly ten!";
@@ -2647,5 +7056,710 @@ mod model_compatibility_tests {
         // Multilingual whisper allows standard codes or auto
         assert!(is_model_language_supported("large-v3", "auto"));
         assert!(is_model_language_supported("large-v3", "de"));
+        assert!(is_model_language_supported("small", "es"));
+        assert!(!is_model_language_supported("small", "xyz"));
+    }
+
+    #[test]
+    fn model_languages_column_constrains_codes() {
+        assert!(model_languages_allow("[\"multilingual\"]", "es"));
+        assert!(model_languages_allow("[\"multilingual\"]", "auto"));
+        assert!(!model_languages_allow("[\"multilingual\"]", "qq"));
+
+        assert!(model_languages_allow("[\"en\"]", "en"));
+        assert!(!model_languages_allow("[\"en\"]", "es"));
+        assert!(!model_languages_allow("[\"en\"]", "auto"));
+
+        assert!(model_languages_allow("[\"fr\", \"de\"]", "auto"));
+        assert!(!model_languages_allow("[\"fr\", \"de\"]", "es"));
+        assert!(!model_languages_allow("not json", "en"));
+    }
+
+    #[test]
+    fn history_language_replaces_auto_with_detected() {
+        assert_eq!(history_language("auto", None), "auto");
+        assert_eq!(history_language("auto", Some("de")), "de");
+        assert_eq!(history_language("en", Some("de")), "en");
+
+        // An engine that can't tell reports "auto", which is not a detection
+        let detected = detected_language("auto".to_string());
+        assert_eq!(history_language("auto", detected.as_deref()), "auto");
+    }
+}
+
+#[cfg(test)]
+mod custom_model_tests {
+    use super::*;
+
+    #[test]
+    fn custom_model_ids_are_namespaced_and_file_safe() {
+        assert!(validate_custom_model_id("custom-whisper-de").is_ok());
+        assert!(validate_custom_model_id("custom-v1.2_q5").is_ok());
+
+        assert!(validate_custom_model_id("base").is_err());
+        assert!(validate_custom_model_id("custom-").is_err());
+        assert!(validate_custom_model_id("custom-../evil").is_err());
+        assert!(validate_custom_model_id("custom-Upper").is_err());
+        assert!(validate_custom_model_id(&format!("custom-{}", "a".repeat(60))).is_err());
+    }
+
+    #[test]
+    fn custom_model_urls_must_be_https() {
+        assert!(validate_custom_model_url("https://huggingface.co/me/model/ggml.bin").is_ok());
+
+        assert!(validate_custom_model_url("http://example.com/ggml.bin").is_err());
+        assert!(validate_custom_model_url("file:///tmp/ggml.bin").is_err());
+        assert!(validate_custom_model_url("not a url").is_err());
+    }
+}
+
+#[cfg(test)]
+mod speaking_rate_tests {
+    use super::*;
+
+    #[test]
+    fn score_transcription_computes_words_per_minute() {
+        // 30 seconds of audio at 16 kHz
+        let scored = score_transcription("one two three four five".to_string(), 16_000 * 30);
+
+        assert_eq!(scored.word_count, 5);
+        assert_eq!(scored.duration_ms, 30_000);
+        assert_eq!(scored.wpm, Some(10.0));
+    }
+
+    #[test]
+    fn score_transcription_handles_zero_duration() {
+        let scored = score_transcription("hello".to_string(), 0);
+
+        assert_eq!(scored.word_count, 1);
+        assert_eq!(scored.duration_ms, 0);
+        assert_eq!(scored.wpm, None);
+    }
+
+    #[test]
+    fn score_transcription_counts_no_words_for_blank_text() {
+        let scored = score_transcription("   \n ".to_string(), 16_000);
+
+        assert_eq!(scored.word_count, 0);
+        assert_eq!(scored.wpm, Some(0.0));
+        assert!(scored.no_speech);
+    }
+}
+
+#[cfg(test)]
+mod model_recommendation_tests {
+    use super::*;
+
+    fn model(id: &str, size_mb: i64, languages: &str, downloaded: bool) -> WhisperModel {
+        WhisperModel {
+            id: id.to_string(),
+            name: id.to_string(),
+            size: format!("{} MB", size_mb),
+            size_bytes: size_mb * 1024 * 1024,
+            description: String::new(),
+            languages: languages.to_string(),
+            downloaded,
+            download_path: None,
+        }
+    }
+
+    fn catalog() -> Vec<WhisperModel> {
+        vec![
+            model("base", 142, "[\"multilingual\"]", false),
+            model("small", 466, "[\"multilingual\"]", false),
+            model("large-v3", 2969, "[\"multilingual\"]", false),
+            model("base.en", 142, "[\"en\"]", false),
+            model("distil-small.en", 166, "[\"en\"]", false),
+        ]
+    }
+
+    #[test]
+    fn non_english_excludes_english_only_models() {
+        let ranked = rank_models_for_language(&catalog(), "bn", None, None);
+        let ids: Vec<&str> = ranked.iter().map(|r| r.model_id.as_str()).collect();
+
+        assert_eq!(ids, vec!["base", "small", "large-v3"]);
+    }
+
+    #[test]
+    fn english_prefers_distil_models() {
+        let ranked = rank_models_for_language(&catalog(), "en", None, None);
+
+        assert_eq!(ranked[0].model_id, "distil-small.en");
+        assert!(ranked[0].reasons.iter().any(|r| r.contains("Distilled")));
+    }
+
+    #[test]
+    fn hardware_limits_push_large_models_down() {
+        let gb = 1024 * 1024 * 1024;
+        let ranked = rank_models_for_language(&catalog(), "de", Some(2 * gb), Some(gb));
+
+        let last = ranked.last().unwrap();
+        assert_eq!(last.model_id, "large-v3");
+        assert!(last.reasons.iter().any(|r| r.contains("memory")));
+        assert!(last.reasons.iter().any(|r| r.contains("disk")));
+    }
+
+    #[test]
+    fn memory_estimate_flags_large_models_on_small_machines() {
+        let gb = 1024 * 1024 * 1024;
+        let large = model_memory_estimate("large-v3", 2969 * 1024 * 1024, Some(4 * gb), None);
+        let base = model_memory_estimate("base", 142 * 1024 * 1024, Some(4 * gb), None);
+        let unknown = model_memory_estimate("base", 142 * 1024 * 1024, None, None);
+
+        assert!(large.estimated_bytes > large.model_size_bytes * 3 / 2);
+        assert_eq!(large.fits, Some(false));
+        assert_eq!(base.fits, Some(true));
+        assert_eq!(unknown.fits, None);
+    }
+
+    #[test]
+    fn fallback_picks_the_smallest_usable_downloaded_model() {
+        let models = vec![
+            model("large-v3", 2969, "[\"multilingual\"]", false),
+            model("small", 466, "[\"multilingual\"]", true),
+            model("base", 142, "[\"multilingual\"]", true),
+            model("tiny.en", 75, "[\"en\"]", true),
+        ];
+        let all_present = |_: &str| true;
+
+        assert_eq!(
+            pick_fallback_model(&models, "large-v3", "en", all_present).as_deref(),
+            Some("tiny.en")
+        );
+        assert_eq!(
+            pick_fallback_model(&models, "large-v3", "de", all_present).as_deref(),
+            Some("base")
+        );
+        // A stale downloaded flag must not be trusted
+        assert_eq!(
+            pick_fallback_model(&models, "large-v3", "de", |id| id != "base").as_deref(),
+            Some("small")
+        );
+        assert_eq!(
+            pick_fallback_model(&models, "large-v3", "de", |_| false),
+            None
+        );
+    }
+}
+
+#[cfg(test)]
+mod empty_transcription_tests {
+    use super::*;
+
+    #[test]
+    fn blank_text_becomes_empty_string_by_default() {
+        let text = handle_empty_transcription("  \n".to_string(), "empty").unwrap();
+
+        assert_eq!(text, "");
+    }
+
+    #[test]
+    fn blank_text_is_an_error_when_configured() {
+        let result = handle_empty_transcription(" ".to_string(), "error");
+
+        assert!(
+            matches!(result, Err(CommandError::Transcription(ref msg)) if msg == NO_SPEECH_DETECTED)
+        );
+    }
+
+    #[test]
+    fn non_blank_text_passes_through() {
+        let text = handle_empty_transcription(" hello ".to_string(), "error").unwrap();
+
+        assert_eq!(text, " hello ");
+    }
+
+    #[test]
+    fn unknown_behaviors_are_rejected() {
+        assert!(check_empty_transcription_behavior("empty").is_ok());
+        assert!(check_empty_transcription_behavior("error").is_ok());
+        assert_eq!(
+            check_empty_transcription_behavior("ignore").unwrap_err(),
+            "Invalid empty transcription behavior: ignore"
+        );
+    }
+}
+
+#[cfg(test)]
+mod recording_length_tests {
+    use super::*;
+
+    #[test]
+    fn short_recordings_are_rejected() {
+        let result = ensure_min_recording_length(3_999, 4_000);
+
+        assert!(
+            matches!(result, Err(CommandError::Recording(ref msg)) if msg == RECORDING_TOO_SHORT)
+        );
+    }
+
+    #[test]
+    fn recordings_at_the_minimum_are_accepted() {
+        assert!(ensure_min_recording_length(4_000, 4_000).is_ok());
+        assert!(ensure_min_recording_length(1, 0).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod silence_padding_tests {
+    use super::*;
+
+    /// Stands in for Whisper: hears the speech and, when given trailing
+    /// silence, loops on a filler phrase
+    struct FakeEngine {
+        speech_samples: usize,
+        heard_samples: usize,
+    }
+
+    impl TranscriptionEngine for FakeEngine {
+        fn name(&self) -> &'static str {
+            "fake"
+        }
+
+        fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+            self.heard_samples = audio_samples.len();
+            let silence = &audio_samples[self.speech_samples..];
+            assert!(silence.iter().all(|sample| *sample == 0.0));
+            if silence.is_empty() {
+                Ok("turn left".to_string())
+            } else {
+                Ok("Turn left. Thank you. Thank you. Thank you. Thank you.".to_string())
+            }
+        }
+
+        fn set_language(&mut self, _language: &str) {}
+    }
+
+    #[test]
+    fn padded_short_clip_keeps_the_spoken_words() {
+        let speech = vec![0.25; 4_000];
+        let mut engine = FakeEngine {
+            speech_samples: speech.len(),
+            heard_samples: 0,
+        };
+
+        let text = transcribe_samples(&mut engine, &speech, 16_000).unwrap().0;
+
+        assert_eq!(engine.heard_samples, 16_000);
+        assert_eq!(text, "Turn left.");
+    }
+
+    #[test]
+    fn long_clips_are_not_padded() {
+        let speech = vec![0.25; 16_000];
+        let mut engine = FakeEngine {
+            speech_samples: speech.len(),
+            heard_samples: 0,
+        };
+
+        assert_eq!(
+            transcribe_samples(&mut engine, &speech, 8_000).unwrap().0,
+            "turn left"
+        );
+        assert_eq!(engine.heard_samples, 16_000);
+    }
+
+    #[test]
+    fn repeat_suppression_keeps_ordinary_repetition() {
+        assert_eq!(
+            transcription::suppress_repeated_phrases("no no no, not that one"),
+            "no no no, not that one"
+        );
+        assert_eq!(
+            transcription::suppress_repeated_phrases("go go go go go now"),
+            "go now"
+        );
+    }
+
+    #[test]
+    fn only_a_loop_at_the_end_is_stripped() {
+        assert_eq!(
+            transcription::strip_trailing_loop("Thank you. Thank you. Thank you. Thank you."),
+            ""
+        );
+        assert_eq!(
+            transcription::strip_trailing_loop("Thank you for coming. Thank you."),
+            "Thank you for coming. Thank you."
+        );
+        assert_eq!(
+            transcription::strip_trailing_loop("go go go go go now"),
+            "go go go go go now"
+        );
+    }
+}
+
+#[cfg(test)]
+mod settings_transfer_tests {
+    use super::*;
+
+    fn known_models() -> Vec<String> {
+        vec!["base".to_string(), "small".to_string()]
+    }
+
+    #[test]
+    fn exported_settings_round_trip() {
+        let settings = AppSettings {
+            language: "de".to_string(),
+            selected_model_id: "small".to_string(),
+            ..AppSettings::default()
+        };
+
+        let post_processing = PostProcessingConfig {
+            stages: vec![
+                ProcessingStage::UserDictionary,
+                ProcessingStage::Punctuation,
+            ],
+            replacements: vec![post_process::UserReplacement {
+                pattern: "wave tea".to_string(),
+                replacement: "Wavee".to_string(),
+            }],
+            action_markers: false,
+        };
+
+        let json = build_settings_export(&settings, &post_processing).unwrap();
+        let imported = parse_settings_import(&json).unwrap();
+
+        assert_eq!(imported.settings.language, "de");
+        assert_eq!(imported.settings.selected_model_id, "small");
+        assert!(validate_settings(&imported.settings, &known_models()).is_ok());
+        assert_eq!(imported.post_processing, Some(post_processing));
+    }
+
+    #[test]
+    fn files_without_post_processing_still_import() {
+        let settings = serde_json::to_value(AppSettings::default()).unwrap();
+        let blob = serde_json::json!({
+            "format": SETTINGS_EXPORT_FORMAT,
+            "version": SETTINGS_EXPORT_VERSION,
+            "checksum": settings_checksum(&settings, None),
+            "settings": settings,
+        });
+
+        let imported = parse_settings_import(&blob.to_string()).unwrap();
+        assert_eq!(imported.post_processing, None);
+    }
+
+    #[test]
+    fn export_leaves_out_machine_local_settings() {
+        let settings = AppSettings {
+            models_dir: Some("/home/someone/models".to_string()),
+            input_device_name: Some("USB Microphone".to_string()),
+            ..AppSettings::default()
+        };
+
+        let json = build_settings_export(&settings, &PostProcessingConfig::default()).unwrap();
+        let blob: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for key in MACHINE_LOCAL_SETTINGS {
+            assert!(blob["settings"].get(*key).is_none(), "{} was exported", key);
+        }
+
+        let imported = parse_settings_import(&json).unwrap().settings;
+        assert_eq!(imported.models_dir, None);
+        assert_eq!(imported.input_device_name, None);
+    }
+
+    #[test]
+    fn import_rejects_other_versions_and_tampering() {
+        let json = build_settings_export(&AppSettings::default(), &PostProcessingConfig::default())
+            .unwrap();
+        let mut blob: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let mut future = blob.clone();
+        future["version"] = serde_json::json!(99);
+        let error = parse_settings_import(&future.to_string()).unwrap_err();
+        assert!(error.contains("version 99"));
+
+        let mut edited = blob.clone();
+        edited["settings"]["language"] = serde_json::json!("fr");
+        let error = parse_settings_import(&edited.to_string()).unwrap_err();
+        assert!(error.contains("checksum"));
+
+        blob["postProcessing"]["action_markers"] = serde_json::json!(false);
+        let error = parse_settings_import(&blob.to_string()).unwrap_err();
+        assert!(error.contains("checksum"));
+    }
+
+    #[test]
+    fn validation_rejects_bad_fields() {
+        let settings = AppSettings {
+            hotkey_mode: "sometimes".to_string(),
+            ..AppSettings::default()
+        };
+        assert!(validate_settings(&settings, &known_models()).is_err());
+
+        let settings = AppSettings {
+            selected_model_id: "huge".to_string(),
+            ..AppSettings::default()
+        };
+        assert!(validate_settings(&settings, &known_models()).is_err());
+    }
+}
+
+#[cfg(test)]
+mod hotkey_collision_tests {
+    use super::*;
+
+    #[test]
+    fn colliding_actions_are_named() {
+        let error = check_hotkey_collisions(&hotkey_set("Alt+Shift+S", "shift+alt+s", "Escape"))
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "The push-to-talk and toggle hotkeys are both set to Alt+Shift+S"
+        );
+
+        let error =
+            check_hotkey_collisions(&hotkey_set("Alt+Shift+S", "Alt+Shift+D", "Alt+Shift+D"))
+                .unwrap_err();
+        assert!(error.contains("toggle and cancel"));
+    }
+
+    #[test]
+    fn distinct_hotkeys_and_an_unset_cancel_key_pass() {
+        assert!(check_hotkey_collisions(&hotkey_set("Alt+Shift+S", "Alt+Shift+D", "")).is_ok());
+        assert!(check_hotkey_collisions(&configured_hotkeys(&AppSettings::default())).is_ok());
+
+        let error = check_hotkey_collisions(&hotkey_set("", "Alt+Shift+D", "")).unwrap_err();
+        assert!(error.starts_with("Invalid push-to-talk hotkey"));
+    }
+
+    #[test]
+    fn settings_validation_blocks_collisions() {
+        let settings = AppSettings {
+            cancel_key: "Alt+Shift+S".to_string(),
+            ..AppSettings::default()
+        };
+        let known = vec![settings.selected_model_id.clone()];
+        assert!(validate_settings(&settings, &known)
+            .unwrap_err()
+            .contains("push-to-talk and cancel"));
+    }
+}
+
+#[cfg(test)]
+mod hotkey_hold_tests {
+    use super::*;
+
+    #[test]
+    fn taps_shorter_than_the_hold_are_discarded() {
+        let mut hold = HotkeyHold::default();
+        let min_hold = Duration::from_millis(120);
+        let now = Instant::now();
+        assert!(hold.press(now));
+        assert_eq!(
+            hold.release(now + Duration::from_millis(50), min_hold),
+            Some(false)
+        );
+        assert!(hold.press(now));
+        assert_eq!(hold.release(now + min_hold, min_hold), Some(true));
+    }
+
+    #[test]
+    fn auto_repeat_does_not_restart_the_press() {
+        let mut hold = HotkeyHold::default();
+        let min_hold = Duration::from_millis(120);
+        let now = Instant::now();
+        assert!(hold.press(now));
+        assert!(!hold.press(now + Duration::from_millis(100)));
+        assert!(!hold.press(now + Duration::from_millis(130)));
+
+        // The hold counts from the first press, and a stray release is ignored
+        assert_eq!(hold.release(now + min_hold, min_hold), Some(true));
+        assert_eq!(hold.release(now + min_hold, min_hold), None);
+    }
+
+    #[test]
+    fn toggle_mode_never_waits() {
+        let settings = AppSettings {
+            min_hold_ms: 5_000,
+            ..AppSettings::default()
+        };
+        assert_eq!(min_hold_for_mode(&settings, "toggle"), Duration::ZERO);
+        assert_eq!(
+            min_hold_for_mode(&settings, "push-to-talk"),
+            Duration::from_millis(MAX_MIN_HOLD_MS as u64)
+        );
+    }
+}
+
+#[cfg(test)]
+mod rate_limiter_tests {
+    use super::*;
+
+    #[test]
+    fn requests_within_limit_are_accepted() {
+        let limiter = RateLimiter::new(2, 60);
+
+        assert!(limiter.check("action").is_ok());
+        assert!(limiter.check("action").is_ok());
+        assert!(limiter.check("other").is_ok());
+    }
+
+    #[test]
+    fn rejection_reports_time_until_oldest_request_expires() {
+        let limiter = RateLimiter::new(1, 60);
+        limiter.check("action").unwrap();
+
+        let retry_after = limiter.check("action").unwrap_err();
+
+        assert!(retry_after <= Duration::from_secs(60));
+        assert!(retry_after > Duration::from_secs(59));
+    }
+
+    #[test]
+    fn rate_limited_error_includes_wait_in_seconds() {
+        let error = CommandError::RateLimited {
+            message: "Rate limit exceeded.".to_string(),
+            retry_after_ms: 1500,
+        };
+
+        assert_eq!(error.to_string(), "Rate limit exceeded. Try again in 2s.");
+    }
+}
+
+#[cfg(test)]
+mod overlay_position_tests {
+    use super::*;
+
+    #[test]
+    fn fullscreen_has_no_frame() {
+        assert_eq!(
+            overlay_frame(OverlayPosition::Fullscreen, (0, 0), (1920, 1080), 1.0),
+            None
+        );
+    }
+
+    #[test]
+    fn corners_respect_margin_and_monitor_origin() {
+        // Secondary monitor to the right of the primary one, at 2x scale
+        let origin = (1920, 0);
+        let size = (2560, 1440);
+
+        let (top_left, frame_size) =
+            overlay_frame(OverlayPosition::TopLeft, origin, size, 2.0).unwrap();
+        assert_eq!(top_left, (1968, 48));
+        assert_eq!(frame_size, (720, 240));
+
+        let (bottom_right, _) =
+            overlay_frame(OverlayPosition::BottomRight, origin, size, 2.0).unwrap();
+        assert_eq!(bottom_right, (1920 + 2560 - 720 - 48, 1440 - 240 - 48));
+    }
+
+    #[test]
+    fn center_is_centered_on_monitor() {
+        let (origin, size) =
+            overlay_frame(OverlayPosition::Center, (0, 0), (1920, 1080), 1.0).unwrap();
+        assert_eq!(size, (360, 120));
+        assert_eq!(origin, (780, 480));
+    }
+
+    #[test]
+    fn positions_parse_from_settings() {
+        assert_eq!(
+            OverlayPosition::parse("bottom_right"),
+            Some(OverlayPosition::BottomRight)
+        );
+        assert_eq!(OverlayPosition::parse("corner"), None);
+    }
+}
+
+#[cfg(test)]
+mod supported_language_tests {
+    use super::*;
+
+    fn codes(options: &[LanguageOption]) -> Vec<&str> {
+        options.iter().map(|o| o.code.as_str()).collect()
+    }
+
+    #[test]
+    fn english_only_models_offer_only_english() {
+        let options = model_language_options("base.en", r#"["en"]"#);
+
+        assert_eq!(
+            options,
+            vec![LanguageOption {
+                code: "en".to_string(),
+                name: "English".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn multilingual_models_offer_auto_and_every_whisper_language() {
+        let options = model_language_options("small", r#"["multilingual"]"#);
+
+        assert_eq!(options[0].code, "auto");
+        assert_eq!(options[0].name, "Auto detect");
+        assert_eq!(options.len(), transcription::WHISPER_LANGUAGES.len() + 1);
+        assert!(options
+            .iter()
+            .any(|o| o.code == "yue" && o.name == "Cantonese"));
+    }
+
+    #[test]
+    fn language_lists_are_intersected_with_engine_support() {
+        let options = model_language_options("parakeet-v3", r#"["en", "de", "ja"]"#);
+
+        assert_eq!(codes(&options), vec!["auto", "en", "de"]);
+    }
+}
+
+#[cfg(test)]
+mod tray_menu_tests {
+    use super::*;
+
+    #[test]
+    fn recording_items_follow_app_state() {
+        // No model loaded: nothing to start, nothing to stop
+        assert_eq!(tray_recording_items_enabled(false, false), (false, false));
+        assert_eq!(tray_recording_items_enabled(false, true), (true, false));
+        assert_eq!(tray_recording_items_enabled(true, true), (false, true));
+    }
+
+    #[test]
+    fn tray_menu_items_parse_known_ids_only() {
+        assert_eq!(
+            parse_tray_menu_items(" start_recording, ,copy_last ").unwrap(),
+            vec!["start_recording", "copy_last"]
+        );
+        assert!(parse_tray_menu_items("").unwrap().is_empty());
+        assert!(parse_tray_menu_items(database::DEFAULT_TRAY_MENU_ITEMS).is_ok());
+        assert!(parse_tray_menu_items("start_recording,quit").is_err());
+    }
+}
+
+#[cfg(test)]
+mod pending_action_tests {
+    use super::*;
+
+    #[test]
+    fn held_actions_are_released_once() {
+        let mut pending = PendingActions::default();
+        let now = Instant::now();
+        let id = pending.hold("hello [[DELETE_LAST]] world".to_string(), now);
+
+        assert_eq!(
+            pending.take(id, now),
+            Some("hello [[DELETE_LAST]] world".to_string())
+        );
+        assert_eq!(pending.take(id, now), None);
+        assert_eq!(pending.take(id + 1, now), None);
+    }
+
+    #[test]
+    fn held_actions_expire() {
+        let mut pending = PendingActions::default();
+        let now = Instant::now();
+        let stale = pending.hold("[[CUT]]".to_string(), now);
+        let later = now + PENDING_ACTION_TTL;
+
+        assert_eq!(pending.take(stale, later), None);
+
+        // Holding again drops anything already expired
+        pending.hold("[[CUT]]".to_string(), now);
+        let fresh = pending.hold("[[SELECT_ALL]]".to_string(), later);
+        assert_eq!(pending.actions.len(), 1);
+        assert!(pending.take(fresh, later).is_some());
     }
 }
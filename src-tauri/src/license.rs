@@ -33,6 +33,13 @@ const OFFLINE_GRACE_HOURS: i64 = 168; // 7 days
 /// HTTP request timeout
 const REQUEST_TIMEOUT_SECS: u64 = 30;
 
+/// How long a request waits for the connection, so an offline machine fails
+/// fast instead of waiting out the request timeout
+const CONNECT_TIMEOUT_SECS: u64 = 5;
+
+const UNREACHABLE_MESSAGE: &str =
+    "Could not reach the license server. Check your connection or firewall and try again.";
+
 // =============================================================================
 // Public Types
 // =============================================================================
@@ -112,6 +119,15 @@ impl LicenseStatus {
     }
 }
 
+/// Progress of an in-flight activation, reported so slow connections show
+/// where they are stuck
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationStage {
+    Connecting,
+    WaitingOnServer,
+}
+
 // =============================================================================
 // Polar API Request/Response Types
 // =============================================================================
@@ -279,28 +295,42 @@ pub struct CachedLicense {
 
 const CACHE_VERSION: i32 = 2;
 
+/// Trial bookkeeping stored next to the license cache, bound to the device
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialRecord {
+    /// When the trial started (RFC 3339)
+    pub trial_started_at: String,
+    /// Latest wall-clock time observed while the trial was checked (RFC 3339)
+    pub last_seen_at: String,
+    /// Device ID this record was written on
+    pub device_id: String,
+    /// Integrity hash to detect tampering
+    pub integrity_hash: String,
+}
+
+/// Backward clock movement tolerated before a rollback is assumed.
+/// Covers NTP corrections and small drift between checks.
+const CLOCK_ROLLBACK_TOLERANCE_SECS: i64 = 10 * 60;
+
+/// How far the clock must move past the last-seen time before it is
+/// advanced, so checks in quick succession don't each rewrite the record
+const LAST_SEEN_GRANULARITY_SECS: i64 = 60;
+
 // =============================================================================
 // Device Identification
 // =============================================================================
 
-/// Generate a unique, stable device fingerprint
-/// Uses hardware identifiers to create a reproducible ID
-pub fn get_device_id() -> String {
-    let mut hasher = Sha256::new();
-
-    // Hostname
-    if let Ok(hostname) = hostname::get() {
-        hasher.update(hostname.to_string_lossy().as_bytes());
-    }
-
-    // OS and architecture
-    hasher.update(std::env::consts::OS.as_bytes());
-    hasher.update(std::env::consts::ARCH.as_bytes());
+/// Raw values hashed into the device id
+struct DeviceFingerprintInputs {
+    hostname: Option<String>,
+    username: Option<String>,
+    /// Where the hardware id came from, and the bytes that were hashed
+    hardware_id: Option<(&'static str, Vec<u8>)>,
+}
 
-    // Username for multi-user systems
-    if let Ok(user) = std::env::var("USER").or_else(|_| std::env::var("USERNAME")) {
-        hasher.update(user.as_bytes());
-    }
+fn collect_device_fingerprint_inputs() -> DeviceFingerprintInputs {
+    #[allow(unused_mut)]
+    let mut hardware_id = None;
 
     // Platform-specific hardware identifiers
     #[cfg(target_os = "macos")]
@@ -312,7 +342,7 @@ pub fn get_device_id() -> String {
         {
             let output_str = String::from_utf8_lossy(&output.stdout);
             if let Some(line) = output_str.lines().find(|l| l.contains("IOPlatformUUID")) {
-                hasher.update(line.as_bytes());
+                hardware_id = Some(("ioreg IOPlatformUUID", line.as_bytes().to_vec()));
             }
         }
     }
@@ -324,15 +354,103 @@ pub fn get_device_id() -> String {
             .args(["csproduct", "get", "UUID"])
             .output()
         {
-            hasher.update(&output.stdout);
+            hardware_id = Some(("wmic csproduct UUID", output.stdout));
         }
     }
 
+    DeviceFingerprintInputs {
+        hostname: hostname::get()
+            .ok()
+            .map(|hostname| hostname.to_string_lossy().to_string()),
+        // Username for multi-user systems
+        username: std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok(),
+        hardware_id,
+    }
+}
+
+fn device_id_from_inputs(inputs: &DeviceFingerprintInputs) -> String {
+    let mut hasher = Sha256::new();
+
+    if let Some(hostname) = &inputs.hostname {
+        hasher.update(hostname.as_bytes());
+    }
+
+    // OS and architecture
+    hasher.update(std::env::consts::OS.as_bytes());
+    hasher.update(std::env::consts::ARCH.as_bytes());
+
+    if let Some(username) = &inputs.username {
+        hasher.update(username.as_bytes());
+    }
+    if let Some((_, hardware_id)) = &inputs.hardware_id {
+        hasher.update(hardware_id);
+    }
+
     // Create readable device ID with prefix
     let hash = hasher.finalize();
     format!("WVT-{}", hex::encode(&hash[..12]).to_uppercase())
 }
 
+/// Generate a unique, stable device fingerprint
+/// Uses hardware identifiers to create a reproducible ID
+pub fn get_device_id() -> String {
+    device_id_from_inputs(&collect_device_fingerprint_inputs())
+}
+
+/// What went into the device id, for support to see why it changed. Raw
+/// values are replaced by short digests that only show whether they differ.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceFingerprintDebug {
+    pub device_id: String,
+    pub os: String,
+    pub arch: String,
+    pub hostname_present: bool,
+    pub hostname_digest: Option<String>,
+    pub username_present: bool,
+    pub username_digest: Option<String>,
+    /// Which hardware id source was found, if any
+    pub hardware_id_source: Option<String>,
+    pub hardware_id_digest: Option<String>,
+}
+
+fn short_digest(value: &[u8]) -> String {
+    hex::encode(&Sha256::digest(value)[..4])
+}
+
+fn device_fingerprint_debug_from_inputs(
+    inputs: &DeviceFingerprintInputs,
+) -> DeviceFingerprintDebug {
+    // A failed hardware query can still produce empty output
+    let hardware_id = inputs
+        .hardware_id
+        .as_ref()
+        .filter(|(_, value)| !value.iter().all(u8::is_ascii_whitespace));
+
+    DeviceFingerprintDebug {
+        device_id: device_id_from_inputs(inputs),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        hostname_present: inputs.hostname.is_some(),
+        hostname_digest: inputs
+            .hostname
+            .as_deref()
+            .map(|h| short_digest(h.as_bytes())),
+        username_present: inputs.username.is_some(),
+        username_digest: inputs
+            .username
+            .as_deref()
+            .map(|u| short_digest(u.as_bytes())),
+        hardware_id_source: hardware_id.map(|(source, _)| source.to_string()),
+        hardware_id_digest: hardware_id.map(|(_, value)| short_digest(value)),
+    }
+}
+
+pub fn get_device_fingerprint_debug() -> DeviceFingerprintDebug {
+    device_fingerprint_debug_from_inputs(&collect_device_fingerprint_inputs())
+}
+
 /// Get a human-readable device label
 pub fn get_device_label() -> String {
     let hostname = hostname::get()
@@ -380,15 +498,62 @@ fn get_cache_path() -> Option<PathBuf> {
     get_cache_dir().map(|d| d.join(".license.dat"))
 }
 
+fn get_trial_record_path() -> Option<PathBuf> {
+    get_cache_dir().map(|d| d.join(".trial.dat"))
+}
+
 /// Calculate integrity hash for cache tampering detection
 fn calculate_integrity_hash(cache: &CachedLicense) -> String {
+    integrity_hash_for_version(cache, CACHE_VERSION).expect("current cache version is hashable")
+}
+
+/// Integrity hash of `cache` under the scheme written by cache `version`, or
+/// `None` for versions this build does not know how to verify. Version 2 is
+/// the first that shipped; add a branch here when the hash inputs change.
+fn integrity_hash_for_version(cache: &CachedLicense, version: i32) -> Option<String> {
+    let salt: &[u8] = match version {
+        2 => b"wavee-integrity-v2",
+        _ => return None,
+    };
+
     let mut hasher = Sha256::new();
     hasher.update(cache.license_key.as_bytes());
     hasher.update(cache.activation_id.as_bytes());
     hasher.update(cache.device_id.as_bytes());
     hasher.update(cache.benefit_id.as_bytes());
-    hasher.update(b"wavee-integrity-v2");
-    hex::encode(hasher.finalize())
+    hasher.update(salt);
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Result of checking a cache's integrity hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheIntegrity {
+    /// Verified with the current scheme
+    Current,
+    /// Written by an older version and verified with that version's scheme;
+    /// it should be rewritten with the current one
+    Outdated,
+    /// The hash does not match the scheme of the version that wrote it
+    Invalid,
+}
+
+/// Verify `cache` against the hashing scheme of the version that wrote it, so
+/// a change to the hash inputs does not log out users with a valid cache.
+fn verify_cache_integrity(cache: &CachedLicense) -> CacheIntegrity {
+    if cache.cache_version > CACHE_VERSION {
+        return CacheIntegrity::Invalid;
+    }
+
+    match integrity_hash_for_version(cache, cache.cache_version) {
+        Some(hash) if hash == cache.integrity_hash => {
+            if cache.cache_version == CACHE_VERSION {
+                CacheIntegrity::Current
+            } else {
+                CacheIntegrity::Outdated
+            }
+        }
+        _ => CacheIntegrity::Invalid,
+    }
 }
 
 /// Encrypt data using device-bound key with AES-256-GCM
@@ -438,11 +603,11 @@ pub fn load_cache() -> Option<CachedLicense> {
     let encrypted = std::fs::read(&cache_path).ok()?;
     let decrypted = decrypt_data(&encrypted).ok()?;
     let json = String::from_utf8(decrypted).ok()?;
-    let cache: CachedLicense = serde_json::from_str(&json).ok()?;
+    let mut cache: CachedLicense = serde_json::from_str(&json).ok()?;
 
-    // Verify integrity
-    let expected_hash = calculate_integrity_hash(&cache);
-    if cache.integrity_hash != expected_hash {
+    // Verify integrity against the scheme of the version that wrote the cache
+    let integrity = verify_cache_integrity(&cache);
+    if integrity == CacheIntegrity::Invalid {
         warn!("License cache integrity check failed - possible tampering");
         return None;
     }
@@ -453,10 +618,17 @@ pub fn load_cache() -> Option<CachedLicense> {
         return None;
     }
 
-    // Check cache version
-    if cache.cache_version != CACHE_VERSION {
-        warn!("License cache version mismatch");
-        return None;
+    // Rewrite caches from older versions with the current scheme
+    if integrity == CacheIntegrity::Outdated {
+        info!(
+            "Upgrading license cache from version {} to {}",
+            cache.cache_version, CACHE_VERSION
+        );
+        if let Err(e) = store_cache(&cache) {
+            warn!("Failed to rewrite upgraded license cache: {}", e);
+        }
+        cache.integrity_hash = calculate_integrity_hash(&cache);
+        cache.cache_version = CACHE_VERSION;
     }
 
     debug!("License cache loaded successfully");
@@ -506,6 +678,107 @@ pub fn clear_cache() -> Result<(), String> {
     Ok(())
 }
 
+// =============================================================================
+// Trial Clock Tracking
+// =============================================================================
+
+fn calculate_trial_record_hash(record: &TrialRecord) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(record.trial_started_at.as_bytes());
+    hasher.update(record.last_seen_at.as_bytes());
+    hasher.update(record.device_id.as_bytes());
+    hasher.update(b"wavee-trial-record-v1");
+    hex::encode(hasher.finalize())
+}
+
+impl TrialRecord {
+    /// Start tracking a trial, seeding the last-seen time with `now`
+    pub fn new(trial_started_at: &str, now: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            trial_started_at: trial_started_at.to_string(),
+            last_seen_at: now.to_rfc3339(),
+            device_id: get_device_id(),
+            integrity_hash: String::new(),
+        }
+    }
+
+    /// Record a check at `now`, refusing clocks that moved backward.
+    /// Returns whether the last-seen time advanced and the record needs
+    /// storing.
+    ///
+    /// The last-seen time only ever moves forward, so a rolled-back clock
+    /// keeps failing until it catches up with the latest time observed.
+    pub fn observe(&mut self, now: chrono::DateTime<chrono::Utc>) -> Result<bool, String> {
+        let tolerance = chrono::Duration::seconds(CLOCK_ROLLBACK_TOLERANCE_SECS);
+
+        let last_seen = chrono::DateTime::parse_from_rfc3339(&self.last_seen_at)
+            .map_err(|_| "Trial record has an invalid timestamp".to_string())?
+            .with_timezone(&chrono::Utc);
+        if now + tolerance < last_seen {
+            return Err("System clock moved backward since the last trial check".to_string());
+        }
+
+        let started = chrono::DateTime::parse_from_rfc3339(&self.trial_started_at)
+            .map_err(|_| "Trial record has an invalid timestamp".to_string())?
+            .with_timezone(&chrono::Utc);
+        if now + tolerance < started {
+            return Err("System clock is earlier than the trial start".to_string());
+        }
+
+        if now < last_seen + chrono::Duration::seconds(LAST_SEEN_GRANULARITY_SECS) {
+            return Ok(false);
+        }
+        self.last_seen_at = now.to_rfc3339();
+        Ok(true)
+    }
+}
+
+/// Store the trial record securely
+pub fn store_trial_record(record: &TrialRecord) -> Result<(), String> {
+    let cache_dir = get_cache_dir().ok_or("Failed to get cache directory")?;
+
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create cache directory: {}", e))?;
+
+    let record_path = get_trial_record_path().ok_or("Failed to get trial record path")?;
+
+    let mut record_with_hash = record.clone();
+    record_with_hash.integrity_hash = calculate_trial_record_hash(record);
+
+    let json = serde_json::to_string(&record_with_hash)
+        .map_err(|e| format!("Failed to serialize trial record: {}", e))?;
+
+    let encrypted = encrypt_data(json.as_bytes())
+        .map_err(|e| format!("Failed to encrypt trial record: {}", e))?;
+
+    std::fs::write(&record_path, encrypted)
+        .map_err(|e| format!("Failed to write trial record: {}", e))?;
+
+    Ok(())
+}
+
+/// Load the trial record from disk
+pub fn load_trial_record() -> Option<TrialRecord> {
+    let record_path = get_trial_record_path()?;
+
+    let encrypted = std::fs::read(&record_path).ok()?;
+    let decrypted = decrypt_data(&encrypted).ok()?;
+    let json = String::from_utf8(decrypted).ok()?;
+    let record: TrialRecord = serde_json::from_str(&json).ok()?;
+
+    if record.integrity_hash != calculate_trial_record_hash(&record) {
+        warn!("Trial record integrity check failed - possible tampering");
+        return None;
+    }
+
+    if record.device_id != get_device_id() {
+        warn!("Trial record device mismatch");
+        return None;
+    }
+
+    Some(record)
+}
+
 // =============================================================================
 // License Manager
 // =============================================================================
@@ -526,6 +799,7 @@ impl LicenseManager {
     /// Create license manager with custom org ID
     pub fn with_org_id(org_id: &str, api_base: &str) -> Self {
         let client = Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
             .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
             .build()
             .unwrap_or_else(|_| Client::new());
@@ -542,6 +816,23 @@ impl LicenseManager {
     /// This creates an activation instance in Polar and stores the activation_id
     /// locally for future validations.
     pub async fn activate(&self, license_key: &str) -> Result<LicenseInfo, String> {
+        self.activate_with_progress(license_key, |_| {}).await
+    }
+
+    /// Activate a license key, reporting each [`ActivationStage`] as it starts.
+    ///
+    /// Requests go through the HTTP client, so proxies are honoured, and its
+    /// connect timeout makes an offline machine fail fast.
+    pub async fn activate_with_progress<F>(
+        &self,
+        license_key: &str,
+        on_stage: F,
+    ) -> Result<LicenseInfo, String>
+    where
+        F: Fn(ActivationStage),
+    {
+        on_stage(ActivationStage::Connecting);
+
         let device_id = get_device_id();
         let device_label = get_device_label();
 
@@ -561,6 +852,7 @@ impl LicenseManager {
         let url = format!("{}/activate", self.api_base);
         debug!("POST {}", url);
 
+        on_stage(ActivationStage::WaitingOnServer);
         let response = self
             .client
             .post(&url)
@@ -568,7 +860,14 @@ impl LicenseManager {
             .json(&request)
             .send()
             .await
-            .map_err(|e| format!("Network error: {}", e))?;
+            .map_err(|e| {
+                if e.is_connect() || e.is_timeout() {
+                    warn!("License server unreachable: {}", e);
+                    UNREACHABLE_MESSAGE.to_string()
+                } else {
+                    format!("Network error: {}", e)
+                }
+            })?;
 
         let status = response.status();
         let body = response.text().await.unwrap_or_default();
@@ -1121,6 +1420,30 @@ mod tests {
         assert!(id1.starts_with("WVT-"));
     }
 
+    #[test]
+    fn test_device_fingerprint_debug_hides_raw_values() {
+        let inputs = DeviceFingerprintInputs {
+            hostname: Some("alices-laptop".to_string()),
+            username: None,
+            hardware_id: Some(("test source", b"UUID-1234-SECRET".to_vec())),
+        };
+
+        let debug = device_fingerprint_debug_from_inputs(&inputs);
+        assert_eq!(debug.device_id, device_id_from_inputs(&inputs));
+        assert!(debug.hostname_present);
+        assert!(!debug.username_present);
+        assert_eq!(debug.hardware_id_source.as_deref(), Some("test source"));
+
+        let json = serde_json::to_string(&debug).unwrap();
+        assert!(!json.contains("alices-laptop"));
+        assert!(!json.contains("SECRET"));
+    }
+
+    #[test]
+    fn test_device_fingerprint_debug_matches_device_id() {
+        assert_eq!(get_device_fingerprint_debug().device_id, get_device_id());
+    }
+
     #[test]
     fn test_device_label() {
         let label = get_device_label();
@@ -1163,4 +1486,106 @@ mod tests {
 
         assert!(!cached_license_allows_offline(&cache));
     }
+
+    fn cached_license_at_version(version: i32) -> CachedLicense {
+        let mut cache = CachedLicense {
+            license_key: "test-license".to_string(),
+            activation_id: "test-activation".to_string(),
+            device_id: get_device_id(),
+            device_label: get_device_label(),
+            customer_email: None,
+            customer_name: None,
+            benefit_id: "test-benefit".to_string(),
+            expires_at: None,
+            last_validated_at: chrono::Utc::now().to_rfc3339(),
+            status: "granted".to_string(),
+            usage: 0,
+            validations: 0,
+            integrity_hash: String::new(),
+            cache_version: version,
+        };
+        cache.integrity_hash = integrity_hash_for_version(&cache, version).unwrap();
+        cache
+    }
+
+    #[test]
+    fn test_cache_integrity_accepts_current_version() {
+        let cache = cached_license_at_version(CACHE_VERSION);
+        assert_eq!(verify_cache_integrity(&cache), CacheIntegrity::Current);
+
+        let mut tampered = cache.clone();
+        tampered.benefit_id = "other-benefit".to_string();
+        assert_eq!(verify_cache_integrity(&tampered), CacheIntegrity::Invalid);
+    }
+
+    #[test]
+    fn test_cache_integrity_rejects_genuine_mismatches() {
+        // No build ever wrote a version 1 cache, so there is no scheme to
+        // check it against
+        let mut unshipped = cached_license_at_version(CACHE_VERSION);
+        unshipped.cache_version = 1;
+        assert_eq!(verify_cache_integrity(&unshipped), CacheIntegrity::Invalid);
+
+        let mut unknown = cached_license_at_version(CACHE_VERSION);
+        unknown.cache_version = CACHE_VERSION + 1;
+        assert_eq!(verify_cache_integrity(&unknown), CacheIntegrity::Invalid);
+    }
+
+    #[test]
+    fn test_trial_record_advances_with_the_clock() {
+        let start = chrono::Utc::now();
+        let mut record = TrialRecord::new(&start.to_rfc3339(), start);
+
+        let later = start + chrono::Duration::hours(5);
+        assert_eq!(record.observe(later), Ok(true));
+        assert_eq!(record.last_seen_at, later.to_rfc3339());
+    }
+
+    #[test]
+    fn test_trial_record_only_changes_when_the_clock_moves_on() {
+        let start = chrono::Utc::now();
+        let mut record = TrialRecord::new(&start.to_rfc3339(), start);
+
+        assert_eq!(
+            record.observe(start + chrono::Duration::seconds(5)),
+            Ok(false)
+        );
+        assert_eq!(record.last_seen_at, start.to_rfc3339());
+    }
+
+    #[test]
+    fn test_trial_record_rejects_clock_rollback() {
+        let start = chrono::Utc::now();
+        let mut record = TrialRecord::new(&start.to_rfc3339(), start);
+        record.observe(start + chrono::Duration::days(3)).unwrap();
+
+        // Rolling the clock back to day one must not reopen the trial
+        let rolled_back = start + chrono::Duration::days(1);
+        assert!(record.observe(rolled_back).is_err());
+        // The high-water mark is kept, so repeated checks keep failing
+        assert!(record.observe(rolled_back).is_err());
+        assert!(record.observe(start + chrono::Duration::days(3)).is_ok());
+    }
+
+    #[test]
+    fn test_trial_record_tolerates_small_corrections() {
+        let start = chrono::Utc::now();
+        let mut record = TrialRecord::new(&start.to_rfc3339(), start);
+        let seen = start + chrono::Duration::hours(1);
+        record.observe(seen).unwrap();
+
+        assert_eq!(
+            record.observe(seen - chrono::Duration::minutes(2)),
+            Ok(false)
+        );
+        assert_eq!(record.last_seen_at, seen.to_rfc3339());
+    }
+
+    #[test]
+    fn test_trial_record_rejects_clock_before_trial_start() {
+        let start = chrono::Utc::now();
+        let mut record = TrialRecord::new(&start.to_rfc3339(), start - chrono::Duration::days(2));
+
+        assert!(record.observe(start - chrono::Duration::days(1)).is_err());
+    }
 }
@@ -0,0 +1,208 @@
+//! Application logging
+//!
+//! Wraps `env_logger` so console output keeps working as before, and adds a
+//! daily-rotated file sink under the app's `logs` directory once it is known.
+//! The level for both sinks comes from `RUST_LOG` (default `info`).
+
+use chrono::{Local, NaiveDate};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+/// Number of daily log files kept on disk
+const MAX_LOG_FILES: usize = 7;
+const LOG_FILE_PREFIX: &str = "wavee-";
+const LOG_FILE_EXTENSION: &str = "log";
+
+struct AppLogger {
+    console: env_logger::Logger,
+    file: Mutex<Option<FileSink>>,
+}
+
+/// A log file that rolls over to a new file each day
+struct FileSink {
+    log_dir: PathBuf,
+    date: NaiveDate,
+    file: File,
+}
+
+impl FileSink {
+    fn open(log_dir: PathBuf, date: NaiveDate) -> std::io::Result<Self> {
+        fs::create_dir_all(&log_dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file_path(&log_dir, date))?;
+        prune_old_logs(&log_dir, MAX_LOG_FILES);
+
+        Ok(Self {
+            log_dir,
+            date,
+            file,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let today = Local::now().date_naive();
+        if today != self.date {
+            *self = Self::open(self.log_dir.clone(), today)?;
+        }
+
+        writeln!(self.file, "{}", line)
+    }
+
+    fn path(&self) -> PathBuf {
+        log_file_path(&self.log_dir, self.date)
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.console.matches(record) {
+            return;
+        }
+
+        self.console.log(record);
+
+        if let Some(ref mut sink) = *self.file.lock().unwrap() {
+            let line = format!(
+                "{} {:<5} {}: {}",
+                Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            let _ = sink.write_line(&line);
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(ref mut sink) = *self.file.lock().unwrap() {
+            let _ = sink.file.flush();
+        }
+    }
+}
+
+/// Install the global logger. Safe to call more than once; only the first
+/// call has any effect.
+pub fn init() {
+    let mut installed = false;
+    let logger = LOGGER.get_or_init(|| {
+        installed = true;
+        AppLogger {
+            console: env_logger::Builder::from_env(
+                env_logger::Env::default().default_filter_or("info"),
+            )
+            .format_timestamp_millis()
+            .build(),
+            file: Mutex::new(None),
+        }
+    });
+
+    if installed && log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.console.filter());
+    }
+}
+
+/// Start mirroring log output to a daily file in `log_dir`
+pub fn attach_file_sink(log_dir: PathBuf) -> std::io::Result<PathBuf> {
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| std::io::Error::other("Logger not initialized"))?;
+
+    let sink = FileSink::open(log_dir, Local::now().date_naive())?;
+    let path = sink.path();
+    *logger.file.lock().unwrap() = Some(sink);
+    Ok(path)
+}
+
+/// Path of the log file currently being written, if the file sink is attached
+pub fn current_log_path() -> Option<PathBuf> {
+    LOGGER
+        .get()
+        .and_then(|logger| logger.file.lock().unwrap().as_ref().map(FileSink::path))
+}
+
+fn log_file_path(log_dir: &Path, date: NaiveDate) -> PathBuf {
+    log_dir.join(format!(
+        "{}{}.{}",
+        LOG_FILE_PREFIX,
+        date.format("%Y-%m-%d"),
+        LOG_FILE_EXTENSION
+    ))
+}
+
+/// Delete the oldest daily log files so at most `keep` remain
+fn prune_old_logs(log_dir: &Path, keep: usize) {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return;
+    };
+
+    let mut logs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension().and_then(|e| e.to_str()) == Some(LOG_FILE_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(LOG_FILE_PREFIX))
+        })
+        .collect();
+
+    if logs.len() <= keep {
+        return;
+    }
+
+    // Date-stamped names sort chronologically
+    logs.sort();
+    for path in &logs[..logs.len() - keep] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn prune_old_logs_keeps_newest_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for day in 1..=5 {
+            let date = NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+            File::create(log_file_path(temp_dir.path(), date)).unwrap();
+        }
+        File::create(temp_dir.path().join("errors.json")).unwrap();
+
+        prune_old_logs(temp_dir.path(), 3);
+
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        assert!(!log_file_path(temp_dir.path(), date(1)).exists());
+        assert!(!log_file_path(temp_dir.path(), date(2)).exists());
+        assert!(log_file_path(temp_dir.path(), date(3)).exists());
+        assert!(log_file_path(temp_dir.path(), date(5)).exists());
+        assert!(temp_dir.path().join("errors.json").exists());
+    }
+
+    #[test]
+    fn file_sink_appends_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut sink =
+            FileSink::open(temp_dir.path().to_path_buf(), Local::now().date_naive()).unwrap();
+
+        sink.write_line("first").unwrap();
+        sink.write_line("second").unwrap();
+
+        let contents = fs::read_to_string(sink.path()).unwrap();
+        assert_eq!(contents, "first\nsecond\n");
+    }
+}
@@ -0,0 +1,164 @@
+//! Least-recently-used cache of loaded models kept warm for fast switching
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// A cached model as reported to the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WarmModelInfo {
+    pub model_id: String,
+    pub size_bytes: u64,
+    pub cached_at: String,
+}
+
+struct Entry<T> {
+    info: WarmModelInfo,
+    model: T,
+}
+
+/// Holds up to `capacity` models, least recently used first. Models are
+/// moved out with `take` while active and put back with `insert`.
+pub struct ModelCache<T> {
+    entries: VecDeque<Entry<T>>,
+    capacity: usize,
+}
+
+impl<T> ModelCache<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Change the capacity, returning the ids of any evicted models
+    pub fn set_capacity(&mut self, capacity: usize) -> Vec<String> {
+        self.capacity = capacity;
+        self.evict(u64::MAX)
+    }
+
+    /// Remove and return the cached model for `model_id`
+    pub fn take(&mut self, model_id: &str) -> Option<T> {
+        let index = self
+            .entries
+            .iter()
+            .position(|entry| entry.info.model_id == model_id)?;
+        self.entries.remove(index).map(|entry| entry.model)
+    }
+
+    /// Cache a model as the most recently used one, then evict the least
+    /// recently used models until both the capacity and `max_total_bytes`
+    /// are respected. Returns the ids of evicted models, which may include
+    /// `model_id` itself when it does not fit.
+    pub fn insert(
+        &mut self,
+        model_id: &str,
+        size_bytes: u64,
+        model: T,
+        max_total_bytes: u64,
+    ) -> Vec<String> {
+        // A model is only ever cached once
+        drop(self.take(model_id));
+
+        self.entries.push_back(Entry {
+            info: WarmModelInfo {
+                model_id: model_id.to_string(),
+                size_bytes,
+                cached_at: chrono::Utc::now().to_rfc3339(),
+            },
+            model,
+        });
+        self.evict(max_total_bytes)
+    }
+
+    fn evict(&mut self, max_total_bytes: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.entries.len() > self.capacity || self.total_bytes() > max_total_bytes {
+            match self.entries.pop_front() {
+                Some(entry) => evicted.push(entry.info.model_id),
+                None => break,
+            }
+        }
+        evicted
+    }
+
+    fn total_bytes(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.info.size_bytes).sum()
+    }
+
+    /// Cached models, least recently used first
+    pub fn entries(&self) -> Vec<WarmModelInfo> {
+        self.entries
+            .iter()
+            .map(|entry| entry.info.clone())
+            .collect()
+    }
+
+    /// Drop every cached model, returning how many were removed
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(cache: &ModelCache<&str>) -> Vec<String> {
+        cache
+            .entries()
+            .into_iter()
+            .map(|entry| entry.model_id)
+            .collect()
+    }
+
+    #[test]
+    fn evicts_least_recently_used_beyond_capacity() {
+        let mut cache = ModelCache::new(2);
+        cache.insert("tiny", 10, "tiny", u64::MAX);
+        cache.insert("base", 10, "base", u64::MAX);
+        let evicted = cache.insert("small", 10, "small", u64::MAX);
+
+        assert_eq!(evicted, vec!["tiny".to_string()]);
+        assert_eq!(ids(&cache), vec!["base", "small"]);
+    }
+
+    #[test]
+    fn take_removes_the_model_and_reinsert_refreshes_it() {
+        let mut cache = ModelCache::new(2);
+        cache.insert("tiny", 10, "tiny", u64::MAX);
+        cache.insert("base", 10, "base", u64::MAX);
+
+        assert_eq!(cache.take("tiny"), Some("tiny"));
+        assert_eq!(cache.take("tiny"), None);
+
+        cache.insert("tiny", 10, "tiny", u64::MAX);
+        let evicted = cache.insert("small", 10, "small", u64::MAX);
+        assert_eq!(evicted, vec!["base".to_string()]);
+    }
+
+    #[test]
+    fn memory_budget_keeps_large_models_out() {
+        let mut cache = ModelCache::new(2);
+        cache.insert("base", 150, "base", 1_000);
+        let evicted = cache.insert("large-v3", 3_000, "large-v3", 1_000);
+
+        assert_eq!(evicted, vec!["base".to_string(), "large-v3".to_string()]);
+        assert!(cache.entries().is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let mut cache = ModelCache::new(1);
+        cache.insert("base", 10, "base", u64::MAX);
+
+        assert_eq!(cache.set_capacity(0), vec!["base".to_string()]);
+        assert_eq!(
+            cache.insert("tiny", 10, "tiny", u64::MAX),
+            vec!["tiny".to_string()]
+        );
+        assert_eq!(cache.clear(), 0);
+    }
+}
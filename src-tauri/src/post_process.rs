@@ -1,7 +1,119 @@
 use lazy_static::lazy_static;
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// A single named pass of the post-processing pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStage {
+    UserDictionary,
+    VoiceCommands,
+    ExplicitCasing,
+    Functions,
+    FileMentions,
+    FilePaths,
+    Variables,
+    Classes,
+    Symbols,
+    SentenceCasing,
+    Abbreviations,
+    Keywords,
+    /// Prose spacing around punctuation. Only the dictation preset runs it:
+    /// in code `a,b` and `Foo.Bar` must stay as they are.
+    Punctuation,
+    Whitespace,
+}
+
+/// Stage order used when no other order is configured.
+/// The user dictionary sees the raw transcription. File paths and mentions
+/// run BEFORE sentence casing to avoid capitalizing letters after dots in
+/// filenames like "build.rs".
+pub const DEFAULT_STAGES: &[ProcessingStage] = &[
+    ProcessingStage::UserDictionary,
+    ProcessingStage::VoiceCommands,
+    ProcessingStage::ExplicitCasing,
+    ProcessingStage::Functions,
+    ProcessingStage::FileMentions,
+    ProcessingStage::FilePaths,
+    ProcessingStage::Variables,
+    ProcessingStage::Classes,
+    ProcessingStage::Symbols,
+    ProcessingStage::SentenceCasing,
+    ProcessingStage::Abbreviations,
+    ProcessingStage::Keywords,
+    ProcessingStage::Whitespace,
+];
+
+/// Plain dictation: voice commands and sentence casing, no code transforms
+pub const DICTATION_STAGES: &[ProcessingStage] = &[
+    ProcessingStage::UserDictionary,
+    ProcessingStage::VoiceCommands,
+    ProcessingStage::SentenceCasing,
+    ProcessingStage::Abbreviations,
+    ProcessingStage::Punctuation,
+    ProcessingStage::Whitespace,
+];
+
+/// Code formatting only: the casing and symbol passes, for text that was
+/// already typed and should not be re-read as voice commands
+pub const CODE_STAGES: &[ProcessingStage] = &[
+    ProcessingStage::ExplicitCasing,
+    ProcessingStage::Functions,
+    ProcessingStage::FileMentions,
+    ProcessingStage::FilePaths,
+    ProcessingStage::Variables,
+    ProcessingStage::Classes,
+    ProcessingStage::Symbols,
+    ProcessingStage::Keywords,
+    ProcessingStage::Whitespace,
+];
+
+impl ProcessingStage {
+    /// Stage order for a named preset
+    pub fn preset(name: &str) -> Option<&'static [ProcessingStage]> {
+        match name {
+            "default" => Some(DEFAULT_STAGES),
+            "dictation" => Some(DICTATION_STAGES),
+            "code" => Some(CODE_STAGES),
+            _ => None,
+        }
+    }
+}
+
+/// A user dictionary entry: a regex and what to replace its matches with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserReplacement {
+    pub pattern: String,
+    pub replacement: String,
+}
+
+/// Most user dictionary entries a config may hold
+pub const MAX_USER_REPLACEMENTS: usize = 500;
+
+/// Post-processing configuration as stored in the database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostProcessingConfig {
+    /// Stages to run, in order. Stages not listed are disabled.
+    pub stages: Vec<ProcessingStage>,
+    /// User dictionary, applied in order by the `UserDictionary` stage
+    pub replacements: Vec<UserReplacement>,
+    /// Emit `[[...]]` markers for action commands like "undo that". When
+    /// off, the command phrases are removed without a trace.
+    pub action_markers: bool,
+}
+
+impl Default for PostProcessingConfig {
+    fn default() -> Self {
+        Self {
+            stages: DEFAULT_STAGES.to_vec(),
+            replacements: Vec::new(),
+            action_markers: true,
+        }
+    }
+}
+
 /// Post-processor for transcribed text
 /// Handles proper casing, file paths, function names, and programming patterns
 pub struct PostProcessor {
@@ -9,6 +121,12 @@ pub struct PostProcessor {
     keywords: HashMap<String, String>,
     /// File extensions for path detection
     file_extensions: Vec<&'static str>,
+    /// Stages run by `process`, in order. Stages not listed are skipped.
+    stages: Vec<ProcessingStage>,
+    /// Compiled user dictionary
+    replacements: Vec<(Regex, String)>,
+    /// Whether action commands become `[[...]]` markers or are stripped
+    action_markers: bool,
 }
 
 lazy_static! {
@@ -318,39 +436,89 @@ impl PostProcessor {
                 "swift", "kt", "scala", "ex", "exs", "erl", "hs", "ml", "fs", "clj", "lisp", "r",
                 "jl", "lua", "pl", "pm",
             ],
+            stages: DEFAULT_STAGES.to_vec(),
+            replacements: Vec::new(),
+            action_markers: true,
         }
     }
 
-    /// Main post-processing function
-    pub fn process(&self, text: &str) -> String {
-        let mut result = text.to_string();
-
-        // Apply transformations in order
-        // IMPORTANT: Process file paths and mentions BEFORE sentence casing
-        // to avoid capitalizing letters after dots in filenames
+    /// Create a processor from a stored config, compiling its user dictionary
+    pub fn from_config(config: &PostProcessingConfig) -> Result<Self, String> {
+        if config.replacements.len() > MAX_USER_REPLACEMENTS {
+            return Err(format!(
+                "User dictionary has more than {} entries",
+                MAX_USER_REPLACEMENTS
+            ));
+        }
 
-        // First, process voice commands (these take highest priority)
-        result = self.process_voice_commands(&result);
+        let replacements = config
+            .replacements
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                compile_user_pattern(&entry.pattern)
+                    .map(|regex| (regex, entry.replacement.clone()))
+                    .map_err(|e| format!("Dictionary entry {}: {}", i + 1, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            stages: config.stages.clone(),
+            replacements,
+            action_markers: config.action_markers,
+            ..Self::new()
+        })
+    }
 
-        // Then apply code-specific transformations that involve "dot"
-        result = self.process_explicit_casing(&result);
-        result = self.process_functions(&result);
-        result = self.process_file_mentions(&result); // Process @file mentions first
-        result = self.process_file_paths(&result); // Then regular file paths
+    /// Create a processor that runs only `stages`, in the given order
+    pub fn with_stages(stages: Vec<ProcessingStage>) -> Self {
+        Self {
+            stages,
+            ..Self::new()
+        }
+    }
 
-        result = self.process_variables(&result);
-        result = self.process_classes(&result);
-        result = self.process_symbols(&result); // Convert remaining "dot" to "."
+    /// Stages run by `process`, in order
+    pub fn stages(&self) -> &[ProcessingStage] {
+        &self.stages
+    }
 
-        // NOW apply sentence casing after file paths are processed
-        // This prevents capitalizing after dots in filenames like "build.rs"
-        result = self.fix_sentence_casing(&result);
+    /// Main post-processing function
+    pub fn process(&self, text: &str) -> String {
+        self.stages.iter().fold(text.to_string(), |result, &stage| {
+            self.apply_stage(stage, &result)
+        })
+    }
 
-        result = self.process_abbreviations(&result);
-        result = self.process_keywords(&result); // Apply keyword casing
-        result = self.cleanup_whitespace(&result);
+    /// Run a single pipeline stage
+    pub fn apply_stage(&self, stage: ProcessingStage, text: &str) -> String {
+        match stage {
+            ProcessingStage::UserDictionary => self.apply_user_dictionary(text),
+            ProcessingStage::VoiceCommands => self.process_voice_commands(text),
+            ProcessingStage::ExplicitCasing => self.process_explicit_casing(text),
+            ProcessingStage::Functions => self.process_functions(text),
+            ProcessingStage::FileMentions => self.process_file_mentions(text),
+            ProcessingStage::FilePaths => self.process_file_paths(text),
+            ProcessingStage::Variables => self.process_variables(text),
+            ProcessingStage::Classes => self.process_classes(text),
+            ProcessingStage::Symbols => self.process_symbols(text),
+            ProcessingStage::SentenceCasing => self.fix_sentence_casing(text),
+            ProcessingStage::Abbreviations => self.process_abbreviations(text),
+            ProcessingStage::Keywords => self.process_keywords(text),
+            ProcessingStage::Punctuation => normalize_punctuation_spacing(text),
+            ProcessingStage::Whitespace => self.cleanup_whitespace(text),
+        }
+    }
 
-        result
+    /// Apply the user dictionary entries in order
+    fn apply_user_dictionary(&self, text: &str) -> String {
+        self.replacements
+            .iter()
+            .fold(text.to_string(), |result, (regex, replacement)| {
+                regex
+                    .replace_all(&result, replacement.as_str())
+                    .into_owned()
+            })
     }
 
     /// Process voice commands like punctuation, new line, delete, etc.
@@ -420,55 +588,81 @@ impl PostProcessor {
         result = COMMAND_SPACE.replace_all(&result, " ").to_string();
 
         // Special action commands - these become control sequences
-        // The frontend will interpret these and perform the action
+        // The frontend will interpret these and perform the action. Pure
+        // dictation setups drop the command phrase instead.
+        let marker = |name: &str| {
+            if self.action_markers {
+                format!("[[{}]]", name)
+            } else {
+                String::new()
+            }
+        };
         result = COMMAND_DELETE_THAT
-            .replace_all(&result, "[[DELETE_LAST]]")
+            .replace_all(&result, marker("DELETE_LAST").as_str())
+            .to_string();
+        result = COMMAND_UNDO
+            .replace_all(&result, marker("UNDO").as_str())
+            .to_string();
+        result = COMMAND_REDO
+            .replace_all(&result, marker("REDO").as_str())
             .to_string();
-        result = COMMAND_UNDO.replace_all(&result, "[[UNDO]]").to_string();
-        result = COMMAND_REDO.replace_all(&result, "[[REDO]]").to_string();
         result = COMMAND_SELECT_ALL
-            .replace_all(&result, "[[SELECT_ALL]]")
+            .replace_all(&result, marker("SELECT_ALL").as_str())
             .to_string();
         result = COMMAND_COPY_THAT
-            .replace_all(&result, "[[COPY]]")
+            .replace_all(&result, marker("COPY").as_str())
+            .to_string();
+        result = COMMAND_CUT_THAT
+            .replace_all(&result, marker("CUT").as_str())
             .to_string();
-        result = COMMAND_CUT_THAT.replace_all(&result, "[[CUT]]").to_string();
         result = COMMAND_PASTE_THAT
-            .replace_all(&result, "[[PASTE]]")
+            .replace_all(&result, marker("PASTE").as_str())
             .to_string();
 
         // Additional editing commands
         result = COMMAND_BACKSPACE
-            .replace_all(&result, "[[BACKSPACE]]")
+            .replace_all(&result, marker("BACKSPACE").as_str())
             .to_string();
         result = COMMAND_DELETE_WORD
-            .replace_all(&result, "[[DELETE_WORD]]")
+            .replace_all(&result, marker("DELETE_WORD").as_str())
             .to_string();
         result = COMMAND_DELETE_LINE
-            .replace_all(&result, "[[DELETE_LINE]]")
+            .replace_all(&result, marker("DELETE_LINE").as_str())
+            .to_string();
+        result = COMMAND_ENTER
+            .replace_all(&result, marker("ENTER").as_str())
+            .to_string();
+        result = COMMAND_TAB_KEY
+            .replace_all(&result, marker("TAB").as_str())
             .to_string();
-        result = COMMAND_ENTER.replace_all(&result, "[[ENTER]]").to_string();
-        result = COMMAND_TAB_KEY.replace_all(&result, "[[TAB]]").to_string();
         result = COMMAND_ESCAPE
-            .replace_all(&result, "[[ESCAPE]]")
+            .replace_all(&result, marker("ESCAPE").as_str())
             .to_string();
 
         // Cursor movement commands
-        result = COMMAND_GO_LEFT.replace_all(&result, "[[LEFT]]").to_string();
+        result = COMMAND_GO_LEFT
+            .replace_all(&result, marker("LEFT").as_str())
+            .to_string();
         result = COMMAND_GO_RIGHT
-            .replace_all(&result, "[[RIGHT]]")
+            .replace_all(&result, marker("RIGHT").as_str())
+            .to_string();
+        result = COMMAND_GO_UP
+            .replace_all(&result, marker("UP").as_str())
+            .to_string();
+        result = COMMAND_GO_DOWN
+            .replace_all(&result, marker("DOWN").as_str())
             .to_string();
-        result = COMMAND_GO_UP.replace_all(&result, "[[UP]]").to_string();
-        result = COMMAND_GO_DOWN.replace_all(&result, "[[DOWN]]").to_string();
         result = COMMAND_GO_START
-            .replace_all(&result, "[[HOME]]")
+            .replace_all(&result, marker("HOME").as_str())
+            .to_string();
+        result = COMMAND_GO_END
+            .replace_all(&result, marker("END").as_str())
             .to_string();
-        result = COMMAND_GO_END.replace_all(&result, "[[END]]").to_string();
         result = COMMAND_GO_WORD_LEFT
-            .replace_all(&result, "[[WORD_LEFT]]")
+            .replace_all(&result, marker("WORD_LEFT").as_str())
             .to_string();
         result = COMMAND_GO_WORD_RIGHT
-            .replace_all(&result, "[[WORD_RIGHT]]")
+            .replace_all(&result, marker("WORD_RIGHT").as_str())
             .to_string();
 
         result
@@ -885,10 +1079,212 @@ impl Default for PostProcessor {
     }
 }
 
+/// Longest pattern accepted from the user
+pub const MAX_USER_PATTERN_LEN: usize = 500;
+/// Longest sample text `apply_user_replacement` will run over
+pub const MAX_REPLACEMENT_SAMPLE_LEN: usize = 10_000;
+/// Upper bound on the compiled size of a user pattern, in bytes
+const USER_PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+/// Compile a user-supplied regex. The regex crate matches in linear time, so
+/// catastrophic backtracking cannot happen; the size limit rejects patterns
+/// whose compiled form would blow up instead (e.g. `(a{1000}){1000}`).
+pub fn compile_user_pattern(pattern: &str) -> Result<Regex, String> {
+    if pattern.is_empty() {
+        return Err("Pattern cannot be empty".to_string());
+    }
+    if pattern.len() > MAX_USER_PATTERN_LEN {
+        return Err(format!(
+            "Pattern is longer than {} characters",
+            MAX_USER_PATTERN_LEN
+        ));
+    }
+
+    RegexBuilder::new(pattern)
+        .size_limit(USER_PATTERN_SIZE_LIMIT)
+        .dfa_size_limit(USER_PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| match e {
+            regex::Error::CompiledTooBig(_) => "Pattern is too complex".to_string(),
+            regex::Error::Syntax(message)
+                if message.contains("look-around") || message.contains("backreferences") =>
+            {
+                "Look-around and backreferences are not supported".to_string()
+            }
+            e => format!("Invalid pattern: {}", e),
+        })
+}
+
+/// Apply a user replacement rule to `sample`, for previewing a rule before it
+/// is saved. `replacement` may refer to capture groups as `$1` or `${name}`.
+pub fn apply_user_replacement(
+    pattern: &str,
+    replacement: &str,
+    sample: &str,
+) -> Result<String, String> {
+    let regex = compile_user_pattern(pattern)?;
+
+    if sample.len() > MAX_REPLACEMENT_SAMPLE_LEN {
+        return Err(format!(
+            "Sample is longer than {} characters",
+            MAX_REPLACEMENT_SAMPLE_LEN
+        ));
+    }
+
+    Ok(regex.replace_all(sample, replacement).into_owned())
+}
+
+/// Fix spacing around punctuation left by transcription and symbol
+/// replacement: "word ," becomes "word,", "word.Next" becomes "word. Next"
+/// and "( word )" becomes "(word)". Decimals, file names, URLs, mentions and
+/// command markers are left alone. Lines are handled separately so line
+/// breaks survive.
+pub fn normalize_punctuation_spacing(text: &str) -> String {
+    text.split('\n')
+        .map(normalize_line_punctuation)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn normalize_line_punctuation(line: &str) -> String {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut open_bracket = String::new();
+
+    for token in line.split(' ').filter(|token| !token.is_empty()) {
+        if matches!(token, "(" | "[") {
+            open_bracket.push_str(token);
+            continue;
+        }
+
+        let mut token = token.to_string();
+        if let Some(previous) = tokens.last_mut().filter(|_| open_bracket.is_empty()) {
+            // Punctuation the transcriber split off belongs to the previous word
+            let leading = leading_punctuation(&token);
+            if !leading.is_empty() {
+                previous.push_str(leading);
+                token = token[leading.len()..].to_string();
+                if token.is_empty() {
+                    continue;
+                }
+            }
+        }
+
+        let token = format!("{}{}", std::mem::take(&mut open_bracket), token);
+        tokens.extend(space_after_punctuation(&token));
+    }
+    if !open_bracket.is_empty() {
+        tokens.push(open_bracket);
+    }
+
+    tokens.join(" ")
+}
+
+/// Closing punctuation at the start of `token` that should attach to the
+/// word before it. A dot followed by a letter starts a name like ".env".
+fn leading_punctuation(token: &str) -> &str {
+    let end = token
+        .find(|c: char| !matches!(c, ',' | '.' | ';' | ':' | '!' | '?' | ')' | ']'))
+        .unwrap_or(token.len());
+    let run = &token[..end];
+    if run.ends_with('.') && end < token.len() && !run.starts_with("..") {
+        return run.trim_end_matches('.');
+    }
+    run
+}
+
+fn is_verbatim_token(token: &str) -> bool {
+    token.starts_with("[[")
+        || token.starts_with('@')
+        || token.starts_with("www.")
+        || token.contains("://")
+        || token.contains('@')
+}
+
+/// Split `token` where sentence punctuation runs straight into the next word
+fn space_after_punctuation(token: &str) -> Vec<String> {
+    if is_verbatim_token(token) {
+        return vec![token.to_string()];
+    }
+
+    let chars: Vec<char> = token.chars().collect();
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        current.push(c);
+        let (Some(&before), Some(&after)) = (i.checked_sub(1).map(|j| &chars[j]), chars.get(i + 1))
+        else {
+            continue;
+        };
+        let splits = match c {
+            ',' | ';' | '!' | '?' => after.is_alphabetic(),
+            ':' => before.is_alphabetic() && after.is_alphabetic(),
+            // "word.Next" and "wait...what", but not "file.ts", "3.14" or "e.g."
+            '.' => {
+                after.is_alphabetic()
+                    && (before == '.'
+                        || (before.is_lowercase() && after.is_uppercase())
+                        || (before.is_ascii_digit() && after.is_uppercase()))
+            }
+            ')' | ']' => after.is_alphanumeric(),
+            _ => false,
+        };
+        if splits {
+            parts.push(std::mem::take(&mut current));
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+/// Action markers that discard or replace text. They are held for
+/// confirmation when `confirm_destructive_commands` is on.
+pub const DESTRUCTIVE_ACTION_MARKERS: &[&str] = &[
+    "[[DELETE_LAST]]",
+    "[[SELECT_ALL]]",
+    "[[CUT]]",
+    "[[BACKSPACE]]",
+    "[[DELETE_WORD]]",
+    "[[DELETE_LINE]]",
+];
+
+/// Destructive action markers in `text`, in the order they appear. The
+/// text itself is left alone so held dictations keep their markers in place.
+pub fn destructive_markers(text: &str) -> Vec<String> {
+    let mut markers = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("[[") {
+        let Some(len) = rest[start..].find("]]").map(|end| end + 2) else {
+            break;
+        };
+        let marker = &rest[start..start + len];
+        if DESTRUCTIVE_ACTION_MARKERS.contains(&marker) {
+            markers.push(marker.to_string());
+        }
+        rest = &rest[start + len..];
+    }
+
+    markers
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_destructive_markers() {
+        assert_eq!(
+            destructive_markers("hello [[DELETE_LAST]] world [[ENTER]] [[SELECT_ALL]]"),
+            vec!["[[DELETE_LAST]]", "[[SELECT_ALL]]"]
+        );
+        assert_eq!(
+            destructive_markers("[[CUT]] rest [[UNDO]]"),
+            vec!["[[CUT]]"]
+        );
+        assert!(destructive_markers("plain [[ text").is_empty());
+        assert!(destructive_markers("[[ENTER]] [[UNDO]]").is_empty());
+    }
+
     #[test]
     fn test_camel_case() {
         let pp = PostProcessor::new();
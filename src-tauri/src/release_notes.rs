@@ -0,0 +1,181 @@
+//! Release notes for the update prompt, read from the project changelog
+
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::time::Duration;
+
+/// Where the latest changelog is fetched from unless the settings override it
+pub const DEFAULT_RELEASE_NOTES_URL: &str =
+    "https://raw.githubusercontent.com/johuniq/wavee/main/CHANGELOG.md";
+
+/// Changelog shipped with this build, used when the endpoint is unreachable
+const BUNDLED_CHANGELOG: &str = include_str!("../../CHANGELOG.md");
+
+const FETCH_TIMEOUT_SECS: u64 = 10;
+
+/// Entries listed under one `## <version>` heading of the changelog
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReleaseNotes {
+    pub version: String,
+    pub notes: Vec<String>,
+}
+
+/// Parse the `## <version>` sections of a Keep a Changelog style file, in the
+/// order they appear. The "Unreleased" section is skipped.
+pub fn parse_changelog(markdown: &str) -> Vec<ReleaseNotes> {
+    let mut releases = Vec::new();
+    let mut current: Option<ReleaseNotes> = None;
+
+    for line in markdown.lines() {
+        let line = line.trim();
+        if let Some(heading) = line.strip_prefix("## ") {
+            releases.extend(current.take());
+            // Headings may carry a link or date: "## [1.2.0] - 2024-05-01"
+            let version = heading
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_matches(|c| c == '[' || c == ']');
+            if !version.eq_ignore_ascii_case("unreleased") && parse_version(version).is_some() {
+                current = Some(ReleaseNotes {
+                    version: version.trim_start_matches('v').to_string(),
+                    notes: Vec::new(),
+                });
+            }
+        } else if let Some(release) = current.as_mut() {
+            if let Some(entry) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+                release.notes.push(entry.trim().to_string());
+            }
+        }
+    }
+    releases.extend(current);
+    releases
+}
+
+/// Numeric `major.minor.patch` parts of a version, ignoring a leading `v` and
+/// any pre-release or build suffix
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let core = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Compare two versions numerically, treating missing parts as zero.
+/// Unparseable versions sort before any valid one.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_version(a), parse_version(b)) {
+        (Some(mut a), Some(mut b)) => {
+            let len = a.len().max(b.len());
+            a.resize(len, 0);
+            b.resize(len, 0);
+            a.cmp(&b)
+        }
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+/// Releases newer than `from` and no newer than `to`, newest first
+pub fn notes_between(releases: Vec<ReleaseNotes>, from: &str, to: &str) -> Vec<ReleaseNotes> {
+    let mut releases: Vec<ReleaseNotes> = releases
+        .into_iter()
+        .filter(|release| {
+            compare_versions(&release.version, from) == Ordering::Greater
+                && compare_versions(&release.version, to) != Ordering::Greater
+        })
+        .collect();
+    releases.sort_by(|a, b| compare_versions(&b.version, &a.version));
+    releases
+}
+
+/// Release notes between two versions from the changelog shipped with this
+/// build
+pub fn bundled_release_notes(from: &str, to: &str) -> Vec<ReleaseNotes> {
+    notes_between(parse_changelog(BUNDLED_CHANGELOG), from, to)
+}
+
+/// Fetch the changelog at `url` and return the release notes between two
+/// versions
+pub async fn fetch_release_notes(
+    url: &str,
+    from: &str,
+    to: &str,
+) -> Result<Vec<ReleaseNotes>, String> {
+    if !url.starts_with("https://") {
+        return Err("Security error: Only HTTPS URLs are allowed for release notes".to_string());
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(FETCH_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release notes: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Release notes request failed with status: {}",
+            response.status()
+        ));
+    }
+
+    let markdown = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read release notes: {}", e))?;
+    Ok(notes_between(parse_changelog(&markdown), from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n## Unreleased\n\n- Work in progress\n\n\
+        ## [1.2.0] - 2024-05-01\n\n- Faster downloads\n* Mirror support\n\n\
+        ## v1.1.0\n\n- Hotkey fixes\n\n## 1.0.0\n\n- Initial release\n";
+
+    #[test]
+    fn test_parse_changelog_sections() {
+        let releases = parse_changelog(CHANGELOG);
+
+        assert_eq!(releases.len(), 3);
+        assert_eq!(releases[0].version, "1.2.0");
+        assert_eq!(
+            releases[0].notes,
+            vec!["Faster downloads", "Mirror support"]
+        );
+        assert_eq!(releases[1].version, "1.1.0");
+        assert_eq!(releases[2].notes, vec!["Initial release"]);
+    }
+
+    #[test]
+    fn test_notes_between_versions() {
+        let versions = |from, to| {
+            notes_between(parse_changelog(CHANGELOG), from, to)
+                .into_iter()
+                .map(|release| release.version)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(versions("1.0.0", "1.2.0"), vec!["1.2.0", "1.1.0"]);
+        assert_eq!(versions("v1.1.0", "1.1"), Vec::<String>::new());
+        assert_eq!(versions("0.9.0", "1.1.0"), vec!["1.1.0", "1.0.0"]);
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("2.0.0-beta.1", "2.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("garbage", "0.0.1"), Ordering::Less);
+    }
+
+    #[test]
+    fn test_bundled_changelog_parses() {
+        assert!(!parse_changelog(BUNDLED_CHANGELOG).is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! Host hardware probes used for model recommendations
+
+use std::path::Path;
+use sysinfo::{Disks, ProcessRefreshKind, System};
+
+/// Memory currently available to new allocations, in bytes
+pub fn available_memory_bytes() -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_memory();
+    match system.available_memory() {
+        0 => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// Total physical memory, in bytes
+pub fn total_memory_bytes() -> Option<u64> {
+    let mut system = System::new();
+    system.refresh_memory();
+    match system.total_memory() {
+        0 => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// CPU cores, not counting hyperthreads
+pub fn physical_core_count() -> Option<usize> {
+    System::new().physical_core_count()
+}
+
+/// Resident memory of this process, in bytes
+pub fn process_memory_bytes() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new().with_memory());
+    system.process(pid).map(|process| process.memory())
+}
+
+/// Free space on the disk that holds `path`, in bytes
+pub fn available_disk_bytes(path: &Path) -> Option<u64> {
+    let disks = Disks::new_with_refreshed_list();
+
+    // Pick the most specific mount point containing the path
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
@@ -1,7 +1,264 @@
 use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-#[cfg(target_os = "windows")]
 use std::time::Duration;
 
+/// How long the target app gets to read a pasted clipboard before the
+/// previous contents are put back
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(150);
+
+/// Most time `inject_text` spends sleeping between typed chunks. Longer text
+/// is typed in bigger chunks, so a typing delay cannot hold the injector for
+/// minutes on a long transcription.
+const MAX_TYPING_PAUSE: Duration = Duration::from_secs(10);
+
+/// Whether the OS allows this app to synthesize keyboard input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InjectionPermission {
+    Granted,
+    Denied,
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "ApplicationServices", kind = "framework")]
+extern "C" {
+    fn AXIsProcessTrusted() -> u8;
+}
+
+/// Check the platform's accessibility grant. Without it, macOS silently drops
+/// synthesized key events. Other platforms need no grant.
+pub fn injection_permission_status() -> InjectionPermission {
+    #[cfg(target_os = "macos")]
+    {
+        // Safety: AXIsProcessTrusted takes no arguments and only reads process state
+        if unsafe { AXIsProcessTrusted() } != 0 {
+            InjectionPermission::Granted
+        } else {
+            InjectionPermission::Denied
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        InjectionPermission::Granted
+    }
+}
+
+/// System settings pane where the user grants the injection permission
+pub fn injection_permission_settings_url() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Accessibility")
+    } else {
+        None
+    }
+}
+
+/// The application that currently has keyboard focus. Fields are `None`
+/// when the platform does not report them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForegroundApp {
+    pub app_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub window_title: Option<String>,
+}
+
+/// Find the blocklist entry matching `app`, if any.
+///
+/// Entries starting with `title:` match when the window title contains the
+/// rest of the entry; other entries match the app name or bundle id exactly.
+/// Both comparisons ignore case.
+pub fn blocklist_match<'a>(app: &ForegroundApp, blocklist: &'a [String]) -> Option<&'a str> {
+    blocklist.iter().map(String::as_str).find(|entry| {
+        if let Some(pattern) = entry.strip_prefix("title:") {
+            let pattern = pattern.trim().to_lowercase();
+            !pattern.is_empty()
+                && app
+                    .window_title
+                    .as_deref()
+                    .is_some_and(|title| title.to_lowercase().contains(&pattern))
+        } else {
+            [&app.app_name, &app.bundle_id]
+                .into_iter()
+                .flatten()
+                .any(|name| name.eq_ignore_ascii_case(entry.trim()))
+        }
+    })
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "user32")]
+extern "system" {
+    fn GetForegroundWindow() -> isize;
+    fn GetWindowTextW(hwnd: isize, text: *mut u16, max_count: i32) -> i32;
+    fn GetWindowThreadProcessId(hwnd: isize, process_id: *mut u32) -> u32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn OpenProcess(desired_access: u32, inherit_handle: i32, process_id: u32) -> isize;
+    fn QueryFullProcessImageNameW(
+        process: isize,
+        flags: u32,
+        name: *mut u16,
+        size: *mut u32,
+    ) -> i32;
+    fn CloseHandle(handle: isize) -> i32;
+}
+
+/// Look up the focused application. This is best-effort: macOS needs the
+/// Automation grant for System Events, Linux needs `xdotool` on X11, and
+/// `None` is returned whenever focus cannot be determined.
+pub fn foreground_app() -> Option<ForegroundApp> {
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("osascript")
+            .args([
+                "-e",
+                "tell application \"System Events\" to set p to first application process whose frontmost is true",
+                "-e",
+                "set t to \"\"",
+                "-e",
+                "try",
+                "-e",
+                "tell application \"System Events\" to set t to name of front window of p",
+                "-e",
+                "end try",
+                "-e",
+                "tell application \"System Events\" to return (name of p) & linefeed & (bundle identifier of p) & linefeed & t",
+            ])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        let text = String::from_utf8_lossy(&output.stdout);
+        let mut lines = text.lines().map(|line| {
+            let line = line.trim();
+            (!line.is_empty()).then(|| line.to_string())
+        });
+        Some(ForegroundApp {
+            app_name: lines.next().flatten(),
+            bundle_id: lines.next().flatten(),
+            window_title: lines.next().flatten(),
+        })
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+
+        // Safety: every buffer passed below outlives the call and its length
+        // is given in UTF-16 units; the process handle is closed before return
+        unsafe {
+            let hwnd = GetForegroundWindow();
+            if hwnd == 0 {
+                return None;
+            }
+
+            let mut title = [0u16; 512];
+            let len = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32);
+            let window_title = (len > 0).then(|| String::from_utf16_lossy(&title[..len as usize]));
+
+            let mut process_id = 0u32;
+            GetWindowThreadProcessId(hwnd, &mut process_id);
+            let mut app_name = None;
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+            if process != 0 {
+                let mut path = [0u16; 1024];
+                let mut size = path.len() as u32;
+                if QueryFullProcessImageNameW(process, 0, path.as_mut_ptr(), &mut size) != 0 {
+                    let path = String::from_utf16_lossy(&path[..size as usize]);
+                    app_name = std::path::Path::new(&path)
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().to_string());
+                }
+                CloseHandle(process);
+            }
+
+            Some(ForegroundApp {
+                app_name,
+                bundle_id: None,
+                window_title,
+            })
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let xdotool = |args: &[&str]| {
+            std::process::Command::new("xdotool")
+                .args(args)
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+
+        let window_title = xdotool(&["getactivewindow", "getwindowname"]);
+        let app_name = xdotool(&["getactivewindow", "getwindowpid"])
+            .and_then(|pid| std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok())
+            .map(|name| name.trim().to_string());
+        if window_title.is_none() && app_name.is_none() {
+            return None;
+        }
+
+        Some(ForegroundApp {
+            app_name,
+            bundle_id: None,
+            window_title,
+        })
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// How `TextInjector::inject_text` delivers text to the focused app
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InjectionMethod {
+    /// Put the text on the clipboard and send the paste shortcut. Instant at
+    /// any length and unaffected by autocomplete or IMEs, but it replaces
+    /// the clipboard contents.
+    #[default]
+    Paste,
+    /// Synthesize a key press per character. Slower, but leaves the
+    /// clipboard alone and works where pasting is blocked.
+    Type,
+}
+
+impl InjectionMethod {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "paste" => Some(Self::Paste),
+            "type" => Some(Self::Type),
+            _ => None,
+        }
+    }
+}
+
+/// Per-call injection settings, read from the user's settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InjectionOptions {
+    pub method: InjectionMethod,
+    /// Put the previous clipboard text back after pasting
+    pub restore_clipboard: bool,
+    /// Pause between typed characters. Zero types at full speed; a few
+    /// milliseconds helps terminals, remote desktops and games that drop
+    /// fast synthetic input, at the cost of slower injection. Pasting ignores
+    /// it.
+    pub typing_delay: Duration,
+}
+
+/// Characters per typed chunk so that the pauses between `char_count`
+/// characters add up to at most `MAX_TYPING_PAUSE`
+fn typing_chunk_len(char_count: usize, delay: Duration) -> usize {
+    let max_pauses = (MAX_TYPING_PAUSE.as_micros() / delay.as_micros().max(1)) as usize;
+    char_count.div_ceil(max_pauses + 1).max(1)
+}
+
 pub struct TextInjector {
     enigo: Enigo,
     clipboard: Option<arboard::Clipboard>,
@@ -31,73 +288,76 @@ impl TextInjector {
         Ok(Self { enigo, clipboard })
     }
 
-    pub fn inject_text(&mut self, text: &str) -> Result<(), String> {
+    /// Deliver `text` to the focused app. Pasting falls back to typing when
+    /// the clipboard cannot be used.
+    pub fn inject_text(&mut self, text: &str, options: &InjectionOptions) -> Result<(), String> {
         if text.is_empty() {
             return Ok(());
         }
 
-        // ALWAYS use clipboard paste - it's significantly faster than keystroke simulation
-        // Clipboard paste is instant regardless of text length
-        // Direct typing can be 10-100x slower for longer text
-        if let Some(ref mut cb) = self.clipboard {
-            // Save current clipboard content to restore later (optional, for user convenience)
-            let _previous = cb.get_text().ok();
-
-            if cb.set_text(text).is_ok() {
-                // Small delay to ensure clipboard is ready.
-                #[cfg(target_os = "windows")]
-                std::thread::sleep(Duration::from_micros(100));
-
-                // Execute Paste shortcut
-                self.execute_paste()?;
-
-                // Optional: Restore previous clipboard after a brief delay
-                // This is commented out as it may interfere with user workflow
-                // if let Some(prev) = _previous {
-                //     std::thread::sleep(Duration::from_millis(50));
-                //     let _ = cb.set_text(&prev);
-                // }
-
-                return Ok(());
+        if options.method == InjectionMethod::Paste {
+            match self.paste_text(text, options.restore_clipboard) {
+                Ok(()) => return Ok(()),
+                Err(e) => log::warn!("Clipboard paste failed, typing instead: {}", e),
             }
         }
 
-        // Fallback to direct typing only if clipboard fails
-        self.enigo
-            .text(text)
-            .map_err(|e| format!("Failed to inject text: {}", e))?;
-
-        Ok(())
+        self.type_text(text, options.typing_delay)
     }
 
-    /// Optimized paste operation for each platform
-    fn execute_paste(&mut self) -> Result<(), String> {
-        #[cfg(target_os = "macos")]
-        {
-            // macOS: Cmd+V
-            self.enigo
-                .key(Key::Meta, Direction::Press)
-                .map_err(|e| e.to_string())?;
-            self.enigo
-                .key(Key::Unicode('v'), Direction::Click)
-                .map_err(|e| e.to_string())?;
+    /// Type `text`, pausing `delay` between chunks. Chunks are single
+    /// characters unless that would sleep longer than `MAX_TYPING_PAUSE`.
+    fn type_text(&mut self, text: &str, delay: Duration) -> Result<(), String> {
+        if delay.is_zero() {
+            return self
+                .enigo
+                .text(text)
+                .map_err(|e| format!("Failed to inject text: {}", e));
+        }
+
+        let chars: Vec<char> = text.chars().collect();
+        let chunk_len = typing_chunk_len(chars.len(), delay);
+        for (i, chunk) in chars.chunks(chunk_len).enumerate() {
+            if i > 0 {
+                std::thread::sleep(delay);
+            }
             self.enigo
-                .key(Key::Meta, Direction::Release)
-                .map_err(|e| e.to_string())?;
+                .text(&chunk.iter().collect::<String>())
+                .map_err(|e| format!("Failed to inject text: {}", e))?;
         }
 
+        Ok(())
+    }
+
+    /// Put `text` on the clipboard and send the paste shortcut. When
+    /// `restore` is set, the previous clipboard text is put back afterwards;
+    /// that step is best-effort and never fails the paste.
+    fn paste_text(&mut self, text: &str, restore: bool) -> Result<(), String> {
+        let clipboard = self
+            .clipboard
+            .as_mut()
+            .ok_or_else(|| "Clipboard is unavailable".to_string())?;
+        // Non-text contents (images, files) cannot be saved, so they are lost
+        let previous = if restore {
+            clipboard.get_text().ok()
+        } else {
+            None
+        };
+        clipboard.set_text(text).map_err(|e| e.to_string())?;
+
+        // Small delay to ensure clipboard is ready.
         #[cfg(target_os = "windows")]
-        {
-            // Windows: Ctrl+V with minimal delay
-            self.enigo
-                .key(Key::Control, Direction::Press)
-                .map_err(|e| e.to_string())?;
-            self.enigo
-                .key(Key::Unicode('v'), Direction::Click)
-                .map_err(|e| e.to_string())?;
-            self.enigo
-                .key(Key::Control, Direction::Release)
-                .map_err(|e| e.to_string())?;
+        std::thread::sleep(Duration::from_micros(100));
+
+        self.execute_shortcut("paste")?;
+
+        if let (Some(previous), Some(clipboard)) = (previous, self.clipboard.as_mut()) {
+            // The target app reads the clipboard asynchronously after the
+            // shortcut, so restoring too early pastes the old text instead
+            std::thread::sleep(CLIPBOARD_RESTORE_DELAY);
+            if let Err(e) = clipboard.set_text(previous) {
+                log::warn!("Failed to restore clipboard: {}", e);
+            }
         }
 
         Ok(())
@@ -1,10 +1,52 @@
 use qwen3_asr::{best_device, AsrInference, TranscribeOptions};
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use transcribe_rs::onnx::parakeet::{ParakeetModel, ParakeetParams, TimestampGranularity};
 use transcribe_rs::onnx::Quantization;
 use transcribe_rs::TranscriptionResult;
 use transcribe_rs::{set_ort_accelerator, OrtAccelerator};
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+};
+
+/// Error from a transcription stopped through its abort flag
+pub const TRANSCRIPTION_CANCELLED: &str = "cancelled";
+
+/// Common interface implemented by every speech-to-text backend
+pub trait TranscriptionEngine {
+    /// Short, stable engine identifier such as "whisper" or "parakeet"
+    fn name(&self) -> &'static str;
+
+    /// Transcribe 16 kHz mono samples
+    fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String>;
+
+    /// Transcribe into timed segments. Engines that don't report timings
+    /// return the whole clip as one segment.
+    fn transcribe_with_timestamps(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<Vec<Segment>, String> {
+        let text = self.transcribe(audio_samples)?;
+        Ok(whole_clip_segment(&text, audio_samples.len()))
+    }
+
+    /// Transcribe and report the language of the text: the detected one
+    /// when transcribing with "auto", otherwise the configured one. Engines
+    /// that can't tell which language they detected report "auto".
+    fn transcribe_detecting_language(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<(String, String), String> {
+        let text = self.transcribe(audio_samples)?;
+        Ok((text, "auto".to_string()))
+    }
+
+    /// Language used for subsequent transcriptions ("auto" to detect)
+    fn set_language(&mut self, language: &str);
+}
 
 pub enum Transcriber {
     Whisper(WhisperTranscriber),
@@ -24,28 +66,124 @@ impl Transcriber {
             )?))
         } else {
             Ok(Self::Whisper(WhisperTranscriber::new(
-                model_path, language,
+                model_path,
+                language,
+                whisper_tuning_for(model_id),
             )?))
         }
     }
 
-    pub fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+    /// Replace the decoding settings; other engines have none to tune
+    pub fn set_whisper_tuning(&mut self, tuning: WhisperTuning) {
+        if let Self::Whisper(transcriber) = self {
+            transcriber.tuning = tuning;
+        }
+    }
+
+    /// Limit the threads decoding runs on; `None` uses every available core.
+    /// Only Whisper decodes on threads it is told to use.
+    pub fn set_cpu_threads(&mut self, threads: Option<usize>) {
+        if let Self::Whisper(transcriber) = self {
+            transcriber.cpu_threads = threads;
+        }
+    }
+
+    /// Share the flag that aborts a running transcription. Whisper checks it
+    /// while decoding; the other engines can't be interrupted mid-run.
+    pub fn set_abort_flag(&mut self, abort: Arc<AtomicBool>) {
+        if let Self::Whisper(transcriber) = self {
+            transcriber.abort = abort;
+        }
+    }
+
+    /// Forget a cancel aimed at an earlier transcription. Commands call this
+    /// once before they start, so a cancel landing between the windows of
+    /// one file still stops the rest of it.
+    pub fn clear_abort(&self) {
+        if let Self::Whisper(transcriber) = self {
+            transcriber.abort.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Transcribe one window of a long recording, at most 30 seconds, and
+    /// report its language like `transcribe_detecting_language`. Unlike
+    /// dictation, Whisper may decode the window in several segments.
+    pub fn transcribe_window(&mut self, audio_samples: &[f32]) -> Result<(String, String), String> {
+        match self {
+            Self::Whisper(transcriber) => {
+                let state = transcriber
+                    .run(transcriber.full_params(DecodeMode::LongForm), audio_samples)?;
+                Ok((collect_text(&state)?, transcriber.language_of(&state)))
+            }
+            other => other.transcribe_detecting_language(audio_samples),
+        }
+    }
+
+    /// Transcribe audio of any length window by window, in order. Windows
+    /// are cut at the quietest point near their end, so words are only split
+    /// when the speaker never pauses. The language is the one reported for
+    /// the first window with speech.
+    pub fn transcribe_long(&mut self, audio_samples: &[f32]) -> Result<(String, String), String> {
+        let mut text = String::new();
+        let mut language: Option<String> = None;
+        for range in crate::audio::split_on_silence(audio_samples, LONG_FORM_WINDOW_SAMPLES) {
+            let (window_text, window_language) = self.transcribe_window(&audio_samples[range])?;
+            if language.is_none() && !window_text.trim().is_empty() {
+                language = Some(window_language);
+            }
+            append_chunk_transcript(&mut text, &window_text);
+        }
+        Ok((text, language.unwrap_or_else(|| "auto".to_string())))
+    }
+
+    fn engine(&self) -> &dyn TranscriptionEngine {
         match self {
-            Self::Whisper(transcriber) => transcriber.transcribe(audio_samples),
-            Self::Parakeet(transcriber) => transcriber.transcribe(audio_samples),
-            Self::Qwen3Asr(transcriber) => transcriber.transcribe(audio_samples),
+            Self::Whisper(transcriber) => transcriber,
+            Self::Parakeet(transcriber) => transcriber,
+            Self::Qwen3Asr(transcriber) => transcriber.as_ref(),
         }
     }
 
-    pub fn set_language(&mut self, language: &str) {
+    fn engine_mut(&mut self) -> &mut dyn TranscriptionEngine {
         match self {
-            Self::Whisper(transcriber) => transcriber.set_language(language),
-            Self::Parakeet(transcriber) => transcriber.set_language(language),
-            Self::Qwen3Asr(transcriber) => transcriber.set_language(language),
+            Self::Whisper(transcriber) => transcriber,
+            Self::Parakeet(transcriber) => transcriber,
+            Self::Qwen3Asr(transcriber) => transcriber.as_mut(),
         }
     }
 }
 
+/// The engine is picked from the model id when the model is loaded, so the
+/// command layer only ever talks to this trait.
+impl TranscriptionEngine for Transcriber {
+    fn name(&self) -> &'static str {
+        self.engine().name()
+    }
+
+    fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+        self.engine_mut().transcribe(audio_samples)
+    }
+
+    fn transcribe_with_timestamps(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<Vec<Segment>, String> {
+        self.engine_mut().transcribe_with_timestamps(audio_samples)
+    }
+
+    fn transcribe_detecting_language(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<(String, String), String> {
+        self.engine_mut()
+            .transcribe_detecting_language(audio_samples)
+    }
+
+    fn set_language(&mut self, language: &str) {
+        self.engine_mut().set_language(language)
+    }
+}
+
 pub struct Qwen3AsrTranscriber {
     engine: AsrInference,
     language: String,
@@ -70,8 +208,14 @@ impl Qwen3AsrTranscriber {
             language: language.to_string(),
         })
     }
+}
+
+impl TranscriptionEngine for Qwen3AsrTranscriber {
+    fn name(&self) -> &'static str {
+        "qwen3-asr"
+    }
 
-    pub fn transcribe(&self, audio_samples: &[f32]) -> Result<String, String> {
+    fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
         if audio_samples.is_empty() {
             return Err("No audio samples to transcribe".to_string());
         }
@@ -89,21 +233,47 @@ impl Qwen3AsrTranscriber {
         Ok(result.text)
     }
 
-    pub fn set_language(&mut self, language: &str) {
+    fn transcribe_detecting_language(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<(String, String), String> {
+        let text = self.transcribe(audio_samples)?;
+        Ok((text, self.language.clone()))
+    }
+
+    fn set_language(&mut self, language: &str) {
         self.language = language.to_string();
     }
 }
 
+/// What a Whisper run decodes for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecodeMode {
+    /// Short dictation: one segment, no timestamps
+    Dictation,
+    /// Timed segments for subtitles and seeking
+    Timestamps,
+    /// One window of a long recording, decoded in as many segments as needed
+    LongForm,
+}
+
 pub struct WhisperTranscriber {
     ctx: WhisperContext,
     language: String,
+    tuning: WhisperTuning,
+    abort: Arc<AtomicBool>,
+    /// Thread cap from the `cpu_threads` setting; `None` uses every core
+    cpu_threads: Option<usize>,
 }
 
 impl WhisperTranscriber {
-    pub fn new(model_path: &str, language: &str) -> Result<Self, String> {
+    pub fn new(model_path: &str, language: &str, tuning: WhisperTuning) -> Result<Self, String> {
         if !Path::new(model_path).exists() {
             return Err(format!("Model file not found: {}", model_path));
         }
+        if let Ok(Some(problem)) = check_ggml_model_file(Path::new(model_path)) {
+            return Err(problem.message().to_string());
+        }
 
         // Configure context parameters for maximum speed
         let mut ctx_params = WhisperContextParameters::default();
@@ -121,16 +291,26 @@ impl WhisperTranscriber {
         Ok(Self {
             ctx,
             language: language.to_string(),
+            tuning,
+            abort: Arc::new(AtomicBool::new(false)),
+            cpu_threads: None,
         })
     }
 
-    pub fn transcribe(&self, audio_samples: &[f32]) -> Result<String, String> {
-        if audio_samples.is_empty() {
-            return Err("No audio samples to transcribe".to_string());
-        }
-
-        // Use Greedy decoding for fastest results
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    /// Decoding parameters for each kind of transcription
+    fn full_params(&self, mode: DecodeMode) -> FullParams<'_, '_> {
+        // Greedy decoding is fastest; see `DecodingStrategy` for when beams
+        // are searched. Patience stays at whisper.cpp's default since it does
+        // not implement it. The speed settings below apply to both.
+        let strategy = if self.tuning.beam_size > 1 {
+            SamplingStrategy::BeamSearch {
+                beam_size: self.tuning.beam_size,
+                patience: -1.0,
+            }
+        } else {
+            SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let mut params = FullParams::new(strategy);
 
         // Set language (empty string = auto-detect)
         if !self.language.is_empty() && self.language != "auto" {
@@ -142,18 +322,36 @@ impl WhisperTranscriber {
 
         // ========== AGGRESSIVE SPEED OPTIMIZATIONS ==========
 
-        // Single segment mode - fastest for voice input (< 30 seconds)
-        params.set_single_segment(true);
-
         // Disable ALL output printing for maximum speed
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
         params.set_print_special(false);
 
-        // Disable ALL timestamps - not needed for text output
-        params.set_token_timestamps(false);
-        params.set_no_timestamps(true);
+        match mode {
+            DecodeMode::Dictation => {
+                // Single segment mode - fastest for voice input (< 30 seconds)
+                params.set_single_segment(true);
+
+                // Disable ALL timestamps - not needed for text output
+                params.set_token_timestamps(false);
+                params.set_no_timestamps(true);
+            }
+            DecodeMode::Timestamps => {
+                // Let Whisper split the clip and time each segment, which
+                // costs the timestamp tokens dictation skips
+                params.set_single_segment(false);
+                params.set_token_timestamps(true);
+                params.set_no_timestamps(false);
+            }
+            DecodeMode::LongForm => {
+                // A full window holds more speech than one segment can; the
+                // timestamp tokens are what lets Whisper start a new one
+                params.set_single_segment(false);
+                params.set_token_timestamps(false);
+                params.set_no_timestamps(false);
+            }
+        }
 
         // Disable context - each utterance is independent
         params.set_no_context(true);
@@ -162,30 +360,48 @@ impl WhisperTranscriber {
         params.set_suppress_blank(true);
         params.set_suppress_nst(true);
 
-        // Reduced max tokens - voice input is typically short
-        params.set_max_tokens(64);
+        // Reduced max tokens - voice input is typically short. Long-form
+        // windows are not capped (0) so their segments aren't cut off.
+        params.set_max_tokens(if mode == DecodeMode::LongForm {
+            0
+        } else {
+            self.tuning.max_tokens
+        });
 
         // Audio context 0 = use default from model (fastest)
         params.set_audio_ctx(0);
 
-        // Use all available CPU cores for parallel inference
-        let num_threads = std::thread::available_parallelism()
-            .map(|p| p.get() as i32)
-            .unwrap_or(4);
-        params.set_n_threads(num_threads);
+        // Use all available CPU cores for parallel inference, unless capped
+        let num_threads = self.cpu_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|p| p.get())
+                .unwrap_or(4)
+        });
+        params.set_n_threads(num_threads as i32);
 
-        // Higher entropy threshold = faster decoding, slightly less accuracy
-        params.set_entropy_thold(2.8);
+        // Decodes below this entropy count as repetitive and are retried
+        params.set_entropy_thold(self.tuning.entropy_thold);
 
         // Temperature 0 = greedy decoding (fastest, deterministic)
         params.set_temperature(0.0);
 
-        // Disable beam search fallback - stick with greedy for speed
-        params.set_temperature_inc(0.0);
+        // Temperature step for those retries; 0 disables the fallback
+        params.set_temperature_inc(self.tuning.temperature_inc);
 
         // Speed penalty - prefer shorter sequences (faster decoding)
         params.set_length_penalty(1.0);
 
+        params
+    }
+
+    fn run(&self, mut params: FullParams, audio_samples: &[f32]) -> Result<WhisperState, String> {
+        if audio_samples.is_empty() {
+            return Err("No audio samples to transcribe".to_string());
+        }
+
+        let abort = Arc::clone(&self.abort);
+        params.set_abort_callback_safe(move || abort.load(Ordering::SeqCst));
+
         // Create state for this transcription
         let mut state = self
             .ctx
@@ -193,39 +409,100 @@ impl WhisperTranscriber {
             .map_err(|e| format!("Failed to create Whisper state: {}", e))?;
 
         // Run inference
-        state
-            .full(params, audio_samples)
-            .map_err(|e| format!("Transcription failed: {}", e))?;
+        let result = state.full(params, audio_samples);
+        if self.abort.load(Ordering::SeqCst) {
+            return Err(TRANSCRIPTION_CANCELLED.to_string());
+        }
+        result.map_err(|e| format!("Transcription failed: {}", e))?;
+
+        Ok(state)
+    }
 
-        // Collect all segments efficiently
-        let num_segments = state.full_n_segments();
+    /// The configured language, or the one Whisper detected in `state`
+    fn language_of(&self, state: &WhisperState) -> String {
+        if !self.language.is_empty() && self.language != "auto" {
+            return self.language.clone();
+        }
+        whisper_rs::get_lang_str(state.full_lang_id_from_state())
+            .unwrap_or("auto")
+            .to_string()
+    }
+}
+
+impl TranscriptionEngine for WhisperTranscriber {
+    fn name(&self) -> &'static str {
+        "whisper"
+    }
+
+    fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+        let state = self.run(self.full_params(DecodeMode::Dictation), audio_samples)?;
+        collect_text(&state)
+    }
+
+    fn transcribe_detecting_language(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<(String, String), String> {
+        let state = self.run(self.full_params(DecodeMode::Dictation), audio_samples)?;
+        Ok((collect_text(&state)?, self.language_of(&state)))
+    }
 
-        // Pre-allocate string capacity for typical transcription length
-        // Average word is ~5 chars, so 128 chars is a reasonable estimate
-        let mut result = String::with_capacity((num_segments as usize).saturating_mul(128));
-        for i in 0..num_segments {
+    fn transcribe_with_timestamps(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<Vec<Segment>, String> {
+        let state = self.run(self.full_params(DecodeMode::Timestamps), audio_samples)?;
+
+        let mut segments = Vec::new();
+        for i in 0..state.full_n_segments() {
             if let Some(segment) = state.get_segment(i) {
                 let text = segment
                     .to_str_lossy()
                     .map_err(|e| format!("Failed to get segment text: {}", e))?;
-                let text = text.trim();
-                if !text.is_empty() {
-                    if !result.is_empty() {
-                        result.push(' ');
-                    }
-                    result.push_str(text);
-                }
+                // Whisper timestamps are in centiseconds
+                push_segment(
+                    &mut segments,
+                    &text,
+                    segment.start_timestamp().max(0) as u64 * 10,
+                    segment.end_timestamp().max(0) as u64 * 10,
+                );
             }
         }
 
-        Ok(result)
+        Ok(segments)
     }
 
-    pub fn set_language(&mut self, language: &str) {
+    fn set_language(&mut self, language: &str) {
         self.language = language.to_string();
     }
 }
 
+/// Join the text of every decoded segment
+fn collect_text(state: &WhisperState) -> Result<String, String> {
+    // Collect all segments efficiently
+    let num_segments = state.full_n_segments();
+
+    // Pre-allocate string capacity for typical transcription length
+    // Average word is ~5 chars, so 128 chars is a reasonable estimate
+    let mut result = String::with_capacity((num_segments as usize).saturating_mul(128));
+    for i in 0..num_segments {
+        if let Some(segment) = state.get_segment(i) {
+            let text = segment
+                .to_str_lossy()
+                .map_err(|e| format!("Failed to get segment text: {}", e))?;
+            let text = text.trim();
+            if !text.is_empty() {
+                if !result.is_empty() {
+                    result.push(' ');
+                }
+                result.push_str(text);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
 pub struct ParakeetTranscriber {
     model: ParakeetModel,
     language: String,
@@ -252,13 +529,12 @@ impl ParakeetTranscriber {
         })
     }
 
-    pub fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+    fn run(&mut self, audio_samples: &[f32]) -> Result<TranscriptionResult, String> {
         if audio_samples.is_empty() {
             return Err("No audio samples to transcribe".to_string());
         }
 
-        let result: TranscriptionResult = self
-            .model
+        self.model
             .transcribe_with(
                 audio_samples,
                 &ParakeetParams {
@@ -270,12 +546,50 @@ impl ParakeetTranscriber {
                     timestamp_granularity: Some(TimestampGranularity::Segment),
                 },
             )
-            .map_err(|e| format!("Parakeet transcription failed: {}", e))?;
+            .map_err(|e| format!("Parakeet transcription failed: {}", e))
+    }
+}
 
-        Ok(result.text)
+impl TranscriptionEngine for ParakeetTranscriber {
+    fn name(&self) -> &'static str {
+        "parakeet"
+    }
+
+    fn transcribe(&mut self, audio_samples: &[f32]) -> Result<String, String> {
+        Ok(self.run(audio_samples)?.text)
+    }
+
+    fn transcribe_with_timestamps(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<Vec<Segment>, String> {
+        let result = self.run(audio_samples)?;
+        let Some(timed) = result.segments else {
+            return Ok(whole_clip_segment(&result.text, audio_samples.len()));
+        };
+
+        // Parakeet times segments in seconds
+        let mut segments = Vec::with_capacity(timed.len());
+        for segment in timed {
+            push_segment(
+                &mut segments,
+                &segment.text,
+                (segment.start.max(0.0) * 1000.0).round() as u64,
+                (segment.end.max(0.0) * 1000.0).round() as u64,
+            );
+        }
+        Ok(segments)
     }
 
-    pub fn set_language(&mut self, language: &str) {
+    fn transcribe_detecting_language(
+        &mut self,
+        audio_samples: &[f32],
+    ) -> Result<(String, String), String> {
+        let text = self.transcribe(audio_samples)?;
+        Ok((text, self.language.clone()))
+    }
+
+    fn set_language(&mut self, language: &str) {
         self.language = language.to_string();
     }
 }
@@ -351,6 +665,127 @@ fn qwen3_language_name(code: &str) -> &'static str {
     }
 }
 
+/// ISO-639-1 codes (plus `haw` and `yue`) that multilingual Whisper models can decode.
+pub const WHISPER_LANGUAGES: &[&str] = &[
+    "af", "am", "ar", "as", "az", "ba", "be", "bg", "bn", "bo", "br", "bs", "ca", "cs", "cy", "da",
+    "de", "el", "en", "es", "et", "eu", "fa", "fi", "fo", "fr", "gl", "gu", "ha", "haw", "he",
+    "hi", "hr", "ht", "hu", "hy", "id", "is", "it", "ja", "jw", "ka", "kk", "km", "kn", "ko", "la",
+    "lb", "ln", "lo", "lt", "lv", "mg", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "ne", "nl",
+    "nn", "no", "oc", "pa", "pl", "ps", "pt", "ro", "ru", "sa", "sd", "si", "sk", "sl", "sn", "so",
+    "sq", "sr", "su", "sv", "sw", "ta", "te", "tg", "th", "tk", "tl", "tr", "tt", "uk", "ur", "uz",
+    "vi", "yi", "yo", "yue", "zh",
+];
+
+/// English display name for a language code accepted by any engine
+pub fn language_display_name(code: &str) -> Option<&'static str> {
+    let name = match code {
+        "auto" => "Auto detect",
+        "af" => "Afrikaans",
+        "am" => "Amharic",
+        "ar" => "Arabic",
+        "as" => "Assamese",
+        "az" => "Azerbaijani",
+        "ba" => "Bashkir",
+        "be" => "Belarusian",
+        "bg" => "Bulgarian",
+        "bn" => "Bengali",
+        "bo" => "Tibetan",
+        "br" => "Breton",
+        "bs" => "Bosnian",
+        "ca" => "Catalan",
+        "cs" => "Czech",
+        "cy" => "Welsh",
+        "da" => "Danish",
+        "de" => "German",
+        "el" => "Greek",
+        "en" => "English",
+        "es" => "Spanish",
+        "et" => "Estonian",
+        "eu" => "Basque",
+        "fa" => "Persian",
+        "fi" => "Finnish",
+        "fil" => "Filipino",
+        "fo" => "Faroese",
+        "fr" => "French",
+        "gl" => "Galician",
+        "gu" => "Gujarati",
+        "ha" => "Hausa",
+        "haw" => "Hawaiian",
+        "he" => "Hebrew",
+        "hi" => "Hindi",
+        "hr" => "Croatian",
+        "ht" => "Haitian Creole",
+        "hu" => "Hungarian",
+        "hy" => "Armenian",
+        "id" => "Indonesian",
+        "is" => "Icelandic",
+        "it" => "Italian",
+        "ja" => "Japanese",
+        "jw" => "Javanese",
+        "ka" => "Georgian",
+        "kk" => "Kazakh",
+        "km" => "Khmer",
+        "kn" => "Kannada",
+        "ko" => "Korean",
+        "la" => "Latin",
+        "lb" => "Luxembourgish",
+        "ln" => "Lingala",
+        "lo" => "Lao",
+        "lt" => "Lithuanian",
+        "lv" => "Latvian",
+        "mg" => "Malagasy",
+        "mi" => "Maori",
+        "mk" => "Macedonian",
+        "ml" => "Malayalam",
+        "mn" => "Mongolian",
+        "mr" => "Marathi",
+        "ms" => "Malay",
+        "mt" => "Maltese",
+        "my" => "Myanmar",
+        "ne" => "Nepali",
+        "nl" => "Dutch",
+        "nn" => "Nynorsk",
+        "no" => "Norwegian",
+        "oc" => "Occitan",
+        "pa" => "Punjabi",
+        "pl" => "Polish",
+        "ps" => "Pashto",
+        "pt" => "Portuguese",
+        "ro" => "Romanian",
+        "ru" => "Russian",
+        "sa" => "Sanskrit",
+        "sd" => "Sindhi",
+        "si" => "Sinhala",
+        "sk" => "Slovak",
+        "sl" => "Slovenian",
+        "sn" => "Shona",
+        "so" => "Somali",
+        "sq" => "Albanian",
+        "sr" => "Serbian",
+        "su" => "Sundanese",
+        "sv" => "Swedish",
+        "sw" => "Swahili",
+        "ta" => "Tamil",
+        "te" => "Telugu",
+        "tg" => "Tajik",
+        "th" => "Thai",
+        "tk" => "Turkmen",
+        "tl" => "Tagalog",
+        "tr" => "Turkish",
+        "tt" => "Tatar",
+        "uk" => "Ukrainian",
+        "ur" => "Urdu",
+        "uz" => "Uzbek",
+        "vi" => "Vietnamese",
+        "yue" => "Cantonese",
+        "yi" => "Yiddish",
+        "yo" => "Yoruba",
+        "zh" => "Chinese",
+        _ => return None,
+    };
+    Some(name)
+}
+
 // Model download URLs (Hugging Face)
 pub fn get_model_url(model_id: &str) -> Option<String> {
     let base = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
@@ -502,6 +937,27 @@ pub fn get_qwen3_asr_files(model_id: &str) -> Option<&'static [ParakeetFile]> {
     }
 }
 
+/// Every model id the app ships with, including the `large` alias. The
+/// database seeds one row for each of these except the alias.
+pub const BUILTIN_MODEL_IDS: &[&str] = &[
+    "tiny",
+    "base",
+    "small",
+    "medium",
+    "large",
+    "large-v2",
+    "large-v3",
+    "large-v3-turbo",
+    "tiny.en",
+    "base.en",
+    "small.en",
+    "medium.en",
+    "distil-small.en",
+    "parakeet-v2",
+    "parakeet-v3",
+    "qwen3-asr-0.6b",
+];
+
 pub fn get_model_filename(model_id: &str) -> String {
     match model_id {
         "qwen3-asr-0.6b" => "qwen3-asr-0.6b".to_string(),
@@ -518,3 +974,442 @@ pub fn get_model_filename(model_id: &str) -> String {
         _ => format!("ggml-{}.bin", model_id),
     }
 }
+
+/// `ggml` magic as read little-endian from the start of a whisper.cpp model
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+/// Magic of GGUF files, which whisper.cpp cannot load
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+/// Newest quantization version whisper.cpp understands
+const GGML_QNT_VERSION: i32 = 2;
+/// whisper.cpp encodes the quantization version into `ftype` in steps of this
+const GGML_QNT_VERSION_FACTOR: i32 = 1000;
+/// Smaller than any published whisper.cpp model; anything below this is a
+/// truncated download or an error page
+pub const MIN_GGML_MODEL_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Why a model file cannot be handed to whisper.cpp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelFileProblem {
+    TooSmall,
+    NotGgml,
+    UnsupportedVersion,
+}
+
+impl ModelFileProblem {
+    /// Guidance shown to the user
+    pub fn message(&self) -> &'static str {
+        match self {
+            Self::TooSmall => {
+                "The model file is incomplete, probably from an interrupted download. Please re-download the model."
+            }
+            Self::NotGgml => {
+                "This doesn't look like a whisper.cpp ggml model. Please re-download the model."
+            }
+            Self::UnsupportedVersion => {
+                "This model uses a ggml format this version of Wavee cannot read. Please re-download the model."
+            }
+        }
+    }
+}
+
+/// Check the header of a whisper.cpp model before whisper-rs sees it, so
+/// broken files get specific guidance instead of a raw load error.
+/// Returns `Ok(None)` for a plausible model.
+pub fn check_ggml_model_file(path: &Path) -> std::io::Result<Option<ModelFileProblem>> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    if file.metadata()?.len() < MIN_GGML_MODEL_BYTES {
+        return Ok(Some(ModelFileProblem::TooSmall));
+    }
+
+    // Magic followed by eleven i32 hyperparameters, `ftype` last
+    let mut header = [0u8; 48];
+    file.read_exact(&mut header)?;
+    let field = |index: usize| {
+        let offset = index * 4;
+        i32::from_le_bytes([
+            header[offset],
+            header[offset + 1],
+            header[offset + 2],
+            header[offset + 3],
+        ])
+    };
+
+    if &header[..4] == GGUF_MAGIC {
+        return Ok(Some(ModelFileProblem::UnsupportedVersion));
+    }
+    if field(0) as u32 != GGML_MAGIC {
+        return Ok(Some(ModelFileProblem::NotGgml));
+    }
+
+    // Whisper models use 80 mel bins, or 128 from large-v3 on
+    let n_mels = field(10);
+    if n_mels != 80 && n_mels != 128 {
+        return Ok(Some(ModelFileProblem::NotGgml));
+    }
+    if field(11) / GGML_QNT_VERSION_FACTOR > GGML_QNT_VERSION {
+        return Ok(Some(ModelFileProblem::UnsupportedVersion));
+    }
+
+    Ok(None)
+}
+
+/// Every engine takes 16 kHz mono input
+const SAMPLE_RATE: u64 = 16_000;
+
+/// Thread cap for a `cpu_threads` setting: `None` (every available core) for
+/// 0, otherwise the request clamped to the physical core count when known
+pub fn cpu_thread_limit(requested: i64, physical_cores: Option<usize>) -> Option<usize> {
+    if requested <= 0 {
+        return None;
+    }
+    let requested = requested as usize;
+    Some(physical_cores.map_or(requested, |cores| requested.min(cores.max(1))))
+}
+
+/// Longest window handed to the model when transcribing long audio; Whisper
+/// decodes at most 30 seconds per pass
+pub const LONG_FORM_WINDOW_SAMPLES: usize = SAMPLE_RATE as usize * 25;
+
+/// Join window transcripts, skipping windows that were silent
+pub fn append_chunk_transcript(text: &mut String, chunk: &str) {
+    let chunk = chunk.trim();
+    if chunk.is_empty() {
+        return;
+    }
+    if !text.is_empty() {
+        text.push(' ');
+    }
+    text.push_str(chunk);
+}
+
+/// Pad `samples` with trailing silence up to `min_samples`. Whisper decodes
+/// 30 second windows and is unreliable on clips much shorter than a second.
+pub fn pad_with_silence(samples: &[f32], min_samples: usize) -> Cow<'_, [f32]> {
+    if samples.len() >= min_samples {
+        return Cow::Borrowed(samples);
+    }
+    let mut padded = Vec::with_capacity(min_samples);
+    padded.extend_from_slice(samples);
+    padded.resize(min_samples, 0.0);
+    Cow::Owned(padded)
+}
+
+/// A stretch of transcribed text and where it falls in the clip
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Segment {
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Add a segment unless its text is blank
+fn push_segment(segments: &mut Vec<Segment>, text: &str, start_ms: u64, end_ms: u64) {
+    let text = text.trim();
+    if !text.is_empty() {
+        segments.push(Segment {
+            text: text.to_string(),
+            start_ms,
+            end_ms: end_ms.max(start_ms),
+        });
+    }
+}
+
+/// The whole clip as one segment, for engines that don't time their output
+pub fn whole_clip_segment(text: &str, sample_count: usize) -> Vec<Segment> {
+    let mut segments = Vec::with_capacity(1);
+    push_segment(
+        &mut segments,
+        text,
+        0,
+        sample_count as u64 * 1000 / SAMPLE_RATE,
+    );
+    segments
+}
+
+/// Trim segments to the first `sample_count` samples, dropping any that
+/// start past them. Used to discard timings inside added silence padding.
+pub fn clamp_segments(segments: Vec<Segment>, sample_count: usize) -> Vec<Segment> {
+    let clip_ms = sample_count as u64 * 1000 / SAMPLE_RATE;
+    segments
+        .into_iter()
+        .filter(|segment| segment.start_ms < clip_ms)
+        .map(|segment| Segment {
+            end_ms: segment.end_ms.min(clip_ms),
+            ..segment
+        })
+        .collect()
+}
+
+/// A phrase repeated back to back this many times is a decoding loop, which
+/// Whisper tends to fall into over long stretches of silence
+const MIN_LOOP_REPEATS: usize = 4;
+const MAX_LOOP_PHRASE_WORDS: usize = 4;
+
+/// Words compared case- and punctuation-insensitively when looking for loops
+fn loop_words(words: &[&str]) -> Vec<String> {
+    words
+        .iter()
+        .map(|word| {
+            word.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .collect()
+}
+
+/// Drop a decoding loop that runs to the end of the text. Over padded
+/// silence such a loop is all hallucination, so not even one copy is kept.
+pub fn strip_trailing_loop(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized = loop_words(&words);
+
+    for len in 1..=MAX_LOOP_PHRASE_WORDS.min(words.len()) {
+        let phrase = &normalized[normalized.len() - len..];
+        if phrase.iter().all(String::is_empty) {
+            continue;
+        }
+        let repeats = normalized
+            .rchunks_exact(len)
+            .take_while(|chunk| *chunk == phrase)
+            .count();
+        if repeats >= MIN_LOOP_REPEATS {
+            return words[..words.len() - repeats * len].join(" ");
+        }
+    }
+    text.to_string()
+}
+
+/// Collapse decoding loops such as "Thank you. Thank you. Thank you. Thank
+/// you." into a single copy of the phrase
+pub fn suppress_repeated_phrases(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized = loop_words(&words);
+
+    let mut kept: Vec<&str> = Vec::with_capacity(words.len());
+    let mut i = 0;
+    'words: while i < words.len() {
+        for len in 1..=MAX_LOOP_PHRASE_WORDS {
+            let phrase = &normalized[i..(i + len).min(words.len())];
+            if phrase.len() < len || phrase.iter().all(String::is_empty) {
+                break;
+            }
+            let repeats = normalized[i..]
+                .chunks_exact(len)
+                .take_while(|chunk| *chunk == phrase)
+                .count();
+            if repeats >= MIN_LOOP_REPEATS {
+                kept.extend_from_slice(&words[i..i + len]);
+                i += repeats * len;
+                continue 'words;
+            }
+        }
+        kept.push(words[i]);
+        i += 1;
+    }
+
+    if kept.len() == words.len() {
+        return text.to_string();
+    }
+    kept.join(" ")
+}
+
+/// Whisper decoding settings. The defaults favour speed for short
+/// dictation; `whisper_tuning_for` adjusts them for models that need it.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct WhisperTuning {
+    /// Beams kept while decoding; 1 decodes greedily
+    pub beam_size: i32,
+    /// Decodes with less token entropy than this are treated as repetitive
+    pub entropy_thold: f32,
+    /// Temperature added when retrying a failed decode; 0 never retries
+    pub temperature_inc: f32,
+    /// Most tokens produced per segment
+    pub max_tokens: i32,
+}
+
+impl Default for WhisperTuning {
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            entropy_thold: 2.8,
+            temperature_inc: 0.0,
+            max_tokens: 64,
+        }
+    }
+}
+
+/// Beams searched when beam search is forced on a model tuned for greedy
+/// decoding, the same as whisper.cpp's default
+pub const DEFAULT_WHISPER_BEAM_SIZE: i32 = 5;
+
+/// How Whisper picks tokens, set with the `decoding_strategy` setting. Beam
+/// search keeps several candidate transcripts alive, so every extra beam
+/// costs close to another decoder pass per token: hard audio comes out more
+/// accurate, but dictation is noticeably slower on CPU.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DecodingStrategy {
+    /// Follow the model's tuning, which is greedy unless the model or the
+    /// user asks for beams
+    #[default]
+    Auto,
+    /// Always take the most likely token; the fastest
+    Greedy,
+    /// Always search beams, `DEFAULT_WHISPER_BEAM_SIZE` unless the model's
+    /// tuning already sets a beam size
+    BeamSearch,
+}
+
+impl DecodingStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(Self::Auto),
+            "greedy" => Some(Self::Greedy),
+            "beam-search" => Some(Self::BeamSearch),
+            _ => None,
+        }
+    }
+}
+
+/// Most tokens whisper decodes in one segment
+pub const MAX_WHISPER_TOKENS: i32 = 224;
+/// Wider beams cost time on every token for little extra accuracy
+pub const MAX_WHISPER_BEAM_SIZE: i32 = 8;
+
+/// Default decoding settings for a model. Unknown and custom models use
+/// `WhisperTuning::default()`.
+///
+/// - tiny / tiny.en: the smallest models fall into repeating the same phrase
+///   most often. A higher entropy threshold flags those loops, and a 0.2
+///   temperature step lets the retry break out of them. Retries are cheap at
+///   this size.
+/// - large / large-v2 / large-v3: beam search (3 beams) recovers words
+///   greedy decoding drops, and on short clips it adds little next to the
+///   encoder pass.
+/// - everything else, including large-v3-turbo, keeps greedy decoding since
+///   it is picked for speed.
+pub fn whisper_tuning_for(model_id: &str) -> WhisperTuning {
+    let defaults = WhisperTuning::default();
+    match model_id {
+        "tiny" | "tiny.en" => WhisperTuning {
+            entropy_thold: 3.2,
+            temperature_inc: 0.2,
+            ..defaults
+        },
+        "large" | "large-v2" | "large-v3" => WhisperTuning {
+            beam_size: 3,
+            ..defaults
+        },
+        _ => defaults,
+    }
+}
+
+/// User changes to a model's default tuning; unset fields keep the default
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WhisperTuningOverrides {
+    pub beam_size: Option<i32>,
+    pub entropy_thold: Option<f32>,
+    pub temperature_inc: Option<f32>,
+    pub max_tokens: Option<i32>,
+}
+
+impl WhisperTuningOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(beam_size) = self.beam_size {
+            if !(1..=MAX_WHISPER_BEAM_SIZE).contains(&beam_size) {
+                return Err(format!(
+                    "Beam size must be between 1 and {}",
+                    MAX_WHISPER_BEAM_SIZE
+                ));
+            }
+        }
+        if let Some(entropy_thold) = self.entropy_thold {
+            if !(0.0..=10.0).contains(&entropy_thold) {
+                return Err("Entropy threshold must be between 0 and 10".to_string());
+            }
+        }
+        if let Some(temperature_inc) = self.temperature_inc {
+            if !(0.0..=1.0).contains(&temperature_inc) {
+                return Err("Temperature increment must be between 0 and 1".to_string());
+            }
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            if !(1..=MAX_WHISPER_TOKENS).contains(&max_tokens) {
+                return Err(format!(
+                    "Max tokens must be between 1 and {}",
+                    MAX_WHISPER_TOKENS
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WhisperTuning {
+    pub fn with_overrides(self, overrides: &WhisperTuningOverrides) -> Self {
+        Self {
+            beam_size: overrides.beam_size.unwrap_or(self.beam_size),
+            entropy_thold: overrides.entropy_thold.unwrap_or(self.entropy_thold),
+            temperature_inc: overrides.temperature_inc.unwrap_or(self.temperature_inc),
+            max_tokens: overrides.max_tokens.unwrap_or(self.max_tokens),
+        }
+    }
+
+    pub fn with_strategy(self, strategy: DecodingStrategy) -> Self {
+        match strategy {
+            DecodingStrategy::Auto => self,
+            DecodingStrategy::Greedy => Self {
+                beam_size: 1,
+                ..self
+            },
+            DecodingStrategy::BeamSearch if self.beam_size > 1 => self,
+            DecodingStrategy::BeamSearch => Self {
+                beam_size: DEFAULT_WHISPER_BEAM_SIZE,
+                ..self
+            },
+        }
+    }
+}
+
+/// Engine that runs a model, following the same id prefixes as
+/// `Transcriber::new`
+fn engine_name_for_model(model_id: &str) -> &'static str {
+    if model_id.starts_with("qwen3-asr-") {
+        "qwen3-asr"
+    } else if model_id.starts_with("parakeet-") {
+        "parakeet"
+    } else {
+        "whisper"
+    }
+}
+
+/// Short hash of everything that decides a transcription's output: model,
+/// language and, for Whisper models, the decoding settings. Stored with each
+/// history entry so support can tell whether two results came from the same
+/// configuration.
+pub fn transcription_params_hash(model_id: &str, language: &str, tuning: &WhisperTuning) -> String {
+    let engine = engine_name_for_model(model_id);
+    let mut config = format!(
+        "v1|model={}|engine={}|language={}",
+        model_id, engine, language
+    );
+    // The other engines have no decoding settings, so tuning changes must not
+    // alter their hash
+    if engine == "whisper" {
+        let decoding = if tuning.beam_size > 1 {
+            format!("beam_search:{}", tuning.beam_size)
+        } else {
+            "greedy".to_string()
+        };
+        config.push_str(&format!(
+            "|decoding={}|entropy_thold={}|temperature_inc={}|max_tokens={}",
+            decoding, tuning.entropy_thold, tuning.temperature_inc, tuning.max_tokens
+        ));
+    }
+    hex::encode(&Sha256::digest(config.as_bytes())[..6])
+}
@@ -0,0 +1,152 @@
+//! FIFO queue that runs dictation transcriptions one at a time, so a burst of
+//! requests waits its turn instead of piling up on the transcriber lock
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Assumed run time before any request has finished, for retry-after hints
+const DEFAULT_RUN_ESTIMATE: Duration = Duration::from_secs(2);
+
+struct QueueInner {
+    next_id: u64,
+    /// Request ids in arrival order; the front one is running or next to run
+    pending: VecDeque<u64>,
+    /// Smoothed run time of recent requests
+    average_run: Option<Duration>,
+}
+
+pub struct TranscriptionQueue {
+    capacity: usize,
+    inner: Mutex<QueueInner>,
+    turn: Condvar,
+}
+
+impl TranscriptionQueue {
+    /// Queue holding at most `capacity` requests, including the running one
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(QueueInner {
+                next_id: 1,
+                pending: VecDeque::new(),
+                average_run: None,
+            }),
+            turn: Condvar::new(),
+        }
+    }
+
+    /// Add a request to the back of the queue, or return roughly how long
+    /// until a slot frees up when the queue is full
+    pub fn enqueue(self: &Arc<Self>) -> Result<QueueTicket, Duration> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.pending.len() >= self.capacity {
+            return Err(inner.average_run.unwrap_or(DEFAULT_RUN_ESTIMATE));
+        }
+
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.pending.push_back(id);
+        Ok(QueueTicket {
+            queue: Arc::clone(self),
+            id,
+            ahead: inner.pending.len() - 1,
+            started: None,
+        })
+    }
+}
+
+/// A place in the queue. Dropping it, whether or not it ran, lets the next
+/// request start.
+pub struct QueueTicket {
+    queue: Arc<TranscriptionQueue>,
+    id: u64,
+    ahead: usize,
+    started: Option<Instant>,
+}
+
+impl QueueTicket {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Requests that were ahead of this one when it was queued
+    pub fn ahead(&self) -> usize {
+        self.ahead
+    }
+
+    /// Block until every request queued before this one has finished
+    pub fn wait_turn(&mut self) {
+        let mut inner = self.queue.inner.lock().unwrap();
+        while inner.pending.front() != Some(&self.id) {
+            inner = self.queue.turn.wait(inner).unwrap();
+        }
+        self.started = Some(Instant::now());
+    }
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        let mut inner = self.queue.inner.lock().unwrap();
+        inner.pending.retain(|&id| id != self.id);
+        if let Some(started) = self.started {
+            let elapsed = started.elapsed();
+            inner.average_run = Some(match inner.average_run {
+                Some(average) => (average * 3 + elapsed) / 4,
+                None => elapsed,
+            });
+        }
+        drop(inner);
+        self.queue.turn.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn test_requests_run_in_arrival_order() {
+        let queue = Arc::new(TranscriptionQueue::new(4));
+        let mut first = queue.enqueue().unwrap();
+        first.wait_turn();
+
+        let (order_tx, order_rx) = mpsc::channel();
+        let waiters: Vec<_> = (0..2)
+            .map(|_| {
+                let mut ticket = queue.enqueue().unwrap();
+                let order_tx = order_tx.clone();
+                thread::spawn(move || {
+                    ticket.wait_turn();
+                    order_tx.send(ticket.id()).unwrap();
+                })
+            })
+            .collect();
+
+        // Nothing else starts while the first request holds its turn
+        thread::sleep(Duration::from_millis(50));
+        assert!(order_rx.try_recv().is_err());
+
+        drop(first);
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+        assert_eq!(order_rx.try_iter().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(queue.enqueue().unwrap().ahead(), 0);
+    }
+
+    #[test]
+    fn test_full_queue_rejects_with_retry_after() {
+        let queue = Arc::new(TranscriptionQueue::new(2));
+        let first = queue.enqueue().unwrap();
+        let second = queue.enqueue().unwrap();
+        assert_eq!(second.ahead(), 1);
+
+        assert_eq!(queue.enqueue().err(), Some(DEFAULT_RUN_ESTIMATE));
+
+        drop(first);
+        assert_eq!(queue.enqueue().unwrap().ahead(), 1);
+    }
+}
@@ -1,4 +1,6 @@
-use vox_ai_lib::database::{AppSettings, AppState, Database};
+use vox_ai_lib::database::{AppSettings, AppState, CustomModel, Database};
+use vox_ai_lib::post_process::{PostProcessingConfig, ProcessingStage, UserReplacement};
+use vox_ai_lib::transcription::{WhisperTuningOverrides, BUILTIN_MODEL_IDS};
 
 fn test_database() -> (tempfile::TempDir, Database) {
     let dir = tempfile::tempdir().unwrap();
@@ -44,6 +46,31 @@ fn update_settings_persists_every_field() {
         post_processing_enabled: false,
         voice_commands_enabled: true,
         clipboard_mode: true,
+        long_form_recording: true,
+        empty_transcription_behavior: "error".to_string(),
+        min_recording_ms: 500,
+        cancel_key: "Ctrl+Escape".to_string(),
+        overlay_position: "center".to_string(),
+        injection_blocklist: "1Password\ntitle:bank".to_string(),
+        warm_model_cache_size: 2,
+        tray_menu_items: "start_recording,stop_recording".to_string(),
+        silence_pad_ms: 500,
+        injection_trailing: "smart".to_string(),
+        models_dir: Some("/data/models".to_string()),
+        min_hold_ms: 200,
+        vad_threshold_dbfs: -40,
+        preallocate_recording_seconds: 60,
+        download_mirrors: "https://hf-mirror.com".to_string(),
+        confirm_destructive_commands: true,
+        release_notes_url: "https://example.com/CHANGELOG.md".to_string(),
+        input_device_name: Some("USB Audio#2".to_string()),
+        silence_timeout_ms: 3000,
+        preroll_ms: 500,
+        decoding_strategy: "beam-search".to_string(),
+        cpu_threads: 2,
+        injection_method: "type".to_string(),
+        restore_clipboard: true,
+        typing_delay_ms: 20,
     };
 
     db.update_settings(&settings).unwrap();
@@ -62,6 +89,54 @@ fn update_settings_persists_every_field() {
     assert!(!stored.post_processing_enabled);
     assert!(stored.voice_commands_enabled);
     assert!(stored.clipboard_mode);
+    assert!(stored.long_form_recording);
+    assert_eq!(stored.empty_transcription_behavior, "error");
+    assert_eq!(stored.min_recording_ms, 500);
+    assert_eq!(stored.cancel_key, "Ctrl+Escape");
+    assert_eq!(stored.overlay_position, "center");
+    assert_eq!(stored.injection_blocklist, "1Password\ntitle:bank");
+    assert_eq!(stored.warm_model_cache_size, 2);
+    assert_eq!(stored.tray_menu_items, "start_recording,stop_recording");
+    assert_eq!(stored.silence_pad_ms, 500);
+    assert_eq!(stored.injection_trailing, "smart");
+    // Only set_models_dir writes the models folder
+    assert_eq!(stored.models_dir, None);
+    assert_eq!(stored.min_hold_ms, 200);
+    assert_eq!(stored.vad_threshold_dbfs, -40);
+    assert_eq!(stored.preallocate_recording_seconds, 60);
+    assert_eq!(stored.download_mirrors, "https://hf-mirror.com");
+    assert!(stored.confirm_destructive_commands);
+    assert_eq!(stored.release_notes_url, "https://example.com/CHANGELOG.md");
+    assert_eq!(stored.input_device_name.as_deref(), Some("USB Audio#2"));
+    assert_eq!(stored.silence_timeout_ms, 3000);
+    assert_eq!(stored.preroll_ms, 500);
+    assert_eq!(stored.decoding_strategy, "beam-search");
+    assert_eq!(stored.cpu_threads, 2);
+    assert_eq!(stored.injection_method, "type");
+    assert!(stored.restore_clipboard);
+    assert_eq!(stored.typing_delay_ms, 20);
+}
+
+#[test]
+fn models_dir_is_set_and_cleared_separately() {
+    let (_dir, db) = test_database();
+
+    db.set_models_dir(Some("/data/models")).unwrap();
+    assert_eq!(
+        db.get_settings().unwrap().models_dir.as_deref(),
+        Some("/data/models")
+    );
+    assert!(db.update_setting("models_dir", "/elsewhere").is_err());
+    let mut stale = db.get_settings().unwrap();
+    stale.models_dir = Some("/old/models".to_string());
+    db.update_settings(&stale).unwrap();
+    assert_eq!(
+        db.reset_settings(None).unwrap().models_dir.as_deref(),
+        Some("/data/models")
+    );
+
+    db.set_models_dir(None).unwrap();
+    assert_eq!(db.get_settings().unwrap().models_dir, None);
 }
 
 #[test]
@@ -75,6 +150,36 @@ fn update_setting_rejects_unknown_keys() {
     assert_eq!(settings.selected_model_id, "base");
 }
 
+#[test]
+fn reset_settings_restores_defaults() {
+    let (_dir, db) = test_database();
+    db.update_setting("language", "es").unwrap();
+    db.update_setting("toggle_key", "Ctrl+Alt+T").unwrap();
+
+    let settings = db.reset_settings(None).unwrap();
+
+    assert_eq!(settings.language, "en");
+    assert_eq!(db.get_settings().unwrap().toggle_key, "Alt+Shift+D");
+}
+
+#[test]
+fn reset_settings_can_reset_selected_keys() {
+    let (_dir, db) = test_database();
+    db.update_setting("language", "es").unwrap();
+    db.update_setting("toggle_key", "Ctrl+Alt+T").unwrap();
+
+    db.reset_settings(Some(&["toggle_key".to_string()]))
+        .unwrap();
+
+    let settings = db.get_settings().unwrap();
+    assert_eq!(settings.toggle_key, "Alt+Shift+D");
+    assert_eq!(settings.language, "es");
+
+    let result = db.reset_settings(Some(&["license_key".to_string()]));
+    assert!(result.is_err());
+    assert_eq!(db.get_settings().unwrap().language, "es");
+}
+
 #[test]
 fn app_state_lifecycle_persists_setup_progress() {
     let (_dir, db) = test_database();
@@ -149,6 +254,29 @@ fn default_models_are_seeded_and_sorted_by_size() {
     assert!(models.iter().any(|model| model.id == "qwen3-asr-0.6b"));
 }
 
+#[test]
+fn seeded_models_match_builtin_model_ids() {
+    let (_dir, db) = test_database();
+
+    let mut seeded: Vec<String> = db
+        .get_models()
+        .unwrap()
+        .into_iter()
+        .map(|model| model.id)
+        .collect();
+    seeded.sort();
+    // `large` is an alias for large-v3 and has no row of its own
+    let mut builtin: Vec<&str> = BUILTIN_MODEL_IDS
+        .iter()
+        .copied()
+        .filter(|id| *id != "large")
+        .collect();
+    builtin.sort();
+
+    assert_eq!(seeded, builtin);
+    assert!(seeded.iter().any(|id| id == "large-v2"));
+}
+
 #[test]
 fn model_download_state_round_trips() {
     let (_dir, db) = test_database();
@@ -168,3 +296,76 @@ fn model_download_state_round_trips() {
     assert!(!model.downloaded);
     assert!(model.download_path.is_none());
 }
+
+#[test]
+fn custom_models_join_the_catalog_until_removed() {
+    let (_temp_dir, db) = test_database();
+    let model = CustomModel {
+        id: "custom-whisper-de".to_string(),
+        name: "Whisper German".to_string(),
+        url: "https://example.com/ggml-de.bin".to_string(),
+        filename: "custom-whisper-de.bin".to_string(),
+        size_bytes: 200 * 1024 * 1024,
+    };
+
+    db.add_custom_model(&model).unwrap();
+    assert_eq!(db.get_custom_models().unwrap(), vec![model.clone()]);
+    assert_eq!(
+        db.get_custom_model("custom-whisper-de").unwrap(),
+        Some(model)
+    );
+
+    let listed = db.get_model("custom-whisper-de").unwrap().unwrap();
+    assert_eq!(listed.name, "Whisper German");
+    assert_eq!(listed.size, "200 MB");
+    assert!(!listed.downloaded);
+
+    assert!(db.remove_custom_model("custom-whisper-de").unwrap());
+    assert!(!db.remove_custom_model("custom-whisper-de").unwrap());
+    assert!(db.get_custom_models().unwrap().is_empty());
+    assert!(db.get_model("custom-whisper-de").unwrap().is_none());
+}
+
+#[test]
+fn model_tuning_overrides_round_trip_and_reset() {
+    let (_temp_dir, db) = test_database();
+    assert!(db.get_model_tuning("tiny").unwrap().is_empty());
+
+    let overrides = WhisperTuningOverrides {
+        beam_size: Some(4),
+        max_tokens: Some(128),
+        ..Default::default()
+    };
+    db.save_model_tuning("tiny", &overrides).unwrap();
+    assert_eq!(db.get_model_tuning("tiny").unwrap(), overrides);
+    assert!(db.get_model_tuning("base").unwrap().is_empty());
+
+    db.save_model_tuning("tiny", &WhisperTuningOverrides::default())
+        .unwrap();
+    assert!(db.get_model_tuning("tiny").unwrap().is_empty());
+}
+
+#[test]
+fn post_processing_config_round_trips() {
+    let (_temp_dir, db) = test_database();
+
+    assert_eq!(
+        db.get_post_processing_config().unwrap(),
+        PostProcessingConfig::default()
+    );
+
+    let config = PostProcessingConfig {
+        stages: vec![ProcessingStage::UserDictionary, ProcessingStage::Whitespace],
+        replacements: vec![UserReplacement {
+            pattern: "jay son".to_string(),
+            replacement: "JSON".to_string(),
+        }],
+        action_markers: false,
+    };
+    db.save_post_processing_config(&config).unwrap();
+    assert_eq!(db.get_post_processing_config().unwrap(), config);
+
+    let updated = PostProcessingConfig::default();
+    db.save_post_processing_config(&updated).unwrap();
+    assert_eq!(db.get_post_processing_config().unwrap(), updated);
+}
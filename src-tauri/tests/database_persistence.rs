@@ -22,6 +22,31 @@ fn database_state_survives_reopen() {
             post_processing_enabled: false,
             voice_commands_enabled: true,
             clipboard_mode: true,
+            long_form_recording: true,
+            empty_transcription_behavior: "error".to_string(),
+            min_recording_ms: 500,
+            cancel_key: "Ctrl+Escape".to_string(),
+            overlay_position: "center".to_string(),
+            injection_blocklist: "1Password\ntitle:bank".to_string(),
+            warm_model_cache_size: 2,
+            tray_menu_items: "start_recording,stop_recording".to_string(),
+            silence_pad_ms: 500,
+            injection_trailing: "smart".to_string(),
+            models_dir: Some("/data/models".to_string()),
+            min_hold_ms: 200,
+            vad_threshold_dbfs: -40,
+            preallocate_recording_seconds: 60,
+            download_mirrors: "https://hf-mirror.com".to_string(),
+            confirm_destructive_commands: true,
+            release_notes_url: "https://example.com/CHANGELOG.md".to_string(),
+            input_device_name: Some("USB Audio#2".to_string()),
+            silence_timeout_ms: 3000,
+            preroll_ms: 500,
+            decoding_strategy: "beam-search".to_string(),
+            cpu_threads: 2,
+            injection_method: "type".to_string(),
+            restore_clipboard: true,
+            typing_delay_ms: 20,
         })
         .unwrap();
         db.update_app_state(&AppState {
@@ -177,3 +202,46 @@ fn legacy_database_is_migrated_without_losing_existing_state() {
     assert_eq!(license.usage, 0);
     assert_eq!(license.validations, 0);
 }
+
+#[test]
+fn corrupt_database_is_backed_up_and_recreated() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Wavee.db"), b"definitely not a sqlite file").unwrap();
+
+    let db = Database::new(dir.path().to_path_buf()).unwrap();
+
+    let recovery = db.recovery().expect("recovery should be reported");
+    let backup_path = recovery
+        .backup_path
+        .clone()
+        .expect("backup should be created");
+    assert!(backup_path.contains("Wavee.db.corrupt-"));
+    assert_eq!(
+        std::fs::read(&backup_path).unwrap(),
+        b"definitely not a sqlite file"
+    );
+    assert_eq!(db.get_settings().unwrap().language, "en");
+}
+
+#[test]
+fn unopenable_database_is_reported_and_left_in_place() {
+    let dir = tempfile::tempdir().unwrap();
+    // A directory in place of the file fails to open without being corrupt
+    std::fs::create_dir(dir.path().join("Wavee.db")).unwrap();
+
+    assert!(Database::new(dir.path().to_path_buf()).is_err());
+
+    assert!(dir.path().join("Wavee.db").is_dir());
+    let entries = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(entries, 1);
+}
+
+#[test]
+fn healthy_database_reports_no_recovery() {
+    let dir = tempfile::tempdir().unwrap();
+    drop(Database::new(dir.path().to_path_buf()).unwrap());
+
+    let db = Database::new(dir.path().to_path_buf()).unwrap();
+
+    assert!(db.recovery().is_none());
+}
@@ -1,8 +1,12 @@
 use httpmock::prelude::*;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::fs;
-use vox_ai_lib::downloader::{DownloadProgress, ModelDownloader};
+use vox_ai_lib::downloader::{
+    download_eta, estimate_download_seconds, sha256_file, DownloadProgress, DownloadRate,
+    ModelDownloader, DOWNLOAD_CANCELLED, SPEED_TEST_BYTES,
+};
 
 #[tokio::test]
 async fn downloader_fetches_file_and_updates_progress() {
@@ -99,6 +103,54 @@ async fn downloader_does_not_leave_temp_file_after_http_error() {
         .exists());
 }
 
+/// SHA256 of the 1024 zero bytes served by the mocks below
+const ZEROS_1024_SHA256: &str = "5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef";
+
+#[tokio::test]
+async fn downloader_rejects_a_checksum_mismatch_and_removes_the_file() {
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(200).body(vec![0u8; 1024]);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(server.url("/model.bin"));
+    downloader.set_expected_sha256("base", Some(&"0".repeat(64)));
+
+    let result = downloader.download_model("base", |_| {}).await;
+
+    mock.assert();
+    assert!(result.unwrap_err().contains("Checksum mismatch for base"));
+    assert!(!downloader.get_model_path("base").exists());
+    assert!(!downloader
+        .get_model_path("base")
+        .with_extension("bin.tmp")
+        .exists());
+}
+
+#[tokio::test]
+async fn downloader_saves_a_file_matching_its_checksum() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(200).body(vec![0u8; 1024]);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(server.url("/model.bin"));
+    downloader.set_expected_sha256("base", Some(&ZEROS_1024_SHA256.to_uppercase()));
+
+    let path = downloader.download_model("base", |_| {}).await.unwrap();
+    assert_eq!(sha256_file(&path).await.unwrap(), ZEROS_1024_SHA256);
+
+    // Clearing the digest skips the check
+    downloader.set_expected_sha256("base", None);
+    assert_eq!(downloader.expected_sha256("base"), None);
+}
+
 #[tokio::test]
 async fn downloader_cancels_in_flight_download() {
     let server = MockServer::start();
@@ -129,7 +181,7 @@ async fn downloader_cancels_in_flight_download() {
 
     let result = handle.await.unwrap();
     assert!(result.is_err());
-    assert_eq!(result.unwrap_err(), "Download cancelled");
+    assert_eq!(result.unwrap_err(), DOWNLOAD_CANCELLED);
 
     // Ensure no temp file is left
     let temp_path = downloader_arc
@@ -137,3 +189,160 @@ async fn downloader_cancels_in_flight_download() {
         .with_extension("bin.tmp");
     assert!(!temp_path.exists());
 }
+
+#[tokio::test]
+async fn downloader_falls_back_to_mirrors_and_skips_failed_ones() {
+    let primary = MockServer::start();
+    let broken_mirror = MockServer::start();
+    let mirror = MockServer::start();
+
+    let primary_mock = primary.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(403);
+    });
+    let broken_mock = broken_mirror.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(429);
+    });
+    let mirror_mock = mirror.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(200)
+            .header("content-length", "16")
+            .body(vec![0u8; 16]);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(primary.url("/model.bin"));
+    downloader.set_mirrors(vec![broken_mirror.base_url(), mirror.base_url()]);
+
+    let sources = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let sources_clone = sources.clone();
+    let result = downloader
+        .download_model("base", move |progress: DownloadProgress| {
+            sources_clone.lock().unwrap().push(progress.source);
+        })
+        .await;
+
+    assert!(result.is_ok());
+    primary_mock.assert();
+    broken_mock.assert();
+    mirror_mock.assert();
+    let mirror_host = mirror.address().to_string();
+    assert!(sources.lock().unwrap().iter().all(|s| *s == mirror_host));
+
+    // The broken mirror is not retried for the rest of the session
+    std::fs::remove_file(result.unwrap()).unwrap();
+    downloader.download_model("base", |_| {}).await.unwrap();
+    broken_mock.assert_hits(1);
+    mirror_mock.assert_hits(2);
+}
+
+#[tokio::test]
+async fn downloader_reports_every_failed_source() {
+    let primary = MockServer::start();
+    let mirror = MockServer::start();
+    primary.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(403);
+    });
+    mirror.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(503);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(primary.url("/model.bin"));
+    downloader.set_mirrors(vec![mirror.base_url()]);
+
+    let error = downloader.download_model("base", |_| {}).await.unwrap_err();
+
+    assert!(error.contains("every source"));
+    assert!(error.contains("status: 403"));
+    assert!(error.contains("status: 503"));
+}
+
+#[tokio::test]
+async fn speed_test_requests_a_range_and_estimates_the_full_download() {
+    let server = MockServer::start();
+    let mock = server.mock(|when, then| {
+        when.method(GET)
+            .path("/model.bin")
+            .header("range", format!("bytes=0-{}", SPEED_TEST_BYTES - 1));
+        then.status(206)
+            .header(
+                "content-range",
+                format!("bytes 0-{}/{}", SPEED_TEST_BYTES - 1, SPEED_TEST_BYTES * 40),
+            )
+            .body(vec![0u8; SPEED_TEST_BYTES as usize]);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(server.url("/model.bin"));
+
+    let result = downloader.test_download_speed("base", 0).await.unwrap();
+
+    mock.assert();
+    assert_eq!(result.bytes_downloaded, SPEED_TEST_BYTES);
+    assert_eq!(result.total_bytes, SPEED_TEST_BYTES * 40);
+    assert!(result.bytes_per_second > 0);
+    assert_eq!(
+        result.estimated_seconds,
+        estimate_download_seconds(result.total_bytes, result.bytes_per_second)
+    );
+    // The sample is only counted, never saved
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn speed_test_stops_reading_when_the_range_is_ignored() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(GET).path("/model.bin");
+        then.status(200)
+            .body(vec![0u8; SPEED_TEST_BYTES as usize * 2]);
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.test_url_override = Some(server.url("/model.bin"));
+
+    let result = downloader
+        .test_download_speed("base", 1_000_000_000)
+        .await
+        .unwrap();
+
+    assert!(result.bytes_downloaded >= SPEED_TEST_BYTES);
+    assert!(result.bytes_downloaded < SPEED_TEST_BYTES * 2);
+    assert_eq!(result.total_bytes, 1_000_000_000);
+    assert!(result.estimated_seconds.is_some());
+}
+
+#[test]
+fn download_estimate_rounds_up_and_needs_both_values() {
+    assert_eq!(estimate_download_seconds(1000, 300), Some(4));
+    assert_eq!(estimate_download_seconds(0, 300), None);
+    assert_eq!(estimate_download_seconds(1000, 0), None);
+}
+
+#[test]
+fn download_rate_averages_over_the_last_second() {
+    let start = Instant::now();
+    let mut rate = DownloadRate::default();
+    assert_eq!(rate.record(start, 0), 0.0);
+
+    // A burst is spread over the time since the first sample
+    let speed = rate.record(start + Duration::from_millis(100), 100_000);
+    assert!((speed - 1_000_000.0).abs() < 1.0);
+
+    // Samples older than the window stop counting once it has elapsed
+    rate.record(start + Duration::from_millis(1100), 200_000);
+    let speed = rate.record(start + Duration::from_millis(2100), 300_000);
+    assert!((speed - 100_000.0).abs() < 1.0);
+
+    assert_eq!(download_eta(300_000, 1_000_000, speed), Some(7));
+    assert_eq!(download_eta(1_000_000, 1_000_000, speed), Some(0));
+    assert_eq!(download_eta(300_000, 0, speed), None);
+}
@@ -1,4 +1,4 @@
-use vox_ai_lib::downloader::ModelDownloader;
+use vox_ai_lib::downloader::{check_dir_writable, mirror_url, parse_mirror_list, ModelDownloader};
 use vox_ai_lib::transcription::{get_model_filename, get_parakeet_files};
 
 #[test]
@@ -29,6 +29,25 @@ fn single_file_model_download_state_uses_file_existence() {
     assert_eq!(downloader.get_downloaded_models(), vec!["base".to_string()]);
 }
 
+#[test]
+fn downloaded_models_include_english_and_distil_variants() {
+    let dir = tempfile::tempdir().unwrap();
+    let downloader = ModelDownloader::new(dir.path().to_path_buf());
+
+    for model_id in ["base.en", "distil-small.en", "large-v2", "large-v3-turbo"] {
+        std::fs::write(downloader.get_model_path(model_id), b"fake model").unwrap();
+    }
+    // distil-large-v3 is no longer offered, so a leftover file is not listed
+    std::fs::write(dir.path().join("ggml-distil-large-v3.bin"), b"fake model").unwrap();
+
+    let mut downloaded = downloader.get_downloaded_models();
+    downloaded.sort();
+    assert_eq!(
+        downloaded,
+        vec!["base.en", "distil-small.en", "large-v2", "large-v3-turbo"]
+    );
+}
+
 #[test]
 fn directory_model_download_state_requires_all_manifest_files() {
     let dir = tempfile::tempdir().unwrap();
@@ -72,3 +91,115 @@ async fn delete_model_removes_file_or_directory_models() {
     downloader.delete_model("parakeet-v3").await.unwrap();
     assert!(!parakeet_path.exists());
 }
+
+#[test]
+fn custom_models_resolve_to_their_registered_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let downloader = ModelDownloader::new(dir.path().to_path_buf());
+    downloader.register_custom_model(
+        "custom-tuned",
+        "https://example.com/tuned.bin",
+        "custom-tuned.bin",
+    );
+
+    assert_eq!(
+        downloader.get_model_path("custom-tuned"),
+        dir.path().join("custom-tuned.bin")
+    );
+    std::fs::write(downloader.get_model_path("custom-tuned"), b"fake model").unwrap();
+    assert_eq!(
+        downloader.get_downloaded_models(),
+        vec!["custom-tuned".to_string()]
+    );
+
+    downloader.unregister_custom_model("custom-tuned");
+    assert!(downloader.get_downloaded_models().is_empty());
+}
+
+#[test]
+fn partial_download_cleanup_only_removes_temp_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let downloader = ModelDownloader::new(dir.path().to_path_buf());
+    std::fs::write(downloader.get_model_path("base"), b"complete model").unwrap();
+    std::fs::write(dir.path().join("ggml-small.bin.tmp"), b"partial").unwrap();
+    std::fs::write(dir.path().join("notes.tmp"), b"user file").unwrap();
+    let model_dir = downloader.get_model_path("parakeet-v3");
+    std::fs::create_dir_all(&model_dir).unwrap();
+    std::fs::write(model_dir.join("encoder-model.int8.onnx.tmp"), b"abc").unwrap();
+
+    let partials = downloader.find_partial_downloads();
+    assert_eq!(partials.len(), 2);
+    assert_eq!(partials.iter().map(|p| p.size_bytes).sum::<u64>(), 10);
+
+    let cleanup = downloader.cleanup_partial_downloads();
+    assert_eq!(cleanup.freed_bytes, 10);
+    assert!(downloader.find_partial_downloads().is_empty());
+    assert!(downloader.is_model_downloaded("base"));
+    assert!(model_dir.is_dir());
+    assert!(dir.path().join("notes.tmp").is_file());
+}
+
+#[test]
+fn partial_download_cleanup_skips_unrelated_folders() {
+    let dir = tempfile::tempdir().unwrap();
+    let downloader = ModelDownloader::new(dir.path().to_path_buf());
+    let other = dir.path().join("projects");
+    std::fs::create_dir_all(&other).unwrap();
+    std::fs::write(other.join("draft.bin.tmp"), b"user file").unwrap();
+
+    assert!(downloader.find_partial_downloads().is_empty());
+    downloader.cleanup_partial_downloads();
+    assert!(other.join("draft.bin.tmp").is_file());
+}
+
+#[test]
+fn writable_check_creates_the_directory_and_leaves_nothing_behind() {
+    let dir = tempfile::tempdir().unwrap();
+    let models_dir = dir.path().join("nested").join("models");
+
+    check_dir_writable(&models_dir).unwrap();
+
+    assert!(models_dir.is_dir());
+    assert_eq!(std::fs::read_dir(&models_dir).unwrap().count(), 0);
+}
+
+#[test]
+fn downloaded_models_move_to_a_new_directory() {
+    let old_dir = tempfile::tempdir().unwrap();
+    let new_dir = tempfile::tempdir().unwrap();
+    let downloader = ModelDownloader::new(old_dir.path().to_path_buf());
+    std::fs::write(downloader.get_model_path("base"), b"fake model").unwrap();
+    let parakeet_dir = downloader.get_model_path("parakeet-v3");
+    std::fs::create_dir_all(&parakeet_dir).unwrap();
+    for file in get_parakeet_files("parakeet-v3").unwrap() {
+        std::fs::write(parakeet_dir.join(file.filename), b"fake").unwrap();
+    }
+
+    let mut moved = downloader.move_models_to(new_dir.path()).unwrap();
+    moved.sort();
+
+    assert_eq!(moved, vec!["base".to_string(), "parakeet-v3".to_string()]);
+    assert_eq!(downloader.models_dir(), new_dir.path());
+    assert!(downloader.is_model_downloaded("base"));
+    assert!(downloader.is_model_downloaded("parakeet-v3"));
+    assert!(!old_dir.path().join(get_model_filename("base")).exists());
+}
+
+#[test]
+fn mirrors_keep_the_original_path() {
+    let url = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin";
+
+    assert_eq!(
+        mirror_url(url, "https://hf-mirror.com/").as_deref(),
+        Some("https://hf-mirror.com/ggerganov/whisper.cpp/resolve/main/ggml-base.bin")
+    );
+    assert_eq!(mirror_url(url, "https://huggingface.co"), None);
+    assert_eq!(mirror_url("not a url", "https://hf-mirror.com"), None);
+    assert_eq!(
+        parse_mirror_list(" https://a.example/ \n\nhttps://b.example\n"),
+        vec![
+            "https://a.example".to_string(),
+            "https://b.example".to_string()
+        ]
+    );
+}
@@ -0,0 +1,45 @@
+use std::panic;
+use std::sync::{Arc, Mutex};
+
+use tempfile::tempdir;
+use vox_ai_lib::error_reporting::{ErrorReporter, PanicHookMode};
+
+// Installs the global error reporter and its panic hook, so it lives in a
+// test binary of its own rather than leaking both into the unit tests
+#[test]
+fn chained_panic_hook_reports_then_defers() {
+    let dir = tempdir().unwrap();
+    let seen_by_previous = Arc::new(Mutex::new(Vec::<String>::new()));
+    let seen = seen_by_previous.clone();
+    panic::set_hook(Box::new(move |info| {
+        if let Some(message) = info.payload().downcast_ref::<&str>() {
+            seen.lock().unwrap().push(message.to_string());
+        }
+    }));
+    ErrorReporter::init_with_panic_hook(dir.path().to_path_buf(), PanicHookMode::Chain);
+    assert!(ErrorReporter::panic_hook_active());
+
+    let _ = panic::catch_unwind(|| panic!("chained hook test"));
+    let reporter = ErrorReporter::global().unwrap();
+    let reported = |message: &str| {
+        reporter
+            .get_crash_reports(None)
+            .iter()
+            .any(|crash| crash.panic_message == message)
+    };
+    assert!(reported("chained hook test"));
+    assert!(seen_by_previous
+        .lock()
+        .unwrap()
+        .contains(&"chained hook test".to_string()));
+
+    // Switched off, panics only reach the previous hook
+    assert!(!ErrorReporter::set_panic_hook_active(false));
+    let _ = panic::catch_unwind(|| panic!("inactive hook test"));
+    assert!(ErrorReporter::set_panic_hook_active(true));
+    assert!(!reported("inactive hook test"));
+    assert!(seen_by_previous
+        .lock()
+        .unwrap()
+        .contains(&"inactive hook test".to_string()));
+}
@@ -1,6 +1,7 @@
 use httpmock::prelude::*;
 use serde_json::json;
-use vox_ai_lib::license::{clear_cache, LicenseManager, LicenseStatus};
+use std::sync::{Arc, Mutex};
+use vox_ai_lib::license::{clear_cache, ActivationStage, LicenseManager, LicenseStatus};
 
 #[tokio::test]
 async fn license_manager_activation_success() {
@@ -277,3 +278,59 @@ async fn license_manager_deactivation_failure_is_sanitized() {
     );
     let _ = clear_cache();
 }
+
+#[tokio::test]
+async fn license_manager_activation_reports_stages_in_order() {
+    let server = MockServer::start();
+    server.mock(|when, then| {
+        when.method(POST).path("/activate");
+        then.status(404);
+    });
+
+    let stages = Arc::new(Mutex::new(Vec::new()));
+    let recorded = stages.clone();
+    let manager = LicenseManager::with_org_id("test-org-id", &server.base_url());
+    let result = manager
+        .activate_with_progress("bad-key", move |stage| recorded.lock().unwrap().push(stage))
+        .await;
+
+    assert!(result.is_err());
+    assert_eq!(
+        *stages.lock().unwrap(),
+        vec![
+            ActivationStage::Connecting,
+            ActivationStage::WaitingOnServer
+        ]
+    );
+}
+
+#[tokio::test]
+async fn license_manager_activation_fails_fast_when_unreachable() {
+    // Nothing listens on a port we just released
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let manager = LicenseManager::with_org_id("test-org-id", &format!("http://127.0.0.1:{}", port));
+    let error = manager.activate("test-key").await.unwrap_err();
+    assert!(error.contains("Could not reach the license server"));
+
+    let stages = Arc::new(Mutex::new(Vec::new()));
+    let recorded = stages.clone();
+    let offline = LicenseManager::with_org_id("test-org-id", "https://license.invalid");
+    let error = offline
+        .activate_with_progress("test-key", move |stage| {
+            recorded.lock().unwrap().push(stage)
+        })
+        .await
+        .unwrap_err();
+    assert!(error.contains("Could not reach the license server"));
+    assert_eq!(
+        *stages.lock().unwrap(),
+        vec![
+            ActivationStage::Connecting,
+            ActivationStage::WaitingOnServer
+        ]
+    );
+}
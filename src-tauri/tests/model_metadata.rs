@@ -1,5 +1,8 @@
 use vox_ai_lib::transcription::{
-    get_model_filename, get_model_url, get_parakeet_files, get_qwen3_asr_files,
+    check_ggml_model_file, cpu_thread_limit, get_model_filename, get_model_url, get_parakeet_files,
+    get_qwen3_asr_files, transcription_params_hash, whisper_tuning_for, DecodingStrategy,
+    ModelFileProblem, WhisperTuning, WhisperTuningOverrides, DEFAULT_WHISPER_BEAM_SIZE,
+    MIN_GGML_MODEL_BYTES,
 };
 
 #[test]
@@ -9,6 +12,7 @@ fn whisper_model_urls_are_https_and_known_models_resolve() {
         "base",
         "small",
         "medium",
+        "large-v2",
         "large-v3",
         "large-v3-turbo",
     ] {
@@ -55,3 +59,157 @@ fn directory_model_manifests_include_required_files_and_https_urls() {
     assert!(qwen.iter().any(|file| file.filename == "vocab.json"));
     assert!(qwen.iter().all(|file| file.url.starts_with("https://")));
 }
+
+fn write_model_file(dir: &tempfile::TempDir, header: &[u8]) -> std::path::PathBuf {
+    let path = dir.path().join("ggml-test.bin");
+    let mut bytes = header.to_vec();
+    bytes.resize(MIN_GGML_MODEL_BYTES as usize, 0);
+    std::fs::write(&path, bytes).unwrap();
+    path
+}
+
+fn ggml_header(n_mels: i32, ftype: i32) -> Vec<u8> {
+    let mut header = 0x6767_6d6c_u32.to_le_bytes().to_vec();
+    for value in [51865, 1500, 384, 6, 4, 448, 384, 6, 4, n_mels, ftype] {
+        header.extend_from_slice(&i32::to_le_bytes(value));
+    }
+    header
+}
+
+#[test]
+fn ggml_check_accepts_whisper_models() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let path = write_model_file(&dir, &ggml_header(80, 1));
+    assert_eq!(check_ggml_model_file(&path).unwrap(), None);
+
+    // Quantized large-v3 style header
+    let path = write_model_file(&dir, &ggml_header(128, 2008));
+    assert_eq!(check_ggml_model_file(&path).unwrap(), None);
+}
+
+#[test]
+fn ggml_check_flags_broken_files() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let truncated = dir.path().join("truncated.bin");
+    std::fs::write(&truncated, ggml_header(80, 1)).unwrap();
+    assert_eq!(
+        check_ggml_model_file(&truncated).unwrap(),
+        Some(ModelFileProblem::TooSmall)
+    );
+
+    let html = write_model_file(&dir, b"<!DOCTYPE html><html><body>Not Found</body></html>");
+    assert_eq!(
+        check_ggml_model_file(&html).unwrap(),
+        Some(ModelFileProblem::NotGgml)
+    );
+
+    let gguf = write_model_file(&dir, b"GGUF\x03\x00\x00\x00");
+    assert_eq!(
+        check_ggml_model_file(&gguf).unwrap(),
+        Some(ModelFileProblem::UnsupportedVersion)
+    );
+
+    let future = write_model_file(&dir, &ggml_header(80, 3001));
+    assert_eq!(
+        check_ggml_model_file(&future).unwrap(),
+        Some(ModelFileProblem::UnsupportedVersion)
+    );
+}
+
+#[test]
+fn model_tuning_defaults_and_overrides() {
+    let defaults = WhisperTuning::default();
+    assert_eq!(whisper_tuning_for("base"), defaults);
+    assert_eq!(whisper_tuning_for("custom-whisper-de"), defaults);
+    assert!(whisper_tuning_for("tiny").entropy_thold > defaults.entropy_thold);
+    assert!(whisper_tuning_for("tiny.en").temperature_inc > 0.0);
+    assert!(whisper_tuning_for("large-v3").beam_size > 1);
+    assert_eq!(whisper_tuning_for("large-v3-turbo").beam_size, 1);
+
+    let overrides = WhisperTuningOverrides {
+        beam_size: Some(1),
+        ..Default::default()
+    };
+    let tuned = whisper_tuning_for("large-v3").with_overrides(&overrides);
+    assert_eq!(tuned, defaults);
+    assert!(overrides.validate().is_ok());
+
+    for invalid in [
+        WhisperTuningOverrides {
+            beam_size: Some(0),
+            ..Default::default()
+        },
+        WhisperTuningOverrides {
+            entropy_thold: Some(f32::NAN),
+            ..Default::default()
+        },
+        WhisperTuningOverrides {
+            max_tokens: Some(1000),
+            ..Default::default()
+        },
+    ] {
+        assert!(invalid.validate().is_err());
+    }
+}
+
+#[test]
+fn decoding_strategy_forces_or_follows_model_tuning() {
+    let base = whisper_tuning_for("base");
+    let large = whisper_tuning_for("large-v3");
+
+    assert_eq!(DecodingStrategy::default(), DecodingStrategy::Auto);
+    assert_eq!(base.with_strategy(DecodingStrategy::Auto), base);
+    assert_eq!(large.with_strategy(DecodingStrategy::Auto), large);
+
+    assert_eq!(large.with_strategy(DecodingStrategy::Greedy).beam_size, 1);
+    assert_eq!(
+        base.with_strategy(DecodingStrategy::BeamSearch).beam_size,
+        DEFAULT_WHISPER_BEAM_SIZE
+    );
+    // A beam size from the model's tuning is kept
+    assert_eq!(
+        large.with_strategy(DecodingStrategy::BeamSearch).beam_size,
+        large.beam_size
+    );
+
+    assert_eq!(
+        DecodingStrategy::parse("beam-search"),
+        Some(DecodingStrategy::BeamSearch)
+    );
+    assert_eq!(DecodingStrategy::parse("beam_search"), None);
+}
+
+#[test]
+fn transcription_params_hash_tracks_effective_settings() {
+    let tuning = whisper_tuning_for("base");
+    let hash = transcription_params_hash("base", "en", &tuning);
+
+    assert_eq!(hash.len(), 12);
+    assert_eq!(hash, transcription_params_hash("base", "en", &tuning));
+    assert_ne!(hash, transcription_params_hash("small", "en", &tuning));
+    assert_ne!(hash, transcription_params_hash("base", "de", &tuning));
+
+    let beam_search = WhisperTuning {
+        beam_size: 3,
+        ..tuning
+    };
+    assert_ne!(hash, transcription_params_hash("base", "en", &beam_search));
+
+    // Parakeet ignores Whisper decoding settings
+    assert_eq!(
+        transcription_params_hash("parakeet-tdt-0.6b-v3", "en", &tuning),
+        transcription_params_hash("parakeet-tdt-0.6b-v3", "en", &beam_search)
+    );
+}
+
+#[test]
+fn cpu_thread_limit_is_clamped_to_physical_cores() {
+    assert_eq!(cpu_thread_limit(0, Some(8)), None);
+    assert_eq!(cpu_thread_limit(-1, Some(8)), None);
+    assert_eq!(cpu_thread_limit(2, Some(8)), Some(2));
+    assert_eq!(cpu_thread_limit(16, Some(8)), Some(8));
+    // Without a core count the request is taken as is
+    assert_eq!(cpu_thread_limit(16, None), Some(16));
+}
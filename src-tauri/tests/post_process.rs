@@ -1,4 +1,7 @@
-use vox_ai_lib::post_process::PostProcessor;
+use vox_ai_lib::post_process::{
+    apply_user_replacement, normalize_punctuation_spacing, PostProcessingConfig, PostProcessor,
+    ProcessingStage, UserReplacement, CODE_STAGES, DEFAULT_STAGES,
+};
 
 fn processor() -> PostProcessor {
     PostProcessor::new()
@@ -103,3 +106,208 @@ fn preserves_common_technical_abbreviations() {
     assert_eq!(pp.process("call the api url"), "Call the API URL");
     assert_eq!(pp.process("parse json and html"), "Parse JSON and HTML");
 }
+
+#[test]
+fn default_pipeline_runs_every_stage_in_order() {
+    let pp = processor();
+
+    assert_eq!(pp.stages(), DEFAULT_STAGES);
+    assert_eq!(ProcessingStage::preset("default"), Some(DEFAULT_STAGES));
+    assert_eq!(ProcessingStage::preset("unknown"), None);
+}
+
+#[test]
+fn disabled_stages_are_skipped() {
+    let pp = PostProcessor::with_stages(ProcessingStage::preset("dictation").unwrap().to_vec());
+
+    assert_eq!(pp.process("edit main dot rs"), "Edit main dot rs");
+    assert_eq!(processor().process("edit main dot rs"), "Edit @main.rs");
+}
+
+#[test]
+fn code_preset_skips_voice_commands_and_sentence_casing() {
+    let pp = PostProcessor::with_stages(ProcessingStage::preset("code").unwrap().to_vec());
+
+    assert_eq!(pp.stages(), CODE_STAGES);
+    assert!(!CODE_STAGES.contains(&ProcessingStage::VoiceCommands));
+    assert_eq!(pp.process("edit main dot rs"), "edit @main.rs");
+}
+
+#[test]
+fn punctuation_spacing_fixes_prose() {
+    assert_eq!(
+        normalize_punctuation_spacing("Hello , world !How are you ?"),
+        "Hello, world! How are you?"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("It works.Next step;then this"),
+        "It works. Next step; then this"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("see ( the docs ) first"),
+        "see (the docs) first"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("first line ,\nsecond line"),
+        "first line,\nsecond line"
+    );
+}
+
+#[test]
+fn punctuation_spacing_leaves_numbers_names_and_links_alone() {
+    assert_eq!(
+        normalize_punctuation_spacing("pi is 3.14 and 1,000 items at 12:30"),
+        "pi is 3.14 and 1,000 items at 12:30"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("open @file.ts and README.md , e.g. this"),
+        "open @file.ts and README.md, e.g. this"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("go to https://example.com/a?b=c,d now"),
+        "go to https://example.com/a?b=c,d now"
+    );
+    assert_eq!(
+        normalize_punctuation_spacing("done [[ENTER]] next"),
+        "done [[ENTER]] next"
+    );
+}
+
+#[test]
+fn punctuation_spacing_handles_ellipses() {
+    assert_eq!(
+        normalize_punctuation_spacing("wait ... what"),
+        "wait... what"
+    );
+    assert_eq!(normalize_punctuation_spacing("wait...what"), "wait... what");
+    assert_eq!(normalize_punctuation_spacing("and then ..."), "and then...");
+}
+
+#[test]
+fn punctuation_stage_runs_in_prose_mode_only() {
+    let prose = PostProcessor::with_stages(ProcessingStage::preset("dictation").unwrap().to_vec());
+    let code = PostProcessor::with_stages(ProcessingStage::preset("code").unwrap().to_vec());
+
+    assert_eq!(prose.process("hello ,world"), "Hello, world");
+    assert!(!CODE_STAGES.contains(&ProcessingStage::Punctuation));
+    assert_eq!(code.process("foo(a,b)"), "foo(a,b)");
+}
+
+#[test]
+fn stage_order_is_respected() {
+    let symbols_first = PostProcessor::with_stages(vec![
+        ProcessingStage::Symbols,
+        ProcessingStage::SentenceCasing,
+    ]);
+    let casing_first = PostProcessor::with_stages(vec![
+        ProcessingStage::SentenceCasing,
+        ProcessingStage::Symbols,
+    ]);
+
+    assert_eq!(
+        symbols_first.process("hello period how are you"),
+        "Hello . How are you"
+    );
+    assert_eq!(
+        casing_first.process("hello period how are you"),
+        "Hello . how are you"
+    );
+}
+
+#[test]
+fn stages_deserialize_from_snake_case_names() {
+    let stages: Vec<ProcessingStage> =
+        serde_json::from_str(r#"["voice_commands", "sentence_casing"]"#).unwrap();
+
+    assert_eq!(
+        stages,
+        vec![
+            ProcessingStage::VoiceCommands,
+            ProcessingStage::SentenceCasing
+        ]
+    );
+}
+
+#[test]
+fn user_replacement_applies_capture_groups() {
+    assert_eq!(
+        apply_user_replacement(r"\bjay son\b", "JSON", "parse the jay son file").unwrap(),
+        "parse the JSON file"
+    );
+    assert_eq!(
+        apply_user_replacement(r"(\w+)@(\w+)", "$2 at $1", "user@host").unwrap(),
+        "host at user"
+    );
+}
+
+#[test]
+fn user_replacement_rejects_unsafe_or_invalid_patterns() {
+    assert_eq!(
+        apply_user_replacement(r"(a+)\1", "", "aa").unwrap_err(),
+        "Look-around and backreferences are not supported"
+    );
+    assert_eq!(
+        apply_user_replacement(r"((a{100}){100}){100}", "", "a").unwrap_err(),
+        "Pattern is too complex"
+    );
+    assert!(apply_user_replacement("(unclosed", "", "a")
+        .unwrap_err()
+        .starts_with("Invalid pattern"));
+    assert!(apply_user_replacement("", "", "a").is_err());
+    assert!(apply_user_replacement("a", "", &"a".repeat(20_000)).is_err());
+}
+
+#[test]
+fn config_user_dictionary_runs_before_code_transforms() {
+    let config = PostProcessingConfig {
+        replacements: vec![UserReplacement {
+            pattern: r"\bmain dot rs\b".to_string(),
+            replacement: "the entry point".to_string(),
+        }],
+        ..PostProcessingConfig::default()
+    };
+    let pp = PostProcessor::from_config(&config).unwrap();
+
+    assert_eq!(pp.process("open main dot rs"), "Open the entry point");
+}
+
+#[test]
+fn config_with_invalid_dictionary_entry_is_rejected() {
+    let config = PostProcessingConfig {
+        replacements: vec![
+            UserReplacement {
+                pattern: "ok".to_string(),
+                replacement: "fine".to_string(),
+            },
+            UserReplacement {
+                pattern: "(".to_string(),
+                replacement: String::new(),
+            },
+        ],
+        ..PostProcessingConfig::default()
+    };
+
+    let error = PostProcessor::from_config(&config).err().unwrap();
+    assert!(error.starts_with("Dictionary entry 2: Invalid pattern"));
+}
+
+#[test]
+fn action_markers_are_emitted_by_default() {
+    let pp = PostProcessor::from_config(&PostProcessingConfig::default()).unwrap();
+
+    assert_eq!(pp.process("undo that"), "[[UNDO]]");
+    assert_eq!(pp.process("delete line"), "[[DELETE_LINE]]");
+}
+
+#[test]
+fn action_markers_off_strips_command_phrases() {
+    let config = PostProcessingConfig {
+        action_markers: false,
+        ..PostProcessingConfig::default()
+    };
+    let pp = PostProcessor::from_config(&config).unwrap();
+
+    assert_eq!(pp.process("undo that"), "");
+    assert_eq!(pp.process("delete line"), "");
+    assert_eq!(pp.process("hello world undo that"), "Hello world");
+}
@@ -1,4 +1,4 @@
-use vox_ai_lib::database::Database;
+use vox_ai_lib::database::{group_history_by_day, Database, TranscriptionHistory};
 
 fn test_database() -> (tempfile::TempDir, Database) {
     let dir = tempfile::tempdir().unwrap();
@@ -26,6 +26,68 @@ fn transcription_history_add_count_and_fetch() {
     assert!(history.iter().any(|item| item.text == "rust backend test"));
 }
 
+#[test]
+fn transcription_history_keeps_params_hash() {
+    let (_dir, db) = test_database();
+
+    db.add_transcription("legacy entry", "base", "en", 500)
+        .unwrap();
+    db.add_transcription_entry(
+        "hashed entry",
+        "base",
+        "en",
+        500,
+        Some("0a1b2c3d4e5f"),
+        None,
+    )
+    .unwrap();
+
+    let history = db.get_transcription_history(10, 0, None).unwrap();
+    let hash_of = |text: &str| {
+        history
+            .iter()
+            .find(|item| item.text == text)
+            .and_then(|item| item.params_hash.clone())
+    };
+    assert_eq!(hash_of("hashed entry").as_deref(), Some("0a1b2c3d4e5f"));
+    assert_eq!(hash_of("legacy entry"), None);
+}
+
+#[test]
+fn transcription_history_keeps_raw_text_for_reprocessing() {
+    let (_dir, db) = test_database();
+
+    let legacy_id = db
+        .add_transcription("legacy entry", "base", "en", 500)
+        .unwrap();
+    let id = db
+        .add_transcription_entry(
+            "Hello, world.",
+            "base",
+            "en",
+            500,
+            None,
+            Some("hello world"),
+        )
+        .unwrap();
+
+    let entry = db.get_transcription(id).unwrap().unwrap();
+    assert_eq!(entry.raw_text.as_deref(), Some("hello world"));
+    assert!(db
+        .get_transcription(legacy_id)
+        .unwrap()
+        .unwrap()
+        .raw_text
+        .is_none());
+    assert!(db.get_transcription(id + 100).unwrap().is_none());
+
+    assert!(db.update_transcription_text(id, "Hello world!").unwrap());
+    assert!(!db.update_transcription_text(id + 100, "missing").unwrap());
+    let entry = db.get_transcription(id).unwrap().unwrap();
+    assert_eq!(entry.text, "Hello world!");
+    assert_eq!(entry.raw_text.as_deref(), Some("hello world"));
+}
+
 #[test]
 fn transcription_history_search_escapes_like_wildcards() {
     let (_dir, db) = test_database();
@@ -88,3 +150,126 @@ fn transcription_history_paginates() {
     assert_eq!(second_page.len(), 2);
     assert_ne!(first_page[0].id, second_page[0].id);
 }
+
+#[test]
+fn concurrent_history_writes_and_reads_do_not_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().to_path_buf();
+    // Separate connections to the same file exercise WAL and the busy timeout
+    let databases: Vec<std::sync::Arc<Database>> = (0..4)
+        .map(|_| std::sync::Arc::new(Database::new(path.clone()).unwrap()))
+        .collect();
+
+    let handles: Vec<_> = databases
+        .iter()
+        .enumerate()
+        .map(|(worker, db)| {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                for i in 0..25 {
+                    db.add_transcription(
+                        &format!("worker {} entry {}", worker, i),
+                        "base",
+                        "en",
+                        100,
+                    )
+                    .unwrap();
+                    db.get_transcription_history(10, 0, None).unwrap();
+                    db.get_transcription_history_count(Some("entry")).unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(
+        databases[0].get_transcription_history_count(None).unwrap(),
+        100
+    );
+
+    let conn = rusqlite::Connection::open(path.join("Wavee.db")).unwrap();
+    let journal_mode: String = conn
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(journal_mode, "wal");
+}
+
+#[test]
+fn latest_transcription_is_most_recent_or_none() {
+    let (_dir, db) = test_database();
+
+    assert!(db.get_latest_transcription().unwrap().is_none());
+
+    db.add_transcription("first", "base", "en", 500).unwrap();
+    let last_id = db.add_transcription("second", "base", "en", 700).unwrap();
+
+    // Rows added within the same second fall back to insertion order
+    let latest = db.get_latest_transcription().unwrap().unwrap();
+    assert_eq!(latest.id, last_id);
+    assert_eq!(latest.text, "second");
+
+    db.clear_transcription_history().unwrap();
+    assert!(db.get_latest_transcription().unwrap().is_none());
+}
+
+fn history_entry(id: i64, created_at: &str) -> TranscriptionHistory {
+    TranscriptionHistory {
+        id,
+        text: format!("entry {}", id),
+        model_id: "base".to_string(),
+        language: "en".to_string(),
+        duration_ms: 1000,
+        params_hash: None,
+        raw_text: None,
+        created_at: created_at.to_string(),
+    }
+}
+
+#[test]
+fn history_is_grouped_by_local_day() {
+    let now = chrono::DateTime::parse_from_rfc3339("2024-03-10T12:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let entries = vec![
+        history_entry(4, "2024-03-10 09:00:00"),
+        history_entry(3, "2024-03-10 02:00:00"),
+        history_entry(2, "2024-03-09 20:00:00"),
+        history_entry(1, "2024-03-01 08:00:00"),
+    ];
+
+    let utc = group_history_by_day(
+        entries.clone(),
+        chrono::FixedOffset::east_opt(0).unwrap(),
+        now,
+    );
+    let dates: Vec<_> = utc
+        .iter()
+        .map(|day| (day.date.as_str(), day.days_ago))
+        .collect();
+    assert_eq!(
+        dates,
+        vec![
+            ("2024-03-10", Some(0)),
+            ("2024-03-09", Some(1)),
+            ("2024-03-01", Some(9)),
+        ]
+    );
+    assert_eq!(utc[0].entries.len(), 2);
+
+    // Five hours behind UTC, 02:00 UTC is still the previous evening
+    let new_york = group_history_by_day(
+        entries,
+        chrono::FixedOffset::west_opt(5 * 3600).unwrap(),
+        now,
+    );
+    assert_eq!(new_york[0].date, "2024-03-10");
+    assert_eq!(new_york[0].entries.len(), 1);
+    assert_eq!(new_york[1].date, "2024-03-09");
+    assert_eq!(
+        new_york[1].entries.iter().map(|e| e.id).collect::<Vec<_>>(),
+        vec![3, 2]
+    );
+}
@@ -0,0 +1,61 @@
+use vox_ai_lib::transcription::{clamp_segments, whole_clip_segment, Segment, TranscriptionEngine};
+
+/// Engine without timings of its own, so it falls back to the default
+struct UntimedEngine;
+
+impl TranscriptionEngine for UntimedEngine {
+    fn name(&self) -> &'static str {
+        "untimed"
+    }
+
+    fn transcribe(&mut self, _audio_samples: &[f32]) -> Result<String, String> {
+        Ok(" hello there ".to_string())
+    }
+
+    fn set_language(&mut self, _language: &str) {}
+}
+
+fn segment(text: &str, start_ms: u64, end_ms: u64) -> Segment {
+    Segment {
+        text: text.to_string(),
+        start_ms,
+        end_ms,
+    }
+}
+
+#[test]
+fn untimed_engines_report_the_whole_clip_as_one_segment() {
+    let segments = UntimedEngine
+        .transcribe_with_timestamps(&[0.0; 24_000])
+        .unwrap();
+    assert_eq!(segments, vec![segment("hello there", 0, 1_500)]);
+
+    assert!(whole_clip_segment("  ", 16_000).is_empty());
+}
+
+#[test]
+fn segments_in_silence_padding_are_trimmed() {
+    let segments = vec![
+        segment("turn left", 0, 800),
+        segment("at the light", 800, 1_400),
+        segment("thank you", 1_600, 2_000),
+    ];
+
+    assert_eq!(
+        clamp_segments(segments, 19_200),
+        vec![
+            segment("turn left", 0, 800),
+            segment("at the light", 800, 1_200),
+        ]
+    );
+}
+
+#[test]
+fn untimed_engines_do_not_claim_a_detected_language() {
+    assert_eq!(
+        UntimedEngine
+            .transcribe_detecting_language(&[0.0; 16_000])
+            .unwrap(),
+        (" hello there ".to_string(), "auto".to_string())
+    );
+}
@@ -2,6 +2,7 @@ use chrono::{Duration, Utc};
 use vox_ai_lib::database::LicenseData;
 use vox_ai_lib::{
     calculate_trial_integrity_hash, db_license_allows_usage_core, has_active_trial_core,
+    trial_time_remaining,
 };
 
 fn create_trial_license(started_at_offset_days: i64) -> LicenseData {
@@ -81,6 +82,37 @@ fn active_trial_expires_at_exact_day_7_boundary() {
     assert!(!has_active_trial_core(&license, now));
 }
 
+#[test]
+fn trial_remaining_reports_final_hour_as_one_day() {
+    let now = Utc::now();
+    let started_at = now - Duration::days(6) - Duration::hours(23);
+    let remaining = trial_time_remaining(&started_at.to_rfc3339(), now).unwrap();
+
+    assert_eq!(remaining.seconds_remaining, 60 * 60);
+    assert_eq!(remaining.days_remaining(), 1);
+    assert_eq!(remaining.expires_at, started_at + Duration::days(7));
+}
+
+#[test]
+fn trial_remaining_is_zero_once_expired() {
+    let now = Utc::now();
+    let started_at = now - Duration::days(7) - Duration::minutes(1);
+    let remaining = trial_time_remaining(&started_at.to_rfc3339(), now).unwrap();
+
+    assert_eq!(remaining.seconds_remaining, 0);
+    assert_eq!(remaining.days_remaining(), 0);
+}
+
+#[test]
+fn trial_remaining_rounds_partial_days_up() {
+    let now = Utc::now();
+    let started_at = now - Duration::hours(1);
+    let remaining = trial_time_remaining(&started_at.to_rfc3339(), now).unwrap();
+
+    assert_eq!(remaining.days_remaining(), 7);
+    assert!(trial_time_remaining("not-a-date", now).is_none());
+}
+
 #[test]
 fn trial_rejects_invalid_start_timestamp() {
     let license = LicenseData {
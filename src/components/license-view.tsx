@@ -16,13 +16,16 @@ import { getErrorMessage } from "@/lib/errors";
 import { reportError } from "@/lib/voice-api";
 import {
     activateLicense,
+    cancelLicenseActivation,
     deactivateLicense,
     formatExpirationDate,
     getLicense,
     getLicenseStatusMessage,
     isLicenseActive,
     maskLicenseKey,
+    onActivationStage,
     validateLicense,
+    type ActivationStage,
     type LicenseData,
 } from "@/lib/license-api";
 import { openUrl } from "@/lib/utils";
@@ -49,10 +52,17 @@ interface LicenseViewProps {
   onLicenseChange?: (isValid: boolean) => void;
 }
 
+const ACTIVATION_STAGE_LABELS: Record<ActivationStage, string> = {
+  connecting: "Connecting...",
+  waiting_on_server: "Waiting for license server...",
+};
+
 export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
   const [license, setLicense] = useState<LicenseData | null>(null);
   const [isLoading, setIsLoading] = useState(true);
   const [isActivating, setIsActivating] = useState(false);
+  const [activationStage, setActivationStage] =
+    useState<ActivationStage | null>(null);
   const [isValidating, setIsValidating] = useState(false);
   const [isDeactivating, setIsDeactivating] = useState(false);
   const [licenseKey, setLicenseKey] = useState("");
@@ -77,6 +87,13 @@ export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
     loadLicense();
   }, []);
 
+  useEffect(() => {
+    const unlisten = onActivationStage(setActivationStage);
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const loadLicense = async () => {
     setIsLoading(true);
     setError(null);
@@ -116,6 +133,9 @@ export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
       onLicenseChange?.(data.is_activated && data.status === "active");
     } catch (err) {
       const msg = extractErrorMessage(err) || "Failed to activate license";
+      if (msg.includes("activation cancelled")) {
+        return;
+      }
       toastError("Activation failed", msg);
       setError(msg);
       await reportError("license", msg, "error", {
@@ -123,6 +143,15 @@ export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
       }).catch(console.error);
     } finally {
       setIsActivating(false);
+      setActivationStage(null);
+    }
+  };
+
+  const handleCancelActivation = async () => {
+    try {
+      await cancelLicenseActivation();
+    } catch (err) {
+      console.error("Failed to cancel activation:", err);
     }
   };
 
@@ -438,7 +467,9 @@ export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
                     {isActivating ? (
                       <>
                         <Loader2 className="h-4 w-4 animate-spin" />
-                        Activating...
+                        {activationStage
+                          ? ACTIVATION_STAGE_LABELS[activationStage]
+                          : "Activating..."}
                       </>
                     ) : (
                       <>
@@ -447,6 +478,14 @@ export function LicenseView({ onClose, onLicenseChange }: LicenseViewProps) {
                       </>
                     )}
                   </button>
+                  {isActivating && (
+                    <button
+                      className="w-full py-2 rounded-xl text-sm font-medium text-foreground/60 hover:text-foreground transition-colors"
+                      onClick={handleCancelActivation}
+                    >
+                      Cancel
+                    </button>
+                  )}
                 </div>
               </div>
             )}
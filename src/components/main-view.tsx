@@ -7,18 +7,28 @@ import {
 } from "@/components/ui/dropdown-menu";
 import { useToast } from "@/hooks/use-toast";
 import { playFeedbackSound } from "@/lib/preferences-api";
+import {
+  getSafeModeStatus,
+  restartApp,
+  type SafeModeStatus,
+} from "@/lib/tauri-api";
 import { cn } from "@/lib/utils";
 import {
-  addTranscription,
+  cancelRecording,
+  confirmAction,
   setAudioCaptureConfig,
   hideRecordingOverlay,
+  isRecording,
   loadModel,
+  onConfirmAction,
   onHotkeyPressed,
   onHotkeyReleased,
+  onHotkeyTap,
+  onRecordingAutoStopped,
   onTrayNavigate,
   onTrayStartRecording,
   onTrayStopRecording,
-  registerHotkey,
+  registerAllHotkeys,
   showRecordingOverlay,
   startRecording,
   stopTranscribeAndInject,
@@ -92,13 +102,20 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
     setLastTranscription,
     settings,
     selectedModel,
+    setSelectedModel,
+    availableModels,
     errorMessage,
     setErrorMessage,
   } = useAppStore();
 
-  const { success: toastSuccess, error: toastError } = useToast();
+  const {
+    toast,
+    success: toastSuccess,
+    error: toastError,
+  } = useToast();
 
   const [isModelLoaded, setIsModelLoaded] = useState(false);
+  const [safeMode, setSafeMode] = useState<SafeModeStatus | null>(null);
   const [isLoadingModel, setIsLoadingModel] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [showModels, setShowModels] = useState(false);
@@ -112,6 +129,7 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
   const isModelLoadedRef = useRef(isModelLoaded);
   const hotkeyModeRef = useRef(settings.hotkeyMode);
   const settingsRef = useRef(settings);
+  const recordingStartRef = useRef<Promise<void> | null>(null);
 
   // Keep refs in sync with state
   useEffect(() => {
@@ -145,20 +163,74 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
     return "Something went wrong. Please try again.";
   };
 
-  // Load the model on mount
+  // Safe mode skips the model and hotkeys; after repeated crashes offer it.
+  // Checked once on mount so the toast is not repeated.
+  useEffect(() => {
+    getSafeModeStatus()
+      .then((status) => {
+        setSafeMode(status);
+        if (status.active) {
+          toast("Running in safe mode", {
+            description:
+              "Hotkeys and automatic model loading are off. Fix your settings, then restart.",
+            duration: Infinity,
+            action: {
+              label: "Restart",
+              onClick: () => {
+                restartApp(false).catch(console.error);
+              },
+            },
+          });
+        } else if (status.suggested) {
+          toast("Wavee keeps crashing", {
+            description:
+              "Restart in safe mode to fix the setting that causes it.",
+            duration: 15000,
+            action: {
+              label: "Safe mode",
+              onClick: () => {
+                restartApp(true).catch(console.error);
+              },
+            },
+          });
+        }
+      })
+      .catch((error) => {
+        console.error("Failed to get safe mode status:", error);
+        setSafeMode({ active: false, recent_crashes: 0, suggested: false });
+      });
+  }, []);
+
+  // Load the model on mount. A selected model whose files are gone is
+  // still requested so the backend can fall back to a downloaded one.
   useEffect(() => {
     if (
-      selectedModel?.downloaded &&
+      safeMode &&
+      !safeMode.active &&
+      selectedModel &&
       !isModelLoaded &&
       !isLoadingModel
     ) {
       setIsLoadingModel(true);
       setErrorMessage(null);
       loadModel(selectedModel.id, settings.language)
-        .then(() => {
+        .then((loaded) => {
           setIsModelLoaded(true);
           setIsLoadingModel(false);
-          console.log("Model loaded:", selectedModel.id);
+          console.log("Model loaded:", loaded.model_id);
+          if (loaded.model_id !== selectedModel.id) {
+            const fallback = availableModels.find(
+              (model) => model.id === loaded.model_id
+            );
+            if (fallback) {
+              setSelectedModel(fallback);
+            }
+            toastSuccess(
+              `${selectedModel.name} is not downloaded, using ${
+                fallback?.name ?? loaded.model_id
+              } instead`
+            );
+          }
         })
         .catch((error) => {
           console.error("Failed to load model:", error);
@@ -174,11 +246,15 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
         });
     }
   }, [
+    safeMode,
     selectedModel,
     isModelLoaded,
     isLoadingModel,
     settings.language,
     setErrorMessage,
+    availableModels,
+    setSelectedModel,
+    toastSuccess,
   ]);
 
   // Handle starting recording
@@ -224,6 +300,23 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
     }
   }, [setErrorMessage, setRecordingStatus]);
 
+  // Drop a push-to-talk take released before the minimum hold
+  const handleDiscardRecording = useCallback(async () => {
+    await recordingStartRef.current;
+    if (recordingStatusRef.current !== "recording") {
+      return;
+    }
+
+    hideRecordingOverlay().catch(console.error);
+    try {
+      await cancelRecording();
+    } catch (error) {
+      console.error("Failed to discard recording:", error);
+    }
+    recordingStatusRef.current = "idle";
+    setRecordingStatus("idle");
+  }, [setRecordingStatus]);
+
   // Handle stopping recording
   const handleStopRecording = useCallback(async () => {
     if (recordingStatusRef.current !== "recording") {
@@ -247,41 +340,49 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
     }
 
     try {
-      const startTime = Date.now();
-      const text = await stopTranscribeAndInject(
+      const result = await stopTranscribeAndInject(
         currentSettings.postProcessingEnabled,
         currentSettings.clipboardMode,
-        selectedModel?.id,
+        selectedModel?.id || "base",
         currentSettings.voiceCommandsEnabled,
+        currentSettings.language,
       );
-      const durationMs = Date.now() - startTime;
+
+      // A full transcription queue leaves the recording running
+      if (!result && (await isRecording())) {
+        recordingStatusRef.current = "recording";
+        setRecordingStatus("recording");
+        if (currentSettings.showRecordingOverlay !== false) {
+          showRecordingOverlay().catch(console.error);
+        }
+        toastError(
+          "Transcription queue is full",
+          "Still recording. Stop again in a moment."
+        );
+        return;
+      }
 
       // Update UI immediately
       recordingStatusRef.current = "idle";
       setRecordingStatus("idle");
 
-      if (text) {
-        setLastTranscription(text);
-        const actionText = currentSettings.clipboardMode
-          ? "Copied to clipboard"
-          : "Transcribed and injected";
-        toastSuccess(actionText);
-
-        // Save to history in background (non-blocking)
-        // Don't await - let it happen asynchronously so it doesn't slow down the response
-        addTranscription(
-          text,
-          selectedModel?.id || "base",
-          currentSettings.language,
-          durationMs,
-        )
-          .then((insertedId) => {
-            console.log("Saved transcription id:", insertedId);
-          })
-          .catch((historyError: any) => {
-            console.error("Failed to save to history:", historyError);
-            // Don't show error toast for background history saving failures
-          });
+      if (result) {
+        setLastTranscription(result.text);
+        if (currentSettings.clipboardMode) {
+          toastSuccess("Copied to clipboard");
+        } else if (result.injected) {
+          toastSuccess("Transcribed and injected");
+        } else {
+          // The text is already in history, so nothing is lost
+          console.error("Injection failed:", result.injection_error);
+          toastError(
+            "Couldn't type the text. Copy it from the last transcription or history.",
+            result.injection_error ?? undefined
+          );
+        }
+        if (result.history_id !== null) {
+          console.log("Saved transcription id:", result.history_id);
+        }
       }
     } catch (error) {
       console.error("Transcription failed:", error);
@@ -302,8 +403,44 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
     toastError,
   ]);
 
+  // Dictations with destructive voice commands wait for the user to confirm
+  useEffect(() => {
+    const unlisten = onConfirmAction((request) => {
+      toast("Run voice command?", {
+        description: request.markers
+          .map((marker) => marker.replace(/\[|\]/g, "").replace(/_/g, " "))
+          .join(", "),
+        duration: 10000,
+        action: {
+          label: "Run",
+          onClick: () => {
+            confirmAction(request.id).catch((error) =>
+              toastError("Command not run", getErrorMessage(error))
+            );
+          },
+        },
+      });
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [toast, toastError]);
+
+  // A recording that stopped itself after silence still needs transcribing
+  useEffect(() => {
+    const unlisten = onRecordingAutoStopped(() => {
+      handleStopRecording();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [handleStopRecording]);
+
   // Register hotkey on mount and when hotkey settings change
   useEffect(() => {
+    if (!safeMode || safeMode.active) {
+      return;
+    }
     let isMounted = true;
 
     const setupHotkey = async () => {
@@ -312,7 +449,12 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
         await unregisterHotkeys();
 
         if (isMounted) {
-          await registerHotkey(currentHotkey);
+          await registerAllHotkeys({
+            pushToTalkKey: settings.pushToTalkKey,
+            toggleKey: settings.toggleKey,
+            cancelKey: settings.cancelKey,
+            hotkeyMode: settings.hotkeyMode,
+          });
           console.log("Hotkey registered:", currentHotkey);
         }
       } catch (error) {
@@ -327,7 +469,14 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
       // Cleanup: unregister hotkeys when component unmounts
       unregisterHotkeys().catch(console.error);
     };
-  }, [currentHotkey]);
+  }, [
+    currentHotkey,
+    settings.pushToTalkKey,
+    settings.toggleKey,
+    settings.cancelKey,
+    settings.hotkeyMode,
+    safeMode,
+  ]);
 
   // Handle hotkey events
   useEffect(() => {
@@ -339,6 +488,7 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
 
     let unlistenPressed: (() => void) | null = null;
     let unlistenReleased: (() => void) | null = null;
+    let unlistenTap: (() => void) | null = null;
 
     const setupListeners = async () => {
       hotkeyListenersSetup = true;
@@ -357,7 +507,7 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
         console.log("Hotkey pressed, mode:", currentMode);
         if (currentMode === "push-to-talk") {
           // Start recording on key press
-          handleStartRecording();
+          recordingStartRef.current = handleStartRecording();
         } else {
           // Toggle mode - toggle recording
           if (recordingStatusRef.current === "idle") {
@@ -378,6 +528,13 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
         }
         // In toggle mode, release does nothing
       });
+
+      // A push-to-talk press shorter than the minimum hold
+      unlistenTap = await onHotkeyTap(() => {
+        if (hotkeyModeRef.current === "push-to-talk") {
+          handleDiscardRecording();
+        }
+      });
     };
 
     setupListeners();
@@ -386,8 +543,9 @@ export function MainView({ trialDaysRemaining }: MainViewProps) {
       hotkeyListenersSetup = false;
       unlistenPressed?.();
       unlistenReleased?.();
+      unlistenTap?.();
     };
-  }, [handleStartRecording, handleStopRecording]);
+  }, [handleStartRecording, handleStopRecording, handleDiscardRecording]);
 
   // Handle tray events
   useEffect(() => {
@@ -23,8 +23,10 @@ import {
   cancelModelDownload,
   deleteModel,
   downloadModel,
+  isDownloadCancelledError,
   onDownloadProgress,
   reportError,
+  testDownloadSpeed,
 } from "@/lib/voice-api";
 import { useAppStore, useAvailableModels, useIsInitialized } from "@/store";
 import {
@@ -57,6 +59,15 @@ interface ModelsViewProps {
   onClose: () => void;
 }
 
+function formatDownloadEstimate(seconds: number): string {
+  if (seconds < 60) return "under a minute";
+  const minutes = Math.round(seconds / 60);
+  if (minutes < 60) return `about ${minutes} min`;
+  const hours = Math.floor(minutes / 60);
+  const rest = minutes % 60;
+  return rest > 0 ? `about ${hours} h ${rest} min` : `about ${hours} h`;
+}
+
 export function ModelsView({ onClose }: ModelsViewProps) {
   const {
     initializeFromDb,
@@ -77,6 +88,9 @@ export function ModelsView({ onClose }: ModelsViewProps) {
   const [downloadProgress, setDownloadProgress] = useState(0);
   const [deletingModelId, setDeletingModelId] = useState<string | null>(null);
   const [cancelingModelId, setCancelingModelId] = useState<string | null>(null);
+  const [speedTestModelId, setSpeedTestModelId] = useState<string | null>(
+    null
+  );
   const [rowErrors, setRowErrors] = useState<Record<string, string>>({});
   const [pageError, setPageError] = useState<string | null>(null);
   const [isRetrying, setIsRetrying] = useState(false);
@@ -164,12 +178,12 @@ export function ModelsView({ onClose }: ModelsViewProps) {
       }
       toastSuccess("Model downloaded", `${model.name} is ready to use`);
     } catch (err) {
-      const message = getErrorMessage(err);
-      console.error("Download failed:", err);
-      if (message.toLowerCase().includes("cancelled")) {
+      if (isDownloadCancelledError(err)) {
         toastSuccess("Download canceled", `${model.name} was not installed`);
         return;
       }
+      const message = getErrorMessage(err);
+      console.error("Download failed:", err);
       setRowError(model.id, message);
       toastError("Download failed", `Failed to download ${model.name} model`);
       await reportError("model", message, "error", {
@@ -182,6 +196,29 @@ export function ModelsView({ onClose }: ModelsViewProps) {
     }
   };
 
+  const handleTestSpeed = async (model: WhisperModel) => {
+    if (speedTestModelId || downloadingModelId) return;
+
+    try {
+      setRowError(model.id, null);
+      setSpeedTestModelId(model.id);
+      const result = await testDownloadSpeed(model.id);
+      const speed = `${(result.bytes_per_second / (1024 * 1024)).toFixed(1)} MB/s`;
+      toastSuccess(
+        result.estimated_seconds !== null
+          ? `${model.name} would take ${formatDownloadEstimate(
+              result.estimated_seconds
+            )}`
+          : `${model.name} download speed`,
+        `Measured ${speed} from ${result.source}`
+      );
+    } catch (err) {
+      setRowError(model.id, getErrorMessage(err));
+    } finally {
+      setSpeedTestModelId(null);
+    }
+  };
+
   const handleCancelDownload = async (model: WhisperModel) => {
     if (downloadingModelId !== model.id || cancelingModelId) return;
 
@@ -543,6 +580,26 @@ export function ModelsView({ onClose }: ModelsViewProps) {
                               </>
                             )}
                           </button>
+                          {!isDownloading && (
+                            <button
+                              className="glass-icon-button p-1.5 rounded-lg"
+                              onClick={(event) => {
+                                event.stopPropagation();
+                                handleTestSpeed(model);
+                              }}
+                              disabled={
+                                speedTestModelId !== null ||
+                                downloadingModelId !== null
+                              }
+                              title="Estimate download time"
+                            >
+                              {speedTestModelId === model.id ? (
+                                <Loader2 className="h-3.5 w-3.5 animate-spin" />
+                              ) : (
+                                <Gauge className="h-3.5 w-3.5" />
+                              )}
+                            </button>
+                          )}
                           {isDownloading && (
                             <button
                               className="glass-icon-button p-1.5 rounded-lg text-red-500 hover:text-red-600 hover:bg-red-500/10"
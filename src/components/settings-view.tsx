@@ -406,8 +406,8 @@ export function SettingsView({ onClose }: SettingsViewProps) {
                 onCheckedChange={async (checked) => {
                   try {
                     setSettingsError(null);
-                    await setAutoStart(checked);
-                    updateSettings({ autoStartOnBoot: checked });
+                    const enabled = await setAutoStart(checked);
+                    updateSettings({ autoStartOnBoot: enabled });
                   } catch (err) {
                     const message = getErrorMessage(err);
                     console.error("Failed to set autostart:", err);
@@ -1,6 +1,11 @@
 import { Textarea } from "@/components/ui/textarea";
 import { cn } from "@/lib/utils";
-import { addTranscription, reportError, transcribeFile } from "@/lib/voice-api";
+import {
+  addTranscription,
+  reportError,
+  supportedAudioFormats,
+  transcribeFileWithDetails,
+} from "@/lib/voice-api";
 import { useAppStore } from "@/store";
 import { open } from "@tauri-apps/plugin-dialog";
 import {
@@ -41,21 +46,13 @@ export function TranscribeView({ onClose }: TranscribeViewProps) {
       setIsSelectingFile(true);
       setError(null);
       setWarning(null);
+      const extensions = await supportedAudioFormats();
       const selected = await open({
         multiple: false,
         filters: [
           {
             name: "Audio",
-            extensions: [
-              "wav",
-              "mp3",
-              "m4a",
-              "flac",
-              "ogg",
-              "webm",
-              "aac",
-              "mkv",
-            ],
+            extensions,
           },
         ],
       });
@@ -91,23 +88,22 @@ export function TranscribeView({ onClose }: TranscribeViewProps) {
     setWarning(null);
     setTranscription("");
 
-    const startTime = Date.now();
     try {
-      const text = await transcribeFile(
-        selectedFile,
-        settings.postProcessingEnabled
-      );
+      const { text, duration_ms, detected_language } =
+        await transcribeFileWithDetails(
+          selectedFile,
+          settings.postProcessingEnabled
+        );
       setTranscription(text);
 
       // Save to history
       if (text) {
-        const durationMs = Date.now() - startTime;
         try {
           await addTranscription(
             text,
             settings.selectedModelId || "base",
-            settings.language,
-            durationMs
+            detected_language ?? settings.language,
+            duration_ms
           );
         } catch (historyErr) {
           const message = getErrorMessage(historyErr);
@@ -250,7 +246,7 @@ export function TranscribeView({ onClose }: TranscribeViewProps) {
                   {isSelectingFile ? "Opening file picker..." : "Click to select an audio file"}
                 </p>
                 <p className="text-xs text-foreground/60">
-                  Supports WAV, MP3, M4A, FLAC, OGG, WebM
+                  Supports WAV, MP3, M4A, FLAC, OGG, AAC
                 </p>
               </div>
             )}
@@ -23,6 +23,31 @@ export interface DbAppSettings {
   post_processing_enabled: boolean;
   voice_commands_enabled: boolean;
   clipboard_mode: boolean;
+  long_form_recording?: boolean;
+  empty_transcription_behavior?: string;
+  min_recording_ms?: number;
+  cancel_key?: string;
+  overlay_position?: string;
+  injection_blocklist?: string;
+  warm_model_cache_size?: number;
+  tray_menu_items?: string;
+  silence_pad_ms?: number;
+  injection_trailing?: "none" | "space" | "newline" | "smart";
+  models_dir?: string | null;
+  min_hold_ms?: number;
+  vad_threshold_dbfs?: number;
+  preallocate_recording_seconds?: number;
+  download_mirrors?: string;
+  confirm_destructive_commands?: boolean;
+  release_notes_url?: string;
+  input_device_name?: string | null;
+  silence_timeout_ms?: number;
+  preroll_ms?: number;
+  decoding_strategy?: "auto" | "greedy" | "beam-search";
+  cpu_threads?: number;
+  injection_method?: "paste" | "type";
+  restore_clipboard?: boolean;
+  typing_delay_ms?: number;
 }
 
 export interface DbAppState {
@@ -103,6 +128,25 @@ export async function dbGetModel(id: string): Promise<DbWhisperModel | null> {
   return await invoke<DbWhisperModel | null>("get_model", { id });
 }
 
+/** Register a custom ggml model; the id must start with "custom-" */
+export async function dbRegisterCustomModel(
+  id: string,
+  name: string,
+  url: string,
+  sizeBytes?: number
+): Promise<DbWhisperModel> {
+  return await invoke<DbWhisperModel>("register_custom_model", {
+    id,
+    name,
+    url,
+    sizeBytes: sizeBytes ?? null,
+  });
+}
+
+export async function dbRemoveCustomModel(id: string): Promise<void> {
+  await invoke("remove_custom_model", { id });
+}
+
 export async function dbSetModelDownloaded(
   id: string,
   downloaded: boolean,
@@ -169,7 +213,7 @@ export async function dbGetAppDataDir(): Promise<string> {
 // Conversion Helpers (DB <-> Frontend types)
 // ============================================
 
-import type { AppSettings, WhisperModel } from "@/types";
+import type { AppSettings, OverlayPosition, WhisperModel } from "@/types";
 
 export function dbSettingsToFrontend(db: DbAppSettings): AppSettings {
   return {
@@ -186,6 +230,33 @@ export function dbSettingsToFrontend(db: DbAppSettings): AppSettings {
     clipboardMode: db.clipboard_mode,
     autoStartOnBoot: db.auto_start_on_boot,
     minimizeToTray: db.minimize_to_tray,
+    longFormRecording: db.long_form_recording ?? false,
+    emptyTranscriptionBehavior: db.empty_transcription_behavior ?? "empty",
+    minRecordingMs: db.min_recording_ms ?? 250,
+    cancelKey: db.cancel_key ?? "Escape",
+    overlayPosition: (db.overlay_position ?? "fullscreen") as OverlayPosition,
+    injectionBlocklist: db.injection_blocklist ?? "",
+    warmModelCacheSize: db.warm_model_cache_size ?? 1,
+    trayMenuItems:
+      db.tray_menu_items ??
+      "transcribe,start_recording,stop_recording,copy_last,history,models,settings,help",
+    silencePadMs: db.silence_pad_ms ?? 1000,
+    injectionTrailing: db.injection_trailing ?? "none",
+    modelsDir: db.models_dir ?? null,
+    minHoldMs: db.min_hold_ms ?? 120,
+    vadThresholdDbfs: db.vad_threshold_dbfs ?? -45,
+    preallocateRecordingSeconds: db.preallocate_recording_seconds ?? 60,
+    downloadMirrors: db.download_mirrors ?? "",
+    confirmDestructiveCommands: db.confirm_destructive_commands ?? false,
+    releaseNotesUrl: db.release_notes_url ?? "https://raw.githubusercontent.com/johuniq/wavee/main/CHANGELOG.md",
+    inputDeviceName: db.input_device_name ?? null,
+    silenceTimeoutMs: db.silence_timeout_ms ?? 0,
+    prerollMs: db.preroll_ms ?? 0,
+    decodingStrategy: db.decoding_strategy ?? "auto",
+    cpuThreads: db.cpu_threads ?? 0,
+    injectionMethod: db.injection_method ?? "paste",
+    restoreClipboard: db.restore_clipboard ?? false,
+    typingDelayMs: db.typing_delay_ms ?? 0,
   };
 }
 
@@ -204,6 +275,31 @@ export function frontendSettingsToDb(settings: AppSettings): DbAppSettings {
     clipboard_mode: settings.clipboardMode,
     auto_start_on_boot: settings.autoStartOnBoot,
     minimize_to_tray: settings.minimizeToTray,
+    long_form_recording: settings.longFormRecording,
+    empty_transcription_behavior: settings.emptyTranscriptionBehavior,
+    min_recording_ms: settings.minRecordingMs,
+    cancel_key: settings.cancelKey,
+    overlay_position: settings.overlayPosition,
+    injection_blocklist: settings.injectionBlocklist,
+    warm_model_cache_size: settings.warmModelCacheSize,
+    tray_menu_items: settings.trayMenuItems,
+    silence_pad_ms: settings.silencePadMs,
+    injection_trailing: settings.injectionTrailing,
+    models_dir: settings.modelsDir,
+    min_hold_ms: settings.minHoldMs,
+    vad_threshold_dbfs: settings.vadThresholdDbfs,
+    preallocate_recording_seconds: settings.preallocateRecordingSeconds,
+    download_mirrors: settings.downloadMirrors,
+    confirm_destructive_commands: settings.confirmDestructiveCommands,
+    release_notes_url: settings.releaseNotesUrl,
+    input_device_name: settings.inputDeviceName,
+    silence_timeout_ms: settings.silenceTimeoutMs,
+    preroll_ms: settings.prerollMs,
+    decoding_strategy: settings.decodingStrategy,
+    cpu_threads: settings.cpuThreads,
+    injection_method: settings.injectionMethod,
+    restore_clipboard: settings.restoreClipboard,
+    typing_delay_ms: settings.typingDelayMs,
   };
 }
 
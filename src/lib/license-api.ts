@@ -3,6 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 // ============================================
 // Types
@@ -21,6 +22,8 @@ export interface LicenseData {
   last_validated_at: string | null;
   trial_started_at: string | null;
   trial_days_remaining: number | null;
+  trial_seconds_remaining: number | null;
+  trial_expires_at: string | null;
   device_id: string;
   device_label: string;
   limit_activations: number | null;
@@ -60,6 +63,26 @@ export async function activateLicense(
   return await invoke<LicenseData>("activate_license", { licenseKey });
 }
 
+/**
+ * Abort an in-flight activation. Resolves to false if none was running.
+ */
+export async function cancelLicenseActivation(): Promise<boolean> {
+  return await invoke<boolean>("cancel_license_activation");
+}
+
+export type ActivationStage = "connecting" | "waiting_on_server";
+
+/**
+ * Listen for the network stage of an in-flight activation
+ */
+export async function onActivationStage(
+  callback: (stage: ActivationStage) => void
+): Promise<UnlistenFn> {
+  return await listen<ActivationStage>("license-activation-stage", (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Validate the current license with Polar API
  */
@@ -101,6 +124,8 @@ export async function startTrial(): Promise<LicenseData> {
 export async function getTrialStatus(): Promise<{
   isInTrial: boolean;
   daysRemaining: number;
+  secondsRemaining?: number;
+  expiresAt?: string;
   trialExpired: boolean;
 }> {
   return await invoke("get_trial_status");
@@ -113,6 +138,7 @@ export async function canUseApp(): Promise<{
   canUse: boolean;
   reason: "licensed" | "trial" | "trial_expired" | "no_license";
   daysRemaining?: number;
+  secondsRemaining?: number;
 }> {
   return await invoke("can_use_app");
 }
@@ -131,6 +157,26 @@ export async function getDeviceInfo(): Promise<DeviceInfo> {
   return await invoke<DeviceInfo>("get_device_info");
 }
 
+/**
+ * Inputs behind the device id, with raw values reduced to short digests.
+ * Only available in debug builds or with WAVEE_DEVICE_DEBUG=1.
+ */
+export interface DeviceFingerprintDebug {
+  device_id: string;
+  os: string;
+  arch: string;
+  hostname_present: boolean;
+  hostname_digest: string | null;
+  username_present: boolean;
+  username_digest: string | null;
+  hardware_id_source: string | null;
+  hardware_id_digest: string | null;
+}
+
+export async function getDeviceFingerprintDebug(): Promise<DeviceFingerprintDebug> {
+  return await invoke<DeviceFingerprintDebug>("get_device_fingerprint_debug");
+}
+
 // ============================================
 // Helper Functions
 // ============================================
@@ -2,29 +2,24 @@
  * Preferences API - Handles system-level preferences like autostart and minimize to tray
  */
 
+import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
-import { disable, enable, isEnabled } from "@tauri-apps/plugin-autostart";
 
 /**
- * Enable or disable autostart on boot
+ * Enable or disable autostart on boot. The backend keeps the
+ * auto_start_on_boot setting in sync and returns the resulting OS state.
  * Note: Launch args (--minimized) are configured in the Rust backend
  * during plugin initialization (see src-tauri/src/lib.rs)
  */
-export async function setAutoStart(enabled: boolean): Promise<void> {
+export async function setAutoStart(enabled: boolean): Promise<boolean> {
   try {
-    if (enabled) {
-      await enable();
-    } else {
-      await disable();
-    }
-
-    // Verify the change took effect
-    const now = await isEnabled();
+    const now = await invoke<boolean>("set_autostart", { enabled });
     if (now !== enabled) {
       console.warn(
         `Autostart toggle did not match expected state (expected=${enabled}, got=${now})`
       );
     }
+    return now;
   } catch (error) {
     console.error("Failed to set autostart:", error);
     throw error;
@@ -32,13 +27,12 @@ export async function setAutoStart(enabled: boolean): Promise<void> {
 }
 
 /**
- * Check if autostart is enabled
- * Note: Launch args (--minimized) are configured in the Rust backend
- * during plugin initialization (see src-tauri/src/lib.rs)
+ * Check if autostart is enabled in the OS, updating the stored setting if
+ * the login item was changed outside the app
  */
 export async function getAutoStartEnabled(): Promise<boolean> {
   try {
-    return await isEnabled();
+    return await invoke<boolean>("get_autostart_status");
   } catch (error) {
     console.error("Failed to check autostart status:", error);
     return false;
@@ -217,8 +217,7 @@ export async function getModelStatus(modelId: string): Promise<string> {
 
 export async function injectText(text: string): Promise<boolean> {
   try {
-    await invoke("inject_text", { text });
-    return true;
+    return await invoke<boolean>("inject_text", { text });
   } catch (error) {
     console.error("Failed to inject text:", error);
     return false;
@@ -238,6 +237,40 @@ export async function getAppVersion(): Promise<string> {
   }
 }
 
+export interface BuildInfo {
+  version: string;
+  git_hash: string;
+  build_time: string;
+  target: string;
+  debug: boolean;
+}
+
+/**
+ * Exact build details (commit, build time, target) for bug reports
+ */
+export async function getBuildInfo(): Promise<BuildInfo> {
+  return await invoke<BuildInfo>("get_build_info");
+}
+
+export interface SafeModeStatus {
+  /** Started with --safe-mode: no hotkeys, no model auto-load */
+  active: boolean;
+  recent_crashes: number;
+  /** The app keeps crashing; offer a restart in safe mode */
+  suggested: boolean;
+}
+
+export async function getSafeModeStatus(): Promise<SafeModeStatus> {
+  return await invoke<SafeModeStatus>("get_safe_mode_status");
+}
+
+/**
+ * Relaunch the app, in safe mode or normally
+ */
+export async function restartApp(safeMode: boolean): Promise<void> {
+  await invoke("restart_app", { safeMode });
+}
+
 export async function getModelsDirectory(): Promise<string> {
   try {
     return await invoke<string>("get_models_directory");
@@ -98,7 +98,9 @@ export async function checkForUpdates(): Promise<UpdateStatus> {
       info: {
         version: update.version,
         date: update.date,
-        body: update.body,
+        body:
+          update.body ||
+          (await releaseNotesBody(currentVersion, update.version)),
         currentVersion,
       },
     };
@@ -208,6 +210,65 @@ export async function relaunchApp(): Promise<void> {
   await relaunch();
 }
 
+export interface ReleaseNotes {
+  version: string;
+  notes: string[];
+}
+
+export interface ReleaseNotesResult {
+  releases: ReleaseNotes[];
+  /** The changelog bundled with this build was used; newer releases may be missing */
+  from_bundle: boolean;
+}
+
+/**
+ * Changelog entries for releases after `fromVersion` (the running version by
+ * default) up to `toVersion`, newest first
+ */
+export async function getReleaseNotes(
+  toVersion: string,
+  fromVersion?: string
+): Promise<ReleaseNotesResult> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<ReleaseNotesResult>("get_release_notes", {
+    fromVersion,
+    toVersion,
+  });
+}
+
+async function releaseNotesBody(
+  fromVersion: string,
+  toVersion: string
+): Promise<string | undefined> {
+  try {
+    const { releases } = await getReleaseNotes(toVersion, fromVersion);
+    const body = releases
+      .map(
+        (release) =>
+          `${release.version}\n${release.notes.map((note) => `- ${note}`).join("\n")}`
+      )
+      .join("\n\n");
+    return body || undefined;
+  } catch (error) {
+    logger.warn("Failed to load release notes", { error: String(error) });
+    return undefined;
+  }
+}
+
+export interface VersionInfo {
+  version: string;
+  build_time: string;
+  git_hash: string;
+}
+
+/**
+ * Get the running version with its build time and git commit
+ */
+export async function getCurrentVersionInfo(): Promise<VersionInfo> {
+  const { invoke } = await import("@tauri-apps/api/core");
+  return await invoke<VersionInfo>("get_current_version_info");
+}
+
 /**
  * Get the current app version
  */
@@ -5,6 +5,8 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { writeText } from "@tauri-apps/plugin-clipboard-manager";
+import { useAppStore } from "@/store";
+import type { LanguageOption } from "@/types";
 
 // ============================================
 // Types
@@ -15,9 +17,17 @@ export interface DownloadProgress {
   bytes_downloaded: number;
   total_bytes: number;
   percentage: number;
+  /** Host serving the download; changes when a mirror takes over */
+  source: string;
+  /** Speed over roughly the last second */
+  bytes_per_second: number;
+  /** Seconds left at the current speed; null while unknown */
+  eta_seconds: number | null;
 }
 
 export interface AudioInputDevice {
+  /** Pass to `setAudioInputDevice`; `name#N` when several devices share a name */
+  id: string;
   name: string;
   is_default: boolean;
 }
@@ -41,10 +51,15 @@ export async function getAudioOutputDevices(): Promise<AudioOutputDevice[]> {
   return await invoke<AudioOutputDevice[]>("get_audio_output_devices");
 }
 
+/**
+ * Switch the microphone and save it as the preferred input. The settings
+ * store is reloaded so its next save keeps the new device.
+ */
 export async function setAudioInputDevice(
   deviceName: string | null
 ): Promise<void> {
   await invoke("set_audio_input_device", { deviceName });
+  await useAppStore.getState().reloadSettings();
 }
 
 export async function setAudioCaptureConfig(
@@ -59,26 +74,117 @@ export async function setAudioCaptureConfig(
   });
 }
 
-export async function startRecording(): Promise<void> {
-  await invoke("start_recording");
+/**
+ * Start recording. With `append`, the new take is added to the takes
+ * recorded since the last fresh start instead of replacing them.
+ */
+export async function startRecording(append: boolean = false): Promise<void> {
+  await invoke("start_recording", { append });
+}
+
+/**
+ * Text of a dictation, with the language it was detected in when the model
+ * runs with "auto". Pass `detected_language` on when saving it to history.
+ */
+export interface Transcript {
+  text: string;
+  detected_language: string | null;
+}
+
+/**
+ * Stop the current take, if any, and transcribe all accumulated takes
+ */
+export async function finalizeRecording(): Promise<Transcript> {
+  return await invoke<Transcript>("finalize_recording");
 }
 
 export async function stopRecording(): Promise<number[]> {
   return await invoke<number[]>("stop_recording");
 }
 
+/**
+ * RMS waveform of the last recording, one value between 0 and 1 per bucket
+ */
+export async function getWaveform(bucketCount: number): Promise<number[]> {
+  return await invoke<number[]>("get_waveform", { bucketCount });
+}
+
 export async function cancelRecording(): Promise<void> {
   await invoke("cancel_recording");
 }
 
+export interface CalibrationResult {
+  peak_dbfs: number;
+  rms_dbfs: number;
+  noise_floor_dbfs: number;
+  speech_dbfs: number;
+  snr_db: number;
+  clipped_ratio: number;
+  recommend_normalization: boolean;
+  verdict: "pass" | "warn" | "fail";
+  recommendations: string[];
+}
+
+export async function calibrateMicrophone(): Promise<CalibrationResult> {
+  return await invoke<CalibrationResult>("calibrate_microphone");
+}
+
+export interface NoiseFloor {
+  rms_dbfs: number;
+  peak_dbfs: number;
+  noise_floor_dbfs: number;
+  recommended_vad_threshold_dbfs: number;
+}
+
+/**
+ * Record the room with nobody speaking and save a matching voice activity
+ * threshold. Run again after changing locations. The settings store is
+ * reloaded so its next save keeps the new threshold.
+ */
+export async function measureNoiseFloor(
+  durationMs?: number
+): Promise<NoiseFloor> {
+  const floor = await invoke<NoiseFloor>("measure_noise_floor", {
+    durationMs,
+  });
+  await useAppStore.getState().reloadSettings();
+  return floor;
+}
+
 export async function saveTempAudio(audioSamples: number[]): Promise<string> {
   return await invoke<string>("save_temp_audio", { samples: audioSamples });
 }
 
+export type WavFormat = "int16" | "float32";
+
+/**
+ * Write the last recording to a .wav file. Defaults to 16-bit PCM.
+ */
+export async function saveLastRecording(
+  path: string,
+  format?: WavFormat
+): Promise<string> {
+  return await invoke<string>("save_last_recording", { path, format });
+}
+
 export async function isRecording(): Promise<boolean> {
   return await invoke<boolean>("is_recording");
 }
 
+/** Current input level from 0 (silence) to 1 (full scale) */
+export async function getInputLevel(): Promise<number> {
+  return await invoke<number>("get_input_level");
+}
+
+/** Input level emitted about every 50ms while recording */
+export async function onAudioLevel(
+  callback: (level: number) => void
+): Promise<UnlistenFn> {
+  return await listen<number>("audio-level", (event) => {
+    callback(event.payload);
+  });
+}
+
 // ============================================
 // Recording Overlay API
 // ============================================
@@ -95,31 +201,379 @@ export async function hideRecordingOverlay(): Promise<void> {
 // Transcription API
 // ============================================
 
+export interface LoadedModelInfo {
+  model_id: string;
+  engine: string;
+  language: string;
+  loaded_at: string;
+}
+
+/**
+ * Load a model. When its files are missing the smallest downloaded model
+ * that supports the language is loaded and selected instead, unless
+ * `strict` is set; check `model_id` on the result.
+ */
 export async function loadModel(
   modelId: string,
-  language: string = "en"
-): Promise<void> {
-  await invoke("load_model", { modelId, language });
+  language: string = "en",
+  strict: boolean = false
+): Promise<LoadedModelInfo> {
+  return await invoke<LoadedModelInfo>("load_model", {
+    modelId,
+    language,
+    strict,
+  });
 }
 
-export async function transcribeAudio(audioSamples: number[]): Promise<string> {
-  return await invoke<string>("transcribe_audio", { audioSamples });
+export interface ModelFallback {
+  requested_model_id: string;
+  fallback_model_id: string;
 }
 
-export async function recordAndTranscribe(): Promise<string> {
-  return await invoke<string>("record_and_transcribe");
+export async function onModelFallback(
+  callback: (fallback: ModelFallback) => void
+): Promise<UnlistenFn> {
+  return await listen<ModelFallback>("model-fallback", (event) => {
+    callback(event.payload);
+  });
 }
 
-export async function transcribeFile(
+/**
+ * Change the loaded model's language without reloading it from disk
+ */
+export async function setTranscriptionLanguage(
+  language: string
+): Promise<LoadedModelInfo> {
+  return await invoke<LoadedModelInfo>("set_transcription_language", {
+    language,
+  });
+}
+
+export interface WhisperTuning {
+  beam_size: number;
+  entropy_thold: number;
+  temperature_inc: number;
+  max_tokens: number;
+}
+
+export type WhisperTuningOverrides = Partial<{
+  [K in keyof WhisperTuning]: WhisperTuning[K] | null;
+}>;
+
+export interface ModelTuning {
+  model_id: string;
+  defaults: WhisperTuning;
+  overrides: WhisperTuningOverrides;
+  effective: WhisperTuning;
+}
+
+/**
+ * A model's default decoding settings and the user's overrides
+ */
+export async function getModelTuning(modelId: string): Promise<ModelTuning> {
+  return await invoke<ModelTuning>("get_model_tuning", { modelId });
+}
+
+/**
+ * Override a model's decoding settings; pass `{}` to restore the defaults
+ */
+export async function setModelTuning(
+  modelId: string,
+  overrides: WhisperTuningOverrides
+): Promise<ModelTuning> {
+  return await invoke<ModelTuning>("set_model_tuning", { modelId, overrides });
+}
+
+export interface WarmModelInfo {
+  model_id: string;
+  size_bytes: number;
+  cached_at: string;
+}
+
+/**
+ * Models kept loaded in memory for instant switching, least recently used first
+ */
+export async function getWarmModels(): Promise<WarmModelInfo[]> {
+  return await invoke<WarmModelInfo[]>("get_warm_models");
+}
+
+/**
+ * Release every warm model, returning how many were freed
+ */
+export async function clearWarmModels(): Promise<number> {
+  return await invoke<number>("clear_warm_models");
+}
+
+export interface ModelMemoryEstimate {
+  model_id: string;
+  model_size_bytes: number;
+  estimated_bytes: number;
+  available_bytes: number | null;
+  total_bytes: number | null;
+  fits: boolean | null;
+}
+
+export interface MemoryUsage {
+  process_bytes: number | null;
+  available_bytes: number | null;
+  total_bytes: number | null;
+}
+
+/**
+ * Approximate memory needed to load a model; use it to warn, not to block
+ */
+export async function getModelMemoryEstimate(
+  modelId: string
+): Promise<ModelMemoryEstimate> {
+  return await invoke<ModelMemoryEstimate>("get_model_memory_estimate", {
+    modelId,
+  });
+}
+
+/**
+ * Memory currently used by the app process and the system
+ */
+export async function getCurrentMemoryUsage(): Promise<MemoryUsage> {
+  return await invoke<MemoryUsage>("get_current_memory_usage");
+}
+
+export async function transcribeAudio(
+  audioSamples: number[]
+): Promise<Transcript> {
+  return await invoke<Transcript>("transcribe_audio", { audioSamples });
+}
+
+export interface TranscriptSegment {
+  text: string;
+  start_ms: number;
+  end_ms: number;
+}
+
+/**
+ * Transcribe into timed segments for subtitles or seeking. Slower than
+ * transcribeAudio, so dictation doesn't use it.
+ */
+export async function transcribeAudioSegments(
+  audioSamples: number[]
+): Promise<TranscriptSegment[]> {
+  return await invoke<TranscriptSegment[]>("transcribe_audio_segments", {
+    audioSamples,
+  });
+}
+
+/**
+ * Stop recording and transcribe. Requests run one at a time; a full queue
+ * is rejected with a retry-after hint.
+ */
+export async function recordAndTranscribe(): Promise<Transcript> {
+  return await invoke<Transcript>("record_and_transcribe");
+}
+
+export interface TranscriptionQueuedEvent {
+  request_id: number;
+  /** Requests ahead of this one, including the running one */
+  ahead: number;
+}
+
+export interface TranscriptionProgressEvent {
+  request_id: number;
+  /** Set on `transcription-done` when the request failed */
+  error: string | null;
+}
+
+export async function onTranscriptionQueued(
+  callback: (event: TranscriptionQueuedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<TranscriptionQueuedEvent>(
+    "transcription-queued",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+export async function onTranscriptionStarted(
+  callback: (event: TranscriptionProgressEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<TranscriptionProgressEvent>(
+    "transcription-started",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+export async function onTranscriptionDone(
+  callback: (event: TranscriptionProgressEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<TranscriptionProgressEvent>(
+    "transcription-done",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+export interface LatencyBreakdown {
+  recorded_at: string;
+  hotkey_to_stop_ms: number | null;
+  stop_recording_ms: number;
+  transcription_ms: number;
+  post_process_ms: number | null;
+  injection_ms: number | null;
+  total_ms: number;
+}
+
+/**
+ * Stage timings of the last dictation, from hotkey release to injected text
+ */
+export async function getLastLatencyBreakdown(): Promise<LatencyBreakdown | null> {
+  return await invoke<LatencyBreakdown | null>("get_last_latency_breakdown");
+}
+
+/**
+ * Fired after each record_and_transcribe, and again once the text has been
+ * injected
+ */
+export async function onLatencyBreakdown(
+  callback: (breakdown: LatencyBreakdown) => void
+): Promise<UnlistenFn> {
+  return await listen<LatencyBreakdown>("latency-breakdown", (event) => {
+    callback(event.payload);
+  });
+}
+
+export interface FileTranscriptionResult {
+  text: string;
+  duration_ms: number;
+  detected_language: string | null;
+  sample_count: number;
+}
+
+/**
+ * Transcribe an audio file, keeping its duration and language alongside the
+ * text
+ */
+export async function transcribeFileWithDetails(
   filePath: string,
+  enablePostProcessing: boolean = true,
+  splitChannels: boolean = false
+): Promise<FileTranscriptionResult> {
+  const result = await invoke<FileTranscriptionResult>("transcribe_file", {
+    filePath,
+    splitChannels,
+  });
+  if (enablePostProcessing && result.text) {
+    const processed = await postProcessText(result.text);
+    result.text = stripVoiceCommandTokens(processed);
+  }
+  return result;
+}
+
+/**
+ * Transcribe audio held in memory (a dropped file or a browser recording).
+ * `mime` picks the decoder, e.g. "audio/wav" or "audio/mpeg".
+ */
+export async function transcribeBytes(
+  data: ArrayBuffer | Uint8Array,
+  mime: string,
   enablePostProcessing: boolean = true
-): Promise<string> {
-  let text = await invoke<string>("transcribe_file", { filePath });
-  if (enablePostProcessing && text) {
-    text = await postProcessText(text);
-    text = stripVoiceCommandTokens(text);
+): Promise<FileTranscriptionResult> {
+  const bytes = data instanceof Uint8Array ? data : new Uint8Array(data);
+  const result = await invoke<FileTranscriptionResult>("transcribe_bytes", {
+    data: Array.from(bytes),
+    mime,
+  });
+  if (enablePostProcessing && result.text) {
+    const processed = await postProcessText(result.text);
+    result.text = stripVoiceCommandTokens(processed);
   }
-  return text;
+  return result;
+}
+
+export interface FileTranscriptionProgress {
+  file_path: string;
+  text: string;
+  chunks_done: number;
+  chunk_count: number;
+  percent: number;
+}
+
+/**
+ * Transcribe a long file in chunks split at pauses. Partial transcripts
+ * arrive through `onFileTranscriptionProgress`; `cancelTranscription` stops
+ * the run.
+ */
+export async function transcribeFileStreaming(
+  filePath: string,
+  enablePostProcessing: boolean = true
+): Promise<FileTranscriptionResult> {
+  const result = await invoke<FileTranscriptionResult>(
+    "transcribe_file_streaming",
+    { filePath }
+  );
+  if (enablePostProcessing && result.text) {
+    const processed = await postProcessText(result.text);
+    result.text = stripVoiceCommandTokens(processed);
+  }
+  return result;
+}
+
+/**
+ * Stop the running transcription; the interrupted call rejects with an
+ * error that `isTranscriptionCancelledError` recognizes
+ */
+export async function cancelTranscription(): Promise<void> {
+  await invoke("cancel_transcription");
+}
+
+/** Whether a transcription error means it was cancelled */
+export function isTranscriptionCancelledError(error: unknown): boolean {
+  return String(error) === "Transcription error: cancelled";
+}
+
+export async function onFileTranscriptionProgress(
+  callback: (progress: FileTranscriptionProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<FileTranscriptionProgress>(
+    "file-transcription-progress",
+    (event) => {
+      callback(event.payload);
+    }
+  );
+}
+
+/**
+ * Audio file extensions this build can decode, for file picker filters
+ */
+export async function supportedAudioFormats(): Promise<string[]> {
+  return await invoke<string[]>("supported_audio_formats");
+}
+
+export interface AudioFileInfo {
+  duration_ms: number;
+  sample_rate: number;
+  channels: number;
+}
+
+/**
+ * Length and layout of an audio file, read without transcribing it
+ */
+export async function getAudioDuration(path: string): Promise<AudioFileInfo> {
+  return await invoke<AudioFileInfo>("get_audio_duration", { path });
+}
+
+export async function transcribeFile(
+  filePath: string,
+  enablePostProcessing: boolean = true,
+  splitChannels: boolean = false
+): Promise<string> {
+  const result = await transcribeFileWithDetails(
+    filePath,
+    enablePostProcessing,
+    splitChannels
+  );
+  return result.text;
 }
 
 // ============================================
@@ -134,6 +588,52 @@ export async function cancelModelDownload(modelId: string): Promise<boolean> {
   return await invoke<boolean>("cancel_model_download", { modelId });
 }
 
+/** Whether a `downloadModel` error means the download was cancelled */
+export function isDownloadCancelledError(error: unknown): boolean {
+  return String(error) === "Download error: cancelled";
+}
+
+export interface DownloadSpeedTest {
+  model_id: string;
+  bytes_downloaded: number;
+  elapsed_ms: number;
+  bytes_per_second: number;
+  /** Full model size; 0 when unknown */
+  total_bytes: number;
+  /** Time the full download would take at the measured speed */
+  estimated_seconds: number | null;
+  source: string;
+}
+
+/**
+ * Download a short sample of a model to estimate how long the full
+ * download will take. Nothing is saved.
+ */
+export async function testDownloadSpeed(
+  modelId: string
+): Promise<DownloadSpeedTest> {
+  return await invoke<DownloadSpeedTest>("test_download_speed", { modelId });
+}
+
+export interface PartialDownload {
+  path: string;
+  size_bytes: number;
+}
+
+export interface PartialDownloadCleanup {
+  files: PartialDownload[];
+  freed_bytes: number;
+}
+
+/** Find temp files left by interrupted downloads; deletes them unless dryRun */
+export async function cleanupPartialDownloads(
+  dryRun: boolean
+): Promise<PartialDownloadCleanup> {
+  return await invoke<PartialDownloadCleanup>("cleanup_partial_downloads", {
+    dryRun,
+  });
+}
+
 export async function deleteModel(modelId: string): Promise<void> {
   await invoke("delete_model", { modelId });
 }
@@ -164,6 +664,20 @@ export async function getModelsDir(): Promise<string> {
   return await invoke<string>("get_models_dir");
 }
 
+/**
+ * Store models in a different folder, moving downloaded models there.
+ * Pass null to go back to the default folder. Returns the new folder.
+ */
+export async function setModelsDir(path: string | null): Promise<string> {
+  return await invoke<string>("set_models_dir", { path });
+}
+
+export async function getSupportedLanguages(
+  modelId: string
+): Promise<LanguageOption[]> {
+  return await invoke<LanguageOption[]>("get_supported_languages", { modelId });
+}
+
 // ============================================
 // Download Progress Listener
 // ============================================
@@ -176,20 +690,147 @@ export async function onDownloadProgress(
   });
 }
 
+// ============================================
+// Rate Limit Listener
+// ============================================
+
+export interface RateLimitedEvent {
+  action: string;
+  retryAfterMs: number;
+}
+
+export async function onRateLimited(
+  callback: (event: RateLimitedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<RateLimitedEvent>("rate-limited", (event) => {
+    callback(event.payload);
+  });
+}
+
 // ============================================
 // Post-Processing API
 // ============================================
 
-export async function postProcessText(text: string): Promise<string> {
-  return await invoke<string>("post_process_text", { text });
+export type ProcessingStage =
+  | "user_dictionary"
+  | "voice_commands"
+  | "explicit_casing"
+  | "functions"
+  | "file_mentions"
+  | "file_paths"
+  | "variables"
+  | "classes"
+  | "symbols"
+  | "sentence_casing"
+  | "abbreviations"
+  | "keywords"
+  | "punctuation"
+  | "whitespace";
+
+export async function testReplacement(
+  pattern: string,
+  replacement: string,
+  sample: string
+): Promise<string> {
+  return await invoke<string>("test_replacement", {
+    pattern,
+    replacement,
+    sample,
+  });
+}
+
+export interface UserReplacement {
+  pattern: string;
+  replacement: string;
+}
+
+export interface PostProcessingConfig {
+  stages: ProcessingStage[];
+  replacements: UserReplacement[];
+  /** Emit [[...]] markers for action commands; when false they are stripped */
+  action_markers: boolean;
+}
+
+export async function getPostProcessingConfig(): Promise<PostProcessingConfig> {
+  return await invoke<PostProcessingConfig>("get_post_processing_config");
+}
+
+export async function setPostProcessingConfig(
+  config: PostProcessingConfig
+): Promise<void> {
+  await invoke("set_post_processing_config", { config });
+}
+
+/**
+ * Post-process text. Pass `executeActions` when the result's action markers
+ * will be run; destructive ones may then be held back for confirmation (see
+ * `onConfirmAction`).
+ */
+export async function postProcessText(
+  text: string,
+  stages?: ProcessingStage[],
+  executeActions: boolean = false
+): Promise<string> {
+  return await invoke<string>("post_process_text", {
+    text,
+    stages,
+    executeActions,
+  });
+}
+
+export type PostProcessingMode = "default" | "dictation" | "code";
+
+/**
+ * Re-process the selection the frontend copied to the clipboard with a
+ * post-processing preset. Returns the text to re-inject.
+ */
+export async function reprocessClipboard(
+  mode: PostProcessingMode
+): Promise<string> {
+  return await invoke<string>("reprocess_clipboard", { mode });
 }
 
 // ============================================
 // Text Injection API
 // ============================================
 
-export async function injectText(text: string): Promise<void> {
-  await invoke("inject_text", { text });
+/**
+ * Type text into the focused app. Resolves to false when the injection
+ * blocklist skipped it.
+ */
+export async function injectText(text: string): Promise<boolean> {
+  return await invoke<boolean>("inject_text", { text });
+}
+
+export type InjectionPermission = "granted" | "denied";
+
+export async function getInjectionPermissionStatus(): Promise<InjectionPermission> {
+  return await invoke<InjectionPermission>("injection_permission_status");
+}
+
+export async function requestInjectionPermission(): Promise<void> {
+  await invoke("request_injection_permission");
+}
+
+export interface InjectionBlockedEvent {
+  rule: string;
+  app: {
+    appName: string | null;
+    bundleId: string | null;
+    windowTitle: string | null;
+  };
+}
+
+/**
+ * Fired when inject_text skips injection because the focused app matches
+ * the injection blocklist
+ */
+export async function onInjectionBlocked(
+  callback: (event: InjectionBlockedEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<InjectionBlockedEvent>("injection-blocked", (event) => {
+    callback(event.payload);
+  });
 }
 
 // ============================================
@@ -201,6 +842,10 @@ export interface VoiceToTextOptions {
   onRecordingStop?: () => void;
   onTranscriptionStart?: () => void;
   onTranscriptionComplete?: (text: string) => void;
+  /** Transcript before post-processing, e.g. to store for reprocessing */
+  onRawTranscription?: (text: string) => void;
+  /** Language the model detected, when it runs with "auto" */
+  onDetectedLanguage?: (language: string | null) => void;
   onError?: (error: string) => void;
   injectToActiveWindow?: boolean;
   enablePostProcessing?: boolean;
@@ -224,11 +869,18 @@ export async function completeVoiceToText(
 
     let text = "";
 
-    text = await recordAndTranscribe();
+    const transcript = await recordAndTranscribe();
+    text = transcript.text;
+    options.onRawTranscription?.(text);
+    options.onDetectedLanguage?.(transcript.detected_language);
 
     // Apply post-processing if enabled
     if (options.enablePostProcessing && text) {
-      text = await postProcessText(text);
+      text = await postProcessText(
+        text,
+        undefined,
+        options.enableVoiceCommands
+      );
       text = options.enableVoiceCommands
         ? await processVoiceCommands(text)
         : stripVoiceCommandTokens(text);
@@ -272,7 +924,7 @@ export async function stopAndTranscribe(
   enablePostProcessing: boolean = true
 ): Promise<string | null> {
   try {
-    let text = await recordAndTranscribe();
+    let { text } = await recordAndTranscribe();
     if (enablePostProcessing && text) {
       text = await postProcessText(text);
     }
@@ -362,6 +1014,42 @@ function stripVoiceCommandTokens(text: string): string {
   return result.trim();
 }
 
+export interface ConfirmActionRequest {
+  id: number;
+  markers: string[];
+}
+
+/**
+ * Listen for dictations held back because they contain destructive voice
+ * commands. Pass the id to `confirmAction` to run them.
+ */
+export async function onConfirmAction(
+  callback: (request: ConfirmActionRequest) => void
+): Promise<UnlistenFn> {
+  return await listen<ConfirmActionRequest>("confirm-action", (event) => {
+    callback(event.payload);
+  });
+}
+
+/**
+ * Release and run the dictation held under `id`, typing its text and running
+ * its voice commands in the order they were spoken. Fails if it expired or
+ * already ran.
+ */
+export async function confirmAction(id: number): Promise<void> {
+  const text = await invoke<string>("confirm_action", { id });
+  for (const part of text.split(/(\[\[[A-Z_]+\]\])/)) {
+    const command = VOICE_COMMANDS[part];
+    if (command) {
+      await command();
+    } else if (part.trim() && !(await injectText(part.trim()))) {
+      // The focused app is blocklisted; don't run the remaining commands
+      // against it
+      return;
+    }
+  }
+}
+
 /**
  * Process voice commands in text and execute them
  * Returns the text with commands removed, and executes the commands
@@ -388,26 +1076,101 @@ async function processVoiceCommands(text: string): Promise<string> {
 /**
  * Stop recording, transcribe, post-process, and inject text or copy to clipboard
  */
+export interface DictationOutcome {
+  history_id: number | null;
+  /** Hash of the model and decoding settings, stored with the history row */
+  params_hash: string | null;
+  injected: boolean;
+  injection_error: string | null;
+}
+
+export interface DictationResult extends DictationOutcome {
+  text: string;
+}
+
+/**
+ * Save a dictation to history, then type it into the active window. The
+ * history entry survives a failed injection, so check `injected` and offer
+ * to copy the text instead. With `language` "auto", `detectedLanguage` is
+ * what gets stored.
+ */
+export async function saveAndInjectText(
+  text: string,
+  modelId: string,
+  language: string,
+  durationMs: number,
+  rawText?: string,
+  detectedLanguage?: string | null
+): Promise<DictationOutcome> {
+  return await invoke<DictationOutcome>("save_and_inject_text", {
+    text,
+    modelId,
+    language,
+    durationMs,
+    rawText,
+    detectedLanguage,
+  });
+}
+
 export async function stopTranscribeAndInject(
   enablePostProcessing: boolean = true,
   clipboardMode: boolean = false,
-  selectedModelId?: string,
-  enableVoiceCommands: boolean = false
-): Promise<string | null> {
+  selectedModelId: string = "base",
+  enableVoiceCommands: boolean = false,
+  language: string = "en"
+): Promise<DictationResult | null> {
   try {
-    let text = await completeVoiceToText({
+    const startTime = Date.now();
+    let rawText: string | undefined;
+    let detectedLanguage: string | null = null;
+    const text = await completeVoiceToText({
       enablePostProcessing,
       enableVoiceCommands,
-      injectToActiveWindow: !clipboardMode,
+      injectToActiveWindow: false,
+      onRawTranscription: (raw) => {
+        rawText = raw;
+      },
+      onDetectedLanguage: (detected) => {
+        detectedLanguage = detected;
+      },
     }, selectedModelId);
 
-    if (text && text.trim()) {
-      if (clipboardMode) {
-        await writeText(text);
-      }
-      // Note: injectText is already handled by completeVoiceToText if injectToActiveWindow is true
+    if (!text || !text.trim()) {
+      return null;
     }
-    return text;
+
+    const durationMs = Date.now() - startTime;
+    if (!clipboardMode) {
+      const outcome = await saveAndInjectText(
+        text,
+        selectedModelId,
+        language,
+        durationMs,
+        rawText,
+        detectedLanguage
+      );
+      return { text, ...outcome };
+    }
+
+    const historyId = await addTranscription(
+      text,
+      selectedModelId,
+      language,
+      durationMs,
+      rawText,
+      detectedLanguage
+    ).catch((historyError) => {
+      console.error("Failed to save to history:", historyError);
+      return null;
+    });
+    await writeText(text);
+    return {
+      text,
+      history_id: historyId,
+      params_hash: null,
+      injected: false,
+      injection_error: null,
+    };
   } catch (error) {
     console.error("Failed to transcribe and inject:", error);
     return null;
@@ -422,6 +1185,28 @@ export async function registerHotkey(hotkey: string): Promise<void> {
   await invoke("register_hotkey", { hotkey });
 }
 
+export interface HotkeyConfig {
+  pushToTalkKey: string;
+  toggleKey: string;
+  cancelKey: string;
+  hotkeyMode: "push-to-talk" | "toggle";
+}
+
+/**
+ * Register the hotkey for the active mode. Rejects without registering
+ * anything when two actions share a combo; the error names both actions.
+ */
+export async function registerAllHotkeys(config: HotkeyConfig): Promise<void> {
+  await invoke("register_all_hotkeys", {
+    config: {
+      push_to_talk_key: config.pushToTalkKey,
+      toggle_key: config.toggleKey,
+      cancel_key: config.cancelKey,
+      hotkey_mode: config.hotkeyMode,
+    },
+  });
+}
+
 export async function unregisterHotkeys(): Promise<void> {
   await invoke("unregister_hotkeys");
 }
@@ -442,6 +1227,18 @@ export async function onHotkeyReleased(
   });
 }
 
+/**
+ * Fired instead of `hotkey-released` when push-to-talk is let go before
+ * `minHoldMs`. Recording already started on press, so discard the take.
+ */
+export async function onHotkeyTap(
+  callback: () => void
+): Promise<UnlistenFn> {
+  return await listen("hotkey-tap", () => {
+    callback();
+  });
+}
+
 // ============================================
 // Tray Events
 // ============================================
@@ -454,6 +1251,27 @@ export async function onTrayStartRecording(
   });
 }
 
+export async function onRecordingCancelled(
+  callback: () => void
+): Promise<UnlistenFn> {
+  return await listen("recording-cancelled", () => {
+    callback();
+  });
+}
+
+/**
+ * Fired when a recording ends itself after `silenceTimeoutMs` of silence.
+ * The microphone is already released; finish the take as usual, e.g. with
+ * `stopTranscribeAndInject`.
+ */
+export async function onRecordingAutoStopped(
+  callback: () => void
+): Promise<UnlistenFn> {
+  return await listen("recording-auto-stopped", () => {
+    callback();
+  });
+}
+
 export async function onTrayStopRecording(
   callback: () => void
 ): Promise<UnlistenFn> {
@@ -462,6 +1280,17 @@ export async function onTrayStopRecording(
   });
 }
 
+/**
+ * Fired when the last transcription was copied to the clipboard
+ */
+export async function onTranscriptionCopied(
+  callback: (item: TranscriptionHistoryItem) => void
+): Promise<UnlistenFn> {
+  return await listen<TranscriptionHistoryItem>("copied", (event) => {
+    callback(event.payload);
+  });
+}
+
 export type TrayNavigationTarget =
   | "main"
   | "transcribe"
@@ -488,6 +1317,10 @@ export interface TranscriptionHistoryItem {
   model_id: string;
   language: string;
   duration_ms: number;
+  /** Hash of the settings that produced the text; null for older entries */
+  params_hash: string | null;
+  /** Transcript before post-processing; null when it wasn't stored */
+  raw_text: string | null;
   created_at: string;
 }
 
@@ -503,26 +1336,70 @@ export async function getTranscriptionHistory(
   });
 }
 
+export interface TranscriptionHistoryDay {
+  /** Local date as YYYY-MM-DD */
+  date: string;
+  /** 0 for today, 1 for yesterday; null when the timestamp was unreadable */
+  days_ago: number | null;
+  entries: TranscriptionHistoryItem[];
+}
+
+/**
+ * History grouped into days in the user's local time zone. A day can span
+ * two pages, so merge the last group of one page with the first of the next.
+ */
+export async function getTranscriptionHistoryGrouped(
+  limit?: number,
+  offset?: number
+): Promise<TranscriptionHistoryDay[]> {
+  return await invoke<TranscriptionHistoryDay[]>(
+    "get_transcription_history_grouped",
+    {
+      limit,
+      offset,
+      utcOffsetMinutes: -new Date().getTimezoneOffset(),
+    }
+  );
+}
+
 export async function getTranscriptionHistoryCount(search?: string): Promise<number> {
   return await invoke<number>("get_transcription_history_count", {
     search: search?.trim() || null,
   });
 }
 
+/**
+ * Save a transcription to history. With `language` "auto", `detectedLanguage`
+ * is what gets stored.
+ */
 export async function addTranscription(
   text: string,
   modelId: string,
   language: string,
-  durationMs: number
+  durationMs: number,
+  rawText?: string,
+  detectedLanguage?: string | null
 ): Promise<number> {
   return await invoke<number>("add_transcription", {
     text,
     modelId,
     language,
     durationMs,
+    rawText,
+    detectedLanguage,
   });
 }
 
+/**
+ * Copy the most recent transcription to the clipboard; null when history is
+ * empty
+ */
+export async function copyLastTranscription(): Promise<TranscriptionHistoryItem | null> {
+  return await invoke<TranscriptionHistoryItem | null>(
+    "copy_last_transcription"
+  );
+}
+
 export async function clearTranscriptionHistory(): Promise<void> {
   await invoke("clear_transcription_history");
 }
@@ -531,6 +1408,14 @@ export async function deleteTranscriptionItem(id: number): Promise<void> {
   await invoke("delete_transcription", { id });
 }
 
+/**
+ * Re-run the current post-processing over a history entry's raw text and
+ * save the result. Fails for entries saved without raw text.
+ */
+export async function reprocessHistoryEntry(id: number): Promise<string> {
+  return await invoke<string>("reprocess_history_entry", { id });
+}
+
 // ============================================
 // Error Reporting API
 // ============================================
@@ -571,6 +1456,22 @@ export interface ErrorReport {
   os_info: string;
 }
 
+export interface CrashReport {
+  id: string;
+  timestamp: string;
+  panic_message: string;
+  backtrace: string;
+  app_version: string;
+  git_hash: string;
+  build_time: string;
+  os_info: string;
+  thread_name: string | null;
+  location: string | null;
+  fingerprint: string;
+  occurrence_count: number;
+  last_seen: string | null;
+}
+
 export interface ErrorStats {
   total_errors: number;
   by_category: Record<string, number>;
@@ -630,6 +1531,34 @@ export async function clearErrorReports(): Promise<void> {
   await invoke("clear_error_reports");
 }
 
+/**
+ * Clear only the error reports in a category and/or older than a date,
+ * returning how many were removed
+ */
+export async function clearErrorReportsFiltered(
+  category?: ErrorCategory,
+  olderThan?: Date
+): Promise<number> {
+  return await invoke<number>("clear_error_reports_filtered", {
+    category,
+    olderThan: olderThan?.toISOString(),
+  });
+}
+
+/**
+ * Whether panics are being written as crash reports
+ */
+export async function getPanicHookStatus(): Promise<boolean> {
+  return await invoke<boolean>("get_panic_hook_status");
+}
+
+/**
+ * Switch crash reporting for panics on or off, returning the new state
+ */
+export async function setPanicHookEnabled(enabled: boolean): Promise<boolean> {
+  return await invoke<boolean>("set_panic_hook_enabled", { enabled });
+}
+
 /**
  * Load error reports from disk
  */
@@ -637,6 +1566,20 @@ export async function loadErrorReports(): Promise<number> {
   return await invoke<number>("load_error_reports");
 }
 
+/**
+ * Get crash reports left by earlier panics, most recent first
+ */
+export async function getCrashReports(limit?: number): Promise<CrashReport[]> {
+  return await invoke<CrashReport[]>("get_crash_reports", { limit });
+}
+
+/**
+ * Delete crash report files; returns how many crashes were removed
+ */
+export async function clearCrashReports(): Promise<number> {
+  return await invoke<number>("clear_crash_reports");
+}
+
 /**
  * Helper to capture and report errors from async operations
  */
@@ -47,6 +47,8 @@ interface AppStore extends AppState {
   // Settings actions
   updateSettings: (settings: Partial<AppSettings>) => void;
   resetSettings: () => void;
+  /** Re-read settings that a backend command saved on its own */
+  reloadSettings: () => Promise<void>;
 
   // Available models from DB
   availableModels: WhisperModel[];
@@ -197,6 +199,11 @@ export const useAppStore = create<AppStore>()((set, get) => ({
     );
   },
 
+  reloadSettings: async () => {
+    const dbSettings = await dbGetSettings();
+    set({ settings: dbSettingsToFrontend(dbSettings) });
+  },
+
   // Utility
   reset: () => set(initialState),
 }));
@@ -41,8 +41,42 @@ export interface AppSettings {
   // Advanced
   autoStartOnBoot: boolean;
   minimizeToTray: boolean;
+  longFormRecording: boolean; // Spool long recordings to disk
+  emptyTranscriptionBehavior: string; // "empty" returns "", "error" fails with no speech detected
+  minRecordingMs: number; // Shorter recordings are rejected before transcription
+  cancelKey: string;
+  overlayPosition: OverlayPosition;
+  injectionBlocklist: string; // One app name, bundle id or "title:<text>" rule per line
+  warmModelCacheSize: number; // Recently used models kept loaded for instant switching; 0 disables
+  trayMenuItems: string; // Comma-separated tray menu item ids to show
+  silencePadMs: number; // Pad shorter clips with trailing silence up to this length (0 = off)
+  injectionTrailing: "none" | "space" | "newline" | "smart"; // Separator typed after injected text
+  modelsDir: string | null; // Custom models folder; null uses the app data directory
+  minHoldMs: number;
+  vadThresholdDbfs: number;
+  preallocateRecordingSeconds: number;
+  downloadMirrors: string;
+  confirmDestructiveCommands: boolean;
+  releaseNotesUrl: string;
+  inputDeviceName: string | null;
+  silenceTimeoutMs: number;
+  prerollMs: number;
+  decodingStrategy: "auto" | "greedy" | "beam-search";
+  cpuThreads: number; // 0 = every available core
+  injectionMethod: "paste" | "type"; // paste via the clipboard, or type key by key
+  restoreClipboard: boolean; // restore the previous clipboard text after pasting
+  typingDelayMs: number; // pause between typed characters; 0 = type at full speed
 }
 
+// Where the recording overlay appears on the monitor under the cursor
+export type OverlayPosition =
+  | "fullscreen"
+  | "center"
+  | "top_left"
+  | "top_right"
+  | "bottom_left"
+  | "bottom_right";
+
 // Recording state
 export type RecordingStatus = "idle" | "recording" | "processing" | "error";
 
@@ -114,6 +148,32 @@ export const DEFAULT_SETTINGS: AppSettings = {
   clipboardMode: false,
   autoStartOnBoot: false,
   minimizeToTray: true,
+  longFormRecording: false,
+  emptyTranscriptionBehavior: "empty",
+  minRecordingMs: 250,
+  cancelKey: "Escape",
+  overlayPosition: "fullscreen",
+  injectionBlocklist: "",
+  warmModelCacheSize: 1,
+  trayMenuItems:
+    "transcribe,start_recording,stop_recording,copy_last,history,models,settings,help",
+  silencePadMs: 1000,
+  injectionTrailing: "none",
+  modelsDir: null,
+  minHoldMs: 120,
+  vadThresholdDbfs: -45,
+  preallocateRecordingSeconds: 60,
+  downloadMirrors: "",
+  confirmDestructiveCommands: false,
+  releaseNotesUrl: "https://raw.githubusercontent.com/johuniq/wavee/main/CHANGELOG.md",
+  inputDeviceName: null,
+  silenceTimeoutMs: 0,
+  prerollMs: 0,
+  decodingStrategy: "auto",
+  cpuThreads: 0,
+  injectionMethod: "paste",
+  restoreClipboard: false,
+  typingDelayMs: 0,
 };
 
 // Model categories for UI grouping
@@ -563,6 +623,15 @@ export const WHISPER_MODELS: WhisperModel[] = [
     languages: ["en"],
   },
   // ========== LARGE MODELS (Best Accuracy) ==========
+  {
+    id: "large-v2",
+    name: "Whisper Large v2",
+    size: "2.9 GB",
+    sizeBytes: 2.9 * 1024 * 1024 * 1024,
+    description:
+      "Previous large Whisper model, still strong on noisy multilingual audio.",
+    languages: ["multilingual"],
+  },
   {
     id: "large-v3",
     name: "Whisper Large v3",